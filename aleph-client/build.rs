@@ -0,0 +1,71 @@
+use std::{env, fs, path::PathBuf};
+
+use codec::Decode;
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+
+/// Snapshot of the `aleph_runtime` metadata, from which the typed bindings are generated.
+const METADATA_PATH: &str = "metadata/aleph_runtime.scale";
+const UPDATE_HINT: &str =
+    "run `scripts/update_aleph_client_metadata.sh` against a node with the current runtime";
+
+/// Prefix of `sp_version::RuntimeVersion`, as encoded in the `System::Version` constant.
+#[derive(Decode)]
+struct RuntimeVersion {
+    _spec_name: String,
+    _impl_name: String,
+    _authoring_version: u32,
+    spec_version: u32,
+    _impl_version: u32,
+    _apis: Vec<([u8; 8], u32)>,
+    transaction_version: u32,
+}
+
+/// Versions of the runtime that the metadata snapshot was taken from.
+fn snapshot_versions() -> (u32, u32) {
+    let bytes = fs::read(METADATA_PATH).unwrap_or_else(|e| {
+        panic!(
+            "Cannot read {}: {}. Please {}.",
+            METADATA_PATH, e, UPDATE_HINT
+        )
+    });
+    let metadata = match RuntimeMetadataPrefixed::decode(&mut bytes.as_slice())
+        .expect("Metadata snapshot should be decodable")
+        .1
+    {
+        RuntimeMetadata::V14(metadata) => metadata,
+        _ => panic!("Only V14 metadata is supported. Please {}.", UPDATE_HINT),
+    };
+
+    let version = metadata
+        .pallets
+        .iter()
+        .find(|pallet| pallet.name == "System")
+        .and_then(|system| {
+            system
+                .constants
+                .iter()
+                .find(|constant| constant.name == "Version")
+        })
+        .expect("Metadata should contain `System::Version` constant");
+    let version = RuntimeVersion::decode(&mut version.value.as_slice())
+        .expect("`System::Version` should be decodable");
+
+    (version.spec_version, version.transaction_version)
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", METADATA_PATH);
+
+    let (spec_version, transaction_version) = snapshot_versions();
+
+    let out = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR should be set"))
+        .join("runtime_version.rs");
+    fs::write(
+        out,
+        format!(
+            "pub const SPEC_VERSION: u32 = {};\npub const TRANSACTION_VERSION: u32 = {};\n",
+            spec_version, transaction_version
+        ),
+    )
+    .expect("Should be able to write runtime version");
+}
@@ -1,41 +1,38 @@
-use crate::{state_query_storage_at, AnyConnection};
+use crate::{
+    asynchronous,
+    error::{Error, Result},
+    state_query_storage_at, AnyConnection,
+};
 use codec::Decode;
+use futures::executor::block_on;
 use pallet_balances::BalanceLock;
 use sp_core::{crypto::AccountId32, storage::StorageKey};
 use substrate_api_client::{AccountId, Balance};
 
-pub fn get_free_balance<C: AnyConnection>(connection: &C, account: &AccountId) -> Balance {
-    match connection
-        .as_connection()
-        .get_account_data(account)
-        .expect("Should be able to access account data")
-    {
-        Some(account_data) => account_data.free,
-        // Account may have not been initialized yet or liquidated due to the lack of funds.
-        None => 0,
-    }
+/// Blocking wrapper for `asynchronous::free_balance`.
+pub fn get_free_balance<C: AnyConnection>(connection: &C, account: &AccountId) -> Result<Balance> {
+    block_on(asynchronous::free_balance(connection, account))
 }
 
 pub fn locks<C: AnyConnection>(
     connection: &C,
     accounts: &[AccountId],
-) -> Vec<Vec<BalanceLock<Balance>>> {
-    let storage_keys = create_storage_keys_from_accounts(connection, accounts);
+) -> Result<Vec<Vec<BalanceLock<Balance>>>> {
+    let storage_keys = create_storage_keys_from_accounts(connection, accounts)?;
     get_locked_balances_from_storage(connection, storage_keys)
 }
 
 fn create_storage_keys_from_accounts<C: AnyConnection>(
     connection: &C,
     accounts: &[AccountId32],
-) -> Vec<StorageKey> {
+) -> Result<Vec<StorageKey>> {
+    let metadata = connection.as_connection().metadata;
     accounts
         .iter()
         .map(|account| {
-            connection
-                .as_connection()
-                .metadata
+            metadata
                 .storage_map_key("Balances", "Locks", account)
-                .unwrap_or_else(|_| panic!("Cannot create storage key for account {}!", account))
+                .map_err(|e| Error::Api(format!("{:?}", e)))
         })
         .collect()
 }
@@ -43,18 +40,16 @@ fn create_storage_keys_from_accounts<C: AnyConnection>(
 fn get_locked_balances_from_storage<C: AnyConnection>(
     connection: &C,
     storage_keys: Vec<StorageKey>,
-) -> Vec<Vec<BalanceLock<Balance>>> {
-    match state_query_storage_at(connection, storage_keys) {
-        Ok(storage_entries) => storage_entries
-            .into_iter()
-            .map(|storage_entry| {
-                let entry_bytes = storage_entry.expect("Storage entry is null!").0;
-                Decode::decode(&mut entry_bytes.as_slice())
-                    .expect("Failed to decode locked balances!")
-            })
-            .collect(),
-        Err(err) => {
-            panic!("Failed to query storage, details: {}", &err[..]);
-        }
-    }
+) -> Result<Vec<Vec<BalanceLock<Balance>>>> {
+    state_query_storage_at(connection, storage_keys)
+        .map_err(Error::Rpc)?
+        .into_iter()
+        .map(|storage_entry| {
+            let entry_bytes = storage_entry
+                .ok_or(Error::MissingStorage("Balances", "Locks"))?
+                .0;
+            Decode::decode(&mut entry_bytes.as_slice())
+                .map_err(|e| Error::Decode(format!("locked balances: {}", e)))
+        })
+        .collect()
 }
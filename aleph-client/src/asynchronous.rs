@@ -0,0 +1,421 @@
+//! Non-blocking counterparts of the basic operations of this crate.
+//!
+//! `substrate-api-client` talks to the node synchronously, so every call is executed on a shared
+//! pool of `BLOCKING_THREADS` threads and the returned futures only await its result. Thus they can
+//! be polled by any executor and never panic on RPC failures. Blocking functions exported from the
+//! crate root are thin wrappers driving these futures to completion and returning their errors.
+//!
+//! Subscriptions live as long as the node keeps sending notifications, so they are forwarded by
+//! dedicated threads instead, not to starve the pool.
+
+use std::{
+    sync::mpsc::{channel, Receiver},
+    thread,
+};
+
+use codec::{Decode, Encode};
+use futures::{
+    channel::{mpsc, oneshot},
+    executor::ThreadPool,
+    StreamExt,
+};
+use log::{error, info, warn};
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use sp_core::{Pair, H256};
+use sp_runtime::Perbill;
+use substrate_api_client::{
+    rpc::ws_client::WsRpcClient, std::error::Error as ApiClientError, AccountId, Api, ApiResult,
+    Balance, UncheckedExtrinsicV4, XtStatus,
+};
+
+use crate::{
+    aleph_zero::{
+        runtime_types,
+        runtime_types::{
+            aleph_runtime::Call,
+            pallet_elections::pallet::Call as ElectionsCall,
+            pallet_staking::{RewardDestination, ValidatorPrefs},
+        },
+        session, staking, sudo,
+    },
+    compose_typed_call, debug, ensure_protocol,
+    error::{Error, Result},
+    receipt::{self, Receipts, TxReceipt},
+    typed::{compare_runtime_version, typed_account, typed_address},
+    AnyConnection, BlockNumber, Connection, Header, KeyPair, RootConnection, SessionKeys,
+    SignedConnection, Signer,
+};
+
+/// How many blocking calls to the node may be executed at once. The remaining ones wait in a queue.
+pub const BLOCKING_THREADS: usize = 16;
+
+static BLOCKING_POOL: Lazy<ThreadPool> = Lazy::new(|| {
+    ThreadPool::builder()
+        .pool_size(BLOCKING_THREADS)
+        .name_prefix("aleph-client-")
+        .create()
+        .expect("Should be able to create the blocking pool")
+});
+
+/// Runs `job` on the blocking pool and awaits its result.
+async fn run_blocking<T, F>(job: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+    BLOCKING_POOL.spawn_ok(async move {
+        // If the receiver has been dropped, nobody is interested in the result anymore.
+        let _ = sender.send(job());
+    });
+    receiver.await.map_err(|_| Error::Cancelled)?
+}
+
+/// Moves all items coming from a blocking `receiver` to an asynchronous stream.
+fn forward<T: Send + 'static>(receiver: Receiver<T>) -> mpsc::UnboundedReceiver<T> {
+    let (sender, forwarded) = mpsc::unbounded();
+    thread::spawn(move || {
+        while let Ok(item) = receiver.recv() {
+            if sender.unbounded_send(item).is_err() {
+                return;
+            }
+        }
+    });
+    forwarded
+}
+
+/// Creates a connection to the node at `address`. Unlike `create_connection`, makes a single
+/// attempt. Fails if the node runs another runtime version than the bindings were generated for.
+pub async fn connect(address: &str) -> Result<Connection> {
+    let address = ensure_protocol(address);
+    run_blocking(move || {
+        let api = Api::<KeyPair, _>::new(WsRpcClient::new(&address))
+            .map_err(|why| Error::Connection(address, format!("{:?}", why)))?;
+        compare_runtime_version(
+            api.runtime_version.spec_version,
+            api.runtime_version.transaction_version,
+        )?;
+        Ok(api)
+    })
+    .await
+}
+
+/// Creates a connection to the node at `address` signed by `signer`.
+pub async fn connect_signed(address: &str, signer: KeyPair) -> Result<SignedConnection> {
+    let connection = connect(address).await?;
+    Ok(SignedConnection::from_any_connection(connection, signer))
+}
+
+/// Creates a connection to the node at `address` signed by `root`.
+pub async fn connect_root(address: &str, root: KeyPair) -> Result<RootConnection> {
+    connect_signed(address, root).await.map(Into::into)
+}
+
+/// Sends transaction `xt` using `connection`.
+///
+/// If `tx_status` is either `Finalized` or `InBlock`, additionally returns hash of the containing
+/// block. `xt_name` is used only for logging purposes.
+pub async fn submit<T: Encode, C: AnyConnection>(
+    connection: &C,
+    xt: UncheckedExtrinsicV4<T>,
+    xt_name: Option<&'static str>,
+    xt_status: XtStatus,
+) -> Result<Option<H256>> {
+    submit_encoded(connection, xt.hex_encode(), xt_name, xt_status).await
+}
+
+/// Sends the hex encoded extrinsic `xt` using `connection`, like `submit`.
+pub async fn submit_encoded<C: AnyConnection>(
+    connection: &C,
+    xt: String,
+    xt_name: Option<&'static str>,
+    xt_status: XtStatus,
+) -> Result<Option<H256>> {
+    let receipts = connection.receipts();
+    let connection = connection.as_connection();
+    run_blocking(move || send_encoded(&connection, receipts.as_ref(), xt, xt_name, xt_status)).await
+}
+
+/// Sends `xt` like `submit_encoded` and records it in `receipts`, if given.
+fn send_encoded(
+    connection: &Connection,
+    receipts: Option<&Receipts>,
+    xt: String,
+    xt_name: Option<&'static str>,
+    xt_status: XtStatus,
+) -> Result<Option<H256>> {
+    let tx_hash = hex::decode(xt.trim_start_matches("0x"))
+        .map(|xt| receipt::tx_hash(&xt))
+        .map_err(|why| Error::Decode(format!("extrinsic: {}", why)))?;
+    let hash = connection
+        .send_extrinsic(xt, xt_status)?
+        .ok_or(Error::NoHash)?;
+
+    let block_hash = match xt_status {
+        XtStatus::Finalized | XtStatus::InBlock => {
+            info!(target: "aleph-client",
+                "Transaction `{}` with hash {:?} was included in block with hash {}.",
+                xt_name.unwrap_or_default(), tx_hash, hash);
+            Some(hash)
+        }
+        // Other variants either do not return (see https://github.com/scs/substrate-api-client/issues/175)
+        // or return xt hash, which is kinda useless here.
+        _ => None,
+    };
+    if let Some(receipts) = receipts {
+        receipts.record(TxReceipt {
+            name: xt_name,
+            tx_hash,
+            block_hash,
+        });
+    }
+    Ok(block_hash)
+}
+
+/// Returns free balance of `account`.
+pub async fn free_balance<C: AnyConnection>(
+    connection: &C,
+    account: &AccountId,
+) -> Result<Balance> {
+    let connection = connection.as_connection();
+    let account = account.clone();
+    run_blocking(move || {
+        Ok(connection
+            .get_account_data(&account)?
+            .map(|account_data| account_data.free)
+            // Account may have not been initialized yet or liquidated due to the lack of funds.
+            .unwrap_or(0))
+    })
+    .await
+}
+
+/// Bonds `initial_stake` of the signer of `connection` with `controller_account_id` as
+/// the controller.
+pub async fn bond<S: Signer>(
+    connection: &SignedConnection<S>,
+    initial_stake: Balance,
+    controller_account_id: &AccountId,
+    status: XtStatus,
+) -> Result<Option<H256>> {
+    let connection = connection.clone();
+    let call = staking::calls::Bond {
+        controller: typed_address(controller_account_id),
+        value: initial_stake,
+        payee: RewardDestination::Staked,
+    };
+    run_blocking(move || {
+        let xt = compose_typed_call(&connection, call);
+        send_encoded(
+            &connection.as_connection(),
+            connection.receipts().as_ref(),
+            xt.hex_encode(),
+            Some("bond"),
+            status,
+        )
+    })
+    .await
+}
+
+/// Declares the signer of `connection` (a controller) willing to validate with
+/// `commission_percentage`.
+pub async fn validate<S: Signer>(
+    connection: &SignedConnection<S>,
+    commission_percentage: u8,
+    status: XtStatus,
+) -> Result<Option<H256>> {
+    let connection = connection.clone();
+    let prefs = ValidatorPrefs {
+        blocked: false,
+        commission: runtime_types::sp_arithmetic::per_things::Perbill(
+            Perbill::from_percent(commission_percentage as u32).deconstruct(),
+        ),
+    };
+    run_blocking(move || {
+        let xt = compose_typed_call(&connection, staking::calls::Validate { prefs });
+        send_encoded(
+            &connection.as_connection(),
+            connection.receipts().as_ref(),
+            xt.hex_encode(),
+            Some("validate"),
+            status,
+        )
+    })
+    .await
+}
+
+/// Sets session keys of the signer of `connection` to `new_keys`.
+pub async fn set_keys<S: Signer>(
+    connection: &SignedConnection<S>,
+    new_keys: SessionKeys,
+    status: XtStatus,
+) -> Result<Option<H256>> {
+    let connection = connection.clone();
+    let call = session::calls::SetKeys {
+        keys: new_keys.into(),
+        proof: Vec::new(),
+    };
+    run_blocking(move || {
+        let xt = compose_typed_call(&connection, call);
+        send_encoded(
+            &connection.as_connection(),
+            connection.receipts().as_ref(),
+            xt.hex_encode(),
+            Some("set_keys"),
+            status,
+        )
+    })
+    .await
+}
+
+/// Changes the validator set (stored in `pallet_elections`) to `new_members`.
+pub async fn change_members<S: Signer>(
+    sudo_connection: &RootConnection<S>,
+    new_members: Vec<AccountId>,
+    status: XtStatus,
+) -> Result<Option<H256>> {
+    info!(target: "aleph-client", "New members {:#?}", new_members);
+    let connection = sudo_connection.as_signed();
+    let call = sudo::calls::SudoUncheckedWeight {
+        call: Box::new(Call::Elections(ElectionsCall::change_members {
+            members: new_members.iter().map(typed_account).collect(),
+        })),
+        weight: 0,
+    };
+    run_blocking(move || {
+        let xt = compose_typed_call(&connection, call);
+        send_encoded(
+            &connection.as_connection(),
+            connection.receipts().as_ref(),
+            xt.hex_encode(),
+            Some("sudo_unchecked_weight"),
+            status,
+        )
+    })
+    .await
+}
+
+/// Waits for the first `event` (given as a pair of pallet and variant names) satisfying
+/// `predicate`.
+///
+/// Events are decoded on a separate thread, which lives until the next event after the returned
+/// future is either resolved or dropped, or until the node closes the subscription, in which case
+/// `Error::SubscriptionClosed` is returned.
+pub async fn wait_for_event<C, E, P>(connection: &C, event: (&str, &str), predicate: P) -> Result<E>
+where
+    C: AnyConnection,
+    E: Decode + Clone + Send + 'static,
+    P: Fn(E) -> bool,
+{
+    let (module, variant) = (event.0.to_string(), event.1.to_string());
+    info!(target: "aleph-client", "Creating event subscription {}/{}", module, variant);
+
+    let connection = connection.as_connection();
+    let (events_in, events_out) = channel();
+    let subscriber = connection.clone();
+    run_blocking(move || Ok(subscriber.subscribe_events(events_in)?)).await?;
+
+    let (decoded_in, mut decoded_out) = mpsc::unbounded();
+    thread::spawn(move || loop {
+        let args: ApiResult<E> = connection.wait_for_event(&module, &variant, None, &events_out);
+        let decoded = match args {
+            Ok(event) => Ok(event),
+            Err(ApiClientError::Disconnected(_)) => {
+                warn!(target: "aleph-client", "Event subscription {}/{} closed", module, variant);
+                Err(Error::SubscriptionClosed)
+            }
+            Err(why) => {
+                error!(target: "aleph-client", "Error {:?}", why);
+                if decoded_in.is_closed() {
+                    return;
+                }
+                continue;
+            }
+        };
+        let closed = decoded.is_err();
+        if decoded_in.unbounded_send(decoded).is_err() || closed {
+            return;
+        }
+    });
+
+    while let Some(event) = decoded_out.next().await {
+        let event = event?;
+        if predicate(event.clone()) {
+            return Ok(event);
+        }
+    }
+    Err(Error::SubscriptionClosed)
+}
+
+/// Waits until the block with number `block_number` is finalized.
+pub async fn wait_for_finalized_block<C: AnyConnection>(
+    connection: &C,
+    block_number: BlockNumber,
+) -> Result<BlockNumber> {
+    let connection = connection.as_connection();
+    let (sender, receiver) = channel();
+    run_blocking(move || Ok(connection.subscribe_finalized_heads(sender)?)).await?;
+
+    let mut headers = forward(receiver);
+    while let Some(header) = headers.next().await {
+        let header = serde_json::from_str::<Header>(&header)
+            .map_err(|why| Error::Decode(format!("header: {}", why)))?;
+        info!(target: "aleph-client", "Received header for a block number {:?}", header.number);
+
+        if header.number.ge(&block_number) {
+            return Ok(block_number);
+        }
+    }
+    Err(Error::SubscriptionClosed)
+}
+
+/// Prints the most interesting parts of the chain storage.
+pub async fn print_storages<C: AnyConnection>(connection: &C) -> Result<()> {
+    let connection = connection.as_connection();
+    run_blocking(move || debug::try_print_storages(&connection)).await
+}
+
+/// The most interesting parts of the chain storage as JSON.
+pub async fn storages_json<C: AnyConnection>(connection: &C) -> Result<Value> {
+    let connection = connection.as_connection();
+    run_blocking(move || debug::try_storages_json(&connection)).await
+}
+
+/// Creates a key pair from `seed`.
+pub fn keypair_from_string(seed: &str) -> Result<KeyPair> {
+    KeyPair::from_string(seed, None).map_err(|_| Error::InvalidSeed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::{executor::block_on, future::join_all};
+
+    use super::*;
+
+    #[test]
+    fn given_more_jobs_than_blocking_threads_when_running_them_then_all_complete() {
+        let count = 4 * BLOCKING_THREADS;
+        let jobs = (0..count).map(|i| {
+            run_blocking(move || {
+                thread::sleep(Duration::from_millis(10));
+                Ok(i)
+            })
+        });
+
+        let results = block_on(join_all(jobs))
+            .into_iter()
+            .collect::<Result<Vec<_>>>()
+            .expect("All the jobs should succeed");
+
+        assert_eq!(results, (0..count).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn given_failing_job_when_running_it_then_its_error_is_returned() {
+        let result = block_on(run_blocking(|| Err::<(), _>(Error::NoHash)));
+
+        assert!(matches!(result, Err(Error::NoHash)));
+    }
+}
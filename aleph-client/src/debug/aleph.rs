@@ -1,23 +1,35 @@
+use codec::Encode;
+use serde_json::{json, Value};
+
 use crate::{
+    aleph_zero::aleph,
     debug::{element_prompt, entry_prompt, pallet_prompt},
-    AnyConnection,
+    error::{Error, Result},
+    read_typed_storage, AnyConnection,
 };
-use primitives::AuthorityId;
 
-pub fn print_storage<C: AnyConnection>(connection: &C) {
-    let authorities: Vec<AuthorityId> = connection
-        .as_connection()
-        .get_storage_value("Aleph", "Authorities", None)
-        .expect("Api call should succeed")
-        .expect("Authorities should always be present");
+pub fn print_storage<C: AnyConnection>(connection: &C) -> Result<()> {
+    let authorities = read_typed_storage(connection, &aleph::storage::Authorities)?
+        .ok_or(Error::MissingStorage("Aleph", "Authorities"))?;
 
     println!("{}", pallet_prompt("Aleph"));
     println!("{}", entry_prompt("Authorities"));
 
     for auth in authorities {
-        println!(
-            "{}",
-            element_prompt(format!("\tAuthority {:?}", auth.to_string()))
-        );
+        println!("{}", element_prompt(format!("\tAuthority {:?}", auth)));
     }
+
+    Ok(())
+}
+
+pub fn storage_json<C: AnyConnection>(connection: &C) -> Result<Value> {
+    let authorities = read_typed_storage(connection, &aleph::storage::Authorities)?
+        .ok_or(Error::MissingStorage("Aleph", "Authorities"))?;
+
+    Ok(json!({
+        "Authorities": authorities
+            .iter()
+            .map(|auth| format!("0x{}", hex::encode(auth.encode())))
+            .collect::<Vec<_>>(),
+    }))
 }
@@ -1,15 +1,15 @@
+use serde_json::{json, Value};
+
 use crate::{
+    aleph_zero::elections,
     debug::{element_prompt, entry_prompt, pallet_prompt},
-    AnyConnection,
+    error::{Error, Result},
+    read_typed_storage, AnyConnection,
 };
-use primitives::AuthorityId;
 
-pub fn print_storage<C: AnyConnection>(connection: &C) {
-    let members: Vec<AuthorityId> = connection
-        .as_connection()
-        .get_storage_value("Elections", "Members", None)
-        .expect("Api call should succeed")
-        .expect("Members should always be present");
+pub fn print_storage<C: AnyConnection>(connection: &C) -> Result<()> {
+    let members = read_typed_storage(connection, &elections::storage::Members)?
+        .ok_or(Error::MissingStorage("Elections", "Members"))?;
 
     println!("{}", pallet_prompt("Elections"));
     println!("{}", entry_prompt("Members"));
@@ -20,4 +20,15 @@ pub fn print_storage<C: AnyConnection>(connection: &C) {
             element_prompt(format!("\tMember {:?}", member.to_string()))
         );
     }
+
+    Ok(())
+}
+
+pub fn storage_json<C: AnyConnection>(connection: &C) -> Result<Value> {
+    let members = read_typed_storage(connection, &elections::storage::Members)?
+        .ok_or(Error::MissingStorage("Elections", "Members"))?;
+
+    Ok(json!({
+        "Members": members.iter().map(|member| member.to_string()).collect::<Vec<_>>(),
+    }))
 }
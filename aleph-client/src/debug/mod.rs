@@ -1,7 +1,13 @@
-use crate::AnyConnection;
+use futures::executor::block_on;
+use primitives::Balance;
+use serde_json::{json, Value};
+
+use crate::{asynchronous, error::Result, AnyConnection};
 
 mod aleph;
 mod elections;
+mod session;
+mod staking;
 mod treasury;
 
 fn pallet_prompt(name: &'static str) -> String {
@@ -16,8 +22,35 @@ fn element_prompt(el: String) -> String {
     format!("\t{}", el)
 }
 
-pub fn print_storages<C: AnyConnection>(connection: &C) {
-    treasury::print_storage(connection);
-    aleph::print_storage(connection);
-    elections::print_storage(connection);
+/// Balances do not fit into JSON numbers, so they are written as strings.
+fn balance_json(balance: Balance) -> Value {
+    Value::String(balance.to_string())
+}
+
+pub(crate) fn try_print_storages<C: AnyConnection>(connection: &C) -> Result<()> {
+    treasury::print_storage(connection)?;
+    aleph::print_storage(connection)?;
+    elections::print_storage(connection)?;
+    staking::print_storage(connection)?;
+    session::print_storage(connection)
+}
+
+pub(crate) fn try_storages_json<C: AnyConnection>(connection: &C) -> Result<Value> {
+    Ok(json!({
+        "Aleph": aleph::storage_json(connection)?,
+        "Elections": elections::storage_json(connection)?,
+        "Treasury": treasury::storage_json(connection)?,
+        "Staking": staking::storage_json(connection)?,
+        "Session": session::storage_json(connection)?,
+    }))
+}
+
+/// Blocking wrapper for `asynchronous::print_storages`.
+pub fn print_storages<C: AnyConnection>(connection: &C) -> Result<()> {
+    block_on(asynchronous::print_storages(connection))
+}
+
+/// Blocking wrapper for `asynchronous::storages_json`.
+pub fn storages_json<C: AnyConnection>(connection: &C) -> Result<Value> {
+    block_on(asynchronous::storages_json(connection))
 }
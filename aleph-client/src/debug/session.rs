@@ -0,0 +1,75 @@
+use serde_json::{json, Value};
+use substrate_api_client::AccountId;
+
+use crate::{
+    aleph_zero::session,
+    debug::{element_prompt, entry_prompt, pallet_prompt},
+    error::Result,
+    read_typed_storage, AnyConnection,
+};
+
+struct SessionStorage {
+    current_index: u32,
+    validators: Vec<AccountId>,
+    queued_changed: bool,
+    disabled_validators: Vec<u32>,
+}
+
+fn read_storage<C: AnyConnection>(connection: &C) -> Result<SessionStorage> {
+    Ok(SessionStorage {
+        current_index: read_typed_storage(connection, &session::storage::CurrentIndex)?
+            .unwrap_or_default(),
+        validators: read_typed_storage(connection, &session::storage::Validators)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|validator| AccountId::from(<[u8; 32]>::from(validator)))
+            .collect(),
+        queued_changed: read_typed_storage(connection, &session::storage::QueuedChanged)?
+            .unwrap_or_default(),
+        disabled_validators: read_typed_storage(connection, &session::storage::DisabledValidators)?
+            .unwrap_or_default(),
+    })
+}
+
+pub fn print_storage<C: AnyConnection>(connection: &C) -> Result<()> {
+    let storage = read_storage(connection)?;
+
+    println!("{}", pallet_prompt("Session"));
+    println!(
+        "{}: {}",
+        entry_prompt("CurrentIndex"),
+        storage.current_index
+    );
+    println!(
+        "{}: {}",
+        entry_prompt("QueuedChanged"),
+        storage.queued_changed
+    );
+    println!(
+        "{}: {:?}",
+        entry_prompt("DisabledValidators"),
+        storage.disabled_validators
+    );
+    println!();
+    println!("{}", entry_prompt("Validators"));
+    for validator in storage.validators {
+        println!("{}", element_prompt(format!("\tValidator {}", validator)));
+    }
+    println!();
+    Ok(())
+}
+
+pub fn storage_json<C: AnyConnection>(connection: &C) -> Result<Value> {
+    let storage = read_storage(connection)?;
+
+    Ok(json!({
+        "CurrentIndex": storage.current_index,
+        "Validators": storage
+            .validators
+            .iter()
+            .map(|validator| validator.to_string())
+            .collect::<Vec<_>>(),
+        "QueuedChanged": storage.queued_changed,
+        "DisabledValidators": storage.disabled_validators,
+    }))
+}
@@ -0,0 +1,126 @@
+use serde_json::{json, Value};
+use substrate_api_client::{AccountId, Balance};
+
+use crate::{
+    aleph_zero::{runtime_types::pallet_staking::ActiveEraInfo, staking},
+    debug::{balance_json, element_prompt, entry_prompt, pallet_prompt},
+    error::Result,
+    read_typed_storage, AnyConnection,
+};
+
+struct StakingStorage {
+    current_era: Option<u32>,
+    active_era: Option<ActiveEraInfo>,
+    validator_count: u32,
+    minimum_validator_count: u32,
+    min_validator_bond: Balance,
+    min_nominator_bond: Balance,
+    counter_for_validators: u32,
+    counter_for_nominators: u32,
+    invulnerables: Vec<AccountId>,
+}
+
+fn read_storage<C: AnyConnection>(connection: &C) -> Result<StakingStorage> {
+    Ok(StakingStorage {
+        current_era: read_typed_storage(connection, &staking::storage::CurrentEra)?,
+        active_era: read_typed_storage(connection, &staking::storage::ActiveEra)?,
+        validator_count: read_typed_storage(connection, &staking::storage::ValidatorCount)?
+            .unwrap_or_default(),
+        minimum_validator_count: read_typed_storage(
+            connection,
+            &staking::storage::MinimumValidatorCount,
+        )?
+        .unwrap_or_default(),
+        min_validator_bond: read_typed_storage(connection, &staking::storage::MinValidatorBond)?
+            .unwrap_or_default(),
+        min_nominator_bond: read_typed_storage(connection, &staking::storage::MinNominatorBond)?
+            .unwrap_or_default(),
+        counter_for_validators: read_typed_storage(
+            connection,
+            &staking::storage::CounterForValidators,
+        )?
+        .unwrap_or_default(),
+        counter_for_nominators: read_typed_storage(
+            connection,
+            &staking::storage::CounterForNominators,
+        )?
+        .unwrap_or_default(),
+        invulnerables: read_typed_storage(connection, &staking::storage::Invulnerables)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|account| AccountId::from(<[u8; 32]>::from(account)))
+            .collect(),
+    })
+}
+
+pub fn print_storage<C: AnyConnection>(connection: &C) -> Result<()> {
+    let storage = read_storage(connection)?;
+
+    println!("{}", pallet_prompt("Staking"));
+    println!("{}: {:?}", entry_prompt("CurrentEra"), storage.current_era);
+    println!(
+        "{}: {:?}",
+        entry_prompt("ActiveEra"),
+        storage.active_era.map(|era| era.index)
+    );
+    println!(
+        "{}: {}",
+        entry_prompt("ValidatorCount"),
+        storage.validator_count
+    );
+    println!(
+        "{}: {}",
+        entry_prompt("MinimumValidatorCount"),
+        storage.minimum_validator_count
+    );
+    println!(
+        "{}: {}",
+        entry_prompt("MinValidatorBond"),
+        storage.min_validator_bond
+    );
+    println!(
+        "{}: {}",
+        entry_prompt("MinNominatorBond"),
+        storage.min_nominator_bond
+    );
+    println!(
+        "{}: {}",
+        entry_prompt("CounterForValidators"),
+        storage.counter_for_validators
+    );
+    println!(
+        "{}: {}",
+        entry_prompt("CounterForNominators"),
+        storage.counter_for_nominators
+    );
+    println!();
+    println!("{}", entry_prompt("Invulnerables"));
+    for account in storage.invulnerables {
+        println!("{}", element_prompt(format!("\tInvulnerable {}", account)));
+    }
+    println!();
+    Ok(())
+}
+
+pub fn storage_json<C: AnyConnection>(connection: &C) -> Result<Value> {
+    let storage = read_storage(connection)?;
+
+    Ok(json!({
+        "CurrentEra": storage.current_era,
+        "ActiveEra": storage.active_era.map(|era| json!({
+            "index": era.index,
+            "start": era.start,
+        })),
+        "ValidatorCount": storage.validator_count,
+        "MinimumValidatorCount": storage.minimum_validator_count,
+        "MinValidatorBond": balance_json(storage.min_validator_bond),
+        "MinNominatorBond": balance_json(storage.min_nominator_bond),
+        "CounterForValidators": storage.counter_for_validators,
+        "CounterForNominators": storage.counter_for_nominators,
+        "Invulnerables": storage
+            .invulnerables
+            .iter()
+            .map(|account| account.to_string())
+            .collect::<Vec<_>>(),
+    }))
+}
@@ -1,29 +1,70 @@
 use crate::{
-    debug::{element_prompt, entry_prompt, pallet_prompt},
+    debug::{balance_json, element_prompt, entry_prompt, pallet_prompt},
+    error::Result,
     AnyConnection,
 };
+use codec::Decode;
 use log::trace;
-use pallet_treasury::{Proposal, ProposalIndex};
+use pallet_treasury::ProposalIndex;
+use serde_json::{json, Value};
 use sp_core::crypto::AccountId32;
 use substrate_api_client::Balance;
 
-pub fn print_storage<C: AnyConnection>(connection: &C) {
+/// The same as `pallet_treasury::Proposal`, whose fields are private.
+#[derive(Debug, Decode)]
+struct Proposal {
+    proposer: AccountId32,
+    value: Balance,
+    beneficiary: AccountId32,
+    bond: Balance,
+}
+
+struct TreasuryStorage {
+    proposal_count: u32,
+    approvals: Vec<ProposalIndex>,
+    proposals: Vec<(ProposalIndex, Proposal)>,
+}
+
+fn read_storage<C: AnyConnection>(connection: &C) -> Result<TreasuryStorage> {
     let connection = connection.as_connection();
     let proposal_count: u32 = connection
-        .get_storage_value("Treasury", "ProposalCount", None)
-        .expect("Api call should succeed")
+        .get_storage_value("Treasury", "ProposalCount", None)?
         .unwrap_or(0);
 
     let approvals: Vec<ProposalIndex> = connection
-        .get_storage_value("Treasury", "Approvals", None)
-        .expect("Api call should succeed")
+        .get_storage_value("Treasury", "Approvals", None)?
         .unwrap_or_default();
 
+    let mut proposals = Vec::new();
+    for x in 0..=proposal_count {
+        let p: Option<Proposal> = connection.get_storage_map("Treasury", "Proposals", x, None)?;
+
+        if let Some(p) = p {
+            proposals.push((x, p));
+        } else {
+            trace!("No proposal with id {:?} in the storage", x)
+        }
+    }
+
+    Ok(TreasuryStorage {
+        proposal_count,
+        approvals,
+        proposals,
+    })
+}
+
+pub fn print_storage<C: AnyConnection>(connection: &C) -> Result<()> {
+    let storage = read_storage(connection)?;
+
     println!("{}", pallet_prompt("Treasury"));
-    println!("{}: {}", entry_prompt("ProposalCount"), proposal_count);
+    println!(
+        "{}: {}",
+        entry_prompt("ProposalCount"),
+        storage.proposal_count
+    );
     println!();
     println!("{}", entry_prompt("Approvals"));
-    for x in approvals {
+    for x in storage.approvals {
         println!(
             "{}",
             element_prompt(format!("Proposal id {} was approved ", x))
@@ -31,16 +72,33 @@ pub fn print_storage<C: AnyConnection>(connection: &C) {
     }
     println!();
     println!("{}", entry_prompt("Proposals"));
-    for x in 0..=proposal_count {
-        let p: Option<Proposal<AccountId32, Balance>> = connection
-            .get_storage_map("Treasury", "Proposals", x, None)
-            .unwrap();
-
-        if let Some(p) = p {
-            println!("{}", element_prompt(format!("\tProposalId {}: {:?}", x, p)));
-        } else {
-            trace!("No proposal with id {:?} in the storage", x)
-        }
+    for (x, p) in storage.proposals {
+        println!("{}", element_prompt(format!("\tProposalId {}: {:?}", x, p)));
     }
     println!();
+    Ok(())
+}
+
+pub fn storage_json<C: AnyConnection>(connection: &C) -> Result<Value> {
+    let storage = read_storage(connection)?;
+
+    let proposals = storage
+        .proposals
+        .into_iter()
+        .map(|(x, p)| {
+            json!({
+                "id": x,
+                "proposer": p.proposer.to_string(),
+                "value": balance_json(p.value),
+                "beneficiary": p.beneficiary.to_string(),
+                "bond": balance_json(p.bond),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(json!({
+        "ProposalCount": storage.proposal_count,
+        "Approvals": storage.approvals,
+        "Proposals": proposals,
+    }))
 }
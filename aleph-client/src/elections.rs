@@ -0,0 +1,14 @@
+use substrate_api_client::AccountId;
+
+use crate::{aleph_zero::elections, error::Result, read_typed_storage, AnyConnection};
+
+/// Accounts that are elected as validators at the start of every era.
+pub fn get_members<C: AnyConnection>(connection: &C) -> Result<Vec<AccountId>> {
+    Ok(
+        read_typed_storage(connection, &elections::storage::Members)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|member| AccountId::from(<[u8; 32]>::from(member)))
+            .collect(),
+    )
+}
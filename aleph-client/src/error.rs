@@ -0,0 +1,62 @@
+use sp_core::H256;
+use substrate_api_client::std::error::Error as ApiClientError;
+use thiserror::Error;
+
+use crate::DispatchFailure;
+
+/// Gathers all errors that may be returned by the fallible (in particular, asynchronous) part of
+/// this crate.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("📡❌ Cannot connect to the node at {0}: {1}.")]
+    Connection(String, String),
+    #[error("📡❌ Api client failure: {0}.")]
+    Api(String),
+    #[error("📡❌ Rpc call failed: {0}.")]
+    Rpc(String),
+    #[error("📡❌ There is no value under {0}::{1} in the storage.")]
+    MissingStorage(&'static str, &'static str),
+    #[error("📡❌ Cannot decode {0}.")]
+    Decode(String),
+    #[error("📡❌ Could not get tx/block hash.")]
+    NoHash,
+    #[error("📡❌ Transaction could not be submitted: {0}.")]
+    Submission(String),
+    #[error("📡❌ Transaction failed: {0}.")]
+    Dispatch(DispatchFailure),
+    #[error("📡❌ Transaction has been dropped from the pool.")]
+    TransactionDropped,
+    #[error("📡❌ Transaction is invalid.")]
+    TransactionInvalid,
+    #[error("📡❌ None of the nodes in the pool is healthy.")]
+    NoHealthyEndpoint,
+    #[error("📡❌ There is no finalized block with number {0}.")]
+    MissingBlock(u32),
+    #[error("📡❌ Transaction {0:?} is not in the block.")]
+    MissingTransaction(H256),
+    #[error("📡❌ Transaction has not emitted {0}::{1} event.")]
+    MissingEvent(&'static str, &'static str),
+    #[error("📡❌ Event sink failure: {0}.")]
+    Sink(String),
+    #[error("📡❌ Subscription has been closed by the node.")]
+    SubscriptionClosed,
+    #[error("📡❌ Cannot create a key pair from the provided seed.")]
+    InvalidSeed,
+    #[error(
+        "📡❌ Client was built for spec_version {0} and transaction_version {1}, \
+        but the node runs {2} and {3}."
+    )]
+    RuntimeVersionMismatch(u32, u32, u32, u32),
+    #[error("📡❌ Background task has been dropped before completion.")]
+    Cancelled,
+}
+
+/// `substrate-api-client` errors are not guaranteed to be `Send`, so we keep only their
+/// description.
+impl From<ApiClientError> for Error {
+    fn from(e: ApiClientError) -> Self {
+        Error::Api(format!("{:?}", e))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -1,4 +1,7 @@
-use crate::AnyConnection;
+use crate::{
+    error::{Error, Result},
+    AnyConnection,
+};
 use codec::Encode;
 use substrate_api_client::{Balance, UncheckedExtrinsicV4};
 
@@ -12,32 +15,33 @@ pub struct FeeInfo {
 pub fn get_tx_fee_info<C: AnyConnection, Call: Encode>(
     connection: &C,
     tx: &UncheckedExtrinsicV4<Call>,
-) -> FeeInfo {
+) -> Result<FeeInfo> {
     let unadjusted_weight = connection
-        .as_connection()
-        .get_payment_info(&tx.hex_encode(), None)
-        .expect("Should access payment info")
-        .expect("Payment info should be present")
+        .read(|connection| connection.get_payment_info(&tx.hex_encode(), None))?
+        .ok_or_else(|| Error::Rpc(String::from("payment info is not available")))?
         .weight as Balance;
 
     let fee = connection
-        .as_connection()
-        .get_fee_details(&tx.hex_encode(), None)
-        .expect("Should access fee details")
-        .expect("Should read fee details");
-    let inclusion_fee = fee.inclusion_fee.expect("Transaction should be payable");
+        .read(|connection| connection.get_fee_details(&tx.hex_encode(), None))?
+        .ok_or_else(|| Error::Rpc(String::from("fee details are not available")))?;
+    let inclusion_fee = fee
+        .inclusion_fee
+        .ok_or_else(|| Error::Rpc(String::from("transaction is not payable")))?;
 
-    FeeInfo {
+    Ok(FeeInfo {
         fee_without_weight: inclusion_fee.base_fee + inclusion_fee.len_fee + fee.tip,
         unadjusted_weight,
         adjusted_weight: inclusion_fee.adjusted_weight_fee,
-    }
+    })
 }
 
-pub fn get_next_fee_multiplier<C: AnyConnection>(connection: &C) -> u128 {
+pub fn get_next_fee_multiplier<C: AnyConnection>(connection: &C) -> Result<u128> {
     connection
-        .as_connection()
-        .get_storage_value("TransactionPayment", "NextFeeMultiplier", None)
-        .expect("Should access storage")
-        .expect("Key `NextFeeMultiplier` should be present in storage")
+        .read(|connection| {
+            connection.get_storage_value("TransactionPayment", "NextFeeMultiplier", None)
+        })?
+        .ok_or(Error::MissingStorage(
+            "TransactionPayment",
+            "NextFeeMultiplier",
+        ))
 }
@@ -0,0 +1,522 @@
+//! Indexing events from finalized blocks.
+//!
+//! Unlike `wait_for_event`, which sees only events emitted after subscribing, `Indexer` walks all
+//! finalized blocks starting from a given height and hands their (fully decoded) events over to
+//! an `EventSink`. The sink keeps a checkpoint, so that indexing can be resumed after a restart.
+//!
+//! Events are decoded with the bindings generated for the current runtime. Blocks produced by an
+//! older runtime, whose events cannot be decoded that way, are recorded in the sink as skipped
+//! instead of stopping the indexer. Their events are still split with the metadata of the runtime
+//! that produced them and kept raw, so that they can be decoded later.
+
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use codec::Decode;
+use frame_metadata::RuntimeMetadataPrefixed;
+use futures::{lock::Mutex, StreamExt};
+use log::{info, warn};
+use sp_core::H256;
+use subxt::{
+    events,
+    rpc::{rpc_params, BlockNumber as RpcBlockNumber, ClientT},
+    sp_core::{Bytes, H256 as SubxtHash},
+    BasicError, Client, ClientBuilder, DefaultConfig, Metadata, Phase, RawEventDetails,
+};
+
+use crate::{
+    aleph_zero::Event,
+    ensure_protocol,
+    error::{Error, Result},
+    BlockNumber,
+};
+
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteSink;
+
+/// A single event together with its position in the chain.
+#[derive(Clone, Debug)]
+pub struct IndexedEvent {
+    pub block_number: BlockNumber,
+    /// Index of the event within the block.
+    pub index: u32,
+    /// Index of the extrinsic that emitted the event, `None` for block initialization and
+    /// finalization.
+    pub extrinsic: Option<u32>,
+    pub pallet: String,
+    pub variant: String,
+    pub event: Event,
+}
+
+/// All events emitted in a block.
+#[derive(Clone, Debug)]
+pub struct IndexedBlock {
+    pub number: BlockNumber,
+    pub hash: H256,
+    pub events: Vec<IndexedEvent>,
+}
+
+/// An event that cannot be decoded with the generated bindings, split off the other events of its
+/// block with the metadata of the runtime that emitted it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawEvent {
+    /// Index of the event within the block.
+    pub index: u32,
+    /// Index of the extrinsic that emitted the event, `None` for block initialization and
+    /// finalization.
+    pub extrinsic: Option<u32>,
+    pub pallet: String,
+    pub pallet_index: u8,
+    pub variant: String,
+    pub variant_index: u8,
+    /// SCALE encoded fields of the event.
+    pub data: Vec<u8>,
+}
+
+impl From<RawEventDetails> for RawEvent {
+    fn from(raw: RawEventDetails) -> Self {
+        Self {
+            index: raw.index,
+            extrinsic: extrinsic_index(&raw.phase),
+            pallet: raw.pallet,
+            pallet_index: raw.pallet_index,
+            variant: raw.variant,
+            variant_index: raw.variant_index,
+            data: raw.data.to_vec(),
+        }
+    }
+}
+
+/// A block whose events cannot be decoded with the generated bindings, most likely because it was
+/// produced by another runtime version.
+#[derive(Clone, Debug)]
+pub struct SkippedBlock {
+    pub number: BlockNumber,
+    pub hash: H256,
+    pub reason: String,
+    /// Events of the block as split by the metadata of its runtime. Empty if even that failed.
+    pub events: Vec<RawEvent>,
+}
+
+/// Storage for indexed events.
+pub trait EventSink {
+    /// Number of the last block whose events have been stored, if any.
+    fn checkpoint(&self) -> Result<Option<BlockNumber>>;
+
+    /// Stores all events of `block` and moves the checkpoint to it. Either both happen or none.
+    fn store(&mut self, block: &IndexedBlock) -> Result<()>;
+
+    /// Records that the events of `block` could not be decoded, keeps them raw and moves the
+    /// checkpoint to it. Either all happen or none.
+    fn skip(&mut self, block: &SkippedBlock) -> Result<()>;
+}
+
+/// A finalized block as fetched by a `BlockSource`.
+#[derive(Clone, Debug)]
+pub enum FetchedBlock {
+    Decoded(IndexedBlock),
+    Undecodable(SkippedBlock),
+}
+
+/// Where `Indexer` takes finalized blocks from.
+#[async_trait]
+pub trait BlockSource: Sync {
+    /// Number of the last finalized block.
+    async fn finalized(&self) -> Result<BlockNumber>;
+
+    /// The finalized block `number` together with its events.
+    async fn fetch(&self, number: BlockNumber) -> Result<FetchedBlock>;
+}
+
+/// `BlockSource` reading blocks from a node.
+pub struct NodeSource {
+    address: String,
+    client: Client<DefaultConfig>,
+    /// Clients using the metadata of other runtimes, by spec version. Built the first time a block
+    /// of such runtime cannot be decoded.
+    other_runtimes: Mutex<HashMap<u32, Arc<Client<DefaultConfig>>>>,
+}
+
+impl NodeSource {
+    /// Connects to the node at `address`.
+    pub async fn new(address: &str) -> Result<Self> {
+        let client = ClientBuilder::new()
+            .set_url(ensure_protocol(address))
+            .build()
+            .await
+            .map_err(|why| Error::Connection(address.to_string(), why.to_string()))?;
+        Ok(Self {
+            address: address.to_string(),
+            client,
+            other_runtimes: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// A client using the metadata of the runtime that produced the block with `hash`.
+    async fn client_at(&self, hash: SubxtHash) -> Result<Arc<Client<DefaultConfig>>> {
+        let spec_version = self
+            .client
+            .rpc()
+            .runtime_version(Some(hash))
+            .await
+            .map_err(rpc_error)?
+            .spec_version;
+        let mut clients = self.other_runtimes.lock().await;
+        if let Some(client) = clients.get(&spec_version) {
+            return Ok(client.clone());
+        }
+
+        let metadata: Bytes = self
+            .client
+            .rpc()
+            .client
+            .request("state_getMetadata", rpc_params![hash])
+            .await
+            .map_err(|why| Error::Rpc(why.to_string()))?;
+        let metadata = RuntimeMetadataPrefixed::decode(&mut &metadata[..])
+            .map_err(|why| Error::Decode(format!("metadata: {}", why)))
+            .and_then(|metadata| {
+                Metadata::try_from(metadata)
+                    .map_err(|why| Error::Decode(format!("metadata: {}", why)))
+            })?;
+        let client = ClientBuilder::new()
+            .set_url(ensure_protocol(&self.address))
+            .set_metadata(metadata)
+            .build()
+            .await
+            .map_err(|why| Error::Connection(self.address.clone(), why.to_string()))?;
+
+        info!(target: "aleph-client",
+            "Fetched metadata of runtime with spec version {}", spec_version);
+        let client = Arc::new(client);
+        clients.insert(spec_version, client.clone());
+        Ok(client)
+    }
+
+    /// Events of the block with `hash`, split with the metadata of the runtime that produced it.
+    async fn raw_events(&self, hash: SubxtHash) -> Result<Vec<RawEvent>> {
+        let client = self.client_at(hash).await?;
+        let block_events = events::at::<DefaultConfig, Event>(&client, hash)
+            .await
+            .map_err(rpc_error)?;
+        block_events
+            .iter_raw()
+            .map(|raw| raw.map(RawEvent::from))
+            .collect::<std::result::Result<_, BasicError>>()
+            .map_err(|why| Error::Decode(format!("events of block {}: {}", hash, why)))
+    }
+}
+
+#[async_trait]
+impl BlockSource for NodeSource {
+    async fn finalized(&self) -> Result<BlockNumber> {
+        let finalized_hash = self
+            .client
+            .rpc()
+            .finalized_head()
+            .await
+            .map_err(rpc_error)?;
+        Ok(self
+            .client
+            .rpc()
+            .header(Some(finalized_hash))
+            .await
+            .map_err(rpc_error)?
+            .ok_or_else(|| Error::Rpc(format!("No header for block {}", finalized_hash)))?
+            .number)
+    }
+
+    async fn fetch(&self, number: BlockNumber) -> Result<FetchedBlock> {
+        let block_hash = self
+            .client
+            .rpc()
+            .block_hash(Some(RpcBlockNumber::from(number)))
+            .await
+            .map_err(rpc_error)?
+            .ok_or(Error::MissingBlock(number))?;
+        let block_events = events::at::<DefaultConfig, Event>(&self.client, block_hash)
+            .await
+            .map_err(rpc_error)?;
+        let hash = H256::from(block_hash.to_fixed_bytes());
+
+        let events = block_events
+            .iter_raw()
+            .zip(block_events.iter())
+            .map(|(raw, decoded)| {
+                let (raw, decoded) = (raw?, decoded?);
+                Ok(IndexedEvent {
+                    block_number: number,
+                    index: decoded.index,
+                    extrinsic: extrinsic_index(&decoded.phase),
+                    pallet: raw.pallet,
+                    variant: raw.variant,
+                    event: decoded.event,
+                })
+            })
+            .collect::<std::result::Result<_, BasicError>>();
+
+        let why = match events {
+            Ok(events) => {
+                return Ok(FetchedBlock::Decoded(IndexedBlock {
+                    number,
+                    hash,
+                    events,
+                }))
+            }
+            Err(why) => why.to_string(),
+        };
+        let (reason, events) = match self.raw_events(block_hash).await {
+            Ok(events) => (why, events),
+            Err(Error::Decode(raw_why)) => (format!("{}; {}", why, raw_why), Vec::new()),
+            Err(e) => return Err(e),
+        };
+        Ok(FetchedBlock::Undecodable(SkippedBlock {
+            number,
+            hash,
+            reason,
+            events,
+        }))
+    }
+}
+
+/// Walks finalized blocks and passes their events to `sink`.
+pub struct Indexer<S: EventSink, B: BlockSource = NodeSource> {
+    source: B,
+    sink: S,
+    start: BlockNumber,
+}
+
+impl<S: EventSink> Indexer<S> {
+    /// Connects to the node at `address`. Unless `sink` has a checkpoint, indexing starts at
+    /// block `start`.
+    pub async fn new(address: &str, sink: S, start: BlockNumber) -> Result<Self> {
+        Ok(Self::with_source(
+            NodeSource::new(address).await?,
+            sink,
+            start,
+        ))
+    }
+
+    /// Indexes blocks as they get finalized. Returns only on error.
+    pub async fn run(&mut self) -> Result<()> {
+        let mut finalized_heads = self
+            .source
+            .client
+            .rpc()
+            .subscribe_finalized_blocks()
+            .await
+            .map_err(rpc_error)?;
+        self.catch_up().await?;
+
+        while let Some(header) = finalized_heads.next().await {
+            header.map_err(|why| Error::Rpc(why.to_string()))?;
+            self.catch_up().await?;
+        }
+        Err(Error::SubscriptionClosed)
+    }
+}
+
+impl<S: EventSink, B: BlockSource> Indexer<S, B> {
+    /// Indexes blocks taken from `source`. Unless `sink` has a checkpoint, indexing starts at
+    /// block `start`.
+    pub fn with_source(source: B, sink: S, start: BlockNumber) -> Self {
+        Self {
+            source,
+            sink,
+            start,
+        }
+    }
+
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    /// Indexes all blocks finalized so far. Returns the number of the last indexed block.
+    pub async fn catch_up(&mut self) -> Result<Option<BlockNumber>> {
+        let finalized = self.source.finalized().await?;
+
+        let mut next = self.next_block()?;
+        while next <= finalized {
+            match self.source.fetch(next).await? {
+                FetchedBlock::Decoded(block) => {
+                    self.sink.store(&block)?;
+                    info!(target: "aleph-client",
+                        "Indexed {} events from block {}", block.events.len(), next);
+                }
+                FetchedBlock::Undecodable(block) => {
+                    self.sink.skip(&block)?;
+                    warn!(target: "aleph-client",
+                        "Skipped block {} with {} undecodable events: {}",
+                        next, block.events.len(), block.reason);
+                }
+            }
+            next += 1;
+        }
+
+        self.sink.checkpoint()
+    }
+
+    fn next_block(&self) -> Result<BlockNumber> {
+        Ok(match self.sink.checkpoint()? {
+            Some(last) => last + 1,
+            None => self.start,
+        })
+    }
+}
+
+fn extrinsic_index(phase: &Phase) -> Option<u32> {
+    match phase {
+        Phase::ApplyExtrinsic(extrinsic) => Some(*extrinsic),
+        _ => None,
+    }
+}
+
+fn rpc_error(error: BasicError) -> Error {
+    Error::Rpc(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::aleph_zero::elections;
+
+    #[derive(Default)]
+    struct MockSource {
+        finalized: BlockNumber,
+        blocks: HashMap<BlockNumber, FetchedBlock>,
+    }
+
+    impl MockSource {
+        fn with_blocks(numbers: impl IntoIterator<Item = BlockNumber>) -> Self {
+            let mut source = Self::default();
+            for number in numbers {
+                source.add(FetchedBlock::Decoded(block(number)));
+            }
+            source
+        }
+
+        fn add(&mut self, block: FetchedBlock) {
+            let number = match &block {
+                FetchedBlock::Decoded(block) => block.number,
+                FetchedBlock::Undecodable(block) => block.number,
+            };
+            self.finalized = self.finalized.max(number);
+            self.blocks.insert(number, block);
+        }
+    }
+
+    #[async_trait]
+    impl BlockSource for MockSource {
+        async fn finalized(&self) -> Result<BlockNumber> {
+            Ok(self.finalized)
+        }
+
+        async fn fetch(&self, number: BlockNumber) -> Result<FetchedBlock> {
+            self.blocks
+                .get(&number)
+                .cloned()
+                .ok_or(Error::MissingBlock(number))
+        }
+    }
+
+    #[derive(Default)]
+    struct MemorySink {
+        checkpoint: Option<BlockNumber>,
+        stored: Vec<BlockNumber>,
+        skipped: Vec<BlockNumber>,
+    }
+
+    impl EventSink for MemorySink {
+        fn checkpoint(&self) -> Result<Option<BlockNumber>> {
+            Ok(self.checkpoint)
+        }
+
+        fn store(&mut self, block: &IndexedBlock) -> Result<()> {
+            self.stored.push(block.number);
+            self.checkpoint = Some(block.number);
+            Ok(())
+        }
+
+        fn skip(&mut self, block: &SkippedBlock) -> Result<()> {
+            self.skipped.push(block.number);
+            self.checkpoint = Some(block.number);
+            Ok(())
+        }
+    }
+
+    fn block(number: BlockNumber) -> IndexedBlock {
+        IndexedBlock {
+            number,
+            hash: H256::repeat_byte(number as u8),
+            events: vec![IndexedEvent {
+                block_number: number,
+                index: 0,
+                extrinsic: None,
+                pallet: String::from("Elections"),
+                variant: String::from("ChangeMembers"),
+                event: Event::Elections(elections::Event::ChangeMembers(vec![])),
+            }],
+        }
+    }
+
+    #[test]
+    fn given_empty_sink_when_catching_up_then_blocks_from_start_to_finalized_are_indexed() {
+        let mut indexer =
+            Indexer::with_source(MockSource::with_blocks(1..=5), MemorySink::default(), 3);
+
+        assert_eq!(block_on(indexer.catch_up()).unwrap(), Some(5));
+        assert_eq!(indexer.sink().stored, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn given_checkpoint_when_catching_up_then_indexing_resumes_after_it() {
+        let sink = MemorySink {
+            checkpoint: Some(4),
+            ..Default::default()
+        };
+        let mut indexer = Indexer::with_source(MockSource::with_blocks(1..=6), sink, 1);
+
+        assert_eq!(block_on(indexer.catch_up()).unwrap(), Some(6));
+        assert_eq!(indexer.sink().stored, vec![5, 6]);
+    }
+
+    #[test]
+    fn given_undecodable_block_when_catching_up_then_it_is_skipped_and_indexing_continues() {
+        let mut source = MockSource::with_blocks([1, 3]);
+        source.add(FetchedBlock::Undecodable(SkippedBlock {
+            number: 2,
+            hash: H256::repeat_byte(2),
+            reason: String::from("older runtime"),
+            events: vec![],
+        }));
+        let mut indexer = Indexer::with_source(source, MemorySink::default(), 1);
+
+        assert_eq!(block_on(indexer.catch_up()).unwrap(), Some(3));
+        assert_eq!(indexer.sink().stored, vec![1, 3]);
+        assert_eq!(indexer.sink().skipped, vec![2]);
+    }
+
+    #[test]
+    fn given_failing_fetch_when_catching_up_then_checkpoint_is_kept_and_next_run_resumes() {
+        let mut source = MockSource::with_blocks(1..=2);
+        source.finalized = 3;
+        let mut indexer = Indexer::with_source(source, MemorySink::default(), 1);
+
+        assert!(matches!(
+            block_on(indexer.catch_up()),
+            Err(Error::MissingBlock(3))
+        ));
+        assert_eq!(indexer.sink().checkpoint, Some(2));
+
+        indexer.source.add(FetchedBlock::Decoded(block(3)));
+        assert_eq!(block_on(indexer.catch_up()).unwrap(), Some(3));
+        assert_eq!(indexer.sink().stored, vec![1, 2, 3]);
+    }
+}
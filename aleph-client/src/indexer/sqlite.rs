@@ -0,0 +1,303 @@
+use std::path::Path;
+
+use codec::{Decode, Encode};
+use rusqlite::{params, Connection, OptionalExtension};
+use sp_core::H256;
+
+use crate::{
+    aleph_zero::Event,
+    error::{Error, Result},
+    indexer::{EventSink, IndexedBlock, IndexedEvent, RawEvent, SkippedBlock},
+    BlockNumber,
+};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS checkpoint (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    block_number INTEGER NOT NULL,
+    block_hash TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS events (
+    block_number INTEGER NOT NULL,
+    event_index INTEGER NOT NULL,
+    extrinsic_index INTEGER,
+    pallet TEXT NOT NULL,
+    variant TEXT NOT NULL,
+    encoded BLOB NOT NULL,
+    details TEXT NOT NULL,
+    PRIMARY KEY (block_number, event_index)
+);
+CREATE INDEX IF NOT EXISTS events_by_name ON events (pallet, variant);
+CREATE TABLE IF NOT EXISTS skipped_blocks (
+    block_number INTEGER PRIMARY KEY,
+    block_hash TEXT NOT NULL,
+    reason TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS raw_events (
+    block_number INTEGER NOT NULL,
+    event_index INTEGER NOT NULL,
+    extrinsic_index INTEGER,
+    pallet TEXT NOT NULL,
+    pallet_index INTEGER NOT NULL,
+    variant TEXT NOT NULL,
+    variant_index INTEGER NOT NULL,
+    data BLOB NOT NULL,
+    PRIMARY KEY (block_number, event_index)
+);
+";
+
+/// `EventSink` backed by an embedded SQLite database.
+///
+/// Every event is kept SCALE encoded (so that it can be decoded back) together with its debug
+/// representation, which is handy when querying the database by hand. Events of skipped blocks are
+/// kept only SCALE encoded, in a separate table.
+pub struct SqliteSink {
+    db: Connection,
+}
+
+impl SqliteSink {
+    /// Opens (or creates) the database at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Creates a database living only as long as the returned sink.
+    pub fn in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(db: Connection) -> Result<Self> {
+        db.execute_batch(SCHEMA)?;
+        Ok(Self { db })
+    }
+
+    /// All stored `pallet::variant` events, in the order they were emitted.
+    pub fn events(&self, pallet: &str, variant: &str) -> Result<Vec<IndexedEvent>> {
+        let mut statement = self.db.prepare(
+            "SELECT block_number, event_index, extrinsic_index, pallet, variant, encoded
+            FROM events WHERE pallet = ?1 AND variant = ?2
+            ORDER BY block_number, event_index",
+        )?;
+        let rows = statement.query_map(params![pallet, variant], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get::<_, Vec<u8>>(5)?,
+            ))
+        })?;
+
+        rows.map(|row| {
+            let (block_number, index, extrinsic, pallet, variant, encoded) = row?;
+            let event = Event::decode(&mut encoded.as_slice())
+                .map_err(|why| Error::Decode(format!("stored event: {}", why)))?;
+            Ok(IndexedEvent {
+                block_number,
+                index,
+                extrinsic,
+                pallet,
+                variant,
+                event,
+            })
+        })
+        .collect()
+    }
+
+    /// Numbers of the blocks whose events could not be decoded, together with the reasons.
+    pub fn skipped_blocks(&self) -> Result<Vec<(BlockNumber, String)>> {
+        let mut statement = self
+            .db
+            .prepare("SELECT block_number, reason FROM skipped_blocks ORDER BY block_number")?;
+        let rows = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Raw events of the skipped block `number`, in the order they were emitted.
+    pub fn raw_events(&self, number: BlockNumber) -> Result<Vec<RawEvent>> {
+        let mut statement = self.db.prepare(
+            "SELECT event_index, extrinsic_index, pallet, pallet_index, variant, variant_index, data
+            FROM raw_events WHERE block_number = ?1
+            ORDER BY event_index",
+        )?;
+        let rows = statement.query_map(params![number], |row| {
+            Ok(RawEvent {
+                index: row.get(0)?,
+                extrinsic: row.get(1)?,
+                pallet: row.get(2)?,
+                pallet_index: row.get(3)?,
+                variant: row.get(4)?,
+                variant_index: row.get(5)?,
+                data: row.get(6)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    fn move_checkpoint(
+        transaction: &rusqlite::Transaction,
+        number: BlockNumber,
+        hash: H256,
+    ) -> Result<()> {
+        transaction.execute(
+            "INSERT OR REPLACE INTO checkpoint (id, block_number, block_hash) VALUES (0, ?1, ?2)",
+            params![number, format!("{:?}", hash)],
+        )?;
+        Ok(())
+    }
+}
+
+impl EventSink for SqliteSink {
+    fn checkpoint(&self) -> Result<Option<BlockNumber>> {
+        Ok(self
+            .db
+            .query_row(
+                "SELECT block_number FROM checkpoint WHERE id = 0",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn store(&mut self, block: &IndexedBlock) -> Result<()> {
+        let transaction = self.db.transaction()?;
+        for event in &block.events {
+            transaction.execute(
+                "INSERT OR REPLACE INTO events
+                (block_number, event_index, extrinsic_index, pallet, variant, encoded, details)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    event.block_number,
+                    event.index,
+                    event.extrinsic,
+                    event.pallet,
+                    event.variant,
+                    event.event.encode(),
+                    format!("{:?}", event.event),
+                ],
+            )?;
+        }
+        Self::move_checkpoint(&transaction, block.number, block.hash)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn skip(&mut self, block: &SkippedBlock) -> Result<()> {
+        let transaction = self.db.transaction()?;
+        transaction.execute(
+            "INSERT OR REPLACE INTO skipped_blocks (block_number, block_hash, reason)
+            VALUES (?1, ?2, ?3)",
+            params![block.number, format!("{:?}", block.hash), block.reason],
+        )?;
+        for event in &block.events {
+            transaction.execute(
+                "INSERT OR REPLACE INTO raw_events
+                (block_number, event_index, extrinsic_index, pallet, pallet_index, variant,
+                variant_index, data)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    block.number,
+                    event.index,
+                    event.extrinsic,
+                    event.pallet,
+                    event.pallet_index,
+                    event.variant,
+                    event.variant_index,
+                    event.data,
+                ],
+            )?;
+        }
+        Self::move_checkpoint(&transaction, block.number, block.hash)?;
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(e: rusqlite::Error) -> Self {
+        Error::Sink(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aleph_zero::elections;
+
+    fn block_with_change_members(number: BlockNumber) -> IndexedBlock {
+        IndexedBlock {
+            number,
+            hash: H256::repeat_byte(number as u8),
+            events: vec![IndexedEvent {
+                block_number: number,
+                index: 0,
+                extrinsic: Some(1),
+                pallet: String::from("Elections"),
+                variant: String::from("ChangeMembers"),
+                event: Event::Elections(elections::Event::ChangeMembers(vec![])),
+            }],
+        }
+    }
+
+    #[test]
+    fn given_empty_database_when_reading_checkpoint_then_there_is_none() {
+        let sink = SqliteSink::in_memory().unwrap();
+
+        assert_eq!(sink.checkpoint().unwrap(), None);
+    }
+
+    #[test]
+    fn given_stored_blocks_when_reading_back_then_events_and_checkpoint_are_as_expected() {
+        let mut sink = SqliteSink::in_memory().unwrap();
+        let blocks = [block_with_change_members(7), block_with_change_members(8)];
+
+        for block in &blocks {
+            sink.store(block).unwrap();
+        }
+
+        assert_eq!(sink.checkpoint().unwrap(), Some(8));
+        let stored = sink.events("Elections", "ChangeMembers").unwrap();
+        assert_eq!(stored.len(), 2);
+        for (stored, block) in stored.iter().zip(blocks.iter()) {
+            let expected = &block.events[0];
+            assert_eq!(stored.block_number, expected.block_number);
+            assert_eq!(stored.extrinsic, expected.extrinsic);
+            assert_eq!(stored.event.encode(), expected.event.encode());
+        }
+        assert!(sink.events("Elections", "Other").unwrap().is_empty());
+    }
+
+    #[test]
+    fn given_skipped_block_when_reading_back_then_it_is_listed_and_checkpoint_moves() {
+        let mut sink = SqliteSink::in_memory().unwrap();
+
+        let raw_event = RawEvent {
+            index: 0,
+            extrinsic: Some(2),
+            pallet: String::from("Elections"),
+            pallet_index: 11,
+            variant: String::from("ChangeMembers"),
+            variant_index: 0,
+            data: vec![0],
+        };
+
+        sink.store(&block_with_change_members(7)).unwrap();
+        sink.skip(&SkippedBlock {
+            number: 8,
+            hash: H256::repeat_byte(8),
+            reason: String::from("older runtime"),
+            events: vec![raw_event.clone()],
+        })
+        .unwrap();
+
+        assert_eq!(sink.checkpoint().unwrap(), Some(8));
+        assert_eq!(
+            sink.skipped_blocks().unwrap(),
+            vec![(8, String::from("older runtime"))]
+        );
+        assert_eq!(sink.raw_events(8).unwrap(), vec![raw_event]);
+        assert!(sink.raw_events(7).unwrap().is_empty());
+        assert_eq!(sink.events("Elections", "ChangeMembers").unwrap().len(), 1);
+    }
+}
@@ -1,39 +1,66 @@
-use std::{thread::sleep, time::Duration};
+use std::{fmt::Debug, thread::sleep, time::Duration};
 
 use codec::Encode;
-use log::{info, warn};
+use futures::executor::block_on;
+use log::warn;
 use sp_core::{sr25519, storage::StorageKey, Pair, H256};
 use sp_runtime::{generic::Header as GenericHeader, traits::BlakeTwo256};
 pub use substrate_api_client;
 use substrate_api_client::{
-    rpc::ws_client::WsRpcClient, std::error::Error, AccountId, Api, ApiResult, RpcClient,
-    UncheckedExtrinsicV4, XtStatus,
+    rpc::ws_client::WsRpcClient, AccountId, Api, RpcClient, UncheckedExtrinsicV4, XtStatus,
 };
+use typed::compare_runtime_version;
 
 pub use account::{get_free_balance, locks};
-pub use debug::print_storages;
+pub use asynchronous::keypair_from_string as try_keypair_from_string;
+pub use debug::{print_storages, storages_json};
+pub use elections::get_members as get_elections_members;
+pub use error::Error;
 pub use fee::{get_next_fee_multiplier, get_tx_fee_info, FeeInfo};
 pub use multisig::{
     compute_call_hash, perform_multisig_with_threshold_1, MultisigError, MultisigParty,
     SignatureAggregation,
 };
-pub use rpc::{rotate_keys, rotate_keys_raw_result, state_query_storage_at};
+pub use nomination_pools::{
+    bond_extra as pools_bond_extra, claim_payout as pools_claim_payout, create as pools_create,
+    join as pools_join, last_pool_id as pools_last_pool_id, member as pools_member,
+    nominate as pools_nominate, unbond as pools_unbond,
+    withdraw_unbonded as pools_withdraw_unbonded, Member as PoolMember, PoolId,
+};
+pub use pool::{ConnectionPool, HealthLimits};
+pub use proxy::{add_proxy, proxy_call, remove_proxy, ProxyType};
+pub use receipt::{tx_events, Receipts, TxEvent, TxReceipt};
+pub use rpc::{
+    has_session_keys, rotate_keys, rotate_keys_raw_result, state_get_keys, state_query_storage_at,
+    validators_performance,
+};
 pub use session::{
-    change_members, get_current as get_current_session, set_keys, wait_for as wait_for_session,
-    Keys as SessionKeys,
+    change_members, get_current as get_current_session, next_keys as get_next_session_keys,
+    performance as session_performance, set_keys, validators as get_session_validators,
+    wait_for as wait_for_session, Keys as SessionKeys,
 };
+pub use signer::Signer;
 pub use staking::{
     batch_bond as staking_batch_bond, batch_nominate as staking_batch_nominate,
     bond as staking_bond, bonded as staking_bonded, force_new_era as staking_force_new_era,
+    get_current_era, get_minimum_validator_bond, get_sessions_per_era, is_era_validator,
     ledger as staking_ledger, multi_bond as staking_multi_bond, nominate as staking_nominate,
     payout_stakers, payout_stakers_and_assert_locked_balance,
     set_staking_limits as staking_set_staking_limits, validate as staking_validate,
+    validator_prefs as staking_validator_prefs, wait_for_era_completion,
     wait_for_full_era_completion, wait_for_next_era,
 };
 pub use system::set_code;
+pub use transaction::{
+    DispatchFailure, NodeSubmitter, PoolStatus, Submitter, TransactionManager, TxStatus,
+};
 pub use transfer::{
     batch_transfer as balances_batch_transfer, transfer as balances_transfer, TransferTransaction,
 };
+pub use typed::{
+    check_runtime_version, compose_typed_call, read_typed_storage, read_typed_storage_at,
+    wait_for_typed_event, SPEC_VERSION, TRANSACTION_VERSION,
+};
 pub use vesting::{
     get_schedules, merge_schedules, vest, vest_other, vested_transfer, VestingError,
     VestingSchedule,
@@ -41,17 +68,41 @@ pub use vesting::{
 pub use waiting::{wait_for_event, wait_for_finalized_block};
 
 mod account;
+pub mod asynchronous;
 mod debug;
+mod elections;
+mod error;
 mod fee;
+pub mod indexer;
+#[cfg(test)]
+mod mock;
 mod multisig;
+mod nomination_pools;
+mod pool;
+mod proxy;
+mod receipt;
 mod rpc;
 mod session;
+mod signer;
 mod staking;
 mod system;
+mod transaction;
 mod transfer;
+mod typed;
 mod vesting;
 mod waiting;
 
+/// Calls, storage items and events generated from the metadata snapshot of `aleph_runtime`.
+///
+/// The snapshot is refreshed with `scripts/update_aleph_client_metadata.sh` and checked against the
+/// current runtime in CI. Connecting to a node that runs another runtime version fails with
+/// `Error::RuntimeVersionMismatch`.
+#[subxt::subxt(
+    runtime_metadata_path = "metadata/aleph_runtime.scale",
+    generated_type_derives = "Clone"
+)]
+pub mod aleph_zero {}
+
 pub trait FromStr: Sized {
     type Err;
 
@@ -78,6 +129,25 @@ pub type Connection = Api<KeyPair, WsRpcClient>;
 /// information for type inferring required for `Into<Connection>`.
 pub trait AnyConnection: Clone {
     fn as_connection(&self) -> Connection;
+
+    /// Like `as_connection`, but fails instead of panicking when there is no connection to give.
+    fn try_as_connection(&self) -> error::Result<Connection> {
+        Ok(self.as_connection())
+    }
+
+    /// Runs the read-only query `f`. Connections to several nodes retry it on another node if it
+    /// fails, so `f` may be called more than once.
+    fn read<T, E: Into<error::Error>>(
+        &self,
+        f: impl Fn(&Connection) -> Result<T, E>,
+    ) -> error::Result<T> {
+        f(&self.try_as_connection()?).map_err(Into::into)
+    }
+
+    /// Where the transactions submitted through this connection are recorded, if anywhere.
+    fn receipts(&self) -> Option<Receipts> {
+        None
+    }
 }
 
 impl AnyConnection for Connection {
@@ -86,20 +156,22 @@ impl AnyConnection for Connection {
     }
 }
 
-/// A connection that is signed.
+/// A connection that is signed by `S`.
 #[derive(Clone)]
-pub struct SignedConnection {
+pub struct SignedConnection<S: Signer = KeyPair> {
     inner: Connection,
-    signer: KeyPair,
+    signer: S,
+    receipts: Option<Receipts>,
 }
 
 impl SignedConnection {
-    pub fn new(address: &str, signer: KeyPair) -> Self {
-        let unsigned = create_connection(address);
-        Self {
+    pub fn new(address: &str, signer: KeyPair) -> error::Result<Self> {
+        let unsigned = create_connection(address)?;
+        Ok(Self {
             inner: unsigned.set_signer(signer.clone()),
             signer,
-        }
+            receipts: None,
+        })
     }
 
     /// Semantically equivalent to `connection.set_signer(signer)`.
@@ -107,19 +179,51 @@ impl SignedConnection {
         Self {
             inner: connection.as_connection().set_signer(signer.clone()),
             signer,
+            receipts: connection.receipts(),
+        }
+    }
+}
+
+impl<S: Signer> SignedConnection<S> {
+    /// Signs transactions composed for `connection` with `signer`.
+    ///
+    /// Unlike with `from_any_connection`, the underlying `Connection` stays unsigned: only
+    /// the transactions composed by the functions of this crate (e.g. with `compose_typed_call`)
+    /// are signed by `signer`, macros like `compose_extrinsic!` cannot be used.
+    pub fn with_signer<C: AnyConnection>(connection: C, signer: S) -> Self {
+        Self {
+            inner: connection.as_connection(),
+            signer,
+            receipts: connection.receipts(),
         }
     }
 
-    /// A signer corresponding to `self.inner`.
-    pub fn signer(&self) -> KeyPair {
+    /// Records the transactions submitted through this connection (and the connections created
+    /// from it) in `receipts`.
+    pub fn with_receipts(mut self, receipts: Receipts) -> Self {
+        self.receipts = Some(receipts);
+        self
+    }
+
+    /// The signer of the transactions sent through this connection.
+    pub fn signer(&self) -> S {
         self.signer.clone()
     }
+
+    /// The account of the signer.
+    pub fn account_id(&self) -> AccountId {
+        self.signer.account_id()
+    }
 }
 
-impl AnyConnection for SignedConnection {
+impl<S: Signer> AnyConnection for SignedConnection<S> {
     fn as_connection(&self) -> Connection {
         self.inner.clone()
     }
+
+    fn receipts(&self) -> Option<Receipts> {
+        self.receipts.clone()
+    }
 }
 
 /// We can always try casting `AnyConnection` to `SignedConnection`, which fails if it is not
@@ -142,37 +246,43 @@ impl TryFrom<Connection> for SignedConnection {
 /// storage), there is no guarantee that in fact the signer has sudo access. Hence, effectively it
 /// is just a type wrapper requiring explicit casting.
 #[derive(Clone)]
-pub struct RootConnection {
-    inner: SignedConnection,
+pub struct RootConnection<S: Signer = KeyPair> {
+    inner: SignedConnection<S>,
 }
 
 impl RootConnection {
-    pub fn new(address: &str, root: KeyPair) -> Self {
-        Self {
-            inner: SignedConnection::new(address, root),
-        }
+    pub fn new(address: &str, root: KeyPair) -> error::Result<Self> {
+        Ok(Self {
+            inner: SignedConnection::new(address, root)?,
+        })
     }
+}
 
+impl<S: Signer> RootConnection<S> {
     /// A direct casting is often more handy than a generic `.into()`.
-    pub fn as_signed(&self) -> SignedConnection {
+    pub fn as_signed(&self) -> SignedConnection<S> {
         self.inner.clone()
     }
 }
 
-impl From<SignedConnection> for RootConnection {
-    fn from(signed: SignedConnection) -> Self {
+impl<S: Signer> From<SignedConnection<S>> for RootConnection<S> {
+    fn from(signed: SignedConnection<S>) -> Self {
         Self { inner: signed }
     }
 }
 
-impl AnyConnection for RootConnection {
+impl<S: Signer> AnyConnection for RootConnection<S> {
     fn as_connection(&self) -> Connection {
         self.as_signed().as_connection()
     }
+
+    fn receipts(&self) -> Option<Receipts> {
+        self.inner.receipts()
+    }
 }
 
-pub fn create_connection(address: &str) -> Connection {
-    create_custom_connection(address).expect("Connection should be created")
+pub fn create_connection(address: &str) -> error::Result<Connection> {
+    create_custom_connection(address)
 }
 
 enum Protocol {
@@ -205,13 +315,28 @@ fn ensure_protocol(address: &str) -> String {
     format!("{}{}", Protocol::default().to_string(), address)
 }
 
+/// Connects to the node at `address`, retrying every second until the node responds.
+///
+/// Fails if `address` cannot be turned into a client or if the node runs another runtime version
+/// than the bindings in `aleph_zero` were generated for.
 pub fn create_custom_connection<Client: FromStr + RpcClient>(
     address: &str,
-) -> Result<Api<sr25519::Pair, Client>, <Client as FromStr>::Err> {
+) -> error::Result<Api<sr25519::Pair, Client>>
+where
+    <Client as FromStr>::Err: Debug,
+{
+    let address = ensure_protocol(address);
     loop {
-        let client = Client::from_str(&ensure_protocol(address))?;
+        let client = Client::from_str(&address)
+            .map_err(|e| Error::Connection(address.clone(), format!("{:?}", e)))?;
         match Api::<sr25519::Pair, _>::new(client) {
-            Ok(api) => return Ok(api),
+            Ok(api) => {
+                compare_runtime_version(
+                    api.runtime_version.spec_version,
+                    api.runtime_version.transaction_version,
+                )?;
+                return Ok(api);
+            }
             Err(why) => {
                 warn!(
                     "[+] Can't create_connection because {:?}, will try again in 1s",
@@ -223,16 +348,6 @@ pub fn create_custom_connection<Client: FromStr + RpcClient>(
     }
 }
 
-/// `panic`able utility wrapper for `try_send_xt`.
-pub fn send_xt<T: Encode, C: AnyConnection>(
-    connection: &C,
-    xt: UncheckedExtrinsicV4<T>,
-    xt_name: Option<&'static str>,
-    xt_status: XtStatus,
-) -> Option<H256> {
-    try_send_xt(connection, xt, xt_name, xt_status).expect("Should manage to send extrinsic")
-}
-
 /// Sends transaction `xt` using `connection`.
 ///
 /// If `tx_status` is either `Finalized` or `InBlock`, additionally returns hash of the containing
@@ -244,31 +359,17 @@ pub fn try_send_xt<T: Encode, C: AnyConnection>(
     xt: UncheckedExtrinsicV4<T>,
     xt_name: Option<&'static str>,
     xt_status: XtStatus,
-) -> ApiResult<Option<H256>> {
-    let hash = connection
-        .as_connection()
-        .send_extrinsic(xt.hex_encode(), xt_status)?
-        .ok_or_else(|| Error::Other(String::from("Could not get tx/block hash").into()))?;
-
-    match xt_status {
-        XtStatus::Finalized | XtStatus::InBlock => {
-            info!(target: "aleph-client",
-                "Transaction `{}` was included in block with hash {}.",
-                xt_name.unwrap_or_default(), hash);
-            Ok(Some(hash))
-        }
-        // Other variants either do not return (see https://github.com/scs/substrate-api-client/issues/175)
-        // or return xt hash, which is kinda useless here.
-        _ => Ok(None),
-    }
+) -> error::Result<Option<H256>> {
+    block_on(asynchronous::submit(connection, xt, xt_name, xt_status))
 }
 
+/// `panic`able utility wrapper for `try_keypair_from_string`.
 pub fn keypair_from_string(seed: &str) -> KeyPair {
-    KeyPair::from_string(seed, None).expect("Can't create pair from seed value")
+    try_keypair_from_string(seed).expect("Can't create pair from seed value")
 }
 
 pub fn account_from_keypair(keypair: &KeyPair) -> AccountId {
-    AccountId::from(keypair.public())
+    keypair.account_id()
 }
 
 fn storage_key(module: &str, version: &str) -> [u8; 32] {
@@ -0,0 +1,95 @@
+//! A node answering RPC calls from memory, for testing code that needs a connection.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+use sp_core::H256;
+use substrate_api_client::{Api, ApiResult, RpcClient, XtStatus};
+
+use crate::{ensure_protocol, BlockNumber, FromStr, KeyPair, SPEC_VERSION, TRANSACTION_VERSION};
+
+const METADATA: &[u8] = include_bytes!("../metadata/aleph_runtime.scale");
+
+/// Best and finalized block of every mocked node that is up, by address.
+static NODES: Lazy<Mutex<HashMap<String, (BlockNumber, BlockNumber)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub(crate) fn set_node(address: &str, heights: Option<(BlockNumber, BlockNumber)>) {
+    let mut nodes = NODES.lock().unwrap();
+    match heights {
+        Some(heights) => nodes.insert(ensure_protocol(address), heights),
+        None => nodes.remove(&ensure_protocol(address)),
+    };
+}
+
+/// Answers the requests made by `Api::new` and by the health checks as the node at `address`
+/// would. Once the node is down, every request returns `null`.
+pub(crate) struct MockClient {
+    address: String,
+}
+
+impl FromStr for MockClient {
+    type Err = String;
+
+    fn from_str(address: &str) -> std::result::Result<Self, Self::Err> {
+        match NODES.lock().unwrap().contains_key(address) {
+            true => Ok(Self {
+                address: address.to_string(),
+            }),
+            false => Err(format!("{} is down", address)),
+        }
+    }
+}
+
+fn header(number: BlockNumber) -> Value {
+    json!({
+        "parentHash": H256::zero(),
+        "number": format!("{:#x}", number),
+        "stateRoot": H256::zero(),
+        "extrinsicsRoot": H256::zero(),
+        "digest": { "logs": [] },
+    })
+}
+
+impl RpcClient for MockClient {
+    fn get_request(&self, request: Value) -> ApiResult<String> {
+        let (best, finalized) = match NODES.lock().unwrap().get(&self.address) {
+            Some(heights) => *heights,
+            None => return Ok(String::from("null")),
+        };
+        let params = &request["params"];
+        let response = match request["method"].as_str().unwrap() {
+            "chain_getBlockHash" => json!(H256::zero()),
+            "state_getMetadata" => json!(format!("0x{}", hex::encode(METADATA))),
+            "state_getRuntimeVersion" => json!({
+                "specName": "aleph-node",
+                "implName": "aleph-node",
+                "authoringVersion": 1,
+                "specVersion": SPEC_VERSION,
+                "implVersion": 1,
+                "apis": [],
+                "transactionVersion": TRANSACTION_VERSION,
+                "stateVersion": 0,
+            }),
+            // The finalized head is identified by its number.
+            "chain_getFinalizedHead" => json!(H256::from_low_u64_be(finalized as u64)),
+            "chain_getHeader" => match params.get(0).and_then(Value::as_str) {
+                Some(hash) => header(hash.parse::<H256>().unwrap().to_low_u64_be() as u32),
+                None => header(best),
+            },
+            method => panic!("Unexpected request {}", method),
+        };
+        Ok(response.to_string())
+    }
+
+    fn send_extrinsic(&self, _: String, _: XtStatus) -> ApiResult<Option<H256>> {
+        Ok(None)
+    }
+}
+
+/// A connection to a mocked node at `address` started just for it.
+pub(crate) fn connect(address: &str) -> Api<KeyPair, MockClient> {
+    set_node(address, Some((1, 1)));
+    Api::new(MockClient::from_str(&ensure_protocol(address)).unwrap()).unwrap()
+}
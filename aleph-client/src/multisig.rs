@@ -1,18 +1,30 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use anyhow::{ensure, Result};
 use codec::{Decode, Encode};
 use log::{error, info};
-use sp_core::{blake2_256, crypto::AccountId32, Pair};
+use sp_core::{blake2_256, storage::StorageKey};
 use sp_runtime::traits::TrailingZeroInput;
-use substrate_api_client::{compose_extrinsic, XtStatus::Finalized};
+use substrate_api_client::XtStatus::Finalized;
+use subxt::{sp_core::crypto::AccountId32, StorageEntry, StorageKeyPrefix, WrapperKeepOpaque};
 use thiserror::Error;
 
 use primitives::Balance;
 
 use crate::{
-    account_from_keypair, try_send_xt, AccountId, AnyConnection, BlockNumber, KeyPair,
-    SignedConnection, UncheckedExtrinsicV4, H256,
+    aleph_zero::{
+        multisig::{
+            calls::{ApproveAsMulti, AsMulti, AsMultiThreshold1, CancelAsMulti},
+            storage::Multisigs,
+        },
+        runtime_types::{aleph_runtime::Call as RuntimeCall, pallet_multisig},
+    },
+    compose_typed_call,
+    rpc::state_get_keys,
+    try_send_xt,
+    typed::typed_account,
+    AccountId, AnyConnection, BlockNumber, KeyPair, SignedConnection, Signer, UncheckedExtrinsicV4,
+    H256,
 };
 
 /// `MAX_WEIGHT` is the extrinsic parameter specifying upperbound for executing approved call.
@@ -36,6 +48,8 @@ pub enum MultisigError {
     IncorrectMemberIndex,
     #[error("👪❌ There is no such member in the party.")]
     NoSuchMember,
+    #[error("👪❌ The key of this member is not known.")]
+    NoKeyForMember,
     #[error("👪❌ There is no entry for this multisig aggregation in the pallet storage.")]
     NoAggregationFound,
     #[error("👪❌ Trying to report approval for a different call that already registered.")]
@@ -48,32 +62,22 @@ type CallHash = [u8; 32];
 type Call = Vec<u8>;
 type Timepoint = pallet_multisig::Timepoint<BlockNumber>;
 
-type ApproveAsMultiCall = UncheckedExtrinsicV4<(
-    [u8; 2],           // call index
-    u16,               // threshold
-    Vec<AccountId32>,  // other signatories
-    Option<Timepoint>, // timepoint, `None` for initiating
-    CallHash,          // call hash
-    u64,               // max weight
-)>;
-
-type AsMultiCall = UncheckedExtrinsicV4<(
-    [u8; 2],           // call index
-    u16,               // threshold
-    Vec<AccountId32>,  // other signatories
-    Option<Timepoint>, // timepoint, `None` for initiating
-    Call,              // call
-    bool,              // whether to store call
-    u64,               // max weight
-)>;
-
-type CancelAsMultiCall = UncheckedExtrinsicV4<(
-    [u8; 2],          // call index
-    u16,              // threshold
-    Vec<AccountId32>, // other signatories
-    Timepoint,        // timepoint, `None` for initiating
-    CallHash,         // call hash
-)>;
+type ApproveAsMultiCall = UncheckedExtrinsicV4<([u8; 2], ApproveAsMulti)>;
+type AsMultiCall = UncheckedExtrinsicV4<([u8; 2], AsMulti)>;
+type CancelAsMultiCall = UncheckedExtrinsicV4<([u8; 2], CancelAsMulti)>;
+
+/// `timepoint` in the form used by the generated calls.
+fn typed_timepoint(timepoint: Timepoint) -> pallet_multisig::Timepoint<BlockNumber> {
+    pallet_multisig::Timepoint {
+        height: timepoint.height,
+        index: timepoint.index,
+    }
+}
+
+/// `accounts` in the form used by the generated calls.
+fn typed_accounts(accounts: &[AccountId]) -> Vec<AccountId32> {
+    accounts.iter().map(typed_account).collect()
+}
 
 pub fn compute_call_hash<CallDetails: Encode>(
     call: &UncheckedExtrinsicV4<CallDetails>,
@@ -121,34 +125,55 @@ impl SignatureAggregation {
     pub fn num_of_approvals(&self) -> usize {
         self.approvers.len()
     }
+
+    /// Members who have already approved the call.
+    pub fn get_approvers(&self) -> &HashSet<AccountId> {
+        &self.approvers
+    }
+
+    pub fn get_call_hash(&self) -> CallHash {
+        self.call_hash
+    }
+
+    pub fn get_timepoint(&self) -> Timepoint {
+        self.timepoint
+    }
 }
 
 /// `MultisigParty` is representing a multiparty entity constructed from
 /// a group of accounts (`members`) and a threshold (`threshold`).
-pub struct MultisigParty {
+pub struct MultisigParty<S: Signer = KeyPair> {
     /// Derived multiparty account (public key).
     account: AccountId,
     /// *Sorted* collection of members.
-    members: Vec<KeyPair>,
+    members: Vec<AccountId>,
+    /// Keys of the members that we can sign with.
+    keys: HashMap<AccountId, S>,
     /// Minimum required approvals.
     threshold: u16,
 }
 
-impl MultisigParty {
+impl<S: Signer> MultisigParty<S> {
     /// Creates new party. `members` does *not* have to be already sorted. Also:
     /// - `members` must be of length between 2 and `pallet_multisig::MaxSignatories`;
     ///    since checking the upperbound is expensive, it is the caller's responsibility
     ///    to ensure it is not exceeded
     /// - `members` may contain duplicates, but they are ignored and not counted to the cardinality
     /// - `threshold` must be between 2 and `members.len()`
-    pub fn new(members: Vec<KeyPair>, threshold: u16) -> Result<Self> {
-        let mut members = members
-            .iter()
-            .map(|m| (m.clone(), account_from_keypair(m)))
-            .collect::<Vec<_>>();
+    pub fn new(members: Vec<S>, threshold: u16) -> Result<Self> {
+        let accounts = members.iter().map(Signer::account_id).collect();
+        let mut party = Self::from_accounts(accounts, threshold)?;
+        party.keys = members.into_iter().map(|m| (m.account_id(), m)).collect();
+        Ok(party)
+    }
 
-        members.sort_by_key(|(_, a)| a.clone());
-        members.dedup_by(|(_, a1), (_, a2)| a1 == a2);
+    /// Creates new party knowing only the accounts of the members. The same constraints as for
+    /// `new` apply.
+    ///
+    /// Such party cannot act until keys of (some of) its members are provided with `with_signer`.
+    pub fn from_accounts(mut members: Vec<AccountId>, threshold: u16) -> Result<Self> {
+        members.sort();
+        members.dedup();
 
         ensure!(2 <= members.len(), MultisigError::TooFewMembers);
         ensure!(
@@ -156,15 +181,22 @@ impl MultisigParty {
             MultisigError::IncorrectThreshold(members.len())
         );
 
-        let (keypairs, accounts): (Vec<_>, Vec<_>) = members.iter().cloned().unzip();
-        let account = Self::multi_account_id(&accounts, threshold);
         Ok(Self {
-            account,
-            members: keypairs,
+            account: Self::multi_account_id(&members, threshold),
+            members,
+            keys: HashMap::new(),
             threshold,
         })
     }
 
+    /// Makes the party able to act as `signer`, who must be one of its members.
+    pub fn with_signer(mut self, signer: S) -> Result<Self> {
+        let account = signer.account_id();
+        self.get_member_index(account.clone())?;
+        self.keys.insert(account, signer);
+        Ok(self)
+    }
+
     /// This method generates deterministic account id for a given set of members and a threshold.
     /// `who` must be sorted, otherwise the result will be incorrect.
     ///
@@ -184,21 +216,34 @@ impl MultisigParty {
         self.account.clone()
     }
 
+    /// *Sorted* accounts of the members.
+    pub fn get_members(&self) -> &[AccountId] {
+        &self.members
+    }
+
+    pub fn get_threshold(&self) -> u16 {
+        self.threshold
+    }
+
     /// This is a convenience method, as usually you may want to perform an action
     /// as a particular member, without sorting their public keys on the callee side.
     pub fn get_member_index(&self, member: AccountId) -> Result<usize> {
         self.members
-            .binary_search_by_key(&member, account_from_keypair)
+            .binary_search(&member)
             .map_err(|_| MultisigError::NoSuchMember.into())
     }
 
     /// For all extrinsics we have to sign it with the caller (representative) and pass
     /// accounts of the other party members.
-    fn designate_representative_and_represented(&self, idx: usize) -> (KeyPair, Vec<AccountId>) {
-        let mut members = self.members.clone();
-        let member = members.remove(idx);
-        let others = members.iter().map(account_from_keypair).collect();
-        (member, others)
+    fn designate_representative_and_represented(&self, idx: usize) -> Result<(S, Vec<AccountId>)> {
+        let mut others = self.members.clone();
+        let member = others.remove(idx);
+        let key = self
+            .keys
+            .get(&member)
+            .cloned()
+            .ok_or(MultisigError::NoKeyForMember)?;
+        Ok((key, others))
     }
 
     /// Shortcut method for designating author and other signatories, and preparing connection
@@ -207,29 +252,30 @@ impl MultisigParty {
         &self,
         author_idx: usize,
         connection: &C,
-    ) -> (SignedConnection, Vec<AccountId>) {
-        let (author, other_signatories) = self.designate_representative_and_represented(author_idx);
-        let connection = SignedConnection::from_any_connection(connection.as_connection(), author);
-        (connection, other_signatories)
+    ) -> Result<(SignedConnection<S>, Vec<AccountId>)> {
+        let (author, other_signatories) =
+            self.designate_representative_and_represented(author_idx)?;
+        let connection = SignedConnection::with_signer(connection.as_connection(), author);
+        Ok((connection, other_signatories))
     }
 
     /// Compose extrinsic for `multisig::approve_as_multi` call.
     fn construct_approve_as_multi(
         &self,
-        connection: &SignedConnection,
+        connection: &SignedConnection<S>,
         other_signatories: Vec<AccountId>,
         timepoint: Option<Timepoint>,
         call_hash: CallHash,
     ) -> ApproveAsMultiCall {
-        compose_extrinsic!(
-            connection.as_connection(),
-            "Multisig",
-            "approve_as_multi",
-            self.threshold,
-            other_signatories,
-            timepoint,
-            call_hash,
-            MAX_WEIGHT
+        compose_typed_call(
+            connection,
+            ApproveAsMulti {
+                threshold: self.threshold,
+                other_signatories: typed_accounts(&other_signatories),
+                maybe_timepoint: timepoint.map(typed_timepoint),
+                call_hash,
+                max_weight: MAX_WEIGHT,
+            },
         )
     }
 
@@ -254,18 +300,72 @@ impl MultisigParty {
         block_hash: H256,
     ) -> Result<Timepoint> {
         let multisig: Multisig = connection
-            .as_connection()
-            .get_storage_double_map(
-                "Multisig",
-                "Multisigs",
-                self.account.clone(),
-                *call_hash,
-                Some(block_hash),
-            )?
+            .read(|connection| {
+                connection.get_storage_double_map(
+                    "Multisig",
+                    "Multisigs",
+                    self.account.clone(),
+                    *call_hash,
+                    Some(block_hash),
+                )
+            })?
             .ok_or(MultisigError::NoAggregationFound)?;
         Ok(multisig.when)
     }
 
+    /// Reads the ongoing aggregation for the call with `call_hash`.
+    pub fn get_aggregation<C: AnyConnection>(
+        &self,
+        connection: &C,
+        call_hash: CallHash,
+    ) -> Result<SignatureAggregation> {
+        let multisig: Multisig = connection
+            .read(|connection| {
+                connection.get_storage_double_map(
+                    "Multisig",
+                    "Multisigs",
+                    self.account.clone(),
+                    call_hash,
+                    None,
+                )
+            })?
+            .ok_or(MultisigError::NoAggregationFound)?;
+
+        Ok(SignatureAggregation {
+            timepoint: multisig.when,
+            author: self.get_member_index(multisig.depositor)?,
+            call_hash,
+            call: None,
+            approvers: multisig.approvals.into_iter().collect(),
+        })
+    }
+
+    /// Reads all ongoing aggregations of the party.
+    pub fn get_pending_aggregations<C: AnyConnection>(
+        &self,
+        connection: &C,
+    ) -> Result<Vec<SignatureAggregation>> {
+        // The generated key of any aggregation of the party without its last part, which is
+        // the call hash hashed with `Blake2_128Concat` (16 bytes of hash followed by the value).
+        let account = typed_account(&self.account);
+        let any_call_hash = CallHash::default();
+        let mut prefix = Multisigs(&account, &any_call_hash)
+            .key()
+            .final_key(StorageKeyPrefix::new::<Multisigs>())
+            .0;
+        prefix.truncate(prefix.len() - 16 - any_call_hash.len());
+
+        state_get_keys(connection, StorageKey(prefix))
+            .map_err(anyhow::Error::msg)?
+            .into_iter()
+            .map(|key| {
+                let mut call_hash = CallHash::default();
+                call_hash.copy_from_slice(&key.0[key.0.len() - call_hash.len()..]);
+                self.get_aggregation(connection, call_hash)
+            })
+            .collect()
+    }
+
     /// Checks whether `member_idx` is a proper position for `self.members`.
     fn ensure_index(&self, member_idx: usize) -> Result<()> {
         ensure!(
@@ -284,7 +384,7 @@ impl MultisigParty {
     ) -> Result<SignatureAggregation> {
         self.ensure_index(author_idx)?;
 
-        let (connection, other_signatories) = self.prepare_for_extrinsic(author_idx, connection);
+        let (connection, other_signatories) = self.prepare_for_extrinsic(author_idx, connection)?;
         let xt = self.construct_approve_as_multi(&connection, other_signatories, None, call_hash);
 
         let block_hash = self.finalize_xt(&connection, xt, "Initiate multisig aggregation")?;
@@ -295,29 +395,29 @@ impl MultisigParty {
             author: author_idx,
             call_hash,
             call: None,
-            approvers: HashSet::from([account_from_keypair(&self.members[author_idx])]),
+            approvers: HashSet::from([self.members[author_idx].clone()]),
         })
     }
 
     /// Compose extrinsic for `multisig::as_multi` call.
     fn construct_as_multi<CallDetails: Encode>(
         &self,
-        connection: &SignedConnection,
+        connection: &SignedConnection<S>,
         other_signatories: Vec<AccountId>,
         timepoint: Option<Timepoint>,
         call: UncheckedExtrinsicV4<CallDetails>,
         store_call: bool,
     ) -> AsMultiCall {
-        compose_extrinsic!(
-            connection.as_connection(),
-            "Multisig",
-            "as_multi",
-            self.threshold,
-            other_signatories,
-            timepoint,
-            call.function.encode(),
-            store_call,
-            MAX_WEIGHT
+        compose_typed_call(
+            connection,
+            AsMulti {
+                threshold: self.threshold,
+                other_signatories: typed_accounts(&other_signatories),
+                maybe_timepoint: timepoint.map(typed_timepoint),
+                call: WrapperKeepOpaque::from_encoded(call.function.encode()),
+                store_call,
+                max_weight: MAX_WEIGHT,
+            },
         )
     }
 
@@ -331,7 +431,7 @@ impl MultisigParty {
     ) -> Result<SignatureAggregation> {
         self.ensure_index(author_idx)?;
 
-        let (connection, other_signatories) = self.prepare_for_extrinsic(author_idx, connection);
+        let (connection, other_signatories) = self.prepare_for_extrinsic(author_idx, connection)?;
         let xt = self.construct_as_multi(
             &connection,
             other_signatories,
@@ -351,7 +451,7 @@ impl MultisigParty {
             author: author_idx,
             call_hash,
             call: Some(call.encode()),
-            approvers: HashSet::from([account_from_keypair(&self.members[author_idx])]),
+            approvers: HashSet::from([self.members[author_idx].clone()]),
         })
     }
 
@@ -364,7 +464,7 @@ impl MultisigParty {
     ) -> Result<SignatureAggregation> {
         self.ensure_index(author_idx)?;
 
-        let (connection, other_signatories) = self.prepare_for_extrinsic(author_idx, connection);
+        let (connection, other_signatories) = self.prepare_for_extrinsic(author_idx, connection)?;
         let xt = self.construct_approve_as_multi(
             &connection,
             other_signatories,
@@ -375,9 +475,7 @@ impl MultisigParty {
         self.finalize_xt(&connection, xt, "Report approval to multisig aggregation")?;
 
         info!(target: "aleph-client", "Registered multisig approval for call hash: {:?}", sig_agg.call_hash);
-        sig_agg
-            .approvers
-            .insert(account_from_keypair(&self.members[author_idx]));
+        sig_agg.approvers.insert(self.members[author_idx].clone());
         Ok(sig_agg)
     }
 
@@ -403,7 +501,7 @@ impl MultisigParty {
             );
         }
 
-        let (connection, other_signatories) = self.prepare_for_extrinsic(author_idx, connection);
+        let (connection, other_signatories) = self.prepare_for_extrinsic(author_idx, connection)?;
         let xt = self.construct_as_multi(
             &connection,
             other_signatories,
@@ -419,9 +517,7 @@ impl MultisigParty {
         )?;
 
         info!(target: "aleph-client", "Registered multisig approval for call hash: {:?}", sig_agg.call_hash);
-        sig_agg
-            .approvers
-            .insert(account_from_keypair(&self.members[author_idx]));
+        sig_agg.approvers.insert(self.members[author_idx].clone());
         sig_agg.call = Some(call.encode());
         Ok(sig_agg)
     }
@@ -429,19 +525,19 @@ impl MultisigParty {
     /// Compose extrinsic for `multisig::cancel_as_multi` call.
     fn construct_cancel_as_multi(
         &self,
-        connection: &SignedConnection,
+        connection: &SignedConnection<S>,
         other_signatories: Vec<AccountId>,
         timepoint: Timepoint,
         call_hash: CallHash,
     ) -> CancelAsMultiCall {
-        compose_extrinsic!(
-            connection.as_connection(),
-            "Multisig",
-            "cancel_as_multi",
-            self.threshold,
-            other_signatories,
-            timepoint,
-            call_hash
+        compose_typed_call(
+            connection,
+            CancelAsMulti {
+                threshold: self.threshold,
+                other_signatories: typed_accounts(&other_signatories),
+                timepoint: typed_timepoint(timepoint),
+                call_hash,
+            },
         )
     }
 
@@ -457,7 +553,7 @@ impl MultisigParty {
         self.ensure_index(author_idx)?;
         ensure!(sig_agg.author == author_idx, MultisigError::NotAuthor);
 
-        let (connection, other_signatories) = self.prepare_for_extrinsic(author_idx, connection);
+        let (connection, other_signatories) = self.prepare_for_extrinsic(author_idx, connection)?;
         let xt = self.construct_cancel_as_multi(
             &connection,
             other_signatories,
@@ -475,19 +571,19 @@ impl MultisigParty {
 ///
 /// `connection` is *not* assumed to be already signed by `author`.
 /// `other_signatories` *must* be sorted (according to the natural ordering on `AccountId`).
-pub fn perform_multisig_with_threshold_1<C: AnyConnection, CallDetails: Encode + Clone>(
+pub fn perform_multisig_with_threshold_1<C: AnyConnection, S: Signer>(
     connection: &C,
-    author: KeyPair,
+    author: S,
     other_signatories: &[AccountId],
-    call: CallDetails,
+    call: RuntimeCall,
 ) -> Result<()> {
-    let connection = SignedConnection::from_any_connection(connection.clone(), author);
-    let xt = compose_extrinsic!(
-        connection.as_connection(),
-        "Multisig",
-        "as_multi_threshold_1",
-        other_signatories,
-        call
+    let connection = SignedConnection::with_signer(connection.clone(), author);
+    let xt = compose_typed_call(
+        &connection,
+        AsMultiThreshold1 {
+            other_signatories: typed_accounts(other_signatories),
+            call: Box::new(call),
+        },
     );
     try_send_xt(
         &connection,
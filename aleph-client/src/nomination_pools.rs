@@ -0,0 +1,118 @@
+use codec::Encode;
+pub use pallet_nomination_pools::{PoolId, PoolMember};
+use sp_core::H256;
+use substrate_api_client::{AccountId, Balance, XtStatus};
+
+use crate::{
+    aleph_zero::{
+        nomination_pools, runtime_types::pallet_nomination_pools::pallet::Event as PoolsEvent,
+        Event,
+    },
+    compose_typed_call,
+    error::{Error, Result},
+    receipt::tx_hash,
+    try_send_xt, tx_events,
+    typed::typed_account,
+    AnyConnection, SignedConnection, Signer, TxReceipt,
+};
+
+const PALLET: &str = "NominationPools";
+
+pub type Member = PoolMember<Balance>;
+
+/// Creates a pool with the signer of `connection` as its depositor and returns its id.
+///
+/// Waits for the transaction to be finalized and reads the id from the `Created` event it emitted,
+/// as other pools may be created in the meantime.
+pub fn create<S: Signer>(connection: &SignedConnection<S>, amount: Balance) -> Result<PoolId> {
+    let xt = compose_typed_call(connection, nomination_pools::calls::Create { amount });
+    let name = Some("create pool");
+    let receipt = TxReceipt {
+        name,
+        tx_hash: tx_hash(&xt.encode()),
+        block_hash: try_send_xt(connection, xt, name, XtStatus::Finalized)?,
+    };
+    tx_events(connection, &receipt)?
+        .into_iter()
+        .find_map(|event| match event.event {
+            Event::NominationPools(PoolsEvent::Created { pool_id, .. }) => Some(pool_id),
+            _ => None,
+        })
+        .ok_or(Error::MissingEvent(PALLET, "Created"))
+}
+
+/// Makes the pool nominate `validator`, the signer of `connection` has to be its depositor.
+pub fn nominate<S: Signer>(
+    connection: &SignedConnection<S>,
+    pool_id: PoolId,
+    validator: &AccountId,
+    status: XtStatus,
+) -> Result<Option<H256>> {
+    let xt = compose_typed_call(
+        connection,
+        nomination_pools::calls::Nominate {
+            pool_id,
+            validator: typed_account(validator),
+        },
+    );
+    try_send_xt(connection, xt, Some("nominate with pool"), status)
+}
+
+pub fn join<S: Signer>(
+    connection: &SignedConnection<S>,
+    amount: Balance,
+    pool_id: PoolId,
+    status: XtStatus,
+) -> Result<Option<H256>> {
+    let xt = compose_typed_call(
+        connection,
+        nomination_pools::calls::Join { amount, pool_id },
+    );
+    try_send_xt(connection, xt, Some("join pool"), status)
+}
+
+pub fn bond_extra<S: Signer>(
+    connection: &SignedConnection<S>,
+    extra: Balance,
+    status: XtStatus,
+) -> Result<Option<H256>> {
+    let xt = compose_typed_call(connection, nomination_pools::calls::BondExtra { extra });
+    try_send_xt(connection, xt, Some("bond extra in pool"), status)
+}
+
+pub fn claim_payout<S: Signer>(
+    connection: &SignedConnection<S>,
+    status: XtStatus,
+) -> Result<Option<H256>> {
+    let xt = compose_typed_call(connection, nomination_pools::calls::ClaimPayout);
+    try_send_xt(connection, xt, Some("claim pool payout"), status)
+}
+
+pub fn unbond<S: Signer>(
+    connection: &SignedConnection<S>,
+    amount: Balance,
+    status: XtStatus,
+) -> Result<Option<H256>> {
+    let xt = compose_typed_call(connection, nomination_pools::calls::Unbond { amount });
+    try_send_xt(connection, xt, Some("unbond from pool"), status)
+}
+
+pub fn withdraw_unbonded<S: Signer>(
+    connection: &SignedConnection<S>,
+    status: XtStatus,
+) -> Result<Option<H256>> {
+    let xt = compose_typed_call(connection, nomination_pools::calls::WithdrawUnbonded);
+    try_send_xt(connection, xt, Some("withdraw from pool"), status)
+}
+
+/// The id of the most recently created pool, not necessarily the one created by the caller.
+pub fn last_pool_id<C: AnyConnection>(connection: &C) -> Result<PoolId> {
+    Ok(connection
+        .read(|connection| connection.get_storage_value(PALLET, "LastPoolId", None))?
+        .unwrap_or_default())
+}
+
+/// The pool membership of `who`, if it is a member of any pool.
+pub fn member<C: AnyConnection>(connection: &C, who: &AccountId) -> Result<Option<Member>> {
+    connection.read(|connection| connection.get_storage_map(PALLET, "PoolMembers", who, None))
+}
@@ -0,0 +1,465 @@
+//! Connections to several nodes of the same chain with failover between them.
+//!
+//! Only code handed a `ConnectionPool` benefits from the failover. `cliain`, `e2e-tests` and the
+//! flooder connect to a single node (with `create_connection` and the like) and fail as soon as
+//! that node does.
+
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, RwLock, Weak,
+    },
+    thread,
+    time::Duration,
+};
+
+use log::{info, warn};
+use substrate_api_client::{rpc::ws_client::WsRpcClient, Api, RpcClient};
+
+use crate::{
+    ensure_protocol,
+    error::{Error, Result},
+    typed::compare_runtime_version,
+    AnyConnection, BlockNumber, Connection, FromStr, Header, KeyPair, SignedConnection, Signer,
+};
+
+/// When a node is considered healthy.
+#[derive(Clone, Copy, Debug)]
+pub struct HealthLimits {
+    /// How many blocks the best block of a node may be behind the highest best block in the pool.
+    pub max_height_lag: BlockNumber,
+    /// How many blocks the finalized block of a node may be behind its best block.
+    pub max_finality_lag: BlockNumber,
+}
+
+impl Default for HealthLimits {
+    fn default() -> Self {
+        Self {
+            max_height_lag: 5,
+            max_finality_lag: 20,
+        }
+    }
+}
+
+struct Endpoint<Client> {
+    address: String,
+    /// Locked only to swap the connection, never during a call to the node.
+    api: RwLock<Option<Arc<Api<KeyPair, Client>>>>,
+    healthy: AtomicBool,
+}
+
+impl<Client: FromStr + RpcClient> Endpoint<Client>
+where
+    <Client as FromStr>::Err: Debug,
+{
+    fn new(address: &str) -> Self {
+        Self {
+            address: ensure_protocol(address),
+            api: RwLock::new(None),
+            healthy: AtomicBool::new(false),
+        }
+    }
+
+    fn connect(&self) -> Result<Api<KeyPair, Client>> {
+        let client = Client::from_str(&self.address)
+            .map_err(|why| Error::Connection(self.address.clone(), format!("{:?}", why)))?;
+        let api = Api::new(client)
+            .map_err(|why| Error::Connection(self.address.clone(), format!("{:?}", why)))?;
+        compare_runtime_version(
+            api.runtime_version.spec_version,
+            api.runtime_version.transaction_version,
+        )?;
+        Ok(api)
+    }
+
+    /// The current connection, if there is any.
+    fn api(&self) -> Option<Arc<Api<KeyPair, Client>>> {
+        self.api
+            .read()
+            .expect("Lock should not be poisoned")
+            .clone()
+    }
+
+    /// Returns numbers of the best and the finalized block, (re)connecting if needed.
+    fn probe(&self) -> Result<(BlockNumber, BlockNumber)> {
+        let connection = match self.api() {
+            Some(connection) => connection,
+            None => {
+                let connection = Arc::new(self.connect()?);
+                *self.api.write().expect("Lock should not be poisoned") = Some(connection.clone());
+                connection
+            }
+        };
+
+        let heights = best_and_finalized(&connection);
+        if heights.is_err() {
+            // The connection might be broken, let the next probe start from scratch. Unless it has
+            // already been replaced in the meantime.
+            let mut api = self.api.write().expect("Lock should not be poisoned");
+            if matches!(api.as_ref(), Some(current) if Arc::ptr_eq(current, &connection)) {
+                *api = None;
+            }
+        }
+        heights
+    }
+}
+
+fn best_and_finalized<Client: RpcClient>(
+    connection: &Api<KeyPair, Client>,
+) -> Result<(BlockNumber, BlockNumber)> {
+    let best = connection
+        .get_header::<Header>(None)?
+        .ok_or_else(|| Error::Rpc(String::from("No best header")))?
+        .number;
+    let finalized_hash = connection
+        .get_finalized_head()?
+        .ok_or_else(|| Error::Rpc(String::from("No finalized head")))?;
+    let finalized = connection
+        .get_header::<Header>(Some(finalized_hash))?
+        .ok_or_else(|| Error::Rpc(String::from("No finalized header")))?
+        .number;
+    Ok((best, finalized))
+}
+
+struct Inner<Client> {
+    endpoints: Vec<Endpoint<Client>>,
+    limits: HealthLimits,
+    next: AtomicUsize,
+    submitter: AtomicUsize,
+}
+
+/// Connections to several nodes of the same chain.
+///
+/// Reads are spread round-robin over the healthy nodes and retried on another node on failure.
+/// Transactions always go through a single node (as long as it stays healthy), so that they reach
+/// the pool in the order they were sent.
+pub struct ConnectionPool<Client = WsRpcClient> {
+    inner: Arc<Inner<Client>>,
+}
+
+impl<Client> Clone for ConnectionPool<Client> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<Client: FromStr + RpcClient> ConnectionPool<Client>
+where
+    <Client as FromStr>::Err: Debug,
+{
+    /// Creates a pool of `addresses` with default `HealthLimits`.
+    ///
+    /// Fails if none of the nodes is healthy.
+    pub fn new(addresses: &[String]) -> Result<Self> {
+        Self::with_limits(addresses, HealthLimits::default())
+    }
+
+    pub fn with_limits(addresses: &[String], limits: HealthLimits) -> Result<Self> {
+        let pool = Self {
+            inner: Arc::new(Inner {
+                endpoints: addresses.iter().map(|a| Endpoint::new(a)).collect(),
+                limits,
+                next: AtomicUsize::new(0),
+                submitter: AtomicUsize::new(0),
+            }),
+        };
+        match pool.refresh() {
+            0 => Err(Error::NoHealthyEndpoint),
+            _ => Ok(pool),
+        }
+    }
+
+    /// Checks the block height and the finality lag of every node. Returns the number of healthy
+    /// nodes.
+    pub fn refresh(&self) -> usize {
+        let heights = self
+            .inner
+            .endpoints
+            .iter()
+            .map(|endpoint| {
+                endpoint
+                    .probe()
+                    .map_err(|why| warn!(target: "aleph-client", "{}", why))
+                    .ok()
+            })
+            .collect::<Vec<_>>();
+        let top = heights.iter().flatten().map(|(best, _)| *best).max();
+        let limits = self.inner.limits;
+
+        let mut healthy_count = 0;
+        for (endpoint, height) in self.inner.endpoints.iter().zip(heights) {
+            let healthy = match (height, top) {
+                (Some((best, finalized)), Some(top)) => {
+                    top - best <= limits.max_height_lag
+                        && best.saturating_sub(finalized) <= limits.max_finality_lag
+                }
+                _ => false,
+            };
+            if !healthy {
+                info!(target: "aleph-client", "Node {} is not healthy", endpoint.address);
+            }
+            endpoint.healthy.store(healthy, Ordering::Relaxed);
+            healthy_count += healthy as usize;
+        }
+        healthy_count
+    }
+
+    /// Runs `f` with a connection to the next healthy node.
+    pub fn with_connection<T>(&self, f: impl FnOnce(&Api<KeyPair, Client>) -> T) -> Result<T> {
+        let index = self.pick()?;
+        self.run_on(index, f)
+    }
+
+    /// Runs `f` with a connection to the next healthy node. If it fails, the node is marked as
+    /// unhealthy and `f` is retried on another one.
+    pub fn read<T, E: Into<Error>>(
+        &self,
+        f: impl Fn(&Api<KeyPair, Client>) -> std::result::Result<T, E>,
+    ) -> Result<T> {
+        let mut last_error = Error::NoHealthyEndpoint;
+        for _ in 0..self.inner.endpoints.len() {
+            let index = match self.pick() {
+                Ok(index) => index,
+                Err(_) => break,
+            };
+            let why = match self.run_on(index, &f) {
+                Ok(Ok(result)) => return Ok(result),
+                Ok(Err(why)) => why.into(),
+                Err(why) => why,
+            };
+            warn!(target: "aleph-client",
+                "Reading from {} failed: {}", self.inner.endpoints[index].address, why);
+            self.inner.endpoints[index]
+                .healthy
+                .store(false, Ordering::Relaxed);
+            last_error = why;
+        }
+        Err(last_error)
+    }
+
+    /// Runs `f` with a connection to the node used for submitting transactions. A new node is
+    /// chosen only if the current one became unhealthy.
+    pub fn submit_with<T>(&self, f: impl FnOnce(&Api<KeyPair, Client>) -> T) -> Result<T> {
+        let current = self.inner.submitter.load(Ordering::Relaxed);
+        let index = if self.inner.endpoints[current]
+            .healthy
+            .load(Ordering::Relaxed)
+        {
+            current
+        } else {
+            let index = self.pick()?;
+            info!(target: "aleph-client",
+                "Submitting transactions through {}", self.inner.endpoints[index].address);
+            self.inner.submitter.store(index, Ordering::Relaxed);
+            index
+        };
+        self.run_on(index, f)
+    }
+
+    fn run_on<T>(&self, index: usize, f: impl FnOnce(&Api<KeyPair, Client>) -> T) -> Result<T> {
+        let endpoint = &self.inner.endpoints[index];
+        match endpoint.api() {
+            Some(connection) => Ok(f(&connection)),
+            None => {
+                endpoint.healthy.store(false, Ordering::Relaxed);
+                Err(Error::Connection(
+                    endpoint.address.clone(),
+                    String::from("not connected"),
+                ))
+            }
+        }
+    }
+
+    /// Index of the next healthy node in the round-robin order. If there is none, checks the
+    /// health of all nodes once again.
+    fn pick(&self) -> Result<usize> {
+        let endpoints = &self.inner.endpoints;
+        for attempt in 0..2 {
+            if attempt > 0 && self.refresh() == 0 {
+                break;
+            }
+            let start = self.inner.next.fetch_add(1, Ordering::Relaxed);
+            if let Some(index) = (start..start + endpoints.len())
+                .map(|i| i % endpoints.len())
+                .find(|i| endpoints[*i].healthy.load(Ordering::Relaxed))
+            {
+                return Ok(index);
+            }
+        }
+        Err(Error::NoHealthyEndpoint)
+    }
+}
+
+impl<Client> ConnectionPool<Client>
+where
+    Client: FromStr + RpcClient + Send + Sync + 'static,
+    <Client as FromStr>::Err: Debug,
+{
+    /// Refreshes the health of the nodes every `interval` in a background thread, so that nodes
+    /// which fell behind are avoided and the recovered ones are used again without waiting for all
+    /// nodes to fail. The thread stops once the pool (with all its clones) is dropped.
+    pub fn refresh_every(&self, interval: Duration) {
+        let inner = Arc::downgrade(&self.inner);
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+            match Weak::upgrade(&inner) {
+                Some(inner) => {
+                    ConnectionPool { inner }.refresh();
+                }
+                None => return,
+            }
+        });
+    }
+}
+
+impl ConnectionPool {
+    /// A connection signed by `signer` to the node used for submitting transactions.
+    pub fn sign<S: Signer>(&self, signer: S) -> Result<SignedConnection<S>> {
+        self.submit_with(|connection| SignedConnection::with_signer(connection.clone(), signer))
+    }
+}
+
+/// Every call of `as_connection` returns a connection to the next healthy node.
+///
+/// The returned connection is bound to that node: unlike `read`, calls made on it are not retried
+/// on another node when it fails. Take a new connection for every operation, so that nodes marked
+/// as unhealthy in the meantime are skipped.
+///
+/// # Panics
+///
+/// `as_connection` panics when none of the nodes is healthy. Use `try_as_connection` to get
+/// `Error::NoHealthyEndpoint` instead.
+impl AnyConnection for ConnectionPool {
+    fn as_connection(&self) -> Connection {
+        self.try_as_connection()
+            .expect("At least one node should be healthy")
+    }
+
+    fn try_as_connection(&self) -> Result<Connection> {
+        self.with_connection(|connection| connection.clone())
+    }
+
+    fn read<T, E: Into<Error>>(
+        &self,
+        f: impl Fn(&Connection) -> std::result::Result<T, E>,
+    ) -> Result<T> {
+        ConnectionPool::read(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{set_node, MockClient};
+
+    fn pool(addresses: &[&str]) -> Result<ConnectionPool<MockClient>> {
+        ConnectionPool::new(&addresses.iter().map(|a| a.to_string()).collect::<Vec<_>>())
+    }
+
+    fn healthy(pool: &ConnectionPool<MockClient>) -> Vec<bool> {
+        pool.inner
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.healthy.load(Ordering::Relaxed))
+            .collect()
+    }
+
+    fn best(connection: &Api<KeyPair, MockClient>) -> Result<BlockNumber> {
+        best_and_finalized(connection).map(|(best, _)| best)
+    }
+
+    #[test]
+    fn given_nodes_lagging_behind_when_refreshing_then_only_the_others_are_healthy() {
+        set_node("lag-a", Some((100, 95)));
+        set_node("lag-b", Some((90, 90)));
+        set_node("lag-c", Some((100, 50)));
+
+        let pool = pool(&["lag-a", "lag-b", "lag-c"]).unwrap();
+
+        assert_eq!(healthy(&pool), vec![true, false, false]);
+    }
+
+    #[test]
+    fn given_no_node_up_when_creating_pool_then_it_fails() {
+        assert!(matches!(
+            pool(&["down-a", "down-b"]),
+            Err(Error::NoHealthyEndpoint)
+        ));
+    }
+
+    #[test]
+    fn given_node_going_down_when_reading_then_another_node_answers() {
+        set_node("failover-a", Some((10, 10)));
+        set_node("failover-b", Some((11, 10)));
+        let pool = pool(&["failover-a", "failover-b"]).unwrap();
+
+        set_node("failover-a", None);
+
+        for _ in 0..4 {
+            assert_eq!(pool.read(best).unwrap(), 11);
+        }
+        assert_eq!(healthy(&pool), vec![false, true]);
+    }
+
+    #[test]
+    fn given_all_nodes_down_when_taking_connection_then_it_fails() {
+        set_node("all-down-a", Some((10, 10)));
+        let pool = pool(&["all-down-a"]).unwrap();
+
+        set_node("all-down-a", None);
+
+        assert!(matches!(
+            pool.with_connection(|connection| connection.clone()),
+            Err(Error::NoHealthyEndpoint)
+        ));
+    }
+
+    #[test]
+    fn given_submitting_node_going_down_when_submitting_then_another_node_takes_over() {
+        set_node("submit-a", Some((10, 10)));
+        set_node("submit-b", Some((10, 10)));
+        let pool = pool(&["submit-a", "submit-b"]).unwrap();
+        assert_eq!(pool.submit_with(best).unwrap().unwrap(), 10);
+
+        set_node("submit-a", None);
+        set_node("submit-b", Some((12, 10)));
+        assert_eq!(pool.refresh(), 1);
+
+        assert_eq!(pool.submit_with(best).unwrap().unwrap(), 12);
+        assert_eq!(pool.inner.submitter.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn given_node_back_up_when_refreshing_then_it_reconnects_and_is_healthy_again() {
+        set_node("recover-a", Some((10, 10)));
+        set_node("recover-b", Some((10, 10)));
+        let pool = pool(&["recover-a", "recover-b"]).unwrap();
+
+        set_node("recover-a", None);
+        assert_eq!(pool.refresh(), 1);
+        assert!(pool.inner.endpoints[0].api().is_none());
+
+        set_node("recover-a", Some((10, 10)));
+        assert_eq!(pool.refresh(), 2);
+        assert_eq!(healthy(&pool), vec![true, true]);
+    }
+
+    #[test]
+    fn given_periodic_refresh_when_node_recovers_then_it_becomes_healthy() {
+        set_node("periodic-a", Some((10, 10)));
+        set_node("periodic-b", None);
+        let pool = pool(&["periodic-a", "periodic-b"]).unwrap();
+        pool.refresh_every(Duration::from_millis(10));
+
+        set_node("periodic-b", Some((10, 10)));
+
+        let recovered = (0..200).any(|_| {
+            thread::sleep(Duration::from_millis(10));
+            healthy(&pool) == vec![true, true]
+        });
+        assert!(recovered);
+    }
+}
@@ -0,0 +1,92 @@
+use log::info;
+pub use primitives::proxy::ProxyType;
+use sp_core::H256;
+use substrate_api_client::{AccountId, XtStatus};
+
+use crate::{
+    aleph_zero::{proxy, runtime_types, runtime_types::aleph_runtime::Call as RuntimeCall},
+    compose_typed_call,
+    error::Result,
+    try_send_xt,
+    typed::typed_account,
+    BlockNumber, SignedConnection, Signer,
+};
+
+impl From<ProxyType> for runtime_types::primitives::proxy::ProxyType {
+    fn from(proxy_type: ProxyType) -> Self {
+        match proxy_type {
+            ProxyType::Any => Self::Any,
+            ProxyType::NonTransfer => Self::NonTransfer,
+            ProxyType::Staking => Self::Staking,
+            ProxyType::SessionKeys => Self::SessionKeys,
+            ProxyType::Governance => Self::Governance,
+        }
+    }
+}
+
+/// Makes `delegate` a proxy of the signer of `connection`, allowed to make calls of `proxy_type`.
+///
+/// With a non-zero `delay` the proxy has to announce its calls `delay` blocks in advance.
+pub fn add_proxy<S: Signer>(
+    connection: &SignedConnection<S>,
+    delegate: &AccountId,
+    proxy_type: ProxyType,
+    delay: BlockNumber,
+    status: XtStatus,
+) -> Result<Option<H256>> {
+    let xt = compose_typed_call(
+        connection,
+        proxy::calls::AddProxy {
+            delegate: typed_account(delegate),
+            proxy_type: proxy_type.into(),
+            delay,
+        },
+    );
+    let block_hash = try_send_xt(connection, xt, Some("add proxy"), status)?;
+    info!(target: "aleph-client", "Added {:?} proxy {}", proxy_type, delegate);
+    Ok(block_hash)
+}
+
+/// Unregisters `delegate` as a proxy of the signer of `connection`.
+///
+/// `proxy_type` and `delay` have to be the same as when the proxy was added.
+pub fn remove_proxy<S: Signer>(
+    connection: &SignedConnection<S>,
+    delegate: &AccountId,
+    proxy_type: ProxyType,
+    delay: BlockNumber,
+    status: XtStatus,
+) -> Result<Option<H256>> {
+    let xt = compose_typed_call(
+        connection,
+        proxy::calls::RemoveProxy {
+            delegate: typed_account(delegate),
+            proxy_type: proxy_type.into(),
+            delay,
+        },
+    );
+    let block_hash = try_send_xt(connection, xt, Some("remove proxy"), status)?;
+    info!(target: "aleph-client", "Removed {:?} proxy {}", proxy_type, delegate);
+    Ok(block_hash)
+}
+
+/// Dispatches `call` as `real`, with the signer of `connection` acting as its proxy.
+///
+/// If `force_proxy_type` is given, only a proxy of that type is used.
+pub fn proxy_call<S: Signer>(
+    connection: &SignedConnection<S>,
+    real: &AccountId,
+    force_proxy_type: Option<ProxyType>,
+    call: RuntimeCall,
+    status: XtStatus,
+) -> Result<Option<H256>> {
+    let xt = compose_typed_call(
+        connection,
+        proxy::calls::Proxy {
+            real: typed_account(real),
+            force_proxy_type: force_proxy_type.map(Into::into),
+            call: Box::new(call),
+        },
+    );
+    try_send_xt(connection, xt, Some("proxy"), status)
+}
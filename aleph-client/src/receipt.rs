@@ -0,0 +1,171 @@
+//! Receipts of the submitted transactions.
+//!
+//! Tools that report what they have done (like `cliain --output json`) attach `Receipts` to their
+//! connection with `SignedConnection::with_receipts`, submit transactions with the usual functions
+//! of this crate and then collect hashes of the transactions and of their blocks with
+//! `Receipts::take`. Events emitted by a transaction can be then read with `tx_events`. Connections
+//! without `Receipts` record nothing.
+
+use std::sync::{Arc, Mutex};
+
+use codec::Encode;
+use sp_core::{hashing::blake2_256, H256};
+use sp_runtime::{generic::Block as GenericBlock, OpaqueExtrinsic};
+use substrate_api_client::Metadata;
+
+use crate::{
+    aleph_zero::{
+        runtime_types::frame_system::Phase, system::storage::Events as EventsStorage, Event,
+    },
+    error::{Error, Result},
+    read_typed_storage_at, AnyConnection, Header,
+};
+
+type Block = GenericBlock<Header, OpaqueExtrinsic>;
+
+/// A transaction submitted with `try_send_xt` (or any function of this crate built on top of it).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxReceipt {
+    /// Name of the transaction, as passed to `try_send_xt`.
+    pub name: Option<&'static str>,
+    pub tx_hash: H256,
+    /// Hash of the containing block, known only if the inclusion has been awaited.
+    pub block_hash: Option<H256>,
+}
+
+/// Receipts of the transactions submitted through the connections they are attached to. Clones
+/// share the recorded receipts.
+#[derive(Clone, Debug, Default)]
+pub struct Receipts(Arc<Mutex<Vec<TxReceipt>>>);
+
+impl Receipts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the receipts recorded so far and forgets them.
+    pub fn take(&self) -> Vec<TxReceipt> {
+        std::mem::take(&mut *self.0.lock().expect("Lock should not be poisoned"))
+    }
+
+    pub(crate) fn record(&self, receipt: TxReceipt) {
+        self.0
+            .lock()
+            .expect("Lock should not be poisoned")
+            .push(receipt);
+    }
+}
+
+/// Hash of the encoded extrinsic `xt`, the same as the one returned by the node on submission.
+pub(crate) fn tx_hash(xt: &[u8]) -> H256 {
+    H256(blake2_256(xt))
+}
+
+/// An event emitted by a transaction.
+#[derive(Clone, Debug)]
+pub struct TxEvent {
+    /// Index of the event within the block.
+    pub index: u32,
+    pub pallet: String,
+    pub variant: String,
+    pub event: Event,
+}
+
+/// Names of the pallet and the variant of `event`. The generated events do not expose them, so they
+/// are looked up in `metadata` by the indices that start the encoded event.
+fn names(metadata: &Metadata, event: &Event) -> Result<(String, String)> {
+    let encoded = event.encode();
+    let event = metadata
+        .event(encoded[0], encoded[1])
+        .map_err(|why| Error::Decode(format!("event {:?}: {:?}", event, why)))?;
+    Ok((event.pallet().to_string(), event.event().to_string()))
+}
+
+/// Events emitted by the transaction of `receipt`.
+pub fn tx_events<C: AnyConnection>(connection: &C, receipt: &TxReceipt) -> Result<Vec<TxEvent>> {
+    let block_hash = receipt.block_hash.ok_or(Error::NoHash)?;
+    let connection = connection.try_as_connection()?;
+    let block = connection
+        .get_block::<Block>(Some(block_hash))?
+        .ok_or_else(|| Error::Rpc(format!("No block with hash {:?}", block_hash)))?;
+    let extrinsic = block
+        .extrinsics
+        .iter()
+        .position(|xt| tx_hash(&xt.encode()) == receipt.tx_hash)
+        .ok_or(Error::MissingTransaction(receipt.tx_hash))? as u32;
+
+    let records =
+        read_typed_storage_at(&connection, &EventsStorage, Some(block_hash))?.unwrap_or_default();
+    records
+        .into_iter()
+        .enumerate()
+        .filter(|(_, record)| matches!(record.phase, Phase::ApplyExtrinsic(i) if i == extrinsic))
+        .map(|(index, record)| {
+            let (pallet, variant) = names(&connection.metadata, &record.event)?;
+            Ok(TxEvent {
+                index: index as u32,
+                pallet,
+                variant,
+                event: record.event,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        aleph_zero::{elections, runtime_types::frame_system::pallet::Event as SystemEvent},
+        mock,
+    };
+
+    fn receipt(name: &'static str) -> TxReceipt {
+        TxReceipt {
+            name: Some(name),
+            tx_hash: tx_hash(name.as_bytes()),
+            block_hash: None,
+        }
+    }
+
+    #[test]
+    fn given_cloned_receipts_when_recording_then_all_are_taken_once_from_any_clone() {
+        let receipts = Receipts::new();
+        let clone = receipts.clone();
+
+        receipts.record(receipt("first"));
+        clone.record(receipt("second"));
+
+        assert_eq!(clone.take(), vec![receipt("first"), receipt("second")]);
+        assert!(receipts.take().is_empty());
+    }
+
+    #[test]
+    fn given_separate_receipts_when_recording_then_they_do_not_mix() {
+        let (first, second) = (Receipts::new(), Receipts::new());
+
+        first.record(receipt("first"));
+
+        assert!(second.take().is_empty());
+        assert_eq!(first.take(), vec![receipt("first")]);
+    }
+
+    #[test]
+    fn given_event_when_reading_names_then_pallet_and_variant_from_metadata_are_returned() {
+        let metadata = mock::connect("receipt-names").metadata;
+
+        assert_eq!(
+            names(&metadata, &Event::System(SystemEvent::CodeUpdated)).unwrap(),
+            (String::from("System"), String::from("CodeUpdated"))
+        );
+        // Unlike the `Debug` output, the names do not depend on the fields of the event.
+        assert_eq!(
+            names(
+                &metadata,
+                &Event::Elections(elections::Event::ChangeMembers(vec![]))
+            )
+            .unwrap(),
+            (String::from("Elections"), String::from("ChangeMembers"))
+        );
+    }
+}
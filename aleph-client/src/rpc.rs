@@ -1,7 +1,9 @@
 use crate::{AnyConnection, SessionKeys, H256};
+use codec::Encode;
+use primitives::{SessionIndex, ValidatorPerformance};
 use serde_json::{json, Value};
 use sp_core::storage::{StorageChangeSet, StorageData};
-use substrate_api_client::StorageKey;
+use substrate_api_client::{AccountId, StorageKey};
 
 fn json_req(method: &str, params: Value, id: u32) -> Value {
     json!({
@@ -16,6 +18,21 @@ pub fn author_rotate_keys_json() -> Value {
     json_req("author_rotateKeys", Value::Null, 1)
 }
 
+fn has_session_keys_json(keys: &SessionKeys) -> Value {
+    json_req(
+        "author_hasSessionKeys",
+        Value::Array(vec![Value::String(format!(
+            "0x{}",
+            hex::encode(keys.encode())
+        ))]),
+        1,
+    )
+}
+
+fn validators_performance_json(session: SessionIndex) -> Value {
+    json_req("elections_validatorsPerformance", json!([session]), 1)
+}
+
 fn state_query_storage_at_json(storage_keys: &[StorageKey]) -> Value {
     json_req(
         "state_queryStorageAt",
@@ -32,6 +49,22 @@ fn state_query_storage_at_json(storage_keys: &[StorageKey]) -> Value {
     )
 }
 
+fn state_get_keys_json(prefix: &StorageKey) -> Value {
+    json_req(
+        "state_getKeys",
+        Value::Array(vec![Value::String(hex::encode(prefix)), Value::Null]),
+        1,
+    )
+}
+
+fn parse_get_keys_result(maybe_json_result: Option<String>) -> Result<Vec<StorageKey>, String> {
+    match maybe_json_result {
+        None => Err(String::from("Returned result was null!")),
+        Some(result) => serde_json::from_str(&result[..])
+            .map_err(|_| format!("Failed to parse result {:?} into JSON", result)),
+    }
+}
+
 fn parse_query_storage_at_result(
     maybe_json_result: Option<String>,
     expected_storage_key_size: usize,
@@ -70,10 +103,8 @@ pub fn state_query_storage_at<C: AnyConnection>(
     connection: &C,
     storage_keys: Vec<StorageKey>,
 ) -> Result<Vec<Option<StorageData>>, String> {
-    match connection
-        .as_connection()
-        .get_request(state_query_storage_at_json(&storage_keys))
-    {
+    let request = state_query_storage_at_json(&storage_keys);
+    match connection.read(|connection| connection.get_request(request.clone())) {
         Ok(maybe_json_result) => {
             parse_query_storage_at_result(maybe_json_result, storage_keys.len())
         }
@@ -84,6 +115,21 @@ pub fn state_query_storage_at<C: AnyConnection>(
     }
 }
 
+/// All storage keys starting with `prefix`, in the best block.
+pub fn state_get_keys<C: AnyConnection>(
+    connection: &C,
+    prefix: StorageKey,
+) -> Result<Vec<StorageKey>, String> {
+    let request = state_get_keys_json(&prefix);
+    match connection.read(|connection| connection.get_request(request.clone())) {
+        Ok(maybe_json_result) => parse_get_keys_result(maybe_json_result),
+        Err(_) => Err(format!(
+            "Failed to obtain storage keys with prefix {:?}",
+            &prefix
+        )),
+    }
+}
+
 pub fn rotate_keys_base<C: AnyConnection, F, R>(
     connection: &C,
     rpc_result_mapper: F,
@@ -118,6 +164,39 @@ pub fn rotate_keys_raw_result<C: AnyConnection>(connection: &C) -> Result<String
     rotate_keys_base(connection, |keys| Some(keys.trim_matches('\"').to_string()))
 }
 
+/// Whether the node behind `connection` has the private parts of `keys` in its keystore.
+pub fn has_session_keys<C: AnyConnection>(
+    connection: &C,
+    keys: &SessionKeys,
+) -> Result<bool, String> {
+    match connection
+        .as_connection()
+        .get_request(has_session_keys_json(keys))
+    {
+        Ok(Some(result)) => serde_json::from_str(&result[..])
+            .map_err(|_| format!("Failed to parse result {:?} into JSON", result)),
+        Ok(None) => Err(String::from("Returned result was null!")),
+        Err(_) => Err(String::from("Failed to check session keys")),
+    }
+}
+
+/// Performance of the validators in `session`, the current one or a past one still kept on chain,
+/// see `primitives::ValidatorPerformance`.
+pub fn validators_performance<C: AnyConnection>(
+    connection: &C,
+    session: SessionIndex,
+) -> Result<Option<Vec<ValidatorPerformance<AccountId>>>, String> {
+    match connection
+        .as_connection()
+        .get_request(validators_performance_json(session))
+    {
+        Ok(Some(result)) => serde_json::from_str(&result[..])
+            .map_err(|_| format!("Failed to parse result {:?} into JSON", result)),
+        Ok(None) => Ok(None),
+        Err(_) => Err(String::from("Failed to obtain validators performance")),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,6 +219,15 @@ mod tests {
         assert_eq!(expected_json, state_query_storage_at_json(&storage_keys));
     }
 
+    #[test]
+    fn given_session_when_validators_performance_json_then_session_is_the_only_param() {
+        let expected_json: Value = serde_json::from_str(
+            r#"{"id": "1", "jsonrpc": "2.0", "method": "elections_validatorsPerformance", "params": [7]}"#,
+        )
+        .unwrap();
+        assert_eq!(expected_json, validators_performance_json(7));
+    }
+
     #[test]
     fn given_expected_input_when_parse_query_storage_at_result_then_json_is_as_expected() {
         let expected_json_string = r#"
@@ -177,4 +265,48 @@ mod tests {
             parse_query_storage_at_result(Some(String::from(expected_json_string)), 3).unwrap()
         );
     }
+
+    #[test]
+    fn given_keys_when_has_session_keys_json_then_json_is_as_expected() {
+        let keys = SessionKeys {
+            aura: [1; 32],
+            aleph: [2; 32],
+        };
+        let expected_json = json!({
+            "id": "1",
+            "jsonrpc": "2.0",
+            "method": "author_hasSessionKeys",
+            "params": [format!("0x{}{}", "01".repeat(32), "02".repeat(32))],
+        });
+
+        assert_eq!(expected_json, has_session_keys_json(&keys));
+    }
+
+    #[test]
+    fn given_prefix_when_state_get_keys_json_then_json_is_as_expected() {
+        let expected_json_string = r#"
+{
+   "id": "1",
+   "jsonrpc": "2.0",
+   "method":"state_getKeys",
+   "params": ["0a0b0c", null]
+}"#;
+
+        let expected_json: Value = serde_json::from_str(expected_json_string).unwrap();
+        assert_eq!(
+            expected_json,
+            state_get_keys_json(&StorageKey(vec![10, 11, 12]))
+        );
+    }
+
+    #[test]
+    fn given_expected_input_when_parse_get_keys_result_then_keys_are_as_expected() {
+        let result = String::from(r#"["0x0a0b0c", "0x0a0b0d"]"#);
+
+        assert_eq!(
+            vec![StorageKey(vec![10, 11, 12]), StorageKey(vec![10, 11, 13])],
+            parse_get_keys_result(Some(result)).unwrap()
+        );
+        assert!(parse_get_keys_result(None).is_err());
+    }
 }
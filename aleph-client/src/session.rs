@@ -1,14 +1,18 @@
 use crate::{
-    send_xt, waiting::wait_for_event, AnyConnection, BlockNumber, RootConnection, SignedConnection,
+    aleph_zero::{runtime_types, session},
+    asynchronous, error, read_typed_storage, wait_for_typed_event, AnyConnection, BlockNumber,
+    RootConnection, SignedConnection, Signer,
 };
 use codec::{Decode, Encode};
+use futures::executor::block_on;
 use log::info;
-use sp_core::Pair;
-use substrate_api_client::{compose_call, compose_extrinsic, AccountId, FromHexString, XtStatus};
+use primitives::ValidatorSessionRecord;
+use sp_core::H256;
+use substrate_api_client::{AccountId, FromHexString, XtStatus};
 
 // Using custom struct and rely on default Encode trait from Parity's codec
 // it works since byte arrays are encoded in a straight forward way, it as-is
-#[derive(Debug, Encode, Clone)]
+#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
 pub struct Keys {
     pub aura: [u8; 32],
     pub aleph: [u8; 32],
@@ -25,6 +29,19 @@ impl From<Vec<u8>> for Keys {
     }
 }
 
+impl From<Keys> for runtime_types::aleph_runtime::SessionKeys {
+    fn from(keys: Keys) -> Self {
+        Self {
+            aura: runtime_types::sp_consensus_aura::sr25519::app_sr25519::Public(
+                runtime_types::sp_core::sr25519::Public(keys.aura),
+            ),
+            aleph: runtime_types::primitives::app::Public(runtime_types::sp_core::ed25519::Public(
+                keys.aleph,
+            )),
+        }
+    }
+}
+
 impl TryFrom<String> for Keys {
     type Error = ();
 
@@ -37,66 +54,69 @@ impl TryFrom<String> for Keys {
     }
 }
 
-pub fn change_members(
-    sudo_connection: &RootConnection,
+/// Blocking wrapper for `asynchronous::change_members`.
+pub fn change_members<S: Signer>(
+    sudo_connection: &RootConnection<S>,
     new_members: Vec<AccountId>,
     status: XtStatus,
-) {
-    info!(target: "aleph-client", "New members {:#?}", new_members);
-    let call = compose_call!(
-        sudo_connection.as_connection().metadata,
-        "Elections",
-        "change_members",
-        new_members
-    );
-    let xt = compose_extrinsic!(
-        sudo_connection.as_connection(),
-        "Sudo",
-        "sudo_unchecked_weight",
-        call,
-        0_u64
-    );
-    send_xt(sudo_connection, xt, Some("sudo_unchecked_weight"), status);
+) -> error::Result<Option<H256>> {
+    block_on(asynchronous::change_members(
+        sudo_connection,
+        new_members,
+        status,
+    ))
 }
 
-pub fn set_keys(connection: &SignedConnection, new_keys: Keys, status: XtStatus) {
-    let xt = compose_extrinsic!(
-        connection.as_connection(),
-        "Session",
-        "set_keys",
-        new_keys,
-        0u8
-    );
-    send_xt(connection, xt, Some("set_keys"), status);
+/// Blocking wrapper for `asynchronous::set_keys`.
+pub fn set_keys<S: Signer>(
+    connection: &SignedConnection<S>,
+    new_keys: Keys,
+    status: XtStatus,
+) -> error::Result<Option<H256>> {
+    block_on(asynchronous::set_keys(connection, new_keys, status))
 }
 
 /// Get the number of the current session.
-pub fn get_current<C: AnyConnection>(connection: &C) -> u32 {
+pub fn get_current<C: AnyConnection>(connection: &C) -> error::Result<u32> {
+    Ok(read_typed_storage(connection, &session::storage::CurrentIndex)?.unwrap_or(0))
+}
+
+/// Session keys of `controller` that will be used from the next session on, if any were set.
+pub fn next_keys<C: AnyConnection>(
+    connection: &C,
+    controller: &AccountId,
+) -> error::Result<Option<Keys>> {
     connection
-        .as_connection()
-        .get_storage_value("Session", "CurrentIndex", None)
-        .unwrap()
-        .unwrap_or(0)
+        .read(|connection| connection.get_storage_map("Session", "NextKeys", controller, None))
+}
+
+/// Validators of the current session.
+pub fn validators<C: AnyConnection>(connection: &C) -> error::Result<Vec<AccountId>> {
+    Ok(connection
+        .read(|connection| connection.get_storage_value("Session", "Validators", None))?
+        .unwrap_or_default())
 }
 
 pub fn wait_for<C: AnyConnection>(
     connection: &C,
     session_index: u32,
-) -> anyhow::Result<BlockNumber> {
+) -> error::Result<BlockNumber> {
     info!(target: "aleph-client", "Waiting for session {}", session_index);
 
-    #[derive(Debug, Decode, Clone)]
-    struct NewSessionEvent {
-        session_index: u32,
-    }
-    wait_for_event(
-        connection,
-        ("Session", "NewSession"),
-        |e: NewSessionEvent| {
-            info!(target: "aleph-client", "New session {}", e.session_index);
+    wait_for_typed_event(connection, |e: session::events::NewSession| {
+        info!(target: "aleph-client", "New session {}", e.session_index);
 
-            e.session_index == session_index
-        },
-    )?;
+        e.session_index == session_index
+    })?;
     Ok(session_index)
 }
+
+/// How the validators performed in `session`, if it is still kept on chain.
+pub fn performance<C: AnyConnection>(
+    connection: &C,
+    session: u32,
+) -> error::Result<Option<Vec<ValidatorSessionRecord<AccountId>>>> {
+    connection.read(|connection| {
+        connection.get_storage_map("Elections", "SessionPerformance", session, None)
+    })
+}
@@ -0,0 +1,28 @@
+use sp_core::{sr25519, Pair};
+
+use crate::{AccountId, KeyPair};
+
+/// Something that can sign transactions of an account, e.g. a key pair or a hardware wallet.
+///
+/// Methods are named after the ones of `sr25519::Pair`, so that any `Signer` can be passed to
+/// `compose_extrinsic_offline!`.
+pub trait Signer: Clone + Send + Sync + 'static {
+    fn public(&self) -> sr25519::Public;
+
+    fn sign(&self, payload: &[u8]) -> sr25519::Signature;
+
+    /// The account whose transactions are signed.
+    fn account_id(&self) -> AccountId {
+        AccountId::from(self.public())
+    }
+}
+
+impl Signer for KeyPair {
+    fn public(&self) -> sr25519::Public {
+        Pair::public(self)
+    }
+
+    fn sign(&self, payload: &[u8]) -> sr25519::Signature {
+        Pair::sign(self, payload)
+    }
+}
@@ -1,167 +1,187 @@
-use codec::Compact;
+use futures::executor::block_on;
 use log::info;
-use pallet_staking::{RewardDestination, ValidatorPrefs};
+use pallet_staking::ValidatorPrefs;
 use rayon::prelude::*;
-use sp_core::Pair;
-use sp_runtime::Perbill;
-use substrate_api_client::{
-    compose_call, compose_extrinsic, AccountId, Balance, GenericAddress, XtStatus,
-};
+use sp_core::H256;
+use substrate_api_client::{AccountId, Balance, XtStatus};
+use subxt::sp_core::crypto::AccountId32;
 
 use crate::{
-    account_from_keypair, create_connection, locks, send_xt, wait_for_session, AnyConnection,
-    BlockNumber, KeyPair, RootConnection, SignedConnection,
+    account_from_keypair,
+    aleph_zero::{
+        runtime_types::{
+            aleph_runtime::Call,
+            pallet_staking::{
+                pallet::pallet::{Call as StakingCall, ConfigOp},
+                RewardDestination,
+            },
+            pallet_sudo::pallet::Call as SudoCall,
+        },
+        staking, sudo, utility,
+    },
+    asynchronous, compose_typed_call, create_connection,
+    error::{Error, Result},
+    locks, read_typed_storage, try_send_xt,
+    typed::{typed_account, typed_address},
+    wait_for_session, AnyConnection, BlockNumber, KeyPair, RootConnection, SignedConnection,
+    Signer,
 };
 
-pub fn bond(
-    connection: &SignedConnection,
+/// Blocking wrapper for `asynchronous::bond`.
+pub fn bond<S: Signer>(
+    connection: &SignedConnection<S>,
     initial_stake: Balance,
     controller_account_id: &AccountId,
     status: XtStatus,
-) {
-    let controller_account_id = GenericAddress::Id(controller_account_id.clone());
-
-    let xt = connection.as_connection().staking_bond(
-        controller_account_id,
+) -> Result<Option<H256>> {
+    block_on(asynchronous::bond(
+        connection,
         initial_stake,
-        RewardDestination::Staked,
-    );
-    send_xt(connection, xt, Some("bond"), status);
+        controller_account_id,
+        status,
+    ))
 }
 
-pub fn multi_bond(node: &str, bonders: &[KeyPair], stake: Balance) {
-    bonders.par_iter().for_each(|bonder| {
-        let connection = create_connection(node)
-            .set_signer(bonder.clone())
-            .try_into()
-            .expect("Signer has been set");
+/// Bonds `stake` of every account of `bonders`, each being its own controller. Returns the first
+/// error encountered.
+pub fn multi_bond(node: &str, bonders: &[KeyPair], stake: Balance) -> Result<()> {
+    bonders.par_iter().try_for_each(|bonder| {
+        let connection =
+            SignedConnection::from_any_connection(create_connection(node)?, bonder.clone());
 
         let controller_account = account_from_keypair(bonder);
-        bond(&connection, stake, &controller_account, XtStatus::InBlock);
-    });
+        bond(&connection, stake, &controller_account, XtStatus::InBlock).map(|_| ())
+    })
 }
 
-pub fn validate(
-    connection: &SignedConnection,
+/// Blocking wrapper for `asynchronous::validate`.
+pub fn validate<S: Signer>(
+    connection: &SignedConnection<S>,
     validator_commission_percentage: u8,
     status: XtStatus,
-) {
-    let prefs = ValidatorPrefs {
-        blocked: false,
-        commission: Perbill::from_percent(validator_commission_percentage as u32),
-    };
-    let xt = compose_extrinsic!(connection.as_connection(), "Staking", "validate", prefs);
-    send_xt(connection, xt, Some("validate"), status);
+) -> Result<Option<H256>> {
+    block_on(asynchronous::validate(
+        connection,
+        validator_commission_percentage,
+        status,
+    ))
 }
 
-pub fn set_staking_limits(
-    connection: &RootConnection,
+/// Sets the minimal bonds of nominators and validators and the maximal numbers of them. `None` as a
+/// count removes the limit.
+pub fn set_staking_limits<S: Signer>(
+    connection: &RootConnection<S>,
     minimal_nominator_stake: u128,
     minimal_validator_stake: u128,
     max_nominators_count: Option<u32>,
     max_validators_count: Option<u32>,
     status: XtStatus,
-) {
-    let set_staking_limits_call = compose_call!(
-        connection.as_connection().metadata,
-        "Staking",
-        "set_staking_limits",
-        minimal_nominator_stake,
-        minimal_validator_stake,
-        max_nominators_count,
-        max_validators_count,
-        0_u8
-    );
-    let xt = compose_extrinsic!(
-        connection.as_connection(),
-        "Sudo",
-        "sudo",
-        set_staking_limits_call
-    );
-    send_xt(connection, xt, Some("set_staking_limits"), status);
+) -> Result<Option<H256>> {
+    let limit = |count: Option<u32>| count.map_or(ConfigOp::Remove, ConfigOp::Set);
+    let call = sudo::calls::Sudo {
+        call: Box::new(Call::Staking(StakingCall::set_staking_configs {
+            min_nominator_bond: ConfigOp::Set(minimal_nominator_stake),
+            min_validator_bond: ConfigOp::Set(minimal_validator_stake),
+            max_nominator_count: limit(max_nominators_count),
+            max_validator_count: limit(max_validators_count),
+            chill_threshold: ConfigOp::Noop,
+            min_commission: ConfigOp::Noop,
+        })),
+    };
+    let xt = compose_typed_call(&connection.as_signed(), call);
+    try_send_xt(connection, xt, Some("set_staking_limits"), status)
 }
 
-pub fn force_new_era(connection: &RootConnection, status: XtStatus) {
-    let force_new_era_call = compose_call!(
-        connection.as_connection().metadata,
-        "Staking",
-        "force_new_era"
-    );
-    let xt = compose_extrinsic!(
-        connection.as_connection(),
-        "Sudo",
-        "sudo",
-        force_new_era_call
-    );
-    send_xt(connection, xt, Some("force_new_era"), status);
+pub fn force_new_era<S: Signer>(
+    connection: &RootConnection<S>,
+    status: XtStatus,
+) -> Result<Option<H256>> {
+    let call = sudo::calls::Sudo {
+        call: Box::new(Call::Staking(StakingCall::force_new_era)),
+    };
+    let xt = compose_typed_call(&connection.as_signed(), call);
+    try_send_xt(connection, xt, Some("force_new_era"), status)
 }
 
-pub fn get_current_era<C: AnyConnection>(connection: &C) -> u32 {
+pub fn get_current_era<C: AnyConnection>(connection: &C) -> Result<u32> {
     let current_era = connection
-        .as_connection()
-        .get_storage_value("Staking", "ActiveEra", None)
-        .expect("Failed to decode ActiveEra extrinsic!")
-        .expect("ActiveEra is empty in the storage!");
+        .read(|connection| connection.get_storage_value("Staking", "ActiveEra", None))?
+        .ok_or(Error::MissingStorage("Staking", "ActiveEra"))?;
     info!(target: "aleph-client", "Current era is {}", current_era);
-    current_era
+    Ok(current_era)
+}
+
+/// The smallest bond with which an account may declare the will to validate.
+pub fn get_minimum_validator_bond<C: AnyConnection>(connection: &C) -> Result<Balance> {
+    Ok(read_typed_storage(connection, &staking::storage::MinValidatorBond)?.unwrap_or_default())
 }
 
-pub fn wait_for_full_era_completion<C: AnyConnection>(
+/// Whether `account` has been elected as a validator for `era`.
+pub fn is_era_validator<C: AnyConnection>(
     connection: &C,
-) -> anyhow::Result<BlockNumber> {
+    era: u32,
+    account: &AccountId,
+) -> Result<bool> {
+    let account = typed_account(account);
+    Ok(read_typed_storage(connection, &staking::storage::ErasStakers(&era, &account))?.is_some())
+}
+
+pub fn wait_for_full_era_completion<C: AnyConnection>(connection: &C) -> Result<BlockNumber> {
     // staking works in such a way, that when we request a controller to be a validator in era N,
     // then the changes are applied in the era N+1 (so the new validator is receiving points in N+1),
     // so that we need N+1 to finish in order to claim the reward in era N+2 for the N+1 era
-    wait_for_era_completion(connection, get_current_era(connection) + 2)
+    wait_for_era_completion(connection, get_current_era(connection)? + 2)
 }
 
-pub fn wait_for_next_era<C: AnyConnection>(connection: &C) -> anyhow::Result<BlockNumber> {
-    wait_for_era_completion(connection, get_current_era(connection) + 1)
+pub fn wait_for_next_era<C: AnyConnection>(connection: &C) -> Result<BlockNumber> {
+    wait_for_era_completion(connection, get_current_era(connection)? + 1)
 }
 
-fn wait_for_era_completion<C: AnyConnection>(
+/// Waits until the era with index `next_era_index` starts, i.e. until the previous one completes.
+pub fn wait_for_era_completion<C: AnyConnection>(
     connection: &C,
     next_era_index: u32,
-) -> anyhow::Result<BlockNumber> {
-    let sessions_per_era: u32 = connection
-        .as_connection()
-        .get_constant("Staking", "SessionsPerEra")
-        .expect("Failed to decode SessionsPerEra extrinsic!");
-    let first_session_in_next_era = next_era_index * sessions_per_era;
+) -> Result<BlockNumber> {
+    let first_session_in_next_era = next_era_index * get_sessions_per_era(connection)?;
     wait_for_session(connection, first_session_in_next_era)?;
     Ok(next_era_index)
 }
 
-pub fn payout_stakers(
-    stash_connection: &SignedConnection,
+/// The number of sessions in every era.
+pub fn get_sessions_per_era<C: AnyConnection>(connection: &C) -> Result<u32> {
+    connection.read(|connection| connection.get_constant("Staking", "SessionsPerEra"))
+}
+
+pub fn payout_stakers<S: Signer>(
+    stash_connection: &SignedConnection<S>,
     stash_account: &AccountId,
     era_number: BlockNumber,
-) {
-    let xt = compose_extrinsic!(
-        stash_connection.as_connection(),
-        "Staking",
-        "payout_stakers",
-        stash_account,
-        era_number
+) -> Result<Option<H256>> {
+    let xt = compose_typed_call(
+        stash_connection,
+        staking::calls::PayoutStakers {
+            validator_stash: typed_account(stash_account),
+            era: era_number,
+        },
     );
 
-    send_xt(
+    try_send_xt(
         stash_connection,
         xt,
         Some("payout stakers"),
         XtStatus::InBlock,
-    );
+    )
 }
 
-pub fn payout_stakers_and_assert_locked_balance(
-    stash_connection: &SignedConnection,
+pub fn payout_stakers_and_assert_locked_balance<S: Signer>(
+    stash_connection: &SignedConnection<S>,
     accounts_to_check_balance: &[AccountId],
     stash_account: &AccountId,
     era: BlockNumber,
-) {
-    let locked_stash_balances_before_payout = locks(stash_connection, accounts_to_check_balance);
-    payout_stakers(stash_connection, stash_account, era - 1);
-    let locked_stash_balances_after_payout = locks(stash_connection, accounts_to_check_balance);
+) -> Result<()> {
+    let locked_stash_balances_before_payout = locks(stash_connection, accounts_to_check_balance)?;
+    payout_stakers(stash_connection, stash_account, era - 1)?;
+    let locked_stash_balances_after_payout = locks(stash_connection, accounts_to_check_balance)?;
     locked_stash_balances_before_payout.iter()
         .zip(locked_stash_balances_after_payout.iter())
         .zip(accounts_to_check_balance.iter())
@@ -170,116 +190,103 @@ pub fn payout_stakers_and_assert_locked_balance(
                     "Expected payout to be positive in locked balance for account {}. Balance before: {}, balance after: {}",
                     account_id, balances_before[0].amount, balances_after[0].amount);
         });
+    Ok(())
 }
 
-pub fn batch_bond(
-    connection: &RootConnection,
+pub fn batch_bond<S: Signer>(
+    connection: &RootConnection<S>,
     stash_controller_accounts: &[(&AccountId, &AccountId)],
     bond_value: u128,
-    reward_destination: RewardDestination<GenericAddress>,
-) {
-    let metadata = &connection.as_connection().metadata;
-
+    reward_destination: RewardDestination<AccountId32>,
+) -> Result<Option<H256>> {
     let batch_bond_calls = stash_controller_accounts
         .iter()
-        .cloned()
         .map(|(stash_account, controller_account)| {
-            let bond_call = compose_call!(
-                metadata,
-                "Staking",
-                "bond",
-                GenericAddress::Id(controller_account.clone()),
-                Compact(bond_value),
-                reward_destination.clone()
-            );
-            compose_call!(
-                metadata,
-                "Sudo",
-                "sudo_as",
-                GenericAddress::Id(stash_account.clone()),
-                bond_call
-            )
+            Call::Sudo(SudoCall::sudo_as {
+                who: typed_address(stash_account),
+                call: Box::new(Call::Staking(StakingCall::bond {
+                    controller: typed_address(controller_account),
+                    value: bond_value,
+                    payee: reward_destination.clone(),
+                })),
+            })
         })
-        .collect::<Vec<_>>();
+        .collect();
 
-    let xt = compose_extrinsic!(
-        connection.as_connection(),
-        "Utility",
-        "batch",
-        batch_bond_calls
+    let xt = compose_typed_call(
+        &connection.as_signed(),
+        utility::calls::Batch {
+            calls: batch_bond_calls,
+        },
     );
-    send_xt(
+    try_send_xt(
         connection,
         xt,
         Some("batch of bond calls"),
         XtStatus::InBlock,
-    );
+    )
 }
 
-pub fn nominate(connection: &SignedConnection, nominee_key_pair: &KeyPair) {
-    let nominee_account_id = AccountId::from(nominee_key_pair.public());
+pub fn nominate<S: Signer>(
+    connection: &SignedConnection<S>,
+    nominee_key_pair: &KeyPair,
+) -> Result<Option<H256>> {
+    let nominee_account_id = nominee_key_pair.account_id();
 
-    let xt = connection
-        .as_connection()
-        .staking_nominate(vec![GenericAddress::Id(nominee_account_id)]);
-    send_xt(connection, xt, Some("nominate"), XtStatus::InBlock);
+    let xt = compose_typed_call(
+        connection,
+        staking::calls::Nominate {
+            targets: vec![typed_address(&nominee_account_id)],
+        },
+    );
+    try_send_xt(connection, xt, Some("nominate"), XtStatus::InBlock)
 }
 
-pub fn batch_nominate(
-    connection: &RootConnection,
+pub fn batch_nominate<S: Signer>(
+    connection: &RootConnection<S>,
     nominator_nominee_pairs: &[(&AccountId, &AccountId)],
-) {
-    let metadata = &connection.as_connection().metadata;
-
+) -> Result<Option<H256>> {
     let batch_nominate_calls = nominator_nominee_pairs
         .iter()
-        .cloned()
         .map(|(nominator, nominee)| {
-            let nominate_call = compose_call!(
-                metadata,
-                "Staking",
-                "nominate",
-                vec![GenericAddress::Id(nominee.clone())]
-            );
-            compose_call!(
-                metadata,
-                "Sudo",
-                "sudo_as",
-                GenericAddress::Id(nominator.clone()),
-                nominate_call
-            )
+            Call::Sudo(SudoCall::sudo_as {
+                who: typed_address(nominator),
+                call: Box::new(Call::Staking(StakingCall::nominate {
+                    targets: vec![typed_address(nominee)],
+                })),
+            })
         })
-        .collect::<Vec<_>>();
+        .collect();
 
-    let xt = compose_extrinsic!(
-        connection.as_connection(),
-        "Utility",
-        "batch",
-        batch_nominate_calls
+    let xt = compose_typed_call(
+        &connection.as_signed(),
+        utility::calls::Batch {
+            calls: batch_nominate_calls,
+        },
     );
-    send_xt(
+    try_send_xt(
         connection,
         xt,
         Some("batch of nominate calls"),
         XtStatus::InBlock,
-    );
+    )
+}
+
+pub fn bonded<C: AnyConnection>(connection: &C, stash: &AccountId) -> Result<Option<AccountId>> {
+    connection.read(|connection| connection.get_storage_map("Staking", "Bonded", stash, None))
 }
 
-pub fn bonded<C: AnyConnection>(connection: &C, stash: &KeyPair) -> Option<AccountId> {
-    let account_id = AccountId::from(stash.public());
-    connection
-        .as_connection()
-        .get_storage_map("Staking", "Bonded", &account_id, None)
-        .unwrap_or_else(|_| panic!("Failed to obtain Bonded for account id {}", account_id))
+/// Preferences of `stash`, if it is willing to validate.
+pub fn validator_prefs<C: AnyConnection>(
+    connection: &C,
+    stash: &AccountId,
+) -> Result<Option<ValidatorPrefs>> {
+    connection.read(|connection| connection.get_storage_map("Staking", "Validators", stash, None))
 }
 
 pub fn ledger<C: AnyConnection>(
     connection: &C,
-    controller: &KeyPair,
-) -> Option<pallet_staking::StakingLedger<AccountId, Balance>> {
-    let account_id = AccountId::from(controller.public());
-    connection
-        .as_connection()
-        .get_storage_map("Staking", "Ledger", &account_id, None)
-        .unwrap_or_else(|_| panic!("Failed to obtain Ledger for account id {}", account_id))
+    controller: &AccountId,
+) -> Result<Option<pallet_staking::StakingLedger<AccountId, Balance>>> {
+    connection.read(|connection| connection.get_storage_map("Staking", "Ledger", controller, None))
 }
@@ -1,20 +1,24 @@
-use crate::{send_xt, AnyConnection, RootConnection};
-use sp_core::Pair;
-use substrate_api_client::{compose_call, compose_extrinsic, XtStatus};
+use crate::{
+    aleph_zero::{
+        runtime_types::{aleph_runtime::Call, frame_system::pallet::Call as SystemCall},
+        sudo,
+    },
+    compose_typed_call,
+    error::Result,
+    try_send_xt, RootConnection, Signer,
+};
+use sp_core::H256;
+use substrate_api_client::XtStatus;
 
-pub fn set_code(connection: &RootConnection, runtime: Vec<u8>, status: XtStatus) {
-    let call = compose_call!(
-        connection.as_connection().metadata,
-        "System",
-        "set_code",
-        runtime
-    );
-    let xt = compose_extrinsic!(
-        connection.as_connection(),
-        "Sudo",
-        "sudo_unchecked_weight",
-        call,
-        0_u64
-    );
-    send_xt(connection, xt, Some("set_code"), status);
+pub fn set_code<S: Signer>(
+    connection: &RootConnection<S>,
+    runtime: Vec<u8>,
+    status: XtStatus,
+) -> Result<Option<H256>> {
+    let call = sudo::calls::SudoUncheckedWeight {
+        call: Box::new(Call::System(SystemCall::set_code { code: runtime })),
+        weight: 0,
+    };
+    let xt = compose_typed_call(&connection.as_signed(), call);
+    try_send_xt(connection, xt, Some("set_code"), status)
 }
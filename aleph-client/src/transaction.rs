@@ -0,0 +1,557 @@
+//! Submitting transactions with a locally cached nonce and tracking their whole lifecycle.
+//!
+//! `try_send_xt` asks the node for the nonce of the signer every time, so two concurrent senders
+//! using the same account end up with the same nonce. `TransactionManager` keeps the nonce
+//! locally (shared between all its clones) and refreshes it from the chain only when some
+//! transaction did not make it to the pool.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::{lock::Mutex, stream::BoxStream, StreamExt};
+use log::{info, warn};
+use primitives::Balance;
+use sp_core::{Pair, H256};
+use subxt::{
+    sp_core::{sr25519, Pair as _, H256 as SubxtHash},
+    sp_runtime::AccountId32,
+    BasicError, Call, Client, ClientBuilder, DefaultConfig, Error as SubxtError, PairSigner,
+    PlainTip, PolkadotExtrinsicParams, PolkadotExtrinsicParamsBuilder, SubmittableExtrinsic,
+    TransactionInBlock, TransactionProgress, TransactionStatus,
+};
+use thiserror::Error;
+
+use crate::{
+    aleph_zero::{DispatchError, Event},
+    ensure_protocol,
+    error::{Error, Result},
+    KeyPair,
+};
+
+type Progress<'client> = TransactionProgress<'client, DefaultConfig, DispatchError, Event>;
+type InBlock<'client> = TransactionInBlock<'client, DefaultConfig, DispatchError, Event>;
+
+/// Message of the pool error returned when a transaction with the same nonce is already waiting
+/// and the new one does not pay more.
+const PRIORITY_TOO_LOW: &str = "Priority is too low";
+/// How many times the tip is raised before giving up.
+const MAX_TIP_BUMPS: usize = 5;
+/// The first non-zero tip. Every subsequent bump doubles it.
+const TIP_STEP: Balance = 1_000_000_000;
+
+/// Why an included transaction failed.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum DispatchFailure {
+    #[error("{pallet}::{error}: {}", description.join(" "))]
+    Module {
+        pallet: String,
+        error: String,
+        description: Vec<String>,
+    },
+    #[error("{0}")]
+    Other(String),
+}
+
+/// A state in the lifecycle of a submitted transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TxStatus {
+    /// The transaction is in the pool, ready to be included.
+    Ready,
+    /// The transaction was included in `block`. `result` tells whether its dispatch succeeded.
+    InBlock {
+        block: H256,
+        result: std::result::Result<(), DispatchFailure>,
+    },
+    /// `block`, which contained the transaction, is no longer in the best chain.
+    Retracted(H256),
+    /// `block`, which contains the transaction, has been finalized.
+    Finalized {
+        block: H256,
+        result: std::result::Result<(), DispatchFailure>,
+    },
+    /// The transaction was removed from the pool (or replaced) without being finalized.
+    Dropped,
+    /// The transaction is no longer valid (e.g. because of an outdated nonce).
+    Invalid,
+}
+
+/// A state in the lifecycle of a submitted transaction as reported by the node, with the outcome
+/// of its dispatch already read from the blocks containing it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PoolStatus {
+    Future,
+    Ready,
+    Broadcast(Vec<String>),
+    InBlock(H256, std::result::Result<(), DispatchFailure>),
+    Retracted(H256),
+    FinalityTimeout(H256),
+    Finalized(H256, std::result::Result<(), DispatchFailure>),
+    Usurped(H256),
+    Dropped,
+    Invalid,
+}
+
+type PoolStatuses<'a> = BoxStream<'a, std::result::Result<PoolStatus, BasicError>>;
+
+/// The part of the node that `TransactionManager` talks to.
+#[async_trait]
+pub trait Submitter: Send + Sync {
+    /// The next nonce of the signing account, including the transactions in the pool.
+    async fn next_index(&self) -> std::result::Result<u32, BasicError>;
+
+    /// Signs `call` with `nonce` and `tip`, submits it and watches its lifecycle.
+    async fn submit_and_watch<C: Call + Send + Sync>(
+        &self,
+        call: C,
+        nonce: u32,
+        tip: Balance,
+    ) -> std::result::Result<PoolStatuses<'_>, BasicError>;
+}
+
+/// Submits transactions to a node, signed by one account.
+#[derive(Clone)]
+pub struct NodeSubmitter {
+    client: Client<DefaultConfig>,
+    pair: sr25519::Pair,
+    account_id: AccountId32,
+}
+
+#[async_trait]
+impl Submitter for NodeSubmitter {
+    async fn next_index(&self) -> std::result::Result<u32, BasicError> {
+        self.client
+            .rpc()
+            .system_account_next_index(&self.account_id)
+            .await
+    }
+
+    async fn submit_and_watch<C: Call + Send + Sync>(
+        &self,
+        call: C,
+        nonce: u32,
+        tip: Balance,
+    ) -> std::result::Result<PoolStatuses<'_>, BasicError> {
+        let mut signer = PairSigner::<DefaultConfig, _>::new(self.pair.clone());
+        signer.set_nonce(nonce);
+        let params = PolkadotExtrinsicParamsBuilder::new().tip(PlainTip::new(tip));
+
+        let progress: Progress<'_> =
+            SubmittableExtrinsic::<_, PolkadotExtrinsicParams<_>, _, _, _>::new(&self.client, call)
+                .sign_and_submit_then_watch(&signer, params)
+                .await?;
+        Ok(progress
+            .then(|status| async move {
+                Ok::<_, BasicError>(match status? {
+                    TransactionStatus::Future => PoolStatus::Future,
+                    TransactionStatus::Ready => PoolStatus::Ready,
+                    TransactionStatus::Broadcast(peers) => PoolStatus::Broadcast(peers),
+                    TransactionStatus::InBlock(in_block) => PoolStatus::InBlock(
+                        to_hash(in_block.block_hash()),
+                        dispatch_result(&in_block).await,
+                    ),
+                    TransactionStatus::Retracted(block) => PoolStatus::Retracted(to_hash(block)),
+                    TransactionStatus::FinalityTimeout(block) => {
+                        PoolStatus::FinalityTimeout(to_hash(block))
+                    }
+                    TransactionStatus::Finalized(in_block) => PoolStatus::Finalized(
+                        to_hash(in_block.block_hash()),
+                        dispatch_result(&in_block).await,
+                    ),
+                    TransactionStatus::Usurped(by) => PoolStatus::Usurped(to_hash(by)),
+                    TransactionStatus::Dropped => PoolStatus::Dropped,
+                    TransactionStatus::Invalid => PoolStatus::Invalid,
+                })
+            })
+            .boxed())
+    }
+}
+
+/// Submits transactions signed by one account, taking care of their nonces.
+#[derive(Clone)]
+pub struct TransactionManager<S = NodeSubmitter> {
+    submitter: S,
+    next_nonce: Arc<Mutex<Option<u32>>>,
+}
+
+impl TransactionManager {
+    /// Connects to the node at `address`. All transactions will be signed by `signer`.
+    pub async fn new(address: &str, signer: &KeyPair) -> Result<Self> {
+        let pair =
+            sr25519::Pair::from_seed_slice(&signer.to_raw_vec()).map_err(|_| Error::InvalidSeed)?;
+        let client = ClientBuilder::new()
+            .set_url(ensure_protocol(address))
+            .build()
+            .await
+            .map_err(|why| Error::Connection(address.to_string(), why.to_string()))?;
+
+        Ok(Self::with_submitter(NodeSubmitter {
+            client,
+            account_id: pair.public().into(),
+            pair,
+        }))
+    }
+}
+
+impl<S: Submitter> TransactionManager<S> {
+    /// Sends all transactions through `submitter`.
+    pub fn with_submitter(submitter: S) -> Self {
+        Self {
+            submitter,
+            next_nonce: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Submits `call` and returns the stream of its lifecycle states.
+    ///
+    /// If the pool rejects the transaction because of too low priority, it is resubmitted with
+    /// the same nonce and a higher tip. Note that a transaction with a higher tip replaces any
+    /// transaction of the same account with the same nonce that is still waiting in the pool,
+    /// including ones sent by other clients.
+    pub async fn submit<'a, C>(&'a self, call: C) -> Result<BoxStream<'a, Result<TxStatus>>>
+    where
+        C: Call + Clone + Send + Sync + 'a,
+    {
+        let nonce = self.next_nonce().await?;
+        let mut tip = 0;
+        let mut bumps = 0;
+
+        loop {
+            match self
+                .submitter
+                .submit_and_watch(call.clone(), nonce, tip)
+                .await
+            {
+                Ok(progress) => return Ok(self.track(progress)),
+                Err(why) if bumps < MAX_TIP_BUMPS && is_priority_too_low(&why) => {
+                    bumps += 1;
+                    tip = (tip * 2).max(TIP_STEP);
+                    warn!(target: "aleph-client",
+                        "Transaction `{}::{}` with nonce {} has too low priority, retrying with tip {}.",
+                        C::PALLET, C::FUNCTION, nonce, tip);
+                }
+                Err(why) => {
+                    self.resync_nonce().await;
+                    return Err(Error::Submission(why.to_string()));
+                }
+            }
+        }
+    }
+
+    /// Submits `call` and waits until it is finalized. Returns hash of the containing block.
+    pub async fn submit_and_finalize<C>(&self, call: C) -> Result<H256>
+    where
+        C: Call + Clone + Send + Sync,
+    {
+        let mut progress = self.submit(call).await?;
+        while let Some(status) = progress.next().await {
+            match status? {
+                TxStatus::Finalized { block, result } => {
+                    return result.map(|_| block).map_err(Error::Dispatch)
+                }
+                TxStatus::Dropped => return Err(Error::TransactionDropped),
+                TxStatus::Invalid => return Err(Error::TransactionInvalid),
+                _ => {}
+            }
+        }
+        Err(Error::SubscriptionClosed)
+    }
+
+    /// Forgets the cached nonce, so that the next transaction uses the one from the chain.
+    pub async fn resync_nonce(&self) {
+        *self.next_nonce.lock().await = None;
+    }
+
+    async fn next_nonce(&self) -> Result<u32> {
+        let mut next_nonce = self.next_nonce.lock().await;
+        let nonce = match *next_nonce {
+            Some(nonce) => nonce,
+            None => self
+                .submitter
+                .next_index()
+                .await
+                .map_err(|why| Error::Rpc(why.to_string()))?,
+        };
+        *next_nonce = Some(nonce + 1);
+        Ok(nonce)
+    }
+
+    fn track<'a>(&'a self, progress: PoolStatuses<'a>) -> BoxStream<'a, Result<TxStatus>> {
+        progress
+            .filter_map(move |status| async move {
+                let status = match status {
+                    Ok(PoolStatus::Future) | Ok(PoolStatus::Broadcast(_)) => return None,
+                    Ok(PoolStatus::Ready) => TxStatus::Ready,
+                    Ok(PoolStatus::InBlock(block, result)) => TxStatus::InBlock { block, result },
+                    Ok(PoolStatus::Retracted(block)) => TxStatus::Retracted(block),
+                    Ok(PoolStatus::Finalized(block, result)) => {
+                        TxStatus::Finalized { block, result }
+                    }
+                    Ok(PoolStatus::Usurped(_))
+                    | Ok(PoolStatus::FinalityTimeout(_))
+                    | Ok(PoolStatus::Dropped) => {
+                        self.resync_nonce().await;
+                        TxStatus::Dropped
+                    }
+                    Ok(PoolStatus::Invalid) => {
+                        self.resync_nonce().await;
+                        TxStatus::Invalid
+                    }
+                    Err(why) => return Some(Err(Error::Submission(why.to_string()))),
+                };
+                info!(target: "aleph-client", "Transaction status: {:?}", status);
+                Some(Ok(status))
+            })
+            .boxed()
+    }
+}
+
+fn is_priority_too_low(error: &BasicError) -> bool {
+    error.to_string().contains(PRIORITY_TOO_LOW)
+}
+
+fn to_hash(hash: SubxtHash) -> H256 {
+    H256::from(hash.to_fixed_bytes())
+}
+
+/// Decodes the outcome of the transaction from the events of the block it was included in.
+async fn dispatch_result(in_block: &InBlock<'_>) -> std::result::Result<(), DispatchFailure> {
+    match in_block.wait_for_success().await {
+        Ok(_) => Ok(()),
+        Err(SubxtError::Module(error)) => Err(DispatchFailure::Module {
+            pallet: error.pallet,
+            error: error.error,
+            description: error.description,
+        }),
+        Err(why) => Err(DispatchFailure::Other(why.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, sync::Mutex as SyncMutex};
+
+    use futures::{executor::block_on, stream};
+
+    use super::*;
+    use crate::aleph_zero::system::calls::Remark;
+
+    type Response = std::result::Result<Vec<PoolStatus>, String>;
+
+    /// Answers submissions with the scripted `responses` and records what was submitted.
+    #[derive(Default)]
+    struct MockSubmitter {
+        next_index: SyncMutex<u32>,
+        index_queries: SyncMutex<usize>,
+        responses: SyncMutex<VecDeque<Response>>,
+        submitted: SyncMutex<Vec<(u32, Balance)>>,
+    }
+
+    impl MockSubmitter {
+        fn new(next_index: u32, responses: Vec<Response>) -> Self {
+            Self {
+                next_index: SyncMutex::new(next_index),
+                responses: SyncMutex::new(responses.into()),
+                ..Default::default()
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Submitter for MockSubmitter {
+        async fn next_index(&self) -> std::result::Result<u32, BasicError> {
+            *self.index_queries.lock().unwrap() += 1;
+            Ok(*self.next_index.lock().unwrap())
+        }
+
+        async fn submit_and_watch<C: Call + Send + Sync>(
+            &self,
+            _call: C,
+            nonce: u32,
+            tip: Balance,
+        ) -> std::result::Result<PoolStatuses<'_>, BasicError> {
+            self.submitted.lock().unwrap().push((nonce, tip));
+            let response = self
+                .responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or_else(|| Ok(vec![PoolStatus::Ready]));
+            match response {
+                Ok(statuses) => Ok(stream::iter(statuses.into_iter().map(Ok)).boxed()),
+                Err(why) => Err(BasicError::Other(why)),
+            }
+        }
+    }
+
+    fn remark() -> Remark {
+        Remark { remark: vec![] }
+    }
+
+    fn statuses<S: Submitter>(manager: &TransactionManager<S>) -> Vec<TxStatus> {
+        block_on(async {
+            manager
+                .submit(remark())
+                .await
+                .expect("Submission should succeed")
+                .map(|status| status.expect("Status should be decoded"))
+                .collect::<Vec<_>>()
+                .await
+        })
+    }
+
+    fn submitted(manager: &TransactionManager<MockSubmitter>) -> Vec<(u32, Balance)> {
+        manager.submitter.submitted.lock().unwrap().clone()
+    }
+
+    #[test]
+    fn given_cached_nonce_when_submitting_then_node_is_asked_only_once() {
+        let manager = TransactionManager::with_submitter(MockSubmitter::new(7, vec![]));
+
+        statuses(&manager);
+        statuses(&manager);
+        statuses(&manager);
+
+        assert_eq!(
+            submitted(&manager)
+                .into_iter()
+                .map(|(nonce, _)| nonce)
+                .collect::<Vec<_>>(),
+            vec![7, 8, 9]
+        );
+        assert_eq!(*manager.submitter.index_queries.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn given_dropped_transaction_when_submitting_next_then_nonce_is_resynced() {
+        let manager = TransactionManager::with_submitter(MockSubmitter::new(
+            7,
+            vec![Ok(vec![PoolStatus::Ready, PoolStatus::Dropped])],
+        ));
+
+        statuses(&manager);
+        *manager.submitter.next_index.lock().unwrap() = 7;
+        statuses(&manager);
+
+        assert_eq!(submitted(&manager), vec![(7, 0), (7, 0)]);
+        assert_eq!(*manager.submitter.index_queries.lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn given_rejected_submission_when_submitting_next_then_nonce_is_resynced() {
+        let manager = TransactionManager::with_submitter(MockSubmitter::new(
+            7,
+            vec![Err(String::from("Transaction is outdated"))],
+        ));
+
+        let result = block_on(manager.submit(remark()));
+        assert!(matches!(result, Err(Error::Submission(_))));
+        *manager.submitter.next_index.lock().unwrap() = 8;
+        statuses(&manager);
+
+        assert_eq!(submitted(&manager), vec![(7, 0), (8, 0)]);
+        assert_eq!(*manager.submitter.index_queries.lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn given_too_low_priority_when_submitting_then_tip_is_bumped_with_the_same_nonce() {
+        let too_low = || Err(format!("1014: {}", PRIORITY_TOO_LOW));
+        let manager = TransactionManager::with_submitter(MockSubmitter::new(
+            3,
+            vec![too_low(), too_low(), Ok(vec![PoolStatus::Ready])],
+        ));
+
+        assert_eq!(statuses(&manager), vec![TxStatus::Ready]);
+        assert_eq!(
+            submitted(&manager),
+            vec![(3, 0), (3, TIP_STEP), (3, 2 * TIP_STEP)]
+        );
+    }
+
+    #[test]
+    fn given_always_too_low_priority_when_submitting_then_gives_up_after_max_bumps() {
+        let manager = TransactionManager::with_submitter(MockSubmitter::new(
+            3,
+            vec![Err(PRIORITY_TOO_LOW.to_string()); MAX_TIP_BUMPS + 1],
+        ));
+
+        let result = block_on(manager.submit(remark()));
+
+        assert!(matches!(result, Err(Error::Submission(_))));
+        assert_eq!(submitted(&manager).len(), MAX_TIP_BUMPS + 1);
+    }
+
+    #[test]
+    fn given_node_statuses_when_tracking_then_they_are_mapped_to_tx_statuses() {
+        let (in_block, finalized) = (H256::repeat_byte(1), H256::repeat_byte(2));
+        let failure = DispatchFailure::Other(String::from("BadOrigin"));
+        let manager = TransactionManager::with_submitter(MockSubmitter::new(
+            0,
+            vec![Ok(vec![
+                PoolStatus::Future,
+                PoolStatus::Ready,
+                PoolStatus::Broadcast(vec![String::from("peer")]),
+                PoolStatus::InBlock(in_block, Ok(())),
+                PoolStatus::Retracted(in_block),
+                PoolStatus::Finalized(finalized, Err(failure.clone())),
+            ])],
+        ));
+
+        assert_eq!(
+            statuses(&manager),
+            vec![
+                TxStatus::Ready,
+                TxStatus::InBlock {
+                    block: in_block,
+                    result: Ok(())
+                },
+                TxStatus::Retracted(in_block),
+                TxStatus::Finalized {
+                    block: finalized,
+                    result: Err(failure)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn given_transaction_leaving_pool_when_tracking_then_it_is_dropped_or_invalid() {
+        let manager = TransactionManager::with_submitter(MockSubmitter::new(
+            0,
+            vec![
+                Ok(vec![PoolStatus::Usurped(H256::zero())]),
+                Ok(vec![PoolStatus::FinalityTimeout(H256::zero())]),
+                Ok(vec![PoolStatus::Dropped]),
+                Ok(vec![PoolStatus::Invalid]),
+            ],
+        ));
+
+        let observed = (0..4).flat_map(|_| statuses(&manager)).collect::<Vec<_>>();
+
+        assert_eq!(
+            observed,
+            vec![
+                TxStatus::Dropped,
+                TxStatus::Dropped,
+                TxStatus::Dropped,
+                TxStatus::Invalid
+            ]
+        );
+        assert_eq!(*manager.submitter.index_queries.lock().unwrap(), 4);
+    }
+
+    #[test]
+    fn given_failed_dispatch_when_waiting_for_finalization_then_failure_is_returned() {
+        let failure = DispatchFailure::Other(String::from("BadOrigin"));
+        let manager = TransactionManager::with_submitter(MockSubmitter::new(
+            0,
+            vec![Ok(vec![
+                PoolStatus::InBlock(H256::zero(), Err(failure.clone())),
+                PoolStatus::Finalized(H256::zero(), Err(failure.clone())),
+            ])],
+        ));
+
+        let result = block_on(manager.submit_and_finalize(remark()));
+
+        assert!(matches!(result, Err(Error::Dispatch(f)) if f == failure));
+    }
+}
@@ -1,50 +1,62 @@
-use crate::{send_xt, AnyConnection, SignedConnection};
+use crate::{
+    aleph_zero::{
+        runtime_types::{aleph_runtime::Call, pallet_balances::pallet::Call as BalancesCall},
+        utility,
+    },
+    compose_typed_call,
+    error::Result,
+    try_send_xt,
+    typed::{sign_call, typed_address},
+    AnyConnection, SignedConnection, Signer,
+};
 use codec::Compact;
-use sp_core::Pair;
+use sp_core::H256;
 use sp_runtime::MultiAddress;
 use substrate_api_client::{
-    compose_call, compose_extrinsic, AccountId, GenericAddress, UncheckedExtrinsicV4, XtStatus,
+    compose_call, AccountId, GenericAddress, UncheckedExtrinsicV4, XtStatus,
 };
 
 pub type TransferTransaction =
     UncheckedExtrinsicV4<([u8; 2], MultiAddress<AccountId, ()>, Compact<u128>)>;
 
-pub fn transfer(
-    connection: &SignedConnection,
+pub fn transfer<S: Signer>(
+    connection: &SignedConnection<S>,
     target: &AccountId,
     value: u128,
     status: XtStatus,
-) -> TransferTransaction {
-    let xt = connection
-        .as_connection()
-        .balance_transfer(GenericAddress::Id(target.clone()), value);
-    send_xt(connection, xt.clone(), Some("transfer"), status);
-    xt
+) -> Result<TransferTransaction> {
+    let call = compose_call!(
+        connection.as_connection().metadata,
+        "Balances",
+        "transfer",
+        GenericAddress::Id(target.clone()),
+        Compact(value)
+    );
+    let xt = sign_call(connection, call);
+    try_send_xt(connection, xt.clone(), Some("transfer"), status)?;
+    Ok(xt)
 }
 
-pub fn batch_transfer(
-    connection: &SignedConnection,
+pub fn batch_transfer<S: Signer>(
+    connection: &SignedConnection<S>,
     account_keys: Vec<AccountId>,
     endowment: u128,
-) {
+) -> Result<Option<H256>> {
     let batch_endow = account_keys
-        .into_iter()
+        .iter()
         .map(|account_id| {
-            compose_call!(
-                connection.as_connection().metadata,
-                "Balances",
-                "transfer",
-                GenericAddress::Id(account_id),
-                Compact(endowment)
-            )
+            Call::Balances(BalancesCall::transfer {
+                dest: typed_address(account_id),
+                value: endowment,
+            })
         })
-        .collect::<Vec<_>>();
+        .collect();
 
-    let xt = compose_extrinsic!(connection.as_connection(), "Utility", "batch", batch_endow);
-    send_xt(
+    let xt = compose_typed_call(connection, utility::calls::Batch { calls: batch_endow });
+    try_send_xt(
         connection,
         xt,
         Some("batch of endow balances"),
         XtStatus::InBlock,
-    );
+    )
 }
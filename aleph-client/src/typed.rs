@@ -0,0 +1,131 @@
+//! Helpers for using the bindings generated from the `aleph_runtime` metadata (see `aleph_zero`)
+//! with the connections of this crate.
+//!
+//! Unlike addressing calls, storage items and events by their names, these fail to compile as
+//! soon as the runtime changes in an incompatible way.
+
+use codec::Encode;
+use sp_core::{storage::StorageKey, H256};
+use sp_runtime::generic::Era;
+use substrate_api_client::{
+    compose_call, compose_extrinsic_offline, AccountId, UncheckedExtrinsicV4,
+};
+use subxt::{
+    sp_core::crypto::AccountId32, sp_runtime::MultiAddress, Call, Event, StorageEntry,
+    StorageKeyPrefix,
+};
+
+use crate::{
+    error::{Error, Result},
+    waiting::wait_for_event,
+    AnyConnection, SignedConnection, Signer,
+};
+
+include!(concat!(env!("OUT_DIR"), "/runtime_version.rs"));
+
+/// Checks whether the node behind `connection` runs the runtime version that the bindings were
+/// generated for.
+pub fn check_runtime_version<C: AnyConnection>(connection: &C) -> Result<()> {
+    let version = &connection.as_connection().runtime_version;
+    compare_runtime_version(version.spec_version, version.transaction_version)
+}
+
+/// Fails with `Error::RuntimeVersionMismatch` unless the given versions are the ones that the
+/// bindings were generated for. Every connection of this crate is checked this way when created.
+pub(crate) fn compare_runtime_version(spec_version: u32, transaction_version: u32) -> Result<()> {
+    match (spec_version, transaction_version) {
+        (SPEC_VERSION, TRANSACTION_VERSION) => Ok(()),
+        (spec_version, transaction_version) => Err(Error::RuntimeVersionMismatch(
+            SPEC_VERSION,
+            TRANSACTION_VERSION,
+            spec_version,
+            transaction_version,
+        )),
+    }
+}
+
+/// Creates an extrinsic from the generated `call`, signed by the signer of `connection`.
+///
+/// # Panics
+///
+/// When the nonce of the signer cannot be read (see `sign_call`).
+pub fn compose_typed_call<S: Signer, T: Call + Clone>(
+    connection: &SignedConnection<S>,
+    call: T,
+) -> UncheckedExtrinsicV4<([u8; 2], T)> {
+    let call = compose_call!(
+        connection.as_connection().metadata,
+        T::PALLET,
+        T::FUNCTION,
+        call
+    );
+    sign_call(connection, call)
+}
+
+/// Creates an immortal extrinsic from `call` (prefixed with its index), signed by the signer of
+/// `connection` with the next nonce of its account.
+///
+/// # Panics
+///
+/// When the nonce cannot be read, just like `compose_extrinsic!`.
+pub(crate) fn sign_call<S: Signer, C: Encode + Clone>(
+    connection: &SignedConnection<S>,
+    call: C,
+) -> UncheckedExtrinsicV4<C> {
+    let api = connection.as_connection();
+    let signer = connection.signer();
+    let nonce = api
+        .get_account_info(&signer.account_id())
+        .expect("Should be able to read the nonce")
+        .map(|info| info.nonce)
+        .unwrap_or(0);
+    compose_extrinsic_offline!(
+        signer,
+        call,
+        nonce,
+        Era::Immortal,
+        api.genesis_hash,
+        api.genesis_hash,
+        api.runtime_version.spec_version,
+        api.runtime_version.transaction_version
+    )
+}
+
+/// `account` in the form used by the generated calls and storage items.
+pub(crate) fn typed_account(account: &AccountId) -> AccountId32 {
+    AccountId32::from(<[u8; 32]>::from(account.clone()))
+}
+
+/// `account` as an address expected by the generated calls.
+pub(crate) fn typed_address(account: &AccountId) -> MultiAddress<AccountId32, ()> {
+    MultiAddress::Id(typed_account(account))
+}
+
+/// Reads the value of the generated storage `entry`, if there is any.
+pub fn read_typed_storage<S: StorageEntry, C: AnyConnection>(
+    connection: &C,
+    entry: &S,
+) -> Result<Option<S::Value>> {
+    read_typed_storage_at(connection, entry, None)
+}
+
+/// Reads the value of the generated storage `entry` as of the block with hash `at` (the best block
+/// if not given), if there is any.
+pub fn read_typed_storage_at<S: StorageEntry, C: AnyConnection>(
+    connection: &C,
+    entry: &S,
+    at: Option<H256>,
+) -> Result<Option<S::Value>> {
+    let key = entry.key().final_key(StorageKeyPrefix::new::<S>());
+    connection.read(|connection| connection.get_storage_by_key_hash(StorageKey(key.0.clone()), at))
+}
+
+/// Waits for the first generated event `E` satisfying `predicate`.
+pub fn wait_for_typed_event<C, E, P>(connection: &C, predicate: P) -> Result<E>
+where
+    C: AnyConnection,
+    E: Event + Clone + Send + 'static,
+    P: Fn(E) -> bool,
+{
+    wait_for_event(connection, (E::PALLET, E::EVENT), predicate)
+}
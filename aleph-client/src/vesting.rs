@@ -1,14 +1,19 @@
 use anyhow::Result;
 use log::info;
 pub use pallet_vesting::VestingInfo;
-use sp_core::Pair;
-use substrate_api_client::{compose_extrinsic, GenericAddress, XtStatus::Finalized};
+use sp_core::H256;
+use substrate_api_client::XtStatus::Finalized;
 use thiserror::Error;
 
 use primitives::Balance;
 
 use crate::{
-    account_from_keypair, try_send_xt, AccountId, AnyConnection, BlockNumber, SignedConnection,
+    aleph_zero::{
+        runtime_types::pallet_vesting::vesting_info::VestingInfo as TypedVestingInfo, vesting,
+    },
+    compose_typed_call, try_send_xt,
+    typed::typed_address,
+    AccountId, AnyConnection, BlockNumber, SignedConnection, Signer,
 };
 
 /// Gathers errors from this module.
@@ -27,62 +32,72 @@ const PALLET: &str = "Vesting";
 /// Calls `pallet_vesting::vest` for the signer of `connection`, i.e. makes all unlocked balances
 /// transferable.
 ///
-/// Fails if transaction could not have been sent.
+/// Returns the hash of the block in which the transaction was finalized. Fails if transaction
+/// could not have been sent.
 ///
 /// *Note*: This function returns `Ok(_)` even if the account has no active vesting schedules
 /// and thus the extrinsic was not successful. However, semantically it is still correct.
-pub fn vest(connection: SignedConnection) -> Result<()> {
-    let vester = connection.signer();
-    let xt = compose_extrinsic!(connection.as_connection(), PALLET, "vest");
+pub fn vest<S: Signer>(connection: SignedConnection<S>) -> Result<H256> {
+    let vester = connection.account_id();
+    let xt = compose_typed_call(&connection, vesting::calls::Vest);
     let block_hash = try_send_xt(&connection, xt, Some("Vesting"), Finalized)?
         .expect("For `Finalized` status a block hash should be returned");
     info!(
         target: "aleph-client", "Vesting for the account {:?}. Finalized in block {:?}",
-        account_from_keypair(&vester), block_hash
+        vester, block_hash
     );
-    Ok(())
+    Ok(block_hash)
 }
 
 /// Calls `pallet_vesting::vest_other` by the signer of `connection` on behalf of `vest_account`,
 /// i.e. makes all unlocked balances of `vest_account` transferable.
 ///
-/// Fails if transaction could not have been sent.
+/// Returns the hash of the block in which the transaction was finalized. Fails if transaction
+/// could not have been sent.
 ///
 /// *Note*: This function returns `Ok(_)` even if the account has no active vesting schedules
 /// and thus the extrinsic was not successful. However, semantically it is still correct.
-pub fn vest_other(connection: SignedConnection, vest_account: AccountId) -> Result<()> {
-    let xt = compose_extrinsic!(
-        connection.as_connection(),
-        PALLET,
-        "vest_other",
-        GenericAddress::Id(vest_account.clone())
+pub fn vest_other<S: Signer>(
+    connection: SignedConnection<S>,
+    vest_account: AccountId,
+) -> Result<H256> {
+    let xt = compose_typed_call(
+        &connection,
+        vesting::calls::VestOther {
+            target: typed_address(&vest_account),
+        },
     );
     let block_hash = try_send_xt(&connection, xt, Some("Vesting on behalf"), Finalized)?
         .expect("For `Finalized` status a block hash should be returned");
     info!(target: "aleph-client", "Vesting on behalf of the account {:?}. Finalized in block {:?}", vest_account, block_hash);
-    Ok(())
+    Ok(block_hash)
 }
 
 /// Performs a vested transfer from the signer of `connection` to `receiver` according to
 /// `schedule`.
 ///
-/// Fails if transaction could not have been sent.
-pub fn vested_transfer(
-    connection: SignedConnection,
+/// Returns the hash of the block in which the transaction was finalized. Fails if transaction
+/// could not have been sent.
+pub fn vested_transfer<S: Signer>(
+    connection: SignedConnection<S>,
     receiver: AccountId,
     schedule: VestingSchedule,
-) -> Result<()> {
-    let xt = compose_extrinsic!(
-        connection.as_connection(),
-        PALLET,
-        "vested_transfer",
-        GenericAddress::Id(receiver.clone()),
-        schedule
+) -> Result<H256> {
+    let xt = compose_typed_call(
+        &connection,
+        vesting::calls::VestedTransfer {
+            target: typed_address(&receiver),
+            schedule: TypedVestingInfo {
+                locked: schedule.locked(),
+                per_block: schedule.per_block(),
+                starting_block: schedule.starting_block(),
+            },
+        },
     );
     let block_hash = try_send_xt(&connection, xt, Some("Vested transfer"), Finalized)?
         .expect("For `Finalized` status a block hash should be returned");
     info!(target: "aleph-client", "Vested transfer to the account {:?}. Finalized in block {:?}", receiver, block_hash);
-    Ok(())
+    Ok(block_hash)
 }
 
 /// Returns all active schedules of `who`.
@@ -93,26 +108,37 @@ pub fn get_schedules<C: AnyConnection>(
     who: AccountId,
 ) -> Result<Vec<VestingSchedule>> {
     connection
-        .as_connection()
-        .get_storage_map::<AccountId, Option<Vec<VestingSchedule>>>(PALLET, "Vesting", who, None)?
+        .read(|connection| {
+            connection.get_storage_map::<AccountId, Option<Vec<VestingSchedule>>>(
+                PALLET,
+                "Vesting",
+                who.clone(),
+                None,
+            )
+        })?
         .flatten()
         .ok_or_else(|| VestingError::NotVesting.into())
 }
 
 /// Merges two vesting schedules (at indices `idx1` and `idx2`) of the signer of `connection`.
 ///
-/// Fails if transaction could not have been sent.
+/// Returns the hash of the block in which the transaction was finalized. Fails if transaction
+/// could not have been sent.
 ///
 /// *Note*: This function returns `Ok(_)` even if the account has no active vesting schedules, or
 /// it has fewer schedules than `max(idx1, idx2) - 1` and thus the extrinsic was not successful.
-pub fn merge_schedules(connection: SignedConnection, idx1: u32, idx2: u32) -> Result<()> {
-    let who = connection.signer();
-    let xt = compose_extrinsic!(
-        connection.as_connection(),
-        PALLET,
-        "merge_schedules",
-        idx1,
-        idx2
+pub fn merge_schedules<S: Signer>(
+    connection: SignedConnection<S>,
+    idx1: u32,
+    idx2: u32,
+) -> Result<H256> {
+    let who = connection.account_id();
+    let xt = compose_typed_call(
+        &connection,
+        vesting::calls::MergeSchedules {
+            schedule1_index: idx1,
+            schedule2_index: idx2,
+        },
     );
 
     let block_hash = try_send_xt(&connection, xt, Some("Merge vesting schedules"), Finalized)?
@@ -120,6 +146,6 @@ pub fn merge_schedules(connection: SignedConnection, idx1: u32, idx2: u32) -> Re
 
     info!(target: "aleph-client", 
         "Merging vesting schedules (indices: {} and {}) for the account {:?}. Finalized in block {:?}", 
-        idx1, idx2, account_from_keypair(&who), block_hash);
-    Ok(())
+        idx1, idx2, who, block_hash);
+    Ok(block_hash)
 }
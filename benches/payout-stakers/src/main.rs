@@ -5,12 +5,13 @@ use rayon::prelude::*;
 use sp_core::{sr25519::Pair as KeyPair, Pair};
 use sp_keyring::AccountKeyring;
 use std::iter;
-use substrate_api_client::{extrinsic::staking::RewardDestination, AccountId, XtStatus};
+use substrate_api_client::{AccountId, XtStatus};
 
 use aleph_client::{
-    balances_batch_transfer, keypair_from_string, payout_stakers_and_assert_locked_balance,
-    staking_batch_bond, staking_batch_nominate, staking_multi_bond, staking_validate,
-    wait_for_next_era, AnyConnection, RootConnection, SignedConnection,
+    aleph_zero::runtime_types::pallet_staking::RewardDestination, balances_batch_transfer,
+    keypair_from_string, payout_stakers_and_assert_locked_balance, staking_batch_bond,
+    staking_batch_nominate, staking_multi_bond, staking_validate, wait_for_next_era, AnyConnection,
+    RootConnection, SignedConnection,
 };
 use primitives::{
     staking::{MAX_NOMINATORS_REWARDED_PER_VALIDATOR, MIN_NOMINATOR_BOND, MIN_VALIDATOR_BOND},
@@ -77,7 +78,7 @@ fn main() -> Result<(), anyhow::Error> {
 
     env_logger::init();
 
-    let connection = RootConnection::new(&address, sudoer);
+    let connection = RootConnection::new(&address, sudoer)?;
     let validators = match validators_seed_file {
         Some(validators_seed_file) => {
             let validators_seeds = std::fs::read_to_string(&validators_seed_file)
@@ -95,9 +96,9 @@ fn main() -> Result<(), anyhow::Error> {
     let validator_count = validators.len() as u32;
     warn!("Make sure you have exactly {} nodes run in the background, otherwise you'll see extrinsic send failed errors.",validator_count);
 
-    let validators = bond_validate(&address, validators);
+    let validators = bond_validate(&address, validators)?;
     let validators_and_its_nominators =
-        create_test_validators_and_its_nominators(&connection, validators, validator_count);
+        create_test_validators_and_its_nominators(&connection, validators, validator_count)?;
     wait_for_successive_eras(
         &address,
         &connection,
@@ -112,7 +113,7 @@ fn create_test_validators_and_its_nominators(
     connection: &RootConnection,
     validators: Vec<KeyPair>,
     validators_count: u32,
-) -> Vec<(KeyPair, Vec<AccountId32>)> {
+) -> Result<Vec<(KeyPair, Vec<AccountId32>)>, anyhow::Error> {
     validators
         .iter()
         .enumerate()
@@ -121,11 +122,11 @@ fn create_test_validators_and_its_nominators(
                 &connection.as_signed(),
                 validator_index as u32,
                 validators_count,
-            );
+            )?;
             let nominee_account = AccountId::from(validator_pair.public());
             info!("Nominating validator {}", nominee_account);
-            nominate_validator(connection, nominator_accounts.clone(), nominee_account);
-            (validator_pair.clone(), nominator_accounts)
+            nominate_validator(connection, nominator_accounts.clone(), nominee_account)?;
+            Ok((validator_pair.clone(), nominator_accounts))
         })
         .collect()
 }
@@ -153,19 +154,17 @@ fn wait_for_successive_eras<C: AnyConnection>(
             current_era,
             current_era - 1
         );
-        validators_and_its_nominators
-            .iter()
-            .for_each(|(validator, nominators)| {
-                let stash_connection = SignedConnection::new(address, validator.clone());
-                let stash_account = AccountId::from(validator.public());
-                info!("Doing payout_stakers for validator {}", stash_account);
-                payout_stakers_and_assert_locked_balance(
-                    &stash_connection,
-                    &[&nominators[..], &[stash_account.clone()]].concat(),
-                    &stash_account,
-                    current_era,
-                );
-            });
+        for (validator, nominators) in &validators_and_its_nominators {
+            let stash_connection = SignedConnection::new(address, validator.clone())?;
+            let stash_account = AccountId::from(validator.public());
+            info!("Doing payout_stakers for validator {}", stash_account);
+            payout_stakers_and_assert_locked_balance(
+                &stash_connection,
+                &[&nominators[..], &[stash_account.clone()]].concat(),
+                &stash_account,
+                current_era,
+            )?;
+        }
         current_era = wait_for_next_era(connection)?;
     }
     Ok(())
@@ -175,46 +174,45 @@ fn nominate_validator(
     connection: &RootConnection,
     nominator_accounts: Vec<AccountId>,
     nominee_account: AccountId,
-) {
+) -> Result<(), anyhow::Error> {
     let stash_validators_accounts = nominator_accounts
         .iter()
         .zip(nominator_accounts.iter())
         .collect::<Vec<_>>();
-    stash_validators_accounts
-        .chunks(BOND_CALL_BATCH_LIMIT)
-        .for_each(|chunk| {
-            let mut rng = thread_rng();
-            staking_batch_bond(
-                connection,
-                chunk,
-                (rng.gen::<u128>() % 100) * TOKEN + MIN_NOMINATOR_BOND,
-                RewardDestination::Staked,
-            )
-        });
+    for chunk in stash_validators_accounts.chunks(BOND_CALL_BATCH_LIMIT) {
+        let mut rng = thread_rng();
+        staking_batch_bond(
+            connection,
+            chunk,
+            (rng.gen::<u128>() % 100) * TOKEN + MIN_NOMINATOR_BOND,
+            RewardDestination::Staked,
+        )?;
+    }
     let nominator_nominee_accounts = nominator_accounts
         .iter()
         .zip(iter::repeat(&nominee_account))
         .collect::<Vec<_>>();
-    nominator_nominee_accounts
-        .chunks(NOMINATE_CALL_BATCH_LIMIT)
-        .for_each(|chunk| staking_batch_nominate(connection, chunk));
+    for chunk in nominator_nominee_accounts.chunks(NOMINATE_CALL_BATCH_LIMIT) {
+        staking_batch_nominate(connection, chunk)?;
+    }
+    Ok(())
 }
 
-fn bond_validate(address: &str, validators: Vec<KeyPair>) -> Vec<KeyPair> {
-    staking_multi_bond(address, &validators, MIN_VALIDATOR_BOND);
-    validators.par_iter().for_each(|account| {
+fn bond_validate(address: &str, validators: Vec<KeyPair>) -> Result<Vec<KeyPair>, anyhow::Error> {
+    staking_multi_bond(address, &validators, MIN_VALIDATOR_BOND)?;
+    validators.par_iter().try_for_each(|account| {
         let mut rng = thread_rng();
-        let connection = SignedConnection::new(address, account.clone());
-        staking_validate(&connection, rng.gen::<u8>() % 100, XtStatus::InBlock);
-    });
-    validators
+        let connection = SignedConnection::new(address, account.clone())?;
+        staking_validate(&connection, rng.gen::<u8>() % 100, XtStatus::InBlock).map(|_| ())
+    })?;
+    Ok(validators)
 }
 
 fn generate_nominator_accounts_with_minimal_bond(
     connection: &SignedConnection,
     validator_number: u32,
     validators_count: u32,
-) -> Vec<AccountId> {
+) -> Result<Vec<AccountId>, anyhow::Error> {
     info!(
         "Generating nominator accounts for validator {}",
         validator_number
@@ -227,10 +225,8 @@ fn generate_nominator_accounts_with_minimal_bond(
         })
         .map(|key_pair| AccountId::from(key_pair.public()))
         .collect::<Vec<_>>();
-    accounts
-        .chunks(TRANSFER_CALL_BATCH_LIMIT)
-        .for_each(|chunk| {
-            balances_batch_transfer(connection, chunk.to_vec(), MIN_NOMINATOR_BOND * 10);
-        });
-    accounts
+    for chunk in accounts.chunks(TRANSFER_CALL_BATCH_LIMIT) {
+        balances_batch_transfer(connection, chunk.to_vec(), MIN_NOMINATOR_BOND * 10)?;
+    }
+    Ok(accounts)
 }
@@ -2,30 +2,56 @@ use aleph_client::{
     rotate_keys as rotate, rotate_keys_raw_result, set_keys as set, staking_bond, AnyConnection,
     RootConnection, SessionKeys, SignedConnection,
 };
+use anyhow::{anyhow, Context, Result};
+use codec::Encode;
 use log::info;
 use primitives::staking::MIN_VALIDATOR_BOND;
+use serde_json::{json, Value};
+use sp_core::bytes::to_hex;
 use substrate_api_client::{AccountId, XtStatus};
 
-pub fn prepare_keys(connection: RootConnection, controller_account_id: AccountId) {
+use crate::{output::hash_json, AnySigner};
+
+pub fn prepare_keys(
+    connection: RootConnection<AnySigner>,
+    controller_account_id: AccountId,
+) -> Result<Value> {
     staking_bond(
         &connection.as_signed(),
         MIN_VALIDATOR_BOND,
         &controller_account_id,
         XtStatus::Finalized,
-    );
-    let new_keys = rotate(&connection).expect("Failed to retrieve keys");
-    set(&connection.as_signed(), new_keys, XtStatus::Finalized);
+    )
+    .context("Bonding has failed")?;
+    let new_keys = rotate(&connection).map_err(|e| anyhow!("Failed to retrieve keys: {}", e))?;
+    let block_hash = set(
+        &connection.as_signed(),
+        new_keys.clone(),
+        XtStatus::Finalized,
+    )
+    .context("Setting keys has failed")?;
+    Ok(json!({
+        "controller": controller_account_id.to_string(),
+        "bonded": MIN_VALIDATOR_BOND.to_string(),
+        "keys": to_hex(&new_keys.encode(), false),
+        "block_hash": hash_json(block_hash),
+    }))
 }
 
-pub fn set_keys(connection: SignedConnection, new_keys: String) {
-    set(
-        &connection,
-        SessionKeys::try_from(new_keys).expect("Failed to parse keys"),
-        XtStatus::InBlock,
-    );
+pub fn set_keys(connection: SignedConnection<AnySigner>, new_keys: String) -> Result<Value> {
+    let keys =
+        SessionKeys::try_from(new_keys.clone()).map_err(|_| anyhow!("Failed to parse keys"))?;
+    let block_hash =
+        set(&connection, keys, XtStatus::InBlock).context("Setting keys has failed")?;
+    Ok(json!({
+        "controller": connection.account_id().to_string(),
+        "keys": new_keys,
+        "block_hash": hash_json(block_hash),
+    }))
 }
 
-pub fn rotate_keys<C: AnyConnection>(connection: C) {
+pub fn rotate_keys<C: AnyConnection>(connection: C) -> Value {
     let new_keys = rotate_keys_raw_result(&connection).expect("Failed to retrieve keys");
     info!("Rotated keys: {:?}", new_keys);
+    json!({ "keys": new_keys })
 }
@@ -1,44 +1,64 @@
 mod keys;
+mod multisig;
+mod offline;
+mod onboarding;
+mod output;
+mod proxy;
 mod runtime;
 mod secret;
+mod signer;
 mod staking;
 mod transfer;
 mod validators;
 mod vesting;
 
 pub use keys::{prepare_keys, rotate_keys, set_keys};
+pub use multisig::{
+    multisig_account, multisig_approve, multisig_cancel, multisig_initiate, multisig_pending,
+    MultisigProposal,
+};
+pub use offline::{prepare_offline, sign_offline, submit_signed, OfflineCall, SigningPayload};
+pub use onboarding::setup_validator;
+pub use output::{parse_output_format, OutputFormat};
+pub use proxy::{add_proxy, parse_account_id, parse_proxy_type, remove_proxy};
 pub use runtime::update_runtime;
-pub use secret::prompt_password_hidden;
+pub use secret::{prompt_optional_password_hidden, prompt_password_hidden};
+pub use signer::{parse_signer_selector, AnySigner, CommandSigner, SignerSelector};
 pub use staking::{bond, force_new_era, set_staking_limits, validate};
 pub use transfer::transfer;
 pub use validators::change_validators;
 pub use vesting::{vest, vest_other, vested_transfer};
 
-use aleph_client::{keypair_from_string, RootConnection, SignedConnection};
+use aleph_client::{ConnectionPool, Receipts, RootConnection, SignedConnection};
 
 pub struct ConnectionConfig {
-    node_endpoint: String,
-    signer_seed: String,
+    node_endpoints: Vec<String>,
+    signer: AnySigner,
+    /// Where the transactions submitted by the command are recorded.
+    receipts: Receipts,
 }
 
 impl ConnectionConfig {
-    pub fn new(node_endpoint: String, signer_seed: String) -> Self {
+    pub fn new(node_endpoints: Vec<String>, signer: AnySigner, receipts: Receipts) -> Self {
         ConnectionConfig {
-            node_endpoint,
-            signer_seed,
+            node_endpoints,
+            signer,
+            receipts,
         }
     }
 }
 
-impl From<ConnectionConfig> for SignedConnection {
+impl From<ConnectionConfig> for SignedConnection<AnySigner> {
     fn from(cfg: ConnectionConfig) -> Self {
-        let key = keypair_from_string(&cfg.signer_seed);
-        SignedConnection::new(cfg.node_endpoint.as_str(), key)
+        ConnectionPool::new(&cfg.node_endpoints)
+            .and_then(|pool| pool.sign(cfg.signer))
+            .expect("At least one of the nodes should be healthy")
+            .with_receipts(cfg.receipts)
     }
 }
 
-impl From<ConnectionConfig> for RootConnection {
+impl From<ConnectionConfig> for RootConnection<AnySigner> {
     fn from(cfg: ConnectionConfig) -> Self {
-        RootConnection::from(Into::<SignedConnection>::into(cfg))
+        RootConnection::from(Into::<SignedConnection<AnySigner>>::into(cfg))
     }
 }
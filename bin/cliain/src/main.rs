@@ -1,29 +1,48 @@
-use aleph_client::{keypair_from_string, print_storages, BlockNumber, SignedConnection};
+use aleph_client::{
+    print_storages, storages_json, BlockNumber, ConnectionPool, ProxyType, Receipts,
+    SignedConnection, Signer,
+};
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use log::{error, info};
-use sp_core::Pair;
-use std::env;
+use serde_json::{json, Value};
+use std::{env, path::PathBuf};
 use substrate_api_client::AccountId;
 
 use cliain::{
-    bond, change_validators, force_new_era, prepare_keys, prompt_password_hidden, rotate_keys,
-    set_keys, set_staking_limits, transfer, update_runtime, validate, vest, vest_other,
-    vested_transfer, ConnectionConfig,
+    add_proxy, bond, change_validators, force_new_era, multisig_account, multisig_approve,
+    multisig_cancel, multisig_initiate, multisig_pending, parse_account_id, parse_output_format,
+    parse_proxy_type, parse_signer_selector, prepare_keys, prepare_offline, remove_proxy,
+    rotate_keys, set_keys, set_staking_limits, setup_validator, sign_offline, submit_signed,
+    transfer, update_runtime, validate, vest, vest_other, vested_transfer, AnySigner,
+    ConnectionConfig, OfflineCall, OutputFormat, SignerSelector,
 };
 use primitives::Balance;
 
 #[derive(Debug, Parser, Clone)]
 #[clap(version = "1.0")]
 struct Config {
-    /// WS endpoint address of the node to connect to
+    /// WS endpoint address of the node to connect to. Can be given several times to fail over
+    /// between nodes of the same chain
     #[clap(long, default_value = "127.0.0.1:9944")]
-    pub node: String,
+    pub node: Vec<String>,
 
-    /// The seed of the key to use for signing calls
+    /// The seed of the key to use for signing calls (with `--signer seed`)
     /// If not given, a user is prompted to provide seed
     #[clap(long)]
     pub seed: Option<String>,
 
+    /// Where to take the signing key from: `seed`, `keystore:<path>:<SS58 id>` (a keystore
+    /// directory with `acco` keys), `json:<path>` (a polkadot.js export) or
+    /// `external:<program>:<SS58 id>` (a program signing the payloads, e.g. with a hardware wallet)
+    #[clap(long, default_value = "seed", parse(try_from_str = parse_signer_selector))]
+    pub signer: SignerSelector,
+
+    /// Format of the result: `text` (logs only) or `json` (a single JSON object with the queried
+    /// values, submitted transactions and their events, printed to stdout)
+    #[clap(long, default_value = "text", parse(try_from_str = parse_output_format))]
+    pub output: OutputFormat,
+
     /// Specific command that executes either a signed transaction or is an auxiliary command
     #[clap(subcommand)]
     pub command: Command,
@@ -31,6 +50,21 @@ struct Config {
 
 #[derive(Debug, Clone, Subcommand)]
 enum Command {
+    /// Make the given account a proxy of the signer account
+    AddProxy {
+        /// SS58 id of the proxy account
+        #[clap(long, parse(try_from_str = parse_account_id))]
+        delegate: AccountId,
+
+        /// Calls the proxy may make: any, non-transfer, staking, session-keys or governance
+        #[clap(long, parse(try_from_str = parse_proxy_type))]
+        proxy_type: ProxyType,
+
+        /// Number of blocks the proxy has to announce its calls in advance
+        #[clap(long, default_value = "0")]
+        delay: BlockNumber,
+    },
+
     /// Staking call to bond stash with controller
     Bond {
         /// SS58 id of the controller account
@@ -52,9 +86,83 @@ enum Command {
     /// Force new era in staking world. Requires sudo.
     ForceNewEra,
 
+    /// Print the account of a multisig party. Does not need the signer
+    MultisigAccount {
+        /// SS58 ids of all the members
+        #[clap(long, value_delimiter = ',')]
+        members: Vec<String>,
+
+        /// Number of approvals needed to dispatch a call
+        #[clap(long)]
+        threshold: u16,
+    },
+
+    /// Approve the call from a proposal file. The last needed approval dispatches the call
+    MultisigApprove {
+        /// File written by `multisig-initiate`
+        #[clap(long)]
+        proposal: PathBuf,
+    },
+
+    /// Cancel the aggregation for the call from a proposal file. Only its initiator can do that
+    MultisigCancel {
+        /// File written by `multisig-initiate`
+        #[clap(long)]
+        proposal: PathBuf,
+    },
+
+    /// Start aggregating approvals for a call and write the proposal file for the other members
+    MultisigInitiate {
+        /// SS58 ids of all the members, including the signer
+        #[clap(long, value_delimiter = ',')]
+        members: Vec<String>,
+
+        /// Number of approvals needed to dispatch a call
+        #[clap(long)]
+        threshold: u16,
+
+        /// File to write the hex encoded proposal to
+        #[clap(long)]
+        proposal: PathBuf,
+
+        /// Call to propose
+        #[clap(subcommand)]
+        call: OfflineCall,
+    },
+
+    /// List ongoing aggregations of a multisig party. Does not need the signer
+    MultisigPending {
+        /// SS58 ids of all the members
+        #[clap(long, value_delimiter = ',')]
+        members: Vec<String>,
+
+        /// Number of approvals needed to dispatch a call
+        #[clap(long)]
+        threshold: u16,
+    },
+
     /// Associate the node with a specific staking account.
     PrepareKeys,
 
+    /// Write down everything needed to sign a call offline. Does not need the signer
+    PrepareOffline {
+        /// SS58 id of the account that will sign the call
+        #[clap(long)]
+        account: String,
+
+        /// Number of blocks the transaction stays valid for. If not given, it never expires
+        #[clap(long)]
+        mortality: Option<u64>,
+
+        /// File to write the hex encoded payload to. If not given, it is printed to stdout
+        #[clap(long)]
+        output: Option<PathBuf>,
+
+        /// Call to sign
+        #[clap(subcommand)]
+        call: OfflineCall,
+    },
+
     /// Call rotate_keys() RPC call and prints them to stdout
     RotateKeys,
 
@@ -66,9 +174,27 @@ enum Command {
         new_keys: String,
     },
 
-    /// Command to convert given seed to SS58 Account id
+    /// Command to convert given signer to SS58 Account id
     SeedToSS58,
 
+    /// Sign a payload written by `prepare-offline`. Does not connect to any node
+    SignOffline {
+        /// File with the hex encoded payload. If not given, it is read from stdin
+        #[clap(long)]
+        input: Option<PathBuf>,
+
+        /// File to write the hex encoded extrinsic to. If not given, it is printed to stdout
+        #[clap(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Submit an extrinsic written by `sign-offline`. Does not need the signer
+    SubmitSigned {
+        /// File with the hex encoded extrinsic. If not given, it is read from stdin
+        #[clap(long)]
+        input: Option<PathBuf>,
+    },
+
     /// Sets lower bound for nominator and validator. Requires root account.
     SetStakingLimits {
         /// Nominator lower bound
@@ -99,6 +225,21 @@ enum Command {
         to_account: String,
     },
 
+    /// Remove a proxy of the signer account, type and delay must be the same as when it was added
+    RemoveProxy {
+        /// SS58 id of the proxy account
+        #[clap(long, parse(try_from_str = parse_account_id))]
+        delegate: AccountId,
+
+        /// Calls the proxy may make: any, non-transfer, staking, session-keys or governance
+        #[clap(long, parse(try_from_str = parse_proxy_type))]
+        proxy_type: ProxyType,
+
+        /// Number of blocks the proxy has to announce its calls in advance
+        #[clap(long, default_value = "0")]
+        delay: BlockNumber,
+    },
+
     /// Send new runtime (requires sudo account)
     UpdateRuntime {
         #[clap(long)]
@@ -106,6 +247,10 @@ enum Command {
         runtime: String,
     },
 
+    /// Commands for validators
+    #[clap(subcommand)]
+    Validator(ValidatorCommand),
+
     /// Call staking validate call for a given controller
     Validate {
         /// Validator commission percentage
@@ -146,46 +291,132 @@ enum Command {
     DebugStorage,
 }
 
+#[derive(Debug, Clone, Subcommand)]
+enum ValidatorCommand {
+    /// Become a validator: check the balance, bond, rotate keys on the validator node, set them,
+    /// validate and wait for joining the committee. Steps that are already done on chain are
+    /// skipped, so the command can be run again after a failure. The signer is both stash and
+    /// controller
+    Setup {
+        /// WS endpoint address of the validator node, where session keys are generated. Defaults
+        /// to the node given with `--node`
+        #[clap(long)]
+        validator_node: Option<String>,
+
+        /// Stake to bond (in tokens). Defaults to the minimal validator bond set on chain
+        #[clap(long)]
+        stake_tokens: Option<u64>,
+
+        /// Validator commission percentage
+        #[clap(long)]
+        commission_percentage: u8,
+
+        /// Do not wait (up to two eras) for the validator to join the committee of a session
+        #[clap(long)]
+        no_wait: bool,
+    },
+}
+
 fn main() {
     init_env();
 
     let Config {
         node,
         seed,
+        signer,
+        output,
         command,
     } = Config::parse();
 
-    let seed = match seed {
-        Some(seed) => seed,
-        None => match prompt_password_hidden("Provide seed for the signer account:") {
-            Ok(seed) => seed,
-            Err(e) => {
-                error!("Failed to parse prompt with error {:?}! Exiting.", e);
-                std::process::exit(1);
-            }
-        },
-    };
-    let cfg = ConnectionConfig::new(node, seed.clone());
-    match command {
-        Command::ChangeValidators { validators } => change_validators(cfg.into(), validators),
-        Command::PrepareKeys => {
-            let key = keypair_from_string(&seed);
-            let controller_account_id = AccountId::from(key.public());
-            prepare_keys(cfg.into(), controller_account_id);
+    let receipts = Receipts::new();
+    match run(command, node.clone(), seed, signer, output, &receipts) {
+        Ok(result) => output.finish(&node, &receipts, result),
+        Err(e) => {
+            error!("{:#}", e);
+            output.fail(&node, &receipts, &e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Runs `command` and returns its result. The transactions it submits are recorded in `receipts`.
+fn run(
+    command: Command,
+    node: Vec<String>,
+    seed: Option<String>,
+    signer: SignerSelector,
+    output: OutputFormat,
+    receipts: &Receipts,
+) -> Result<Value> {
+    let command = match command {
+        Command::PrepareOffline {
+            account,
+            mortality,
+            output: file,
+            call,
+        } => {
+            return Ok(prepare_offline(
+                connection_pool(&node)?,
+                account,
+                mortality,
+                call,
+                file,
+                output,
+            ))
+        }
+        Command::SubmitSigned { input } => return submit_signed(connection_pool(&node)?, input),
+        Command::MultisigAccount { members, threshold } => {
+            return multisig_account(members, threshold)
+        }
+        Command::MultisigPending { members, threshold } => {
+            return multisig_pending(connection_pool(&node)?, members, threshold)
         }
+        Command::SignOffline {
+            input,
+            output: file,
+        } => return Ok(sign_offline(&signer.signer(seed), input, file, output)),
+        command => command,
+    };
+
+    let signer = signer.signer(seed);
+    let cfg = ConnectionConfig::new(node.clone(), signer.clone(), receipts.clone());
+    Ok(match command {
+        Command::AddProxy {
+            delegate,
+            proxy_type,
+            delay,
+        } => add_proxy(cfg.into(), delegate, proxy_type, delay)?,
+        Command::ChangeValidators { validators } => change_validators(cfg.into(), validators)?,
+        Command::PrepareKeys => prepare_keys(cfg.into(), signer.account_id())?,
         Command::Bond {
             controller_account,
             initial_stake_tokens,
-        } => bond(cfg.into(), initial_stake_tokens, controller_account),
-        Command::SetKeys { new_keys } => set_keys(cfg.into(), new_keys),
+        } => bond(cfg.into(), initial_stake_tokens, controller_account)?,
+        Command::SetKeys { new_keys } => set_keys(cfg.into(), new_keys)?,
         Command::Validate {
             commission_percentage,
-        } => validate(cfg.into(), commission_percentage),
+        } => validate(cfg.into(), commission_percentage)?,
+        Command::Validator(ValidatorCommand::Setup {
+            validator_node,
+            stake_tokens,
+            commission_percentage,
+            no_wait,
+        }) => setup_validator(
+            cfg.into(),
+            validator_node,
+            stake_tokens,
+            commission_percentage,
+            !no_wait,
+        )
+        .unwrap_or_else(|report| {
+            output.finish(&node, receipts, report);
+            std::process::exit(1);
+        }),
         Command::Transfer {
             amount_in_tokens,
             to_account,
-        } => transfer(cfg.into(), amount_in_tokens, to_account),
-        Command::RotateKeys => rotate_keys::<SignedConnection>(cfg.into()),
+        } => transfer(cfg.into(), amount_in_tokens, to_account)?,
+        Command::RotateKeys => rotate_keys::<SignedConnection<AnySigner>>(cfg.into()),
         Command::SetStakingLimits {
             minimal_nominator_stake,
             minimal_validator_stake,
@@ -197,18 +428,40 @@ fn main() {
             minimal_validator_stake,
             max_nominators_count,
             max_validators_count,
-        ),
-        Command::ForceNewEra => {
-            force_new_era(cfg.into());
+        )?,
+        Command::ForceNewEra => force_new_era(cfg.into())?,
+        Command::SeedToSS58 => {
+            let account = signer.public().to_string();
+            info!("SS58 Address: {}", account);
+            json!({ "account": account })
         }
-        Command::SeedToSS58 => info!(
-            "SS58 Address: {}",
-            keypair_from_string(&seed).public().to_string()
-        ),
-        Command::DebugStorage => print_storages::<SignedConnection>(&cfg.into()),
-        Command::UpdateRuntime { runtime } => update_runtime(cfg.into(), runtime),
-        Command::Vest => vest(cfg.into()),
-        Command::VestOther { vesting_account } => vest_other(cfg.into(), vesting_account),
+        Command::MultisigInitiate {
+            members,
+            threshold,
+            proposal,
+            call,
+        } => multisig_initiate(cfg.into(), members, threshold, call, proposal)?,
+        Command::MultisigApprove { proposal } => multisig_approve(cfg.into(), proposal)?,
+        Command::MultisigCancel { proposal } => multisig_cancel(cfg.into(), proposal)?,
+        Command::PrepareOffline { .. }
+        | Command::SubmitSigned { .. }
+        | Command::MultisigAccount { .. }
+        | Command::MultisigPending { .. }
+        | Command::SignOffline { .. } => unreachable!("Handled before choosing the signer"),
+        Command::DebugStorage => match output {
+            OutputFormat::Text => {
+                print_storages::<SignedConnection<AnySigner>>(&cfg.into())?.into()
+            }
+            OutputFormat::Json => storages_json::<SignedConnection<AnySigner>>(&cfg.into())?,
+        },
+        Command::RemoveProxy {
+            delegate,
+            proxy_type,
+            delay,
+        } => remove_proxy(cfg.into(), delegate, proxy_type, delay)?,
+        Command::UpdateRuntime { runtime } => update_runtime(cfg.into(), runtime)?,
+        Command::Vest => vest(cfg.into())?,
+        Command::VestOther { vesting_account } => vest_other(cfg.into(), vesting_account)?,
         Command::VestedTransfer {
             to_account,
             amount_in_tokens,
@@ -220,8 +473,12 @@ fn main() {
             amount_in_tokens,
             per_block,
             starting_block,
-        ),
-    }
+        )?,
+    })
+}
+
+fn connection_pool(node: &[String]) -> Result<ConnectionPool> {
+    ConnectionPool::new(node).context("None of the nodes is healthy")
 }
 
 fn init_env() {
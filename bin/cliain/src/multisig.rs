@@ -0,0 +1,288 @@
+//! Multisig workflow: one member initiates an aggregation for a call and writes a proposal file,
+//! which the other members use to approve (or the initiator to cancel) it.
+
+use std::path::PathBuf;
+
+use aleph_client::{
+    compute_call_hash, AnyConnection, MultisigParty, SignatureAggregation, SignedConnection,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use codec::{Decode, Encode};
+use log::info;
+use serde_json::{json, Value};
+use sp_core::H256;
+use substrate_api_client::{AccountId, UncheckedExtrinsicV4};
+
+use crate::{
+    offline::{
+        describe_call, parse_account, read_hex, verify_description, write_hex_file, EncodedCall,
+        OfflineCall,
+    },
+    AnySigner,
+};
+
+/// Everything the members need to know about a call proposed to their party.
+#[derive(Clone, Debug, Encode, Decode)]
+pub struct MultisigProposal {
+    pub members: Vec<AccountId>,
+    pub threshold: u16,
+    /// `call` as decoded by `describe_call`. It is checked against `call` when read.
+    pub description: String,
+    /// SCALE encoded call.
+    pub call: Vec<u8>,
+    pub call_hash: [u8; 32],
+}
+
+impl MultisigProposal {
+    fn new(
+        party: &MultisigParty<AnySigner>,
+        call: OfflineCall,
+        connection: &SignedConnection<AnySigner>,
+    ) -> Self {
+        let call = call.encode_call(&connection.as_connection());
+        let mut proposal = Self {
+            members: party.get_members().to_vec(),
+            threshold: party.get_threshold(),
+            description: describe_call(&call).expect("Composed call should be decodable"),
+            call,
+            call_hash: Default::default(),
+        };
+        proposal.call_hash = compute_call_hash(&proposal.xt());
+        proposal
+    }
+
+    /// Reads the proposal from `path`. Fails if its call hash or description do not match its
+    /// call, as then the members would approve something else than they are shown.
+    fn read(path: PathBuf) -> Result<Self> {
+        let proposal = Self::decode(&mut read_hex(Some(path.clone())).as_slice())
+            .with_context(|| format!("{} does not contain a multisig proposal", path.display()))?;
+        proposal.verify()?;
+        info!(
+            "Proposal of {} with call hash {:?}",
+            proposal.description,
+            H256::from(proposal.call_hash)
+        );
+        Ok(proposal)
+    }
+
+    fn verify(&self) -> Result<()> {
+        let call_hash = compute_call_hash(&self.xt());
+        if call_hash != self.call_hash {
+            bail!(
+                "The proposal declares call hash {:?}, but its call hashes to {:?}",
+                H256::from(self.call_hash),
+                H256::from(call_hash)
+            );
+        }
+        verify_description(&self.description, &self.call).map_err(|e| anyhow!(e))
+    }
+
+    fn party(&self) -> Result<MultisigParty<AnySigner>> {
+        MultisigParty::from_accounts(self.members.clone(), self.threshold)
+            .context("The proposal does not describe a valid party")
+    }
+
+    fn xt(&self) -> UncheckedExtrinsicV4<EncodedCall> {
+        UncheckedExtrinsicV4 {
+            signature: None,
+            function: EncodedCall(self.call.clone()),
+        }
+    }
+}
+
+fn party(members: Vec<String>, threshold: u16) -> Result<MultisigParty<AnySigner>> {
+    let members = members.iter().map(|m| parse_account(m)).collect();
+    MultisigParty::from_accounts(members, threshold).context("Invalid party")
+}
+
+/// The party of `connection` signer and index of the signer within the party.
+fn join(
+    party: MultisigParty<AnySigner>,
+    connection: &SignedConnection<AnySigner>,
+) -> Result<(MultisigParty<AnySigner>, usize)> {
+    let account = connection.account_id();
+    let party = party
+        .with_signer(connection.signer())
+        .context("Signer cannot act for the party")?;
+    let index = party
+        .get_member_index(account)
+        .expect("Signer has just joined the party");
+    Ok((party, index))
+}
+
+/// Prints the account of the party of `members` with `threshold`.
+pub fn multisig_account(members: Vec<String>, threshold: u16) -> Result<Value> {
+    let account = party(members, threshold)?.get_account();
+    info!("Multisig account: {}", account);
+    Ok(json!({ "account": account.to_string() }))
+}
+
+/// Starts an aggregation of approvals for `call` and writes the proposal to `proposal_file`.
+pub fn multisig_initiate(
+    connection: SignedConnection<AnySigner>,
+    members: Vec<String>,
+    threshold: u16,
+    call: OfflineCall,
+    proposal_file: PathBuf,
+) -> Result<Value> {
+    let (party, author) = join(party(members, threshold)?, &connection)?;
+    let proposal = MultisigProposal::new(&party, call, &connection);
+
+    let aggregation = party
+        .initiate_aggregation_with_call(&connection, proposal.xt(), false, author)
+        .context("Initiating the aggregation has failed")?;
+    info!(
+        "Aggregation started at {:?}, share the proposal with the other members",
+        aggregation.get_timepoint()
+    );
+    write_hex_file(&proposal_file, &proposal.encode());
+    Ok(aggregation_json(&aggregation))
+}
+
+fn aggregation_json(aggregation: &SignatureAggregation) -> Value {
+    let timepoint = aggregation.get_timepoint();
+    json!({
+        "call_hash": format!("{:?}", H256::from(aggregation.get_call_hash())),
+        "timepoint": { "height": timepoint.height, "index": timepoint.index },
+        "approvals": aggregation.num_of_approvals(),
+        "approvers": aggregation
+            .get_approvers()
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn print_aggregation(aggregation: &SignatureAggregation, threshold: u16) {
+    info!(
+        "Call hash {:?}, started at {:?}, {} of {} approvals: {:?}",
+        H256::from(aggregation.get_call_hash()),
+        aggregation.get_timepoint(),
+        aggregation.num_of_approvals(),
+        threshold,
+        aggregation
+            .get_approvers()
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<_>>()
+    );
+}
+
+/// Prints all ongoing aggregations of the party of `members` with `threshold`.
+pub fn multisig_pending<C: AnyConnection>(
+    connection: C,
+    members: Vec<String>,
+    threshold: u16,
+) -> Result<Value> {
+    let aggregations = party(members, threshold)?
+        .get_pending_aggregations(&connection)
+        .context("Reading pending aggregations has failed")?;
+    if aggregations.is_empty() {
+        info!("No pending aggregations");
+    }
+    Ok(aggregations
+        .iter()
+        .map(|aggregation| {
+            print_aggregation(aggregation, threshold);
+            aggregation_json(aggregation)
+        })
+        .collect())
+}
+
+/// Approves the call from `proposal_file`. The approval that reaches the threshold also dispatches
+/// the call.
+pub fn multisig_approve(
+    connection: SignedConnection<AnySigner>,
+    proposal_file: PathBuf,
+) -> Result<Value> {
+    let proposal =
+        MultisigProposal::read(proposal_file).context("Refusing to approve the proposal")?;
+    let (party, member) = join(proposal.party()?, &connection)?;
+    let aggregation = party
+        .get_aggregation(&connection, proposal.call_hash)
+        .context("Reading the aggregation has failed")?;
+    if aggregation
+        .get_approvers()
+        .contains(&party.get_members()[member])
+    {
+        bail!("The signer has already approved this call");
+    }
+
+    let aggregation = if aggregation.num_of_approvals() + 1 >= proposal.threshold as usize {
+        party.approve_with_call(&connection, member, aggregation, proposal.xt(), false)
+    } else {
+        party.approve(&connection, member, aggregation)
+    }
+    .context("Approving has failed")?;
+    print_aggregation(&aggregation, proposal.threshold);
+    Ok(aggregation_json(&aggregation))
+}
+
+/// Cancels the aggregation for the call from `proposal_file`. Only its initiator can do that.
+pub fn multisig_cancel(
+    connection: SignedConnection<AnySigner>,
+    proposal_file: PathBuf,
+) -> Result<Value> {
+    let proposal =
+        MultisigProposal::read(proposal_file).context("Refusing to cancel the proposal")?;
+    let (party, member) = join(proposal.party()?, &connection)?;
+    let aggregation = party
+        .get_aggregation(&connection, proposal.call_hash)
+        .context("Reading the aggregation has failed")?;
+    let cancelled = aggregation_json(&aggregation);
+    party
+        .cancel(&connection, member, aggregation)
+        .context("Cancelling has failed")?;
+    info!("Aggregation has been cancelled");
+    Ok(cancelled)
+}
+
+#[cfg(test)]
+mod tests {
+    use aleph_client::aleph_zero::runtime_types::{aleph_runtime::Call, frame_system};
+
+    use super::*;
+
+    fn proposal(remark: Vec<u8>) -> MultisigProposal {
+        let call = Call::System(frame_system::pallet::Call::remark { remark }).encode();
+        let mut proposal = MultisigProposal {
+            members: vec![AccountId::new([1; 32]), AccountId::new([2; 32])],
+            threshold: 2,
+            description: describe_call(&call).unwrap(),
+            call,
+            call_hash: Default::default(),
+        };
+        proposal.call_hash = compute_call_hash(&proposal.xt());
+        proposal
+    }
+
+    #[test]
+    fn given_consistent_proposal_when_verifying_then_it_is_accepted() {
+        let proposal = proposal(vec![1, 2, 3]);
+
+        let decoded = MultisigProposal::decode(&mut proposal.encode().as_slice()).unwrap();
+
+        assert!(decoded.verify().is_ok());
+    }
+
+    #[test]
+    fn given_call_swapped_for_another_when_verifying_then_it_is_refused() {
+        let mut proposal = proposal(vec![1, 2, 3]);
+        let other = self::proposal(vec![4]);
+        proposal.call = other.call;
+
+        assert!(proposal.verify().is_err());
+
+        // Even if the description is swapped as well, the call hash gives it away.
+        proposal.description = other.description;
+        assert!(proposal.verify().is_err());
+    }
+
+    #[test]
+    fn given_matching_hash_but_other_description_when_verifying_then_it_is_refused() {
+        let mut proposal = proposal(vec![1, 2, 3]);
+        proposal.description = self::proposal(vec![4]).description;
+
+        assert!(proposal.verify().is_err());
+    }
+}
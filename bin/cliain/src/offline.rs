@@ -0,0 +1,435 @@
+//! Signing transactions on a machine that is never connected to the network.
+//!
+//! The workflow has three steps:
+//! 1. `prepare_offline` (online, no seed needed) reads everything a signature depends on from the
+//!    chain and writes it down as a `SigningPayload`,
+//! 2. `sign_offline` (offline) signs the payload and writes down the extrinsic,
+//! 3. `submit_signed` (online, no seed needed) sends the extrinsic to the chain.
+//!
+//! Both the payload and the extrinsic are written as a single line of hex, so they can be moved
+//! between machines on a usb stick or as a QR code.
+
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use aleph_client::{
+    aleph_zero::runtime_types::aleph_runtime::Call, asynchronous, AnyConnection, BlockNumber,
+    Connection, Header, Signer, VestingSchedule,
+};
+use anyhow::Context;
+use clap::Subcommand;
+use codec::{Compact, Decode, Encode, Output};
+use futures::executor::block_on;
+use log::{error, info};
+use pallet_staking::{RewardDestination, ValidatorPrefs};
+use primitives::{Balance, TOKEN};
+use serde_json::{json, Value};
+use sp_core::{
+    bytes::{from_hex, to_hex},
+    crypto::Ss58Codec,
+    hashing::blake2_256,
+    H256,
+};
+use sp_runtime::{generic::Era, Perbill};
+use substrate_api_client::{
+    compose_call, compose_extrinsic_offline, AccountId, GenericAddress, XtStatus,
+};
+
+use crate::{output::hash_json, OutputFormat};
+
+/// Calls that can be signed offline or proposed to a multisig party.
+#[derive(Debug, Clone, Subcommand)]
+pub enum OfflineCall {
+    /// Staking call to bond stash with controller
+    Bond {
+        /// SS58 id of the controller account
+        #[clap(long)]
+        controller_account: String,
+
+        /// a Stake to bond (in tokens)
+        #[clap(long)]
+        initial_stake_tokens: u32,
+    },
+
+    /// Call staking validate call for a given controller
+    Validate {
+        /// Validator commission percentage
+        #[clap(long)]
+        commission_percentage: u8,
+    },
+
+    /// Transfer funds via balances pallet
+    Transfer {
+        /// Number of tokens to send,
+        #[clap(long)]
+        amount_in_tokens: u64,
+
+        /// SS58 id of target account
+        #[clap(long)]
+        to_account: String,
+    },
+
+    /// Transfer funds with a vesting schedule
+    VestedTransfer {
+        /// Number of tokens to send.
+        #[clap(long)]
+        amount_in_tokens: u64,
+
+        /// SS58 id of target account
+        #[clap(long)]
+        to_account: String,
+
+        /// How much balance (in rappens, not in tokens) should be unlocked per block.
+        #[clap(long)]
+        per_block: Balance,
+
+        /// Block number when unlocking should start.
+        #[clap(long)]
+        starting_block: BlockNumber,
+    },
+}
+
+impl OfflineCall {
+    /// SCALE encoded call, as it is put into an extrinsic.
+    pub(crate) fn encode_call(self, connection: &Connection) -> Vec<u8> {
+        match self {
+            OfflineCall::Bond {
+                controller_account,
+                initial_stake_tokens,
+            } => compose_call!(
+                connection.metadata,
+                "Staking",
+                "bond",
+                GenericAddress::Id(parse_account(&controller_account)),
+                Compact(initial_stake_tokens as Balance * TOKEN),
+                RewardDestination::<GenericAddress>::Staked
+            )
+            .encode(),
+            OfflineCall::Validate {
+                commission_percentage,
+            } => compose_call!(
+                connection.metadata,
+                "Staking",
+                "validate",
+                ValidatorPrefs {
+                    blocked: false,
+                    commission: Perbill::from_percent(commission_percentage as u32),
+                }
+            )
+            .encode(),
+            OfflineCall::Transfer {
+                amount_in_tokens,
+                to_account,
+            } => compose_call!(
+                connection.metadata,
+                "Balances",
+                "transfer",
+                GenericAddress::Id(parse_account(&to_account)),
+                Compact(amount_in_tokens as Balance * TOKEN)
+            )
+            .encode(),
+            OfflineCall::VestedTransfer {
+                amount_in_tokens,
+                to_account,
+                per_block,
+                starting_block,
+            } => compose_call!(
+                connection.metadata,
+                "Vesting",
+                "vested_transfer",
+                GenericAddress::Id(parse_account(&to_account)),
+                VestingSchedule::new(
+                    amount_in_tokens as Balance * TOKEN,
+                    per_block,
+                    starting_block
+                )
+            )
+            .encode(),
+        }
+    }
+}
+
+/// Everything needed to sign a transaction without access to the chain.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct SigningPayload {
+    /// Account that is expected to sign the transaction.
+    pub signer: AccountId,
+    /// `call` as decoded by `describe_call`. It is checked against `call` before signing.
+    pub description: String,
+    /// SCALE encoded call.
+    pub call: Vec<u8>,
+    pub nonce: u32,
+    pub era: Era,
+    /// Hash of the block in which `era` starts (genesis hash for an immortal transaction).
+    pub era_start: H256,
+    pub genesis_hash: H256,
+    pub spec_version: u32,
+    pub transaction_version: u32,
+}
+
+/// A call that is already SCALE encoded, so it is put into an extrinsic as it is.
+#[derive(Clone, Debug)]
+pub(crate) struct EncodedCall(pub(crate) Vec<u8>);
+
+impl Encode for EncodedCall {
+    fn size_hint(&self) -> usize {
+        self.0.len()
+    }
+
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        dest.write(&self.0)
+    }
+}
+
+/// Human readable form of the SCALE encoded `call`, decoded with the bindings of `aleph_client`.
+///
+/// Fails unless `call` is exactly one call of the runtime the bindings were generated for.
+pub(crate) fn describe_call(call: &[u8]) -> Result<String, String> {
+    let mut input = call;
+    let decoded = Call::decode(&mut input).map_err(|e| format!("Cannot decode the call: {}", e))?;
+    if !input.is_empty() {
+        return Err(format!(
+            "{} bytes are left after decoding the call",
+            input.len()
+        ));
+    }
+    Ok(format!("{:?}", decoded))
+}
+
+/// Checks that `description` is what `describe_call` shows for `call`, so that nobody is asked to
+/// sign something else than they are shown.
+pub(crate) fn verify_description(description: &str, call: &[u8]) -> Result<(), String> {
+    let decoded = describe_call(call)?;
+    if decoded != description {
+        return Err(format!(
+            "The call is described as {}, but it is {}",
+            description, decoded
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn parse_account(account: &str) -> AccountId {
+    AccountId::from_ss58check(account).expect("Address is valid")
+}
+
+pub(crate) fn read_hex(input: Option<PathBuf>) -> Vec<u8> {
+    let hex = match input {
+        Some(path) => fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Cannot read {}: {}", path.display(), e)),
+        None => {
+            let mut hex = String::new();
+            io::stdin()
+                .read_to_string(&mut hex)
+                .expect("Cannot read from stdin");
+            hex
+        }
+    };
+    from_hex(hex.trim()).expect("Input should be hex encoded")
+}
+
+pub(crate) fn write_hex_file(path: &Path, bytes: &[u8]) {
+    fs::write(path, to_hex(bytes, false) + "\n")
+        .unwrap_or_else(|e| panic!("Cannot write {}: {}", path.display(), e));
+    info!("Written to {}", path.display());
+}
+
+/// Writes `bytes` hex encoded to `output` or, if not given, prints them to stdout. With the JSON
+/// format nothing is printed, as the hex becomes a part of the result instead.
+fn write_hex(output: Option<PathBuf>, bytes: &[u8], format: OutputFormat) -> Value {
+    let hex = to_hex(bytes, false);
+    match output {
+        Some(path) => write_hex_file(&path, bytes),
+        None if format == OutputFormat::Text => println!("{}", hex),
+        None => {}
+    }
+    json!({ "hex": hex })
+}
+
+/// Writes to `output` (or stdout) the payload for signing `call` by `signer_account`.
+///
+/// The transaction is immortal unless `mortality` (in blocks) is given.
+pub fn prepare_offline<C: AnyConnection>(
+    connection: C,
+    signer_account: String,
+    mortality: Option<u64>,
+    call: OfflineCall,
+    output: Option<PathBuf>,
+    format: OutputFormat,
+) -> Value {
+    let connection = connection.as_connection();
+    let signer = parse_account(&signer_account);
+    let nonce = connection
+        .get_account_info(&signer)
+        .expect("Should be able to read account info")
+        .map(|info| info.nonce)
+        .unwrap_or(0);
+
+    let (era, era_start) = match mortality {
+        None => (Era::Immortal, connection.genesis_hash),
+        Some(period) => {
+            let finalized_hash = connection
+                .get_finalized_head()
+                .expect("Should be able to read finalized head")
+                .expect("There should be a finalized head");
+            let finalized = connection
+                .get_header::<Header>(Some(finalized_hash))
+                .expect("Should be able to read finalized header")
+                .expect("Finalized header should exist")
+                .number as u64;
+            let era = Era::mortal(period, finalized);
+            let era_start = connection
+                .get_block_hash(Some(era.birth(finalized) as BlockNumber))
+                .expect("Should be able to read block hash")
+                .expect("Block in which the era starts should exist");
+            (era, era_start)
+        }
+    };
+
+    let call = call.encode_call(&connection);
+    let payload = SigningPayload {
+        signer,
+        description: describe_call(&call).expect("Composed call should be decodable"),
+        call,
+        nonce,
+        era,
+        era_start,
+        genesis_hash: connection.genesis_hash,
+        spec_version: connection.runtime_version.spec_version,
+        transaction_version: connection.runtime_version.transaction_version,
+    };
+    info!("Prepared payload {:?}", payload);
+    write_hex(output, &payload.encode(), format)
+}
+
+/// Signs the payload read from `input` (or stdin) with `signer` and writes the extrinsic to
+/// `output` (or stdout). Does not connect to any node.
+pub fn sign_offline(
+    signer: &impl Signer,
+    input: Option<PathBuf>,
+    output: Option<PathBuf>,
+    format: OutputFormat,
+) -> Value {
+    let payload = SigningPayload::decode(&mut read_hex(input).as_slice())
+        .expect("Input should be a signing payload");
+    if signer.account_id() != payload.signer {
+        error!(
+            "The payload is meant to be signed by {}, but the signer is {}. Exiting.",
+            payload.signer,
+            signer.public()
+        );
+        std::process::exit(1);
+    }
+    if let Err(e) = verify_description(&payload.description, &payload.call) {
+        error!("Refusing to sign the payload. {}. Exiting.", e);
+        std::process::exit(1);
+    }
+
+    info!(
+        "Signing {} by {} with nonce {}, era {:?}, spec version {} and transaction version {}",
+        payload.description,
+        payload.signer,
+        payload.nonce,
+        payload.era,
+        payload.spec_version,
+        payload.transaction_version
+    );
+    let xt = compose_extrinsic_offline!(
+        signer,
+        EncodedCall(payload.call),
+        payload.nonce,
+        payload.era,
+        payload.genesis_hash,
+        payload.era_start,
+        payload.spec_version,
+        payload.transaction_version
+    );
+    write_hex(output, &xt.encode(), format)
+}
+
+/// Submits the signed extrinsic read from `input` (or stdin) and waits until it is finalized.
+pub fn submit_signed<C: AnyConnection>(
+    connection: C,
+    input: Option<PathBuf>,
+) -> anyhow::Result<Value> {
+    let xt = read_hex(input);
+    let tx_hash = H256(blake2_256(&xt));
+    let block_hash = block_on(asynchronous::submit_encoded(
+        &connection,
+        to_hex(&xt, false),
+        Some("signed offline"),
+        XtStatus::Finalized,
+    ))
+    .context("Submitting the extrinsic has failed")?;
+    info!("Transaction finalized in block {:?}", block_hash);
+    Ok(json!({
+        "tx_hash": hash_json(Some(tx_hash)),
+        "block_hash": hash_json(block_hash),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use aleph_client::aleph_zero::runtime_types::frame_system;
+
+    use super::*;
+
+    fn remark(remark: Vec<u8>) -> Vec<u8> {
+        Call::System(frame_system::pallet::Call::remark { remark }).encode()
+    }
+
+    fn payload(call: Vec<u8>) -> SigningPayload {
+        SigningPayload {
+            signer: AccountId::new([1; 32]),
+            description: describe_call(&call).unwrap(),
+            call,
+            nonce: 7,
+            era: Era::mortal(64, 1000),
+            era_start: H256::repeat_byte(2),
+            genesis_hash: H256::repeat_byte(3),
+            spec_version: 30,
+            transaction_version: 9,
+        }
+    }
+
+    #[test]
+    fn given_payload_when_encoding_and_decoding_then_it_is_the_same() {
+        let payload = payload(remark(vec![1, 2, 3]));
+
+        let decoded = SigningPayload::decode(&mut payload.encode().as_slice()).unwrap();
+
+        assert_eq!(decoded, payload);
+        assert!(verify_description(&decoded.description, &decoded.call).is_ok());
+    }
+
+    #[test]
+    fn given_call_when_describing_then_its_pallet_and_arguments_are_shown() {
+        let description = describe_call(&remark(vec![1, 2, 3])).unwrap();
+
+        assert!(description.starts_with("System(remark"));
+        assert!(description.contains("[1, 2, 3]"));
+    }
+
+    #[test]
+    fn given_description_of_another_call_when_verifying_then_it_is_refused() {
+        let mut payload = payload(remark(vec![1, 2, 3]));
+        payload.description = describe_call(&remark(vec![4])).unwrap();
+
+        assert!(verify_description(&payload.description, &payload.call).is_err());
+    }
+
+    #[test]
+    fn given_undecodable_call_when_verifying_then_it_is_refused() {
+        let call = remark(vec![1, 2, 3]);
+        let description = describe_call(&call).unwrap();
+        let truncated = &call[..call.len() - 1];
+        let extended = [call.as_slice(), &[0]].concat();
+
+        assert!(verify_description(&description, truncated).is_err());
+        assert!(verify_description(&description, &extended).is_err());
+        assert!(verify_description(&description, &[0xff, 0xff]).is_err());
+    }
+}
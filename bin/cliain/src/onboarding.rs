@@ -0,0 +1,354 @@
+//! `validator setup`: all the steps of becoming a validator in one command.
+//!
+//! Every step first checks on chain whether it has already been done, so after a failure the
+//! command can simply be run again and it continues from the failed step.
+
+use std::fmt::{Display, Formatter};
+
+use aleph_client::{
+    asynchronous, create_connection, get_current_era, get_current_session, get_elections_members,
+    get_free_balance, get_minimum_validator_bond, get_next_session_keys, get_session_validators,
+    get_sessions_per_era, has_session_keys, rotate_keys, staking_bonded, staking_ledger,
+    staking_validator_prefs, wait_for_session, AnyConnection, Connection, SessionKeys,
+    SignedConnection,
+};
+use futures::executor::block_on;
+use log::{error, info};
+use primitives::{Balance, TOKEN};
+use serde_json::{json, Map, Value};
+use sp_runtime::Perbill;
+use substrate_api_client::{AccountId, XtStatus};
+
+use crate::AnySigner;
+
+enum StepStatus {
+    Done(String),
+    Skipped(String),
+    Failed(String),
+    NotAttempted,
+}
+
+impl Display for StepStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StepStatus::Done(details) => write!(f, "done ({})", details),
+            StepStatus::Skipped(why) => write!(f, "skipped ({})", why),
+            StepStatus::Failed(why) => write!(f, "FAILED ({})", why),
+            StepStatus::NotAttempted => write!(f, "not attempted"),
+        }
+    }
+}
+
+/// Result of a step: `Ok(StepStatus::Done)` or `Ok(StepStatus::Skipped)` if there is nothing to do.
+type StepResult = Result<StepStatus, String>;
+
+struct Setup {
+    connection: SignedConnection<AnySigner>,
+    /// Connection to the validator node, where the session keys are generated.
+    node_connection: Connection,
+    account: AccountId,
+    /// Stake to bond, by default the minimal validator bond read when checking the balance.
+    stake: Option<Balance>,
+    commission_percentage: u8,
+    /// Keys that should be set on chain, known after the keys are rotated.
+    keys: Option<SessionKeys>,
+}
+
+impl Setup {
+    fn check_balance(&mut self) -> StepResult {
+        let min_bond = get_minimum_validator_bond(&self.connection).map_err(|e| e.to_string())?;
+        let stake = *self.stake.get_or_insert(min_bond);
+        if stake < min_bond {
+            return Err(format!(
+                "stake {} is below the minimal validator bond {}",
+                stake, min_bond
+            ));
+        }
+        if staking_bonded(&self.connection, &self.account)
+            .map_err(|e| e.to_string())?
+            .is_some()
+        {
+            return Ok(StepStatus::Skipped(String::from("already bonded")));
+        }
+        let free = get_free_balance(&self.connection, &self.account).map_err(|e| e.to_string())?;
+        if free <= stake {
+            return Err(format!(
+                "free balance {} should be higher than the stake {}, so that fees can be paid",
+                free, stake
+            ));
+        }
+        Ok(StepStatus::Done(format!("free balance {}", free)))
+    }
+
+    fn bond(&mut self) -> StepResult {
+        match staking_bonded(&self.connection, &self.account).map_err(|e| e.to_string())? {
+            Some(controller) if controller == self.account => {
+                Ok(StepStatus::Skipped(String::from("already bonded")))
+            }
+            Some(controller) => Err(format!(
+                "already bonded with a different controller {}, which is not supported",
+                controller
+            )),
+            None => {
+                let stake = self
+                    .stake
+                    .expect("Stake is known after the balance is checked");
+                block_on(asynchronous::bond(
+                    &self.connection,
+                    stake,
+                    &self.account,
+                    XtStatus::Finalized,
+                ))
+                .map_err(|e| e.to_string())?;
+                Ok(StepStatus::Done(format!("bonded {}", stake)))
+            }
+        }
+    }
+
+    fn rotate_keys(&mut self) -> StepResult {
+        if let Some(keys) =
+            get_next_session_keys(&self.connection, &self.account).map_err(|e| e.to_string())?
+        {
+            if has_session_keys(&self.node_connection, &keys)? {
+                self.keys = Some(keys);
+                return Ok(StepStatus::Skipped(String::from(
+                    "keys set on chain are present on the node",
+                )));
+            }
+        }
+        let keys = rotate_keys(&self.node_connection)?;
+        self.keys = Some(keys);
+        Ok(StepStatus::Done(String::from(
+            "new keys generated on the node",
+        )))
+    }
+
+    fn set_keys(&mut self) -> StepResult {
+        let keys = self
+            .keys
+            .clone()
+            .expect("Keys are known after the keys are rotated");
+        if get_next_session_keys(&self.connection, &self.account).map_err(|e| e.to_string())?
+            == Some(keys.clone())
+        {
+            return Ok(StepStatus::Skipped(String::from("already set")));
+        }
+        block_on(asynchronous::set_keys(
+            &self.connection,
+            keys,
+            XtStatus::Finalized,
+        ))
+        .map_err(|e| e.to_string())?;
+        Ok(StepStatus::Done(String::from("keys set")))
+    }
+
+    fn validate(&mut self) -> StepResult {
+        let commission = Perbill::from_percent(self.commission_percentage as u32);
+        if let Some(prefs) =
+            staking_validator_prefs(&self.connection, &self.account).map_err(|e| e.to_string())?
+        {
+            if prefs.commission == commission {
+                return Ok(StepStatus::Skipped(String::from(
+                    "already validating with this commission",
+                )));
+            }
+        }
+        block_on(asynchronous::validate(
+            &self.connection,
+            self.commission_percentage,
+            XtStatus::Finalized,
+        ))
+        .map_err(|e| e.to_string())?;
+        Ok(StepStatus::Done(format!(
+            "commission {}%",
+            self.commission_percentage
+        )))
+    }
+
+    /// Waits until the account is in the committee of some session, but at most until the end of
+    /// the era after the next one: if `validate` was sent after the next era had been planned, the
+    /// account can be elected only for the era after it.
+    fn wait_for_committee(&mut self) -> StepResult {
+        let session = get_current_session(&self.connection).map_err(|e| e.to_string())?;
+        if self.in_committee()? {
+            return Ok(StepStatus::Skipped(format!(
+                "already in the committee of session {}",
+                session
+            )));
+        }
+        let members = get_elections_members(&self.connection).map_err(|e| e.to_string())?;
+        if !members.contains(&self.account) {
+            return Err(String::from(
+                "not among the members of the Elections pallet, from which validators are elected",
+            ));
+        }
+
+        let era = get_current_era(&self.connection).map_err(|e| e.to_string())?;
+        let sessions_per_era = get_sessions_per_era(&self.connection).map_err(|e| e.to_string())?;
+        let last_session = (era + 3) * sessions_per_era - 1;
+        let mut session = session;
+        while session < last_session {
+            session = wait_for_session(&self.connection, session + 1).map_err(|e| e.to_string())?;
+            if self.in_committee()? {
+                return Ok(StepStatus::Done(format!(
+                    "in the committee of session {}",
+                    session
+                )));
+            }
+        }
+        Err(format!(
+            "not in the committee of any session until the end of era {}",
+            era + 2
+        ))
+    }
+
+    /// Whether the account is in the committee of the current session, i.e. among the validators
+    /// producing and finalizing its blocks.
+    fn in_committee(&self) -> Result<bool, String> {
+        get_session_validators(&self.connection)
+            .map(|validators| validators.contains(&self.account))
+            .map_err(|e| e.to_string())
+    }
+
+    fn in_committee_or_false(&self) -> bool {
+        self.in_committee().unwrap_or_else(|e| {
+            error!("Cannot read the committee of the current session: {}", e);
+            false
+        })
+    }
+
+    fn print_report(&self, steps: &[(&str, StepStatus)]) {
+        info!("Validator setup report for {}:", self.account);
+        for (name, status) in steps {
+            info!("  {:<20} {}", name, status);
+        }
+
+        let ledger = staking_ledger(&self.connection, &self.account)
+            .ok()
+            .flatten();
+        info!(
+            "  {:<20} {}",
+            "free balance",
+            get_free_balance(&self.connection, &self.account)
+                .map_or_else(|e| e.to_string(), |free| free.to_string())
+        );
+        info!(
+            "  {:<20} {}",
+            "bonded",
+            ledger.map_or(0, |ledger| ledger.active)
+        );
+        info!(
+            "  {:<20} {:?}",
+            "next session keys",
+            get_next_session_keys(&self.connection, &self.account)
+                .ok()
+                .flatten()
+        );
+        info!(
+            "  {:<20} {:?}",
+            "commission",
+            staking_validator_prefs(&self.connection, &self.account)
+                .ok()
+                .flatten()
+                .map(|prefs| prefs.commission)
+        );
+        info!(
+            "  {:<20} {}",
+            "current era",
+            get_current_era(&self.connection).map_or_else(|e| e.to_string(), |era| era.to_string())
+        );
+        info!(
+            "  {:<20} {}",
+            "current session",
+            get_current_session(&self.connection)
+                .map_or_else(|e| e.to_string(), |session| session.to_string())
+        );
+        info!("  {:<20} {}", "in committee", self.in_committee_or_false());
+    }
+
+    fn report_json(&self, steps: &[(&str, StepStatus)], completed: bool) -> Value {
+        let steps = steps
+            .iter()
+            .map(|(name, status)| (name.to_string(), Value::String(status.to_string())))
+            .collect::<Map<_, _>>();
+        json!({
+            "account": self.account.to_string(),
+            "completed": completed,
+            "steps": steps,
+            "bonded": staking_ledger(&self.connection, &self.account)
+                .ok()
+                .flatten()
+                .map(|ledger| ledger.active.to_string()),
+            "commission_percentage": self.commission_percentage,
+            "current_era": get_current_era(&self.connection).ok(),
+            "current_session": get_current_session(&self.connection).ok(),
+            "in_committee": self.in_committee_or_false(),
+        })
+    }
+}
+
+/// Bonds `stake_tokens` (by default the minimal validator bond) of the signer of `connection`,
+/// generates session keys on `validator_node` (by default the node of `connection`), sets them and
+/// declares the signer willing to validate with `commission_percentage`. Unless `wait` is false,
+/// waits then (for up to two eras) until the signer enters the committee of a session.
+///
+/// The signer acts both as stash and controller. Returns the report of the setup, as an error if
+/// some step has failed.
+pub fn setup_validator(
+    connection: SignedConnection<AnySigner>,
+    validator_node: Option<String>,
+    stake_tokens: Option<u64>,
+    commission_percentage: u8,
+    wait: bool,
+) -> Result<Value, Value> {
+    let node_connection = match validator_node {
+        Some(node) => create_connection(&node).map_err(|e| {
+            error!("Cannot connect to the validator node: {}", e);
+            json!({ "completed": false, "error": e.to_string() })
+        })?,
+        None => connection.as_connection(),
+    };
+    let mut setup = Setup {
+        node_connection,
+        account: connection.account_id(),
+        connection,
+        stake: stake_tokens.map(|tokens| tokens as Balance * TOKEN),
+        commission_percentage,
+        keys: None,
+    };
+
+    type Step = fn(&mut Setup) -> StepResult;
+    let mut steps: Vec<(&str, Step)> = vec![
+        ("check balance", Setup::check_balance),
+        ("bond", Setup::bond),
+        ("rotate keys", Setup::rotate_keys),
+        ("set keys", Setup::set_keys),
+        ("validate", Setup::validate),
+    ];
+    if wait {
+        steps.push(("join committee", Setup::wait_for_committee));
+    }
+
+    let mut report = Vec::new();
+    let mut failed = false;
+    for (name, step) in steps {
+        let status = if failed {
+            StepStatus::NotAttempted
+        } else {
+            info!("Validator setup: {}", name);
+            step(&mut setup).unwrap_or_else(|why| {
+                failed = true;
+                StepStatus::Failed(why)
+            })
+        };
+        report.push((name, status));
+    }
+
+    setup.print_report(&report);
+    let report_json = setup.report_json(&report, !failed);
+    if failed {
+        error!("Validator setup has not been completed. Run the command again to retry.");
+        return Err(report_json);
+    }
+    Ok(report_json)
+}
@@ -0,0 +1,107 @@
+//! Machine readable results of the commands, printed with `--output json`.
+//!
+//! In the text format commands report their results through logs. In the JSON format a single
+//! JSON object is printed to stdout when the command finishes: the value it has queried (under
+//! `result`) and all the transactions it has submitted together with their events (under
+//! `transactions`). If the command fails, the message is put under `error` instead of `result`
+//! and cliain exits with a non-zero code. Logs still go to stderr.
+//!
+//! The transactions are those recorded in the `Receipts` passed to the connection of the command
+//! (see `ConnectionConfig`).
+
+use aleph_client::{tx_events, ConnectionPool, Receipts, TxEvent, TxReceipt};
+use log::warn;
+use serde_json::{json, Value};
+use sp_core::H256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// Parses the output format: `text` or `json`.
+pub fn parse_output_format(format: &str) -> Result<OutputFormat, String> {
+    match format {
+        "text" => Ok(OutputFormat::Text),
+        "json" => Ok(OutputFormat::Json),
+        _ => Err(format!(
+            "Unknown output format {}, expected text or json",
+            format
+        )),
+    }
+}
+
+impl OutputFormat {
+    /// Prints `result` of the command and its transactions from `receipts`, whose events are read
+    /// from `nodes`.
+    pub fn finish(self, nodes: &[String], receipts: &Receipts, result: Value) {
+        self.print(nodes, receipts, "result", result);
+    }
+
+    /// Prints `error` with which the command has failed and the transactions it has submitted
+    /// before.
+    pub fn fail(self, nodes: &[String], receipts: &Receipts, error: &anyhow::Error) {
+        self.print(
+            nodes,
+            receipts,
+            "error",
+            Value::String(format!("{:#}", error)),
+        );
+    }
+
+    fn print(self, nodes: &[String], receipts: &Receipts, key: &str, value: Value) {
+        if self == OutputFormat::Text {
+            return;
+        }
+        let receipts = receipts.take();
+        let pool = if receipts.is_empty() {
+            None
+        } else {
+            ConnectionPool::new(nodes)
+                .map_err(|e| warn!("Cannot read events of the transactions: {}", e))
+                .ok()
+        };
+        let transactions = receipts
+            .iter()
+            .map(|receipt| receipt_json(pool.as_ref(), receipt))
+            .collect::<Vec<_>>();
+        println!(
+            "{}",
+            json!({
+                key: value,
+                "transactions": transactions,
+            })
+        );
+    }
+}
+
+/// `hash` in the `0x...` form, `null` if it is not known.
+pub(crate) fn hash_json(hash: Option<H256>) -> Value {
+    hash.map(|hash| format!("{:?}", hash)).into()
+}
+
+fn receipt_json(pool: Option<&ConnectionPool>, receipt: &TxReceipt) -> Value {
+    let events = match (pool, receipt.block_hash) {
+        (Some(pool), Some(_)) => tx_events(pool, receipt)
+            .map_err(|e| warn!("Cannot read events of {:?}: {}", receipt.tx_hash, e))
+            .ok(),
+        _ => None,
+    };
+
+    json!({
+        "name": receipt.name,
+        "tx_hash": hash_json(Some(receipt.tx_hash)),
+        "block_hash": hash_json(receipt.block_hash),
+        "events": events.map(|events| events.iter().map(event_json).collect::<Vec<_>>()),
+    })
+}
+
+fn event_json(event: &TxEvent) -> Value {
+    json!({
+        "index": event.index,
+        "pallet": event.pallet,
+        "variant": event.variant,
+        "details": format!("{:?}", event.event),
+    })
+}
@@ -0,0 +1,87 @@
+use aleph_client::{BlockNumber, ProxyType, SignedConnection};
+use serde_json::{json, Value};
+use sp_core::{crypto::Ss58Codec, H256};
+use substrate_api_client::{AccountId, XtStatus};
+
+use crate::{output::hash_json, AnySigner};
+
+/// Parses the name of a proxy type, e.g. `staking` or `non-transfer`.
+pub fn parse_proxy_type(proxy_type: &str) -> Result<ProxyType, String> {
+    match proxy_type.to_lowercase().replace('_', "-").as_str() {
+        "any" => Ok(ProxyType::Any),
+        "non-transfer" => Ok(ProxyType::NonTransfer),
+        "staking" => Ok(ProxyType::Staking),
+        "session-keys" => Ok(ProxyType::SessionKeys),
+        "governance" => Ok(ProxyType::Governance),
+        _ => Err(format!(
+            "Unknown proxy type {}, expected one of: any, non-transfer, staking, session-keys, governance",
+            proxy_type
+        )),
+    }
+}
+
+/// Parses the SS58 address of an account.
+pub fn parse_account_id(address: &str) -> Result<AccountId, String> {
+    AccountId::from_ss58check(address)
+        .map_err(|e| format!("Invalid SS58 address {}: {:?}", address, e))
+}
+
+pub fn add_proxy(
+    connection: SignedConnection<AnySigner>,
+    delegate: AccountId,
+    proxy_type: ProxyType,
+    delay: BlockNumber,
+) -> anyhow::Result<Value> {
+    let block_hash = aleph_client::add_proxy(
+        &connection,
+        &delegate,
+        proxy_type,
+        delay,
+        XtStatus::Finalized,
+    )?;
+    Ok(proxy_json(
+        &connection,
+        &delegate,
+        proxy_type,
+        delay,
+        block_hash,
+    ))
+}
+
+pub fn remove_proxy(
+    connection: SignedConnection<AnySigner>,
+    delegate: AccountId,
+    proxy_type: ProxyType,
+    delay: BlockNumber,
+) -> anyhow::Result<Value> {
+    let block_hash = aleph_client::remove_proxy(
+        &connection,
+        &delegate,
+        proxy_type,
+        delay,
+        XtStatus::Finalized,
+    )?;
+    Ok(proxy_json(
+        &connection,
+        &delegate,
+        proxy_type,
+        delay,
+        block_hash,
+    ))
+}
+
+fn proxy_json(
+    connection: &SignedConnection<AnySigner>,
+    delegate: &AccountId,
+    proxy_type: ProxyType,
+    delay: BlockNumber,
+    block_hash: Option<H256>,
+) -> Value {
+    json!({
+        "delegator": connection.account_id().to_string(),
+        "delegate": delegate.to_string(),
+        "proxy_type": format!("{:?}", proxy_type),
+        "delay": delay,
+        "block_hash": hash_json(block_hash),
+    })
+}
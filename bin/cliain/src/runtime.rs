@@ -1,8 +1,19 @@
 use aleph_client::{set_code, RootConnection};
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use sp_core::{hashing::blake2_256, H256};
 use std::fs;
 use substrate_api_client::XtStatus;
 
-pub fn update_runtime(connection: RootConnection, runtime: String) {
-    let runtime = fs::read(runtime).expect("Runtime file not found");
-    set_code(&connection, runtime, XtStatus::Finalized);
+use crate::{output::hash_json, AnySigner};
+
+pub fn update_runtime(connection: RootConnection<AnySigner>, runtime: String) -> Result<Value> {
+    let code = fs::read(&runtime).with_context(|| format!("Cannot read runtime {}", runtime))?;
+    let code_hash = H256(blake2_256(&code));
+    let block_hash = set_code(&connection, code, XtStatus::Finalized)?;
+    Ok(json!({
+        "runtime": runtime,
+        "code_hash": hash_json(Some(code_hash)),
+        "block_hash": hash_json(block_hash),
+    }))
 }
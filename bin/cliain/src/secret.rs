@@ -9,3 +9,12 @@ pub fn prompt_password_hidden(message: &str) -> Result<String> {
     let value = input.interact()?;
     Ok(value)
 }
+
+/// Same as `prompt_password_hidden`, but an empty password is accepted.
+pub fn prompt_optional_password_hidden(message: &str) -> Result<String> {
+    let theme = ColorfulTheme::default();
+    let mut input = dialoguer::Password::with_theme(&theme);
+
+    input.with_prompt(message).allow_empty_password(true);
+    input.interact()
+}
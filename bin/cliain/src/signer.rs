@@ -0,0 +1,336 @@
+//! Sources of the key that cliain signs with, chosen by the `--signer` selector.
+
+use std::{
+    fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+use aleph_client::{keypair_from_string, KeyPair, Signer};
+use log::error;
+use sc_keystore::LocalKeystore;
+use schnorrkel::SecretKey;
+use scrypt::{scrypt, Params};
+use serde_json::Value;
+use sp_core::{
+    bytes::{from_hex, to_hex},
+    crypto::{SecretString, Ss58Codec},
+    sr25519,
+};
+use xsalsa20poly1305::{
+    aead::{Aead, NewAead},
+    Key, Nonce, XSalsa20Poly1305,
+};
+
+use crate::{prompt_optional_password_hidden, prompt_password_hidden};
+
+/// Key type of the accounts kept in a keystore.
+mod account {
+    use sp_application_crypto::{app_crypto, sr25519, KeyTypeId};
+
+    app_crypto!(sr25519, KeyTypeId(*b"acco"));
+}
+
+/// Delegates signing to an external program (e.g. talking to a hardware wallet).
+///
+/// The program gets the hex encoded payload on its stdin and should print the hex encoded sr25519
+/// signature to its stdout.
+#[derive(Clone)]
+pub struct CommandSigner {
+    program: String,
+    public: sr25519::Public,
+}
+
+impl CommandSigner {
+    pub fn new(program: String, public: sr25519::Public) -> Self {
+        CommandSigner { program, public }
+    }
+}
+
+impl Signer for CommandSigner {
+    fn public(&self) -> sr25519::Public {
+        self.public
+    }
+
+    fn sign(&self, payload: &[u8]) -> sr25519::Signature {
+        let mut child = Command::new(&self.program)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap_or_else(|e| panic!("Cannot run {}: {}", self.program, e));
+        child
+            .stdin
+            .take()
+            .expect("Stdin of the signer should be piped")
+            .write_all(to_hex(payload, false).as_bytes())
+            .expect("Should be able to pass the payload to the signer");
+        let output = child
+            .wait_with_output()
+            .expect("Should be able to read the signature");
+        if !output.status.success() {
+            panic!("{} has failed with {}", self.program, output.status);
+        }
+
+        let signature = from_hex(String::from_utf8_lossy(&output.stdout).trim())
+            .expect("Signer should print hex encoded signature");
+        sr25519::Signature::from_raw(
+            signature
+                .try_into()
+                .expect("Signature should have 64 bytes"),
+        )
+    }
+}
+
+/// The signer chosen by `SignerSelector`.
+#[derive(Clone)]
+pub enum AnySigner {
+    Key(KeyPair),
+    External(CommandSigner),
+}
+
+impl Signer for AnySigner {
+    fn public(&self) -> sr25519::Public {
+        match self {
+            AnySigner::Key(keypair) => Signer::public(keypair),
+            AnySigner::External(signer) => signer.public(),
+        }
+    }
+
+    fn sign(&self, payload: &[u8]) -> sr25519::Signature {
+        match self {
+            AnySigner::Key(keypair) => Signer::sign(keypair, payload),
+            AnySigner::External(signer) => signer.sign(payload),
+        }
+    }
+}
+
+/// Where to take the signing key from.
+#[derive(Debug, Clone)]
+pub enum SignerSelector {
+    /// Seed given with `--seed` or prompted for.
+    Seed,
+    /// `LocalKeystore` directory and the SS58 id of the account, keys have to be of `acco` type.
+    Keystore(PathBuf, String),
+    /// Encrypted JSON export of polkadot.js.
+    Json(PathBuf),
+    /// External program and the SS58 id of the account it signs for, see `CommandSigner`.
+    External(String, String),
+}
+
+/// Parses the signer selector: `seed`, `keystore:<path>:<SS58 id>`, `json:<path>` or
+/// `external:<program>:<SS58 id>`.
+pub fn parse_signer_selector(selector: &str) -> Result<SignerSelector, String> {
+    let (kind, location) = selector.split_once(':').unwrap_or((selector, ""));
+    let with_account = || {
+        location
+            .rsplit_once(':')
+            .map(|(location, account)| (location.to_string(), account.to_string()))
+            .ok_or_else(|| format!("Expected {}:<location>:<SS58 id>", kind))
+    };
+    match kind {
+        "seed" => Ok(SignerSelector::Seed),
+        "keystore" => {
+            with_account().map(|(path, account)| SignerSelector::Keystore(path.into(), account))
+        }
+        "json" if !location.is_empty() => Ok(SignerSelector::Json(location.into())),
+        "external" => {
+            with_account().map(|(program, account)| SignerSelector::External(program, account))
+        }
+        _ => Err(format!(
+            "Unknown signer {}, expected one of: seed, keystore:<path>:<SS58 id>, json:<path>, external:<program>:<SS58 id>",
+            selector
+        )),
+    }
+}
+
+impl SignerSelector {
+    /// The signer of the selected source, `seed` is used only with `SignerSelector::Seed`.
+    pub fn signer(self, seed: Option<String>) -> AnySigner {
+        let signer = match self {
+            SignerSelector::Seed => Ok(AnySigner::Key(keypair_from_string(&read_seed(seed)))),
+            SignerSelector::Keystore(path, account) => {
+                keypair_from_keystore(path, &account).map(AnySigner::Key)
+            }
+            SignerSelector::Json(path) => keypair_from_json(path).map(AnySigner::Key),
+            SignerSelector::External(program, account) => sr25519::Public::from_ss58check(&account)
+                .map(|public| AnySigner::External(CommandSigner::new(program, public)))
+                .map_err(|e| format!("Invalid account {}: {:?}.", account, e)),
+        };
+        signer.unwrap_or_else(|e| {
+            error!("{} Exiting.", e);
+            std::process::exit(1);
+        })
+    }
+}
+
+fn prompt_or_exit(prompt: std::io::Result<String>) -> String {
+    prompt.unwrap_or_else(|e| {
+        error!("Failed to parse prompt with error {:?}! Exiting.", e);
+        std::process::exit(1);
+    })
+}
+
+fn read_seed(seed: Option<String>) -> String {
+    seed.unwrap_or_else(|| {
+        prompt_or_exit(prompt_password_hidden(
+            "Provide seed for the signer account:",
+        ))
+    })
+}
+
+fn keypair_from_keystore(path: PathBuf, account: &str) -> Result<KeyPair, String> {
+    let public = sr25519::Public::from_ss58check(account)
+        .map_err(|e| format!("Invalid account {}: {:?}.", account, e))?;
+    let password = prompt_or_exit(prompt_optional_password_hidden(
+        "Provide password of the keystore (if any):",
+    ));
+    let password = (!password.is_empty()).then(|| SecretString::new(password));
+
+    LocalKeystore::open(path.clone(), password)
+        .map_err(|e| format!("Cannot open keystore {}: {}.", path.display(), e))?
+        .key_pair::<account::Pair>(&public.into())
+        .map_err(|e| format!("Cannot read key of {}: {}.", account, e))?
+        .map(Into::into)
+        .ok_or_else(|| format!("There is no key of {} in the keystore.", account))
+}
+
+/// Length of the scrypt parameters (salt, N, p and r) preceding the encrypted data.
+const SCRYPT_LENGTH: usize = 32 + 3 * 4;
+const NONCE_LENGTH: usize = 24;
+const PKCS8_HEADER: [u8; 16] = [48, 83, 2, 1, 1, 48, 5, 6, 3, 43, 101, 112, 4, 34, 4, 32];
+const SECRET_KEY_LENGTH: usize = 64;
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes.try_into().expect("Slice has 4 bytes"))
+}
+
+/// Decrypts the key from a polkadot.js JSON export (version 3, sr25519).
+fn keypair_from_json(path: PathBuf) -> Result<KeyPair, String> {
+    let json: Value = fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+        .map_err(|e| format!("Cannot read {}: {}.", path.display(), e))?;
+    decrypt_json_export(&json, || {
+        prompt_or_exit(prompt_password_hidden(
+            "Provide password of the JSON export:",
+        ))
+    })
+}
+
+/// Decrypts the key from the parsed export `json`. `password` is asked for only if the key is
+/// encrypted.
+fn decrypt_json_export(json: &Value, password: impl FnOnce() -> String) -> Result<KeyPair, String> {
+    let field = |name: &str| {
+        json["encoding"][name]
+            .as_array()
+            .map(|values| values.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+            .unwrap_or_default()
+    };
+    if !field("content").contains(&"sr25519") {
+        return Err(String::from("Only sr25519 keys are supported."));
+    }
+    let encoded = json["encoded"]
+        .as_str()
+        .and_then(|encoded| base64::decode(encoded).ok())
+        .ok_or("The export has no encoded key.")?;
+
+    let decoded = if field("type").contains(&"xsalsa20-poly1305") {
+        let password = password();
+        let (key, encrypted) = if field("type").contains(&"scrypt") {
+            if encoded.len() < SCRYPT_LENGTH + NONCE_LENGTH {
+                return Err(String::from("The encoded key is too short."));
+            }
+            let (salt, n, p, r) = (
+                &encoded[..32],
+                read_u32(&encoded[32..36]),
+                read_u32(&encoded[36..40]),
+                read_u32(&encoded[40..44]),
+            );
+            let params = Params::new(n.trailing_zeros() as u8, r, p)
+                .map_err(|e| format!("Invalid scrypt parameters: {}.", e))?;
+            let mut key = [0u8; 32];
+            scrypt(password.as_bytes(), salt, &params, &mut key)
+                .map_err(|e| format!("Cannot derive the key: {}.", e))?;
+            (key, &encoded[SCRYPT_LENGTH..])
+        } else {
+            let mut key = [0u8; 32];
+            let len = password.len().min(key.len());
+            key[..len].copy_from_slice(&password.as_bytes()[..len]);
+            (key, &encoded[..])
+        };
+        if encrypted.len() < NONCE_LENGTH {
+            return Err(String::from("The encoded key is too short."));
+        }
+        let (nonce, ciphertext) = encrypted.split_at(NONCE_LENGTH);
+        XSalsa20Poly1305::new(Key::from_slice(&key))
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| "Wrong password.")?
+    } else {
+        encoded
+    };
+
+    if decoded.len() < PKCS8_HEADER.len() + SECRET_KEY_LENGTH
+        || decoded[..PKCS8_HEADER.len()] != PKCS8_HEADER
+    {
+        return Err(String::from("The decrypted key has unexpected format."));
+    }
+    let secret = &decoded[PKCS8_HEADER.len()..PKCS8_HEADER.len() + SECRET_KEY_LENGTH];
+    SecretKey::from_ed25519_bytes(secret)
+        .map(KeyPair::from)
+        .map_err(|e| format!("Invalid secret key: {}.", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_core::Pair;
+
+    use super::*;
+
+    /// Export of an account in the format of polkadot.js (version 3, scrypt with N = 2^15, r = 8,
+    /// p = 1 and xsalsa20-poly1305), encrypted with `PASSWORD`.
+    const EXPORT: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/fixtures/polkadot_js_export.json"
+    ));
+    const PASSWORD: &str = "correct horse";
+
+    fn export() -> Value {
+        serde_json::from_str(EXPORT).unwrap()
+    }
+
+    #[test]
+    fn given_export_when_decrypting_with_password_then_key_of_its_address_is_returned() {
+        let export = export();
+
+        let keypair = decrypt_json_export(&export, || PASSWORD.to_string()).unwrap();
+
+        let public = Pair::public(&keypair);
+        assert_eq!(public.to_ss58check(), export["address"].as_str().unwrap());
+        let message = b"cliain";
+        assert!(sr25519::Pair::verify(
+            &Pair::sign(&keypair, message),
+            message,
+            &public
+        ));
+    }
+
+    #[test]
+    fn given_export_when_decrypting_with_wrong_password_then_it_fails() {
+        assert_eq!(
+            decrypt_json_export(&export(), || String::from("wrong horse")).err(),
+            Some(String::from("Wrong password."))
+        );
+    }
+
+    #[test]
+    fn given_ed25519_export_when_decrypting_then_it_is_refused_without_asking_for_password() {
+        let mut export = export();
+        export["encoding"]["content"] = serde_json::json!(["pkcs8", "ed25519"]);
+
+        assert_eq!(
+            decrypt_json_export(&export, || panic!("Password should not be asked for")).err(),
+            Some(String::from("Only sr25519 keys are supported."))
+        );
+    }
+}
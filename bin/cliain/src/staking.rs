@@ -2,48 +2,76 @@ use aleph_client::{
     staking_bond, staking_force_new_era, staking_set_staking_limits, staking_validate,
     RootConnection, SignedConnection,
 };
+use anyhow::Result;
 use primitives::TOKEN;
+use serde_json::{json, Value};
 use sp_core::crypto::Ss58Codec;
 use substrate_api_client::{AccountId, XtStatus};
 
+use crate::{output::hash_json, AnySigner};
+
 pub fn bond(
-    stash_connection: SignedConnection,
+    stash_connection: SignedConnection<AnySigner>,
     initial_stake_in_tokens: u32,
     controller_account: String,
-) {
+) -> Result<Value> {
     let controller_account =
         AccountId::from_ss58check(&controller_account).expect("Address is valid");
 
     let initial_stake = initial_stake_in_tokens as u128 * TOKEN;
-    staking_bond(
+    let block_hash = staking_bond(
         &stash_connection,
         initial_stake,
         &controller_account,
         XtStatus::Finalized,
-    );
+    )?;
+    Ok(json!({
+        "stash": stash_connection.account_id().to_string(),
+        "controller": controller_account.to_string(),
+        "stake": initial_stake.to_string(),
+        "block_hash": hash_json(block_hash),
+    }))
 }
 
-pub fn validate(connection: SignedConnection, commission_percentage: u8) {
-    staking_validate(&connection, commission_percentage, XtStatus::Finalized);
+pub fn validate(
+    connection: SignedConnection<AnySigner>,
+    commission_percentage: u8,
+) -> Result<Value> {
+    let block_hash = staking_validate(&connection, commission_percentage, XtStatus::Finalized)?;
+    Ok(json!({
+        "controller": connection.account_id().to_string(),
+        "commission_percentage": commission_percentage,
+        "block_hash": hash_json(block_hash),
+    }))
 }
 
 pub fn set_staking_limits(
-    root_connection: RootConnection,
+    root_connection: RootConnection<AnySigner>,
     minimal_nominator_stake_tokens: u64,
     minimal_validator_stake_tokens: u64,
     max_nominators_count: Option<u32>,
     max_validators_count: Option<u32>,
-) {
-    staking_set_staking_limits(
+) -> Result<Value> {
+    let minimal_nominator_stake = minimal_nominator_stake_tokens as u128 * TOKEN;
+    let minimal_validator_stake = minimal_validator_stake_tokens as u128 * TOKEN;
+    let block_hash = staking_set_staking_limits(
         &root_connection,
-        minimal_nominator_stake_tokens as u128 * TOKEN,
-        minimal_validator_stake_tokens as u128 * TOKEN,
+        minimal_nominator_stake,
+        minimal_validator_stake,
         max_nominators_count,
         max_validators_count,
         XtStatus::Finalized,
-    );
+    )?;
+    Ok(json!({
+        "minimal_nominator_stake": minimal_nominator_stake.to_string(),
+        "minimal_validator_stake": minimal_validator_stake.to_string(),
+        "max_nominators_count": max_nominators_count,
+        "max_validators_count": max_validators_count,
+        "block_hash": hash_json(block_hash),
+    }))
 }
 
-pub fn force_new_era(root_connection: RootConnection) {
-    staking_force_new_era(&root_connection, XtStatus::Finalized);
+pub fn force_new_era(root_connection: RootConnection<AnySigner>) -> Result<Value> {
+    let block_hash = staking_force_new_era(&root_connection, XtStatus::Finalized)?;
+    Ok(json!({ "block_hash": hash_json(block_hash) }))
 }
@@ -1,14 +1,25 @@
 use aleph_client::{balances_transfer, SignedConnection};
+use anyhow::Result;
+use codec::Encode;
 use primitives::TOKEN;
-use sp_core::crypto::Ss58Codec;
+use serde_json::{json, Value};
+use sp_core::{crypto::Ss58Codec, hashing::blake2_256, H256};
 use substrate_api_client::{AccountId, XtStatus};
 
-pub fn transfer(connection: SignedConnection, amount_in_tokens: u64, to_account: String) {
+use crate::{output::hash_json, AnySigner};
+
+pub fn transfer(
+    connection: SignedConnection<AnySigner>,
+    amount_in_tokens: u64,
+    to_account: String,
+) -> Result<Value> {
     let to_account = AccountId::from_ss58check(&to_account).expect("Address is valid");
-    balances_transfer(
-        &connection,
-        &to_account,
-        amount_in_tokens as u128 * TOKEN,
-        XtStatus::Finalized,
-    );
+    let amount = amount_in_tokens as u128 * TOKEN;
+    let xt = balances_transfer(&connection, &to_account, amount, XtStatus::Finalized)?;
+    Ok(json!({
+        "from": connection.account_id().to_string(),
+        "to": to_account.to_string(),
+        "amount": amount.to_string(),
+        "tx_hash": hash_json(Some(H256(blake2_256(&xt.encode())))),
+    }))
 }
@@ -1,16 +1,28 @@
 use aleph_client::{change_members, RootConnection};
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
 use sp_core::crypto::Ss58Codec;
 use substrate_api_client::{AccountId, XtStatus};
 
+use crate::{output::hash_json, AnySigner};
+
 /// Change validators to the provided list by calling the provided node.
-pub fn change_validators(root_connection: RootConnection, validators: Vec<String>) {
-    let validators = validators
+pub fn change_validators(
+    root_connection: RootConnection<AnySigner>,
+    validators: Vec<String>,
+) -> Result<Value> {
+    let validators: Vec<AccountId> = validators
         .iter()
         .map(|address| AccountId::from_ss58check(address).expect("Address is valid"))
         .collect();
 
-    change_members(&root_connection, validators, XtStatus::Finalized);
+    let block_hash = change_members(&root_connection, validators.clone(), XtStatus::Finalized)
+        .context("Changing the validators has failed")?;
     // TODO we need to check state here whether change members actually succeed
     // not only here, but for all cliain commands
     // see https://cardinal-cryptography.atlassian.net/browse/AZ-699
+    Ok(json!({
+        "validators": validators.iter().map(|v| v.to_string()).collect::<Vec<_>>(),
+        "block_hash": hash_json(block_hash),
+    }))
 }
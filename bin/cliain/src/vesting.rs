@@ -1,28 +1,41 @@
 use aleph_client::{
     account_from_keypair, keypair_from_string, BlockNumber, SignedConnection, VestingSchedule,
 };
-use log::{error, info};
+use anyhow::{Context, Result};
+use log::info;
 use primitives::{Balance, TOKEN};
+use serde_json::{json, Value};
+
+use crate::{output::hash_json, AnySigner};
 
 /// Delegates to `aleph_client::vest`.
 ///
 /// Vesting is performed for the signer of `connection`.
-pub fn vest(connection: SignedConnection) {
-    match aleph_client::vest(connection) {
-        Ok(_) => info!("Vesting has succeeded"),
-        Err(e) => error!("Vesting has failed with:\n {:?}", e),
-    }
+pub fn vest(connection: SignedConnection<AnySigner>) -> Result<Value> {
+    let vester = connection.account_id();
+    let block_hash = aleph_client::vest(connection).context("Vesting has failed")?;
+    info!("Vesting has succeeded");
+    Ok(json!({
+        "account": vester.to_string(),
+        "block_hash": hash_json(Some(block_hash)),
+    }))
 }
 
 /// Delegates to `aleph_client::vest_other`.
 ///
 /// Vesting is performed by the signer of `connection` for `vesting_account_seed`.
-pub fn vest_other(connection: SignedConnection, vesting_account_seed: String) {
+pub fn vest_other(
+    connection: SignedConnection<AnySigner>,
+    vesting_account_seed: String,
+) -> Result<Value> {
     let vester = account_from_keypair(&keypair_from_string(vesting_account_seed.as_str()));
-    match aleph_client::vest_other(connection, vester) {
-        Ok(_) => info!("Vesting on behalf has succeeded"),
-        Err(e) => error!("Vesting on behalf has failed with:\n {:?}", e),
-    }
+    let block_hash = aleph_client::vest_other(connection, vester.clone())
+        .context("Vesting on behalf has failed")?;
+    info!("Vesting on behalf has succeeded");
+    Ok(json!({
+        "account": vester.to_string(),
+        "block_hash": hash_json(Some(block_hash)),
+    }))
 }
 
 /// Delegates to `aleph_client::vested_transfer`.
@@ -31,17 +44,23 @@ pub fn vest_other(connection: SignedConnection, vesting_account_seed: String) {
 /// `amount_in_tokens`, `per_block` and `starting_block` corresponds to the fields of
 /// `aleph_client::VestingSchedule` struct.
 pub fn vested_transfer(
-    connection: SignedConnection,
+    connection: SignedConnection<AnySigner>,
     target_seed: String,
     amount_in_tokens: u64,
     per_block: Balance,
     starting_block: BlockNumber,
-) {
+) -> Result<Value> {
     let receiver = account_from_keypair(&keypair_from_string(target_seed.as_str()));
-    let schedule =
-        VestingSchedule::new(amount_in_tokens as u128 * TOKEN, per_block, starting_block);
-    match aleph_client::vested_transfer(connection, receiver, schedule) {
-        Ok(_) => info!("Vested transfer has succeeded"),
-        Err(e) => error!("Vested transfer has failed with:\n {:?}", e),
-    }
+    let locked = amount_in_tokens as u128 * TOKEN;
+    let schedule = VestingSchedule::new(locked, per_block, starting_block);
+    let block_hash = aleph_client::vested_transfer(connection, receiver.clone(), schedule)
+        .context("Vested transfer has failed")?;
+    info!("Vested transfer has succeeded");
+    Ok(json!({
+        "receiver": receiver.to_string(),
+        "locked": locked.to_string(),
+        "per_block": per_block.to_string(),
+        "starting_block": starting_block,
+        "block_hash": hash_json(Some(block_hash)),
+    }))
 }
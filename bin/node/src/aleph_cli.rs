@@ -1,18 +1,594 @@
 use aleph_primitives::DEFAULT_UNIT_CREATION_DELAY;
-use clap::Parser;
-use finality_aleph::UnitCreationDelay;
+use aleph_runtime::opaque::Block;
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use finality_aleph::{
+    ChainInfoCacheConfig, ChainTrackerConfig, ConnectionManagerConfig, ConsensusDelayConfig,
+    DataStoreConfig, JustificationHandlerConfig, MillisecsPerBlock, SessionPeriod,
+    UnitCreationDelay, MAX_DATA_BRANCH_LEN,
+};
+use std::{fmt::Display, fs, path::PathBuf, time::Duration};
+use toml::value::Table;
 
-#[derive(Debug, Parser, Clone)]
+/// Finality parameters of the node: the ones passed as flags and the path to the config file.
+#[derive(Debug, Args, Clone)]
+struct AlephArgs {
+    /// Path to a TOML file with finality tuning parameters. The keys are the names of the
+    /// corresponding flags, e.g. `justification-verifier-timeout = 500`. Values passed as flags
+    /// take precedence over the ones read from the file.
+    #[clap(long, value_name = "PATH")]
+    aleph_config: Option<PathBuf>,
+
+    #[clap(flatten)]
+    tuning: FinalityTuning,
+}
+
+#[derive(Debug, Clone)]
 pub struct AlephCli {
+    args: AlephArgs,
+    /// Names of the tuning parameters passed explicitly as flags.
+    explicit: Vec<String>,
+}
+
+impl FromArgMatches for AlephCli {
+    fn from_arg_matches(matches: &ArgMatches) -> Result<Self, clap::Error> {
+        Ok(AlephCli {
+            args: AlephArgs::from_arg_matches(matches)?,
+            explicit: explicit_parameters(matches),
+        })
+    }
+
+    fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), clap::Error> {
+        self.args.update_from_arg_matches(matches)?;
+        self.explicit = explicit_parameters(matches);
+        Ok(())
+    }
+}
+
+impl Args for AlephCli {
+    fn augment_args(cmd: Command<'_>) -> Command<'_> {
+        AlephArgs::augment_args(cmd)
+    }
+
+    fn augment_args_for_update(cmd: Command<'_>) -> Command<'_> {
+        AlephArgs::augment_args_for_update(cmd)
+    }
+}
+
+fn explicit_parameters(matches: &ArgMatches) -> Vec<String> {
+    TUNING_PARAMETERS
+        .iter()
+        .map(|field| parameter_name(field))
+        .filter(|name| matches.occurrences_of(name) > 0)
+        .collect()
+}
+
+/// Name of the flag, and of the config file key, of the parameter stored in `field`.
+fn parameter_name(field: &str) -> String {
+    field.replace('_', "-")
+}
+
+/// Finality tuning parameters. All durations are in milliseconds.
+#[derive(Debug, Clone, Args)]
+pub struct FinalityTuning {
+    /// Base delay between creating consecutive AlephBFT units
+    #[clap(long, default_value_t = DEFAULT_UNIT_CREATION_DELAY)]
+    unit_creation_delay: u64,
+
+    /// Delay before creating the AlephBFT unit of the first round
+    #[clap(long, default_value_t = as_millis(default_consensus().initial_unit_creation_delay))]
+    initial_unit_creation_delay: u64,
+
+    /// Base delay between rebroadcasts of an AlephBFT unit
+    #[clap(long, default_value_t = as_millis(default_consensus().unit_broadcast_delay))]
+    unit_broadcast_delay: u64,
+
+    /// How often AlephBFT checks its delays
+    #[clap(long, default_value_t = as_millis(default_consensus().tick_interval))]
+    consensus_tick_interval: u64,
+
+    /// How often AlephBFT repeats requests for missing units
+    #[clap(long, default_value_t = as_millis(default_consensus().requests_interval))]
+    consensus_requests_interval: u64,
+
+    /// The maximal round of AlephBFT units in a single session
+    #[clap(long, default_value_t = default_consensus().max_round)]
+    max_round: u16,
+
+    /// Maximal number of blocks in a proposed branch
+    #[clap(long, default_value_t = MAX_DATA_BRANCH_LEN)]
+    max_data_branch_len: usize,
+
+    /// How long to wait before retrying when the session verifier is not available
+    #[clap(long, default_value_t = as_millis(default_justification().verifier_timeout))]
+    justification_verifier_timeout: u64,
+
+    /// How long to wait for a justification notification
+    #[clap(long, default_value_t = as_millis(default_justification().notification_timeout))]
+    justification_notification_timeout: u64,
+
+    /// Minimal distance (in blocks) between the best block and the block we request a justification
+    /// for
+    #[clap(long, default_value_t = default_justification().min_allowed_delay)]
+    justification_min_allowed_delay: u32,
+
+    /// Maximal number of event triggers registered in the data store before pruning
+    #[clap(long, default_value_t = default_data_store().max_triggers_pending)]
+    data_store_max_triggers_pending: usize,
+
+    /// Maximal number of proposals pending in the data store
+    #[clap(long, default_value_t = default_data_store().max_proposals_pending)]
+    data_store_max_proposals_pending: usize,
+
+    /// Maximal number of messages pending in the data store
+    #[clap(long, default_value_t = default_data_store().max_messages_pending)]
+    data_store_max_messages_pending: usize,
+
+    /// Capacity of the cache of available proposals in the data store
+    #[clap(long, default_value_t = default_data_store().available_proposals_cache_capacity)]
+    data_store_available_proposals_cache_capacity: usize,
+
+    /// How often the data store runs maintenance
+    #[clap(long, default_value_t = as_millis(default_data_store().periodic_maintenance_interval))]
+    data_store_maintenance_interval: u64,
+
+    /// After how long a block missing for a pending proposal gets requested
+    #[clap(long, default_value_t = as_millis(default_data_store().request_block_after))]
+    data_store_request_block_after: u64,
+
+    /// Capacity of the block caches used for checking proposals
+    #[clap(long, default_value_t = ChainInfoCacheConfig::default().block_cache_capacity)]
+    block_cache_capacity: usize,
+
+    /// How often authentications are rebroadcast to discover session peers
+    /// [default: one fifth of the session duration]
+    #[clap(long)]
+    discovery_cooldown: Option<u64>,
+
+    /// How often the network manager runs maintenance [default: half of the discovery cooldown]
     #[clap(long)]
-    unit_creation_delay: Option<u64>,
+    network_maintenance_period: Option<u64>,
+}
+
+/// Generates the list of fields of the tuning parameters and the function overriding them with
+/// the values read from the config file.
+macro_rules! tuning_parameters {
+    ($($field:ident),*) => {
+        const TUNING_PARAMETERS: &[&str] = &[$(stringify!($field)),*];
+
+        impl FinalityTuning {
+            /// Overrides the parameters not in `explicit` with the values from `file`.
+            fn apply_file(&mut self, mut file: Table, explicit: &[String]) -> Result<(), String> {
+                $(
+                    let name = parameter_name(stringify!($field));
+                    if let Some(value) = file.remove(&name) {
+                        if !explicit.contains(&name) {
+                            self.$field = value
+                                .try_into()
+                                .map_err(|e| format!("Invalid `{}`: {}", name, e))?;
+                        }
+                    }
+                )*
+                match file.keys().next() {
+                    Some(key) => Err(format!("Unknown parameter `{}`", key)),
+                    None => Ok(()),
+                }
+            }
+        }
+    };
+}
+
+tuning_parameters!(
+    unit_creation_delay,
+    initial_unit_creation_delay,
+    unit_broadcast_delay,
+    consensus_tick_interval,
+    consensus_requests_interval,
+    max_round,
+    max_data_branch_len,
+    justification_verifier_timeout,
+    justification_notification_timeout,
+    justification_min_allowed_delay,
+    data_store_max_triggers_pending,
+    data_store_max_proposals_pending,
+    data_store_max_messages_pending,
+    data_store_available_proposals_cache_capacity,
+    data_store_maintenance_interval,
+    data_store_request_block_after,
+    block_cache_capacity,
+    discovery_cooldown,
+    network_maintenance_period
+);
+
+fn default_consensus() -> ConsensusDelayConfig {
+    ConsensusDelayConfig::default()
+}
+
+fn default_justification() -> JustificationHandlerConfig<Block> {
+    JustificationHandlerConfig::default()
+}
+
+fn default_data_store() -> DataStoreConfig {
+    DataStoreConfig::default()
+}
+
+fn as_millis(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
+
+fn ensure_positive<T: Default + PartialEq>(name: &str, value: T) -> Result<(), String> {
+    if value == T::default() {
+        return Err(format!("`{}` must be positive", name));
+    }
+    Ok(())
+}
+
+fn ensure_not_above<T: PartialOrd + Display>(
+    name: &str,
+    value: T,
+    bound_name: &str,
+    bound: T,
+) -> Result<(), String> {
+    if value > bound {
+        return Err(format!(
+            "`{}` ({}) must not exceed `{}` ({})",
+            name, value, bound_name, bound
+        ));
+    }
+    Ok(())
 }
 
 impl AlephCli {
+    /// Combines the parameters passed as flags with the ones from the config file, if any,
+    /// and checks that they are sane.
+    pub fn finality_tuning(&self) -> Result<FinalityTuning, String> {
+        let mut tuning = self.args.tuning.clone();
+        if let Some(path) = &self.args.aleph_config {
+            let content = fs::read_to_string(path)
+                .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+            let file = toml::from_str(&content)
+                .map_err(|e| format!("Cannot parse {}: {}", path.display(), e))?;
+            tuning
+                .apply_file(file, &self.explicit)
+                .map_err(|e| format!("Cannot apply {}: {}", path.display(), e))?;
+        }
+        tuning.validate()?;
+        Ok(tuning)
+    }
+}
+
+impl FinalityTuning {
+    fn validate(&self) -> Result<(), String> {
+        ensure_positive("unit-creation-delay", self.unit_creation_delay)?;
+        ensure_positive("unit-broadcast-delay", self.unit_broadcast_delay)?;
+        ensure_positive("consensus-tick-interval", self.consensus_tick_interval)?;
+        ensure_positive(
+            "consensus-requests-interval",
+            self.consensus_requests_interval,
+        )?;
+        ensure_positive("max-round", self.max_round)?;
+        ensure_positive("max-data-branch-len", self.max_data_branch_len)?;
+        ensure_positive(
+            "justification-verifier-timeout",
+            self.justification_verifier_timeout,
+        )?;
+        ensure_positive(
+            "justification-notification-timeout",
+            self.justification_notification_timeout,
+        )?;
+        ensure_positive(
+            "justification-min-allowed-delay",
+            self.justification_min_allowed_delay,
+        )?;
+        ensure_positive(
+            "data-store-max-triggers-pending",
+            self.data_store_max_triggers_pending,
+        )?;
+        ensure_positive(
+            "data-store-max-messages-pending",
+            self.data_store_max_messages_pending,
+        )?;
+        ensure_positive(
+            "data-store-max-proposals-pending",
+            self.data_store_max_proposals_pending,
+        )?;
+        ensure_positive(
+            "data-store-available-proposals-cache-capacity",
+            self.data_store_available_proposals_cache_capacity,
+        )?;
+        ensure_positive(
+            "data-store-maintenance-interval",
+            self.data_store_maintenance_interval,
+        )?;
+        ensure_positive(
+            "data-store-request-block-after",
+            self.data_store_request_block_after,
+        )?;
+        ensure_positive("block-cache-capacity", self.block_cache_capacity)?;
+        if let Some(cooldown) = self.discovery_cooldown {
+            ensure_positive("discovery-cooldown", cooldown)?;
+        }
+        if let Some(period) = self.network_maintenance_period {
+            ensure_positive("network-maintenance-period", period)?;
+        }
+
+        ensure_not_above(
+            "max-data-branch-len",
+            self.max_data_branch_len,
+            "MAX_DATA_BRANCH_LEN",
+            MAX_DATA_BRANCH_LEN,
+        )?;
+        ensure_not_above(
+            "consensus-tick-interval",
+            self.consensus_tick_interval,
+            "consensus-requests-interval",
+            self.consensus_requests_interval,
+        )?;
+        // Every pending proposal keeps at least one trigger registered, and only triggers of
+        // proposals no longer pending are pruned, so a lower limit would prune in vain.
+        ensure_not_above(
+            "data-store-max-proposals-pending",
+            self.data_store_max_proposals_pending,
+            "data-store-max-triggers-pending",
+            self.data_store_max_triggers_pending,
+        )?;
+        ensure_not_above(
+            "data-store-maintenance-interval",
+            self.data_store_maintenance_interval,
+            "data-store-request-block-after",
+            self.data_store_request_block_after,
+        )?;
+        if let (Some(period), Some(cooldown)) =
+            (self.network_maintenance_period, self.discovery_cooldown)
+        {
+            ensure_not_above(
+                "network-maintenance-period",
+                period,
+                "discovery-cooldown",
+                cooldown,
+            )?;
+        }
+        Ok(())
+    }
+
     pub fn unit_creation_delay(&self) -> UnitCreationDelay {
-        UnitCreationDelay(
-            self.unit_creation_delay
-                .unwrap_or(DEFAULT_UNIT_CREATION_DELAY),
-        )
+        UnitCreationDelay(self.unit_creation_delay)
+    }
+
+    pub fn consensus_delay_config(&self) -> ConsensusDelayConfig {
+        ConsensusDelayConfig {
+            tick_interval: Duration::from_millis(self.consensus_tick_interval),
+            requests_interval: Duration::from_millis(self.consensus_requests_interval),
+            unit_broadcast_delay: Duration::from_millis(self.unit_broadcast_delay),
+            initial_unit_creation_delay: Duration::from_millis(self.initial_unit_creation_delay),
+            max_round: self.max_round,
+        }
+    }
+
+    pub fn justification_handler_config(&self) -> JustificationHandlerConfig<Block> {
+        JustificationHandlerConfig {
+            verifier_timeout: Duration::from_millis(self.justification_verifier_timeout),
+            notification_timeout: Duration::from_millis(self.justification_notification_timeout),
+            min_allowed_delay: self.justification_min_allowed_delay,
+        }
+    }
+
+    pub fn data_store_config(&self) -> DataStoreConfig {
+        DataStoreConfig {
+            max_triggers_pending: self.data_store_max_triggers_pending,
+            max_proposals_pending: self.data_store_max_proposals_pending,
+            max_messages_pending: self.data_store_max_messages_pending,
+            available_proposals_cache_capacity: self.data_store_available_proposals_cache_capacity,
+            periodic_maintenance_interval: Duration::from_millis(
+                self.data_store_maintenance_interval,
+            ),
+            request_block_after: Duration::from_millis(self.data_store_request_block_after),
+            chain_info_cache_config: ChainInfoCacheConfig {
+                block_cache_capacity: self.block_cache_capacity,
+            },
+        }
+    }
+
+    pub fn chain_tracker_config(&self) -> ChainTrackerConfig {
+        ChainTrackerConfig {
+            max_branch_len: self.max_data_branch_len,
+            ..ChainTrackerConfig::default()
+        }
+    }
+
+    pub fn connection_manager_config(
+        &self,
+        session_period: &SessionPeriod,
+        millisecs_per_block: &MillisecsPerBlock,
+    ) -> ConnectionManagerConfig {
+        let default =
+            ConnectionManagerConfig::with_session_period(session_period, millisecs_per_block);
+        let discovery_cooldown = self
+            .discovery_cooldown
+            .map(Duration::from_millis)
+            .unwrap_or(default.discovery_cooldown);
+        let maintenance_period = self
+            .network_maintenance_period
+            .map(Duration::from_millis)
+            .unwrap_or(discovery_cooldown / 2);
+        ConnectionManagerConfig::new(discovery_cooldown, maintenance_period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Debug, Parser)]
+    struct TestCli {
+        #[clap(flatten)]
+        aleph: AlephCli,
+    }
+
+    fn parse(args: &[&str]) -> AlephCli {
+        let args = std::iter::once("aleph-node").chain(args.iter().copied());
+        TestCli::try_parse_from(args)
+            .expect("Arguments should parse")
+            .aleph
+    }
+
+    fn config_file(name: &str, content: &str) -> String {
+        let path =
+            std::env::temp_dir().join(format!("aleph-cli-{}-{}.toml", name, std::process::id()));
+        fs::write(&path, content).expect("Config file should be written");
+        path.display().to_string()
+    }
+
+    fn tuning(args: &[&str]) -> Result<FinalityTuning, String> {
+        parse(args).finality_tuning()
+    }
+
+    #[test]
+    fn defaults_match_finality_aleph_defaults() {
+        let tuning = tuning(&[]).expect("Defaults should be valid");
+        let data_store = tuning.data_store_config();
+        let default_data_store = DataStoreConfig::default();
+
+        assert_eq!(tuning.unit_creation_delay().0, DEFAULT_UNIT_CREATION_DELAY);
+        assert_eq!(
+            tuning.consensus_delay_config().requests_interval,
+            ConsensusDelayConfig::default().requests_interval
+        );
+        assert_eq!(
+            tuning.justification_handler_config().min_allowed_delay,
+            JustificationHandlerConfig::<Block>::default().min_allowed_delay
+        );
+        assert_eq!(
+            data_store.max_triggers_pending,
+            default_data_store.max_triggers_pending
+        );
+        assert_eq!(
+            data_store.request_block_after,
+            default_data_store.request_block_after
+        );
+        assert_eq!(
+            tuning.chain_tracker_config().max_branch_len,
+            MAX_DATA_BRANCH_LEN
+        );
+    }
+
+    #[test]
+    fn parameters_are_read_from_file() {
+        let path = config_file(
+            "read",
+            "max-round = 5000\njustification-min-allowed-delay = 5\ndiscovery-cooldown = 800\n",
+        );
+
+        let tuning = tuning(&["--aleph-config", &path]).expect("Config should be valid");
+
+        assert_eq!(tuning.consensus_delay_config().max_round, 5000);
+        assert_eq!(tuning.justification_handler_config().min_allowed_delay, 5);
+        assert_eq!(tuning.discovery_cooldown, Some(800));
+        assert_eq!(tuning.network_maintenance_period, None);
+    }
+
+    #[test]
+    fn flags_take_precedence_over_file() {
+        let path = config_file(
+            "precedence",
+            "max-round = 5000\nunit-creation-delay = 500\n",
+        );
+
+        let tuning = tuning(&["--aleph-config", &path, "--max-round", "6000"])
+            .expect("Config should be valid");
+
+        assert_eq!(tuning.consensus_delay_config().max_round, 6000);
+        assert_eq!(tuning.unit_creation_delay().0, 500);
+    }
+
+    #[test]
+    fn flag_with_default_value_takes_precedence_over_file() {
+        let default = ConsensusDelayConfig::default().max_round.to_string();
+        let path = config_file("default-precedence", "max-round = 5000\n");
+
+        let tuning = tuning(&["--aleph-config", &path, "--max-round", &default])
+            .expect("Config should be valid");
+
+        assert_eq!(
+            tuning.consensus_delay_config().max_round,
+            ConsensusDelayConfig::default().max_round
+        );
+    }
+
+    #[test]
+    fn unknown_file_parameter_is_rejected() {
+        let path = config_file("unknown", "max-rounds = 5000\n");
+
+        let error = tuning(&["--aleph-config", &path]).expect_err("Config should be rejected");
+
+        assert!(
+            error.contains("Unknown parameter `max-rounds`"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn mistyped_file_parameter_is_rejected() {
+        let path = config_file("mistyped", "max-round = \"many\"\n");
+
+        let error = tuning(&["--aleph-config", &path]).expect_err("Config should be rejected");
+
+        assert!(error.contains("Invalid `max-round`"), "{}", error);
+    }
+
+    #[test]
+    fn zero_min_allowed_delay_is_rejected() {
+        let error = tuning(&["--justification-min-allowed-delay", "0"])
+            .expect_err("Config should be rejected");
+
+        assert!(
+            error.contains("justification-min-allowed-delay"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn zero_max_triggers_pending_from_file_is_rejected() {
+        let path = config_file("zero-triggers", "data-store-max-triggers-pending = 0\n");
+
+        let error = tuning(&["--aleph-config", &path]).expect_err("Config should be rejected");
+
+        assert!(
+            error.contains("data-store-max-triggers-pending"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn max_triggers_pending_below_max_proposals_pending_is_rejected() {
+        let error = tuning(&[
+            "--data-store-max-triggers-pending",
+            "100",
+            "--data-store-max-proposals-pending",
+            "200",
+        ])
+        .expect_err("Config should be rejected");
+
+        assert!(
+            error.contains("data-store-max-triggers-pending"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn inverted_intervals_are_rejected() {
+        assert!(tuning(&["--consensus-tick-interval", "5000"]).is_err());
+        assert!(tuning(&["--max-data-branch-len", "100"]).is_err());
+        assert!(tuning(&[
+            "--discovery-cooldown",
+            "1000",
+            "--network-maintenance-period",
+            "2000"
+        ])
+        .is_err());
     }
 }
@@ -381,6 +381,7 @@ fn generate_genesis_config(
             members: accounts_config.members.clone(),
             members_per_session: DEFAULT_MEMBERS_PER_SESSION,
         },
+        inflation: Default::default(),
         session: SessionConfig {
             keys: accounts_config.keys,
         },
@@ -98,4 +98,18 @@ pub enum Subcommand {
 
     /// Revert the chain to a previous state.
     Revert(sc_cli::RevertCmd),
+
+    /// Benchmark the runtime pallets. It requires the node to be built with
+    /// `--features runtime-benchmarks`.
+    Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+
+    /// Try some command against the runtime state, e.g. run `on_runtime_upgrade` against a
+    /// snapshot. It requires the node to be built with `--features try-runtime`.
+    #[cfg(feature = "try-runtime")]
+    TryRuntime(try_runtime_cli::TryRuntimeCmd),
+
+    /// Try some command against the runtime state. It requires the node to be built with
+    /// `--features try-runtime`.
+    #[cfg(not(feature = "try-runtime"))]
+    TryRuntime,
 }
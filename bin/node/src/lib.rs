@@ -8,4 +8,5 @@ mod rpc;
 mod service;
 
 pub use cli::{Cli, Subcommand};
+pub use executor::ExecutorDispatch;
 pub use service::{new_authority, new_full, new_partial};
@@ -2,7 +2,8 @@ use sc_cli::SubstrateCli;
 use sc_network::config::Role;
 use sc_service::PartialComponents;
 
-use aleph_node::{new_authority, new_full, new_partial, Cli, Subcommand};
+use aleph_node::{new_authority, new_full, new_partial, Cli, ExecutorDispatch, Subcommand};
+use aleph_runtime::Block;
 use clap::Parser;
 
 fn main() -> sc_cli::Result<()> {
@@ -74,17 +75,40 @@ fn main() -> sc_cli::Result<()> {
                 Ok((cmd.run(client, backend, None), task_manager))
             })
         }
+        Some(Subcommand::Benchmark(cmd)) => {
+            if !cfg!(feature = "runtime-benchmarks") {
+                return Err("Benchmarking wasn't enabled when building the node. \
+                    You can enable it with `--features runtime-benchmarks`."
+                    .into());
+            }
+            let runner = cli.create_runner(cmd)?;
+            runner.sync_run(|config| cmd.run::<Block, ExecutorDispatch>(config))
+        }
+        #[cfg(feature = "try-runtime")]
+        Some(Subcommand::TryRuntime(cmd)) => {
+            let runner = cli.create_runner(cmd)?;
+            runner.async_run(|config| {
+                // the task manager is needed by `async_run`, the command itself spawns no tasks
+                let registry = config.prometheus_config.as_ref().map(|cfg| &cfg.registry);
+                let task_manager =
+                    sc_service::TaskManager::new(config.tokio_handle.clone(), registry)
+                        .map_err(|e| sc_cli::Error::Service(sc_service::Error::Prometheus(e)))?;
+                Ok((cmd.run::<Block, ExecutorDispatch>(config), task_manager))
+            })
+        }
+        #[cfg(not(feature = "try-runtime"))]
+        Some(Subcommand::TryRuntime) => Err("TryRuntime wasn't enabled when building the node. \
+            You can enable it with `--features try-runtime`."
+            .into()),
         None => {
             let runner = cli.create_runner(&cli.run)?;
-            let aleph_cli_config = cli.aleph;
+            let finality_tuning = cli.aleph.finality_tuning().map_err(sc_cli::Error::Input)?;
             runner.run_node_until_exit(|config| async move {
                 match config.role {
                     Role::Authority => {
-                        new_authority(config, aleph_cli_config).map_err(sc_cli::Error::Service)
-                    }
-                    Role::Full => {
-                        new_full(config, aleph_cli_config).map_err(sc_cli::Error::Service)
+                        new_authority(config, finality_tuning).map_err(sc_cli::Error::Service)
                     }
+                    Role::Full => new_full(config, finality_tuning).map_err(sc_cli::Error::Service),
                     // TODO: introduce apprioprate error here (no error in the sc_cli::Error is good here)
                     Role::Light => panic!("no light client yet"),
                 }
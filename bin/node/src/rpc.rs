@@ -5,14 +5,20 @@
 
 #![warn(missing_docs)]
 
-use std::sync::Arc;
+use std::{marker::PhantomData, sync::Arc};
 
+use aleph_primitives::{
+    ElectionsApi as ElectionsRuntimeApi, SessionIndex, ValidatorPerformance, ValidatorSessionRecord,
+};
 use aleph_runtime::{opaque::Block, AccountId, Balance, BlockNumber, Hash, Index};
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result as RpcResult};
+use jsonrpc_derive::rpc;
 pub use sc_rpc_api::DenyUnsafe;
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 
 /// Full client dependencies.
 pub struct FullDeps<C, P> {
@@ -33,6 +39,7 @@ where
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
     C::Api: pallet_contracts_rpc::ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber, Hash>,
+    C::Api: ElectionsRuntimeApi<Block, AccountId>,
     C::Api: BlockBuilder<Block>,
     P: TransactionPool + 'static,
 {
@@ -57,7 +64,85 @@ where
         client.clone(),
     )));
 
-    io.extend_with(ContractsApi::to_delegate(Contracts::new(client)));
+    io.extend_with(ContractsApi::to_delegate(Contracts::new(client.clone())));
+
+    io.extend_with(ElectionsApi::to_delegate(Elections::new(client)));
 
     io
 }
+
+/// RPC methods exposing the validator performance tracked by the elections pallet.
+#[rpc]
+pub trait ElectionsApi<BlockHash, AccountId> {
+    /// Performance of the validators in the given session, the current one or a past one still
+    /// kept on chain, as of the block `at`, the best block by default.
+    #[rpc(name = "elections_validatorsPerformance")]
+    fn validators_performance(
+        &self,
+        session: SessionIndex,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<Vec<ValidatorPerformance<AccountId>>>>;
+
+    /// Performance of the validators in the given past session, if still kept on chain.
+    #[rpc(name = "elections_sessionPerformance")]
+    fn session_performance(
+        &self,
+        session: SessionIndex,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<Vec<ValidatorSessionRecord<AccountId>>>>;
+}
+
+/// Implementation of [`ElectionsApi`] querying the runtime.
+pub struct Elections<C, B> {
+    client: Arc<C>,
+    _block: PhantomData<B>,
+}
+
+impl<C, B> Elections<C, B> {
+    /// Creates the RPC handler reading from `client`.
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _block: PhantomData,
+        }
+    }
+}
+
+impl<C, B> ElectionsApi<<B as BlockT>::Hash, AccountId> for Elections<C, B>
+where
+    B: BlockT,
+    C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync + 'static,
+    C::Api: ElectionsRuntimeApi<B, AccountId>,
+{
+    fn validators_performance(
+        &self,
+        session: SessionIndex,
+        at: Option<<B as BlockT>::Hash>,
+    ) -> RpcResult<Option<Vec<ValidatorPerformance<AccountId>>>> {
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.client
+            .runtime_api()
+            .validators_performance(&at, session)
+            .map_err(|e| RpcError {
+                code: ErrorCode::ServerError(1),
+                message: "Unable to query validators performance.".into(),
+                data: Some(e.to_string().into()),
+            })
+    }
+
+    fn session_performance(
+        &self,
+        session: SessionIndex,
+        at: Option<<B as BlockT>::Hash>,
+    ) -> RpcResult<Option<Vec<ValidatorSessionRecord<AccountId>>>> {
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.client
+            .runtime_api()
+            .session_performance(&at, session)
+            .map_err(|e| RpcError {
+                code: ErrorCode::ServerError(1),
+                message: "Unable to query session performance.".into(),
+                data: Some(e.to_string().into()),
+            })
+    }
+}
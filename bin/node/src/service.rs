@@ -1,11 +1,12 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use crate::{aleph_cli::AlephCli, executor::AlephExecutor};
+use crate::{aleph_cli::FinalityTuning, executor::AlephExecutor};
 use aleph_primitives::AlephSessionApi;
 use aleph_runtime::{self, opaque::Block, RuntimeApi, MAX_BLOCK_SIZE};
 use finality_aleph::{
     run_nonvalidator_node, run_validator_node, AlephBlockImport, AlephConfig,
-    JustificationNotification, Metrics, MillisecsPerBlock, Protocol, SessionPeriod,
+    FinalizedInherentDataProvider, ItemRegistry, JustificationNotification, Metrics,
+    MillisecsPerBlock, Protocol, RandomnessBeacon, SessionPeriod,
 };
 use futures::channel::mpsc;
 use log::warn;
@@ -44,6 +45,7 @@ pub fn new_partial(
             mpsc::UnboundedReceiver<JustificationNotification<Block>>,
             Option<Telemetry>,
             Option<Metrics<<<Block as BlockT>::Header as HeaderT>::Hash>>,
+            RandomnessBeacon,
         ),
     >,
     ServiceError,
@@ -106,21 +108,27 @@ pub fn new_partial(
 
     let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
 
+    let randomness_beacon = RandomnessBeacon::new();
+    let import_beacon = randomness_beacon.clone();
+
     let import_queue = sc_consensus_aura::import_queue::<AuraPair, _, _, _, _, _, _>(
         ImportQueueParams {
             block_import: aleph_block_import.clone(),
             justification_import: Some(Box::new(aleph_block_import.clone())),
             client: client.clone(),
-            create_inherent_data_providers: move |_, ()| async move {
-                let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
-
-                let slot =
-                    sp_consensus_aura::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
-                        *timestamp,
-                        slot_duration,
-                    );
-
-                Ok((timestamp, slot))
+            create_inherent_data_providers: move |_, ()| {
+                let randomness = import_beacon.inherent_data_provider();
+                async move {
+                    let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+
+                    let slot =
+                        sp_consensus_aura::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
+                            *timestamp,
+                            slot_duration,
+                        );
+
+                    Ok((timestamp, slot, randomness))
+                }
             },
             spawner: &task_manager.spawn_essential_handle(),
             registry: config.prometheus_registry(),
@@ -140,7 +148,13 @@ pub fn new_partial(
         keystore_container,
         select_chain,
         transaction_pool,
-        other: (aleph_block_import, justification_rx, telemetry, metrics),
+        other: (
+            aleph_block_import,
+            justification_rx,
+            telemetry,
+            metrics,
+            randomness_beacon,
+        ),
     })
 }
 
@@ -213,7 +227,7 @@ fn setup(
 /// Builds a new service for a full client.
 pub fn new_authority(
     mut config: Configuration,
-    aleph_config: AlephCli,
+    finality_tuning: FinalityTuning,
 ) -> Result<TaskManager, ServiceError> {
     let sc_service::PartialComponents {
         client,
@@ -223,7 +237,7 @@ pub fn new_authority(
         keystore_container,
         select_chain,
         transaction_pool,
-        other: (block_import, justification_rx, mut telemetry, metrics),
+        other: (block_import, justification_rx, mut telemetry, metrics, randomness_beacon),
     } = new_partial(&config)?;
     config
         .network
@@ -244,8 +258,6 @@ pub fn new_authority(
             .unwrap(),
     );
 
-    let unit_creation_delay = aleph_config.unit_creation_delay();
-
     let force_authoring = config.force_authoring;
     let backoff_authoring_blocks: Option<()> = None;
     let prometheus_registry = config.prometheus_registry().cloned();
@@ -274,6 +286,9 @@ pub fn new_authority(
 
     let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
 
+    let authoring_beacon = randomness_beacon.clone();
+    let authoring_client = client.clone();
+
     let aura = sc_consensus_aura::start_aura::<AuraPair, _, _, _, _, _, _, _, _, _, _, _>(
         StartAuraParams {
             slot_duration,
@@ -281,16 +296,20 @@ pub fn new_authority(
             select_chain: select_chain.clone(),
             block_import,
             proposer_factory,
-            create_inherent_data_providers: move |_, ()| async move {
-                let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
-
-                let slot =
-                    sp_consensus_aura::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
-                        *timestamp,
-                        slot_duration,
-                    );
-
-                Ok((timestamp, slot))
+            create_inherent_data_providers: move |_, ()| {
+                let randomness = authoring_beacon.inherent_data_provider();
+                let finalized = FinalizedInherentDataProvider::new(&*authoring_client);
+                async move {
+                    let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+
+                    let slot =
+                        sp_consensus_aura::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
+                            *timestamp,
+                            slot_duration,
+                        );
+
+                    Ok((timestamp, slot, randomness, finalized))
+                }
             },
             force_authoring,
             backoff_authoring_blocks,
@@ -318,7 +337,15 @@ pub fn new_authority(
         keystore: keystore_container.keystore(),
         justification_rx,
         metrics,
-        unit_creation_delay,
+        unit_creation_delay: finality_tuning.unit_creation_delay(),
+        consensus_delay_config: finality_tuning.consensus_delay_config(),
+        justification_handler_config: finality_tuning.justification_handler_config(),
+        data_store_config: finality_tuning.data_store_config(),
+        chain_tracker_config: finality_tuning.chain_tracker_config(),
+        connection_manager_config: finality_tuning
+            .connection_manager_config(&session_period, &millisecs_per_block),
+        item_registry: ItemRegistry::new(),
+        randomness_beacon,
     };
     task_manager.spawn_essential_handle().spawn_blocking(
         "aleph",
@@ -332,7 +359,7 @@ pub fn new_authority(
 
 pub fn new_full(
     config: Configuration,
-    aleph_config: AlephCli,
+    finality_tuning: FinalityTuning,
 ) -> Result<TaskManager, ServiceError> {
     let sc_service::PartialComponents {
         client,
@@ -342,7 +369,7 @@ pub fn new_full(
         keystore_container,
         select_chain,
         transaction_pool,
-        other: (_, justification_rx, mut telemetry, metrics),
+        other: (_, justification_rx, mut telemetry, metrics, randomness_beacon),
     } = new_partial(&config)?;
 
     let (_rpc_handlers, network, network_starter) = setup(
@@ -370,8 +397,6 @@ pub fn new_full(
             .unwrap(),
     );
 
-    let unit_creation_delay = aleph_config.unit_creation_delay();
-
     let aleph_config = AlephConfig {
         network,
         client,
@@ -382,7 +407,15 @@ pub fn new_full(
         keystore: keystore_container.keystore(),
         justification_rx,
         metrics,
-        unit_creation_delay,
+        unit_creation_delay: finality_tuning.unit_creation_delay(),
+        consensus_delay_config: finality_tuning.consensus_delay_config(),
+        justification_handler_config: finality_tuning.justification_handler_config(),
+        data_store_config: finality_tuning.data_store_config(),
+        chain_tracker_config: finality_tuning.chain_tracker_config(),
+        connection_manager_config: finality_tuning
+            .connection_manager_config(&session_period, &millisecs_per_block),
+        item_registry: ItemRegistry::new(),
+        randomness_beacon,
     };
 
     task_manager.spawn_essential_handle().spawn_blocking(
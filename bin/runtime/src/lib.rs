@@ -16,7 +16,7 @@ use sp_runtime::{
         Verify,
     },
     transaction_validity::{TransactionSource, TransactionValidity},
-    ApplyExtrinsicResult, MultiSignature, RuntimeAppPublic,
+    ApplyExtrinsicResult, DispatchResult, MultiSignature, RuntimeAppPublic,
 };
 use sp_staking::EraIndex;
 
@@ -40,18 +40,23 @@ pub use frame_support::{
 use frame_support::{
     pallet_prelude::ConstU32,
     sp_runtime::Perquintill,
-    traits::{EqualPrivilegeOnly, SortedMembers, U128CurrencyToVote},
+    traits::{
+        EnsureOneOf, EqualPrivilegeOnly, Get, InstanceFilter, SortedMembers, U128CurrencyToVote,
+    },
     weights::constants::WEIGHT_PER_MILLIS,
     PalletId,
 };
 use frame_system::{EnsureRoot, EnsureSignedBy};
-pub use primitives::Balance;
+pub use primitives::{proxy::ProxyType, Balance};
 use primitives::{
-    staking::MAX_NOMINATORS_REWARDED_PER_VALIDATOR, wrap_methods, ApiError as AlephApiError,
-    AuthorityId as AlephId, DEFAULT_MILLISECS_PER_BLOCK, DEFAULT_SESSIONS_PER_ERA,
-    DEFAULT_SESSION_PERIOD, TOKEN,
+    staking::{InflationModel, MAX_NOMINATORS_REWARDED_PER_VALIDATOR},
+    wrap_methods, ApiError as AlephApiError, AuthorityId as AlephId, SessionIndex,
+    DEFAULT_AUTHORITY_HISTORY_DEPTH, DEFAULT_MILLISECS_PER_BLOCK,
+    DEFAULT_PERFORMANCE_HISTORY_DEPTH, DEFAULT_SESSIONS_PER_ERA, DEFAULT_SESSION_PERIOD,
+    MAX_VALIDATORS, TOKEN,
 };
 
+use aleph_chain_extension::AlephChainExtension;
 pub use pallet_balances::Call as BalancesCall;
 use pallet_contracts::weights::WeightInfo;
 use pallet_contracts_primitives::{
@@ -113,7 +118,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("aleph-node"),
     impl_name: create_runtime_str!("aleph-node"),
     authoring_version: 1,
-    spec_version: 14,
+    spec_version: 24,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
     transaction_version: 6,
@@ -300,6 +305,8 @@ impl pallet_transaction_payment::Config for Runtime {
 parameter_types! {
     pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
     pub const MaxScheduledPerBlock: u32 = 50;
+    // Calls scheduled by hash whose preimage is missing are retried 10 blocks later.
+    pub const NoPreimagePostponement: Option<BlockNumber> = Some(10);
 }
 
 impl pallet_scheduler::Config for Runtime {
@@ -308,12 +315,28 @@ impl pallet_scheduler::Config for Runtime {
     type PalletsOrigin = OriginCaller;
     type Call = Call;
     type MaximumWeight = MaximumSchedulerWeight;
-    type ScheduleOrigin = frame_system::EnsureRoot<AccountId>;
+    type ScheduleOrigin = EnsureRootOrCouncilMajority;
     type MaxScheduledPerBlock = MaxScheduledPerBlock;
     type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
     type OriginPrivilegeCmp = EqualPrivilegeOnly;
-    type PreimageProvider = ();
-    type NoPreimagePostponement = ();
+    type PreimageProvider = Preimage;
+    type NoPreimagePostponement = NoPreimagePostponement;
+}
+
+parameter_types! {
+    pub const PreimageMaxSize: u32 = 4096 * 1024;
+    pub const PreimageBaseDeposit: Balance = deposit(2, 64);
+    pub const PreimageByteDeposit: Balance = deposit(0, 1);
+}
+
+impl pallet_preimage::Config for Runtime {
+    type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+    type Event = Event;
+    type Currency = Balances;
+    type ManagerOrigin = EnsureRoot<AccountId>;
+    type MaxSize = PreimageMaxSize;
+    type BaseDeposit = PreimageBaseDeposit;
+    type ByteDeposit = PreimageByteDeposit;
 }
 
 impl pallet_sudo::Config for Runtime {
@@ -321,8 +344,15 @@ impl pallet_sudo::Config for Runtime {
     type Call = Call;
 }
 
+parameter_types! {
+    pub const AuthorityHistoryDepth: SessionIndex = DEFAULT_AUTHORITY_HISTORY_DEPTH;
+}
+
 impl pallet_aleph::Config for Runtime {
     type AuthorityId = AlephId;
+    type CurrentSession = CurrentSessionIndex;
+    type AuthorityHistoryDepth = AuthorityHistoryDepth;
+    type WeightInfo = pallet_aleph::weights::SubstrateWeight<Runtime>;
 }
 
 impl_opaque_keys! {
@@ -336,15 +366,43 @@ parameter_types! {
     pub const SessionPeriod: u32 = DEFAULT_SESSION_PERIOD;
 }
 
+parameter_types! {
+    pub const PerformanceHistoryDepth: SessionIndex = DEFAULT_PERFORMANCE_HISTORY_DEPTH;
+    pub const MaxRecordedValidators: u32 = MAX_VALIDATORS;
+}
+
 impl pallet_elections::Config for Runtime {
     type Event = Event;
     type DataProvider = Staking;
     type SessionPeriod = SessionPeriod;
     type SessionManager = pallet_session::historical::NoteHistoricalRoot<Runtime, Staking>;
+    type PerformanceHistoryDepth = PerformanceHistoryDepth;
+    type MaxValidators = MaxRecordedValidators;
+    type WeightInfo = pallet_elections::weights::SubstrateWeight<Runtime>;
 }
 
 impl pallet_randomness_collective_flip::Config for Runtime {}
 
+pub struct CurrentSessionIndex;
+impl Get<SessionIndex> for CurrentSessionIndex {
+    fn get() -> SessionIndex {
+        Session::current_index()
+    }
+}
+
+pub struct AlephCommittees;
+impl pallet_randomness_beacon::CommitteeKeys for AlephCommittees {
+    fn committee(session: SessionIndex) -> Option<Vec<AlephId>> {
+        Aleph::session_authorities(session).map(|(authorities, _)| authorities)
+    }
+}
+
+impl pallet_randomness_beacon::Config for Runtime {
+    type CurrentSession = CurrentSessionIndex;
+    type CommitteeKeys = AlephCommittees;
+    type FallbackRandomness = RandomnessCollectiveFlip;
+}
+
 parameter_types! {
     pub const Offset: u32 = 0;
 }
@@ -377,13 +435,34 @@ parameter_types! {
     pub const SessionsPerEra: EraIndex = DEFAULT_SESSIONS_PER_ERA;
 }
 
+// Every era is paid out as if it lasted as long as planned, following the inflation model kept by
+// the inflation pallet.
 pub struct UniformEraPayout {}
 
+impl UniformEraPayout {
+    /// The payouts for the active era, provided that the stake and the issuance stay as they are.
+    fn projected() -> (Balance, Balance) {
+        let total_staked = Staking::active_era()
+            .map(|era| Staking::eras_total_stake(era.index))
+            .unwrap_or_default();
+        <Self as pallet_staking::EraPayout<Balance>>::era_payout(
+            total_staked,
+            Balances::total_issuance(),
+            0,
+        )
+    }
+}
+
 impl pallet_staking::EraPayout<Balance> for UniformEraPayout {
-    fn era_payout(_: Balance, _: Balance, _: u64) -> (Balance, Balance) {
+    fn era_payout(total_staked: Balance, total_issuance: Balance, _: u64) -> (Balance, Balance) {
         let miliseconds_per_era =
             MILLISECS_PER_BLOCK * SessionPeriod::get() as u64 * SessionsPerEra::get() as u64;
-        primitives::staking::era_payout(miliseconds_per_era)
+        primitives::staking::era_payout(
+            &Inflation::inflation_model(),
+            total_staked,
+            total_issuance,
+            miliseconds_per_era,
+        )
     }
 }
 
@@ -493,6 +572,78 @@ impl pallet_staking::Config for Runtime {
     type WeightInfo = PayoutStakersDecreasedWeightInfo;
 }
 
+/// Bonds and nominates with the bonded accounts of the nomination pools.
+pub struct PoolsStaking;
+
+impl pallet_nomination_pools::StakingInterface<AccountId, Balance> for PoolsStaking {
+    fn minimum_bond() -> Balance {
+        pallet_staking::MinNominatorBond::<Runtime>::get()
+    }
+
+    fn current_era() -> EraIndex {
+        Staking::current_era().unwrap_or(0)
+    }
+
+    fn unbonding_era() -> EraIndex {
+        Self::current_era() + BondingDuration::get()
+    }
+
+    fn bond(stash: AccountId, value: Balance, payee: AccountId) -> DispatchResult {
+        Staking::bond(
+            Origin::signed(stash.clone()),
+            stash.into(),
+            value,
+            pallet_staking::RewardDestination::Account(payee),
+        )
+    }
+
+    fn bond_extra(stash: AccountId, extra: Balance) -> DispatchResult {
+        Staking::bond_extra(Origin::signed(stash), extra)
+    }
+
+    fn unbond(stash: AccountId, value: Balance) -> DispatchResult {
+        Staking::unbond(Origin::signed(stash), value)
+    }
+
+    fn withdraw_unbonded(stash: AccountId) -> DispatchResult {
+        // Stakers are never slashed, so there are no slashing spans.
+        Staking::withdraw_unbonded(Origin::signed(stash), 0)
+            .map(|_| ())
+            .map_err(|e| e.error)
+    }
+
+    fn nominate(stash: AccountId, target: AccountId) -> DispatchResult {
+        Staking::nominate(Origin::signed(stash), vec![target.into()])
+    }
+
+    fn chill(stash: AccountId) -> DispatchResult {
+        Staking::chill(Origin::signed(stash))
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn set_current_era(era: EraIndex) {
+        pallet_staking::CurrentEra::<Runtime>::put(era);
+    }
+}
+
+parameter_types! {
+    pub const NominationPoolsPalletId: PalletId = PalletId(*b"a0/nopls");
+    pub const MinJoinBond: Balance = TOKEN;
+    pub const MaxMembersPerPool: u32 = 1024;
+    pub const MaxUnbonding: u32 = 8;
+}
+
+impl pallet_nomination_pools::Config for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+    type Staking = PoolsStaking;
+    type PalletId = NominationPoolsPalletId;
+    type MinJoinBond = MinJoinBond;
+    type MaxMembersPerPool = MaxMembersPerPool;
+    type MaxUnbonding = MaxUnbonding;
+    type WeightInfo = pallet_nomination_pools::weights::SubstrateWeight<Runtime>;
+}
+
 parameter_types! {
     pub const MinimumPeriod: u64 = MILLISECS_PER_BLOCK / 2;
 }
@@ -555,6 +706,82 @@ impl pallet_multisig::Config for Runtime {
     type WeightInfo = pallet_multisig::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+    // One storage item; key size 32, value size 8.
+    pub const ProxyDepositBase: Balance = deposit(1, 8);
+    // Additional storage item size of 33 bytes.
+    pub const ProxyDepositFactor: Balance = deposit(0, 33);
+    pub const AnnouncementDepositBase: Balance = deposit(1, 8);
+    // Additional storage item size of 68 bytes: account, call hash and block number.
+    pub const AnnouncementDepositFactor: Balance = deposit(0, 68);
+    pub const MaxProxies: u32 = 32;
+    pub const MaxPending: u32 = 32;
+}
+
+impl InstanceFilter<Call> for ProxyType {
+    fn filter(&self, c: &Call) -> bool {
+        match self {
+            ProxyType::Any => true,
+            ProxyType::NonTransfer => matches!(
+                c,
+                Call::System(..)
+                    | Call::Staking(..)
+                    | Call::Session(..)
+                    | Call::NominationPools(..)
+                    | Call::Treasury(..)
+                    | Call::Vesting(pallet_vesting::Call::vest { .. })
+                    | Call::Vesting(pallet_vesting::Call::vest_other { .. })
+                    | Call::Vesting(pallet_vesting::Call::merge_schedules { .. })
+                    | Call::Utility(..)
+                    | Call::Multisig(..)
+                    | Call::Council(..)
+                    | Call::Inflation(..)
+                    | Call::Preimage(..)
+                    | Call::Proxy(..)
+            ),
+            ProxyType::Staking => {
+                matches!(c, Call::Staking(..) | Call::Session(..) | Call::Utility(..))
+            }
+            ProxyType::SessionKeys => matches!(
+                c,
+                Call::Session(
+                    pallet_session::Call::set_keys { .. } | pallet_session::Call::purge_keys { .. }
+                )
+            ),
+            ProxyType::Governance => matches!(
+                c,
+                Call::Treasury(..) | Call::Council(..) | Call::Inflation(..) | Call::Utility(..)
+            ),
+        }
+    }
+
+    fn is_superset(&self, o: &Self) -> bool {
+        match (self, o) {
+            (x, y) if x == y => true,
+            (ProxyType::Any, _) => true,
+            (_, ProxyType::Any) => false,
+            (ProxyType::NonTransfer, _) => true,
+            (ProxyType::Staking, ProxyType::SessionKeys) => true,
+            _ => false,
+        }
+    }
+}
+
+impl pallet_proxy::Config for Runtime {
+    type Event = Event;
+    type Call = Call;
+    type Currency = Balances;
+    type ProxyType = ProxyType;
+    type ProxyDepositBase = ProxyDepositBase;
+    type ProxyDepositFactor = ProxyDepositFactor;
+    type MaxProxies = MaxProxies;
+    type WeightInfo = pallet_proxy::weights::SubstrateWeight<Runtime>;
+    type MaxPending = MaxPending;
+    type CallHasher = BlakeTwo256;
+    type AnnouncementDepositBase = AnnouncementDepositBase;
+    type AnnouncementDepositFactor = AnnouncementDepositFactor;
+}
+
 // We do not burn any money within treasury.
 pub const TREASURY_BURN: u32 = 0;
 // The percentage of the amount of the proposal that the proposer should deposit.
@@ -580,6 +807,34 @@ parameter_types! {
     pub const TreasuryPalletId: PalletId = PalletId(*b"a0/trsry");
 }
 
+parameter_types! {
+    pub CouncilMotionDuration: BlockNumber = hours_as_block_num(24);
+    pub const CouncilMaxProposals: u32 = 100;
+    pub const CouncilMaxMembers: u32 = 100;
+}
+
+pub type CouncilCollective = pallet_collective::Instance1;
+
+impl pallet_collective::Config<CouncilCollective> for Runtime {
+    type Origin = Origin;
+    type Proposal = Call;
+    type Event = Event;
+    type MotionDuration = CouncilMotionDuration;
+    type MaxProposals = CouncilMaxProposals;
+    type MaxMembers = CouncilMaxMembers;
+    type DefaultVote = pallet_collective::PrimeDefaultVote;
+    type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+}
+
+type EnsureRootOrCouncilMajority = EnsureOneOf<
+    EnsureRoot<AccountId>,
+    pallet_collective::EnsureProportionMoreThan<AccountId, CouncilCollective, 1, 2>,
+>;
+
+// The sudo account keeps governing the treasury while the council takes over. Sudo hands over
+// control by setting the council members with `Council::set_members`, after which motions of the
+// council approve and reject proposals as well. Removing the sudo account from the treasury
+// origins below is the last step of the handover.
 pub struct TreasuryGovernance;
 impl SortedMembers<AccountId> for TreasuryGovernance {
     fn sorted_members() -> Vec<AccountId> {
@@ -587,8 +842,25 @@ impl SortedMembers<AccountId> for TreasuryGovernance {
     }
 }
 
+// Spending treasury funds requires three fifths of the council, rejecting a proposal a majority.
+type TreasuryApproveOrigin = EnsureOneOf<
+    EnsureSignedBy<TreasuryGovernance, AccountId>,
+    pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 3, 5>,
+>;
+type TreasuryRejectOrigin = EnsureOneOf<
+    EnsureSignedBy<TreasuryGovernance, AccountId>,
+    pallet_collective::EnsureProportionMoreThan<AccountId, CouncilCollective, 1, 2>,
+>;
+
+impl pallet_inflation::Config for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+    type UpdateOrigin = EnsureRootOrCouncilMajority;
+    type WeightInfo = pallet_inflation::weights::SubstrateWeight<Runtime>;
+}
+
 impl pallet_treasury::Config for Runtime {
-    type ApproveOrigin = EnsureSignedBy<TreasuryGovernance, AccountId>;
+    type ApproveOrigin = TreasuryApproveOrigin;
     type Burn = Burn;
     type BurnDestination = ();
     type Currency = Balances;
@@ -599,7 +871,7 @@ impl pallet_treasury::Config for Runtime {
     type ProposalBond = ProposalBond;
     type ProposalBondMinimum = ProposalBondMinimum;
     type ProposalBondMaximum = ProposalBondMaximum;
-    type RejectOrigin = EnsureSignedBy<TreasuryGovernance, AccountId>;
+    type RejectOrigin = TreasuryRejectOrigin;
     type SpendFunds = ();
     type SpendPeriod = SpendPeriod;
     type WeightInfo = pallet_treasury::weights::SubstrateWeight<Runtime>;
@@ -632,6 +904,8 @@ parameter_types! {
 
 impl pallet_contracts::Config for Runtime {
     type Time = Timestamp;
+    // The beacon seed can still be biased by withholding shares, so contracts keep using the
+    // collective flip until the beacon is based on threshold signatures.
     type Randomness = RandomnessCollectiveFlip;
     type Currency = Balances;
     type Event = Event;
@@ -642,7 +916,8 @@ impl pallet_contracts::Config for Runtime {
     type DepositPerByte = DepositPerByte;
     type WeightPrice = pallet_transaction_payment::Pallet<Self>;
     type WeightInfo = pallet_contracts::weights::SubstrateWeight<Self>;
-    type ChainExtension = ();
+    type ChainExtension =
+        AlephChainExtension<aleph_chain_extension::weights::SubstrateWeight<Runtime>>;
     type DeletionQueueDepth = DeletionQueueDepth;
     type DeletionWeightLimit = DeletionWeightLimit;
     type Schedule = Schedule;
@@ -668,7 +943,7 @@ construct_runtime!(
         Staking: pallet_staking::{Pallet, Call, Storage, Config<T>, Event<T>} = 8,
         History: pallet_session::historical::{Pallet} = 9,
         Session: pallet_session::{Pallet, Call, Storage, Event, Config<T>} = 10,
-        Aleph: pallet_aleph::{Pallet, Storage} = 11,
+        Aleph: pallet_aleph::{Pallet, Call, Storage, Inherent} = 11,
         Elections: pallet_elections::{Pallet, Call, Storage, Config<T>, Event<T>} = 12,
         Treasury: pallet_treasury::{Pallet, Call, Storage, Config, Event<T>} = 13,
         Vesting: pallet_vesting::{Pallet, Call, Storage, Event<T>, Config<T>} = 14,
@@ -676,6 +951,12 @@ construct_runtime!(
         Multisig: pallet_multisig::{Pallet, Call, Storage, Event<T>} = 16,
         Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>} = 17,
         Contracts: pallet_contracts::{Pallet, Call, Storage, Event<T>} = 18,
+        RandomnessBeacon: pallet_randomness_beacon::{Pallet, Call, Storage, Inherent} = 19,
+        Council: pallet_collective::<Instance1>::{Pallet, Call, Storage, Origin<T>, Event<T>} = 20,
+        Preimage: pallet_preimage::{Pallet, Call, Storage, Event<T>} = 21,
+        Proxy: pallet_proxy::{Pallet, Call, Storage, Event<T>} = 22,
+        NominationPools: pallet_nomination_pools::{Pallet, Call, Storage, Event<T>} = 23,
+        Inflation: pallet_inflation::{Pallet, Call, Storage, Config, Event<T>} = 24,
     }
 );
 
@@ -832,6 +1113,39 @@ impl_runtime_apis! {
                 .map(|(_, key)| key.get(AlephId::ID).ok_or(AlephApiError::DecodeKey))
                 .collect::<Result<Vec<AlephId>, AlephApiError>>()
         }
+
+        fn session_authorities(session: SessionIndex) -> Option<Vec<AlephId>> {
+            Aleph::session_authorities(session).map(|(authorities, _)| authorities)
+        }
+
+        fn authority_set_hash(session: SessionIndex) -> Option<Hash> {
+            Aleph::authority_set_hash(session)
+        }
+    }
+
+    impl primitives::ElectionsApi<Block, AccountId> for Runtime {
+        fn validators_performance(
+            session: SessionIndex,
+        ) -> Option<Vec<primitives::ValidatorPerformance<AccountId>>> {
+            let (validators_payout, _) = UniformEraPayout::projected();
+            Elections::validators_performance(session, validators_payout)
+        }
+
+        fn session_performance(
+            session: SessionIndex,
+        ) -> Option<Vec<primitives::ValidatorSessionRecord<AccountId>>> {
+            Elections::session_performance(session).map(|records| records.into_inner())
+        }
+    }
+
+    impl primitives::InflationApi<Block> for Runtime {
+        fn inflation_model() -> InflationModel {
+            Inflation::inflation_model()
+        }
+
+        fn projected_era_payout() -> (Balance, Balance) {
+            UniformEraPayout::projected()
+        }
     }
 
     impl pallet_contracts_rpc_runtime_api::ContractsApi<Block, AccountId, Balance, BlockNumber, Hash> for Runtime {
@@ -878,4 +1192,178 @@ impl_runtime_apis! {
 
     }
 
+    #[cfg(feature = "try-runtime")]
+    impl frame_try_runtime::TryRuntime<Block> for Runtime {
+        fn on_runtime_upgrade() -> (Weight, Weight) {
+            let weight = Executive::try_runtime_upgrade().unwrap();
+            (weight, BlockWeights::get().max_block)
+        }
+
+        fn execute_block_no_check(block: Block) -> Weight {
+            Executive::execute_block_no_check(block)
+        }
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    impl frame_benchmarking::Benchmark<Block> for Runtime {
+        fn benchmark_metadata(extra: bool) -> (
+            Vec<frame_benchmarking::BenchmarkList>,
+            Vec<frame_support::traits::StorageInfo>,
+        ) {
+            use aleph_chain_extension::benchmarking::Pallet as ChainExtensionBench;
+            use frame_benchmarking::{list_benchmark, Benchmarking, BenchmarkList};
+            use frame_support::traits::StorageInfoTrait;
+            use frame_system_benchmarking::Pallet as SystemBench;
+
+            let mut list = Vec::<BenchmarkList>::new();
+
+            list_benchmark!(list, extra, frame_system, SystemBench::<Runtime>);
+            list_benchmark!(list, extra, aleph_chain_extension, ChainExtensionBench::<Runtime>);
+            list_benchmark!(list, extra, pallet_aleph, Aleph);
+            list_benchmark!(list, extra, pallet_balances, Balances);
+            list_benchmark!(list, extra, pallet_collective, Council);
+            list_benchmark!(list, extra, pallet_contracts, Contracts);
+            list_benchmark!(list, extra, pallet_elections, Elections);
+            list_benchmark!(list, extra, pallet_multisig, Multisig);
+            list_benchmark!(list, extra, pallet_inflation, Inflation);
+            list_benchmark!(list, extra, pallet_nomination_pools, NominationPools);
+            list_benchmark!(list, extra, pallet_preimage, Preimage);
+            list_benchmark!(list, extra, pallet_proxy, Proxy);
+            list_benchmark!(list, extra, pallet_scheduler, Scheduler);
+            list_benchmark!(list, extra, pallet_staking, Staking);
+            list_benchmark!(list, extra, pallet_timestamp, Timestamp);
+            list_benchmark!(list, extra, pallet_treasury, Treasury);
+            list_benchmark!(list, extra, pallet_utility, Utility);
+            list_benchmark!(list, extra, pallet_vesting, Vesting);
+
+            let storage_info = AllPalletsWithSystem::storage_info();
+
+            (list, storage_info)
+        }
+
+        fn dispatch_benchmark(
+            config: frame_benchmarking::BenchmarkConfig
+        ) -> Result<Vec<frame_benchmarking::BenchmarkBatch>, sp_runtime::RuntimeString> {
+            use aleph_chain_extension::benchmarking::Pallet as ChainExtensionBench;
+            use frame_benchmarking::{add_benchmark, Benchmarking, BenchmarkBatch, TrackedStorageKey};
+            use frame_system_benchmarking::Pallet as SystemBench;
+
+            impl frame_system_benchmarking::Config for Runtime {}
+            impl aleph_chain_extension::benchmarking::Config for Runtime {}
+
+            let whitelist: Vec<TrackedStorageKey> = vec![
+                // Block Number
+                hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef702a5c1b19ab7a04f536c519aca4983ac").to_vec().into(),
+                // Total Issuance
+                hex_literal::hex!("c2261276cc9d1f8598ea4b6a74b15c2f57c875e4cff74148e4628f264b974c80").to_vec().into(),
+                // Execution Phase
+                hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef7ff553b5a9862a516939d82b3d3d8661a").to_vec().into(),
+                // Event Count
+                hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef70a98fdbe9ce6c55837576c60c7af3850").to_vec().into(),
+                // System Events
+                hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7").to_vec().into(),
+            ];
+
+            let mut batches = Vec::<BenchmarkBatch>::new();
+            let params = (&config, &whitelist);
+
+            add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
+            add_benchmark!(params, batches, aleph_chain_extension, ChainExtensionBench::<Runtime>);
+            add_benchmark!(params, batches, pallet_aleph, Aleph);
+            add_benchmark!(params, batches, pallet_balances, Balances);
+            add_benchmark!(params, batches, pallet_collective, Council);
+            add_benchmark!(params, batches, pallet_contracts, Contracts);
+            add_benchmark!(params, batches, pallet_elections, Elections);
+            add_benchmark!(params, batches, pallet_multisig, Multisig);
+            add_benchmark!(params, batches, pallet_inflation, Inflation);
+            add_benchmark!(params, batches, pallet_nomination_pools, NominationPools);
+            add_benchmark!(params, batches, pallet_preimage, Preimage);
+            add_benchmark!(params, batches, pallet_proxy, Proxy);
+            add_benchmark!(params, batches, pallet_scheduler, Scheduler);
+            add_benchmark!(params, batches, pallet_staking, Staking);
+            add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+            add_benchmark!(params, batches, pallet_treasury, Treasury);
+            add_benchmark!(params, batches, pallet_utility, Utility);
+            add_benchmark!(params, batches, pallet_vesting, Vesting);
+
+            if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
+            Ok(batches)
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::{ed25519, sr25519};
+
+    fn set_keys() -> Call {
+        let keys = SessionKeys {
+            aura: sr25519::Public::from_raw([1; 32]).into(),
+            aleph: ed25519::Public::from_raw([2; 32]).into(),
+        };
+        Call::Session(pallet_session::Call::set_keys {
+            keys,
+            proof: Vec::new(),
+        })
+    }
+
+    fn purge_keys() -> Call {
+        Call::Session(pallet_session::Call::purge_keys {})
+    }
+
+    fn transfer() -> Call {
+        Call::Balances(pallet_balances::Call::transfer {
+            dest: Address::Id(AccountId::new([3; 32])),
+            value: TOKEN,
+        })
+    }
+
+    fn chill() -> Call {
+        Call::Staking(pallet_staking::Call::chill {})
+    }
+
+    fn batch(calls: Vec<Call>) -> Call {
+        Call::Utility(pallet_utility::Call::batch { calls })
+    }
+
+    #[test]
+    fn staking_proxy_cannot_reach_balances() {
+        assert!(ProxyType::Staking.filter(&chill()));
+        assert!(ProxyType::Staking.filter(&set_keys()));
+        assert!(!ProxyType::Staking.filter(&transfer()));
+    }
+
+    #[test]
+    fn session_keys_proxy_only_sets_and_purges_keys() {
+        assert!(ProxyType::SessionKeys.filter(&set_keys()));
+        assert!(ProxyType::SessionKeys.filter(&purge_keys()));
+        assert!(!ProxyType::SessionKeys.filter(&chill()));
+        assert!(!ProxyType::SessionKeys.filter(&transfer()));
+        assert!(!ProxyType::SessionKeys.filter(&batch(vec![set_keys()])));
+    }
+
+    #[test]
+    fn only_any_proxy_can_transfer() {
+        assert!(ProxyType::Any.filter(&transfer()));
+        for proxy_type in [
+            ProxyType::NonTransfer,
+            ProxyType::Staking,
+            ProxyType::SessionKeys,
+            ProxyType::Governance,
+        ] {
+            assert!(!proxy_type.filter(&transfer()), "{:?}", proxy_type);
+        }
+    }
+
+    #[test]
+    fn proxy_types_are_ordered_by_allowed_calls() {
+        assert!(ProxyType::Any.is_superset(&ProxyType::NonTransfer));
+        assert!(ProxyType::NonTransfer.is_superset(&ProxyType::Staking));
+        assert!(ProxyType::Staking.is_superset(&ProxyType::SessionKeys));
+        assert!(!ProxyType::SessionKeys.is_superset(&ProxyType::Staking));
+        assert!(!ProxyType::Staking.is_superset(&ProxyType::Governance));
+        assert!(!ProxyType::NonTransfer.is_superset(&ProxyType::Any));
+    }
 }
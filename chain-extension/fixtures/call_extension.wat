@@ -0,0 +1,34 @@
+;; Calls the chain extension with the function id given in the first 4 bytes of the input and the
+;; rest of the input as its input. Returns the output of the chain extension.
+(module
+	(import "seal0" "seal_call_chain_extension"
+		(func $seal_call_chain_extension (param i32 i32 i32 i32 i32) (result i32))
+	)
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) length of the input buffer
+	(data (i32.const 0) "\00\80")
+
+	;; [4, 8) length of the output buffer
+	(data (i32.const 4) "\00\40")
+
+	;; [16, 32784) input buffer
+	;; [32784, 49168) output buffer
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 16) (i32.const 0))
+		;; the status code is ignored, the chain extension only returns 0
+		(drop (call $seal_call_chain_extension
+			(i32.load (i32.const 16))
+			(i32.const 20)
+			(i32.sub (i32.load (i32.const 0)) (i32.const 4))
+			(i32.const 32784)
+			(i32.const 4)
+		))
+		(call $seal_return (i32.const 0) (i32.const 32784) (i32.load (i32.const 4)))
+	)
+)
@@ -0,0 +1,95 @@
+//! ink! bindings of the Aleph chain extension.
+//!
+//! Contracts using it have to declare `AlephEnvironment` as their environment:
+//!
+//! ```ignore
+//! #[ink::contract(env = aleph_extension_ink::AlephEnvironment)]
+//! mod my_contract {
+//!     // ...
+//!     #[ink(message)]
+//!     pub fn session(&self) -> u32 {
+//!         self.env().extension().current_session()
+//!     }
+//! }
+//! ```
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_env::{DefaultEnvironment, Environment};
+use ink_prelude::vec::Vec;
+
+pub type AccountId = <DefaultEnvironment as Environment>::AccountId;
+pub type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+pub type Hash = <DefaultEnvironment as Environment>::Hash;
+pub type SessionIndex = u32;
+/// The raw ed25519 public key of an AlephBFT authority.
+pub type AuthorityId = [u8; 32];
+
+/// A signature to verify together with the signer's public key and the signed message. The
+/// message can be at most 16 KiB long.
+#[derive(Clone, Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct SignatureInput {
+    pub public: [u8; 32],
+    pub signature: [u8; 64],
+    pub message: Vec<u8>,
+}
+
+// The function ids have to match the ones in `aleph-chain-extension`.
+#[ink_lang::chain_extension]
+pub trait AlephExtension {
+    type ErrorCode = AlephExtensionError;
+
+    /// The current session index.
+    #[ink(extension = 0x0101, returns_result = false)]
+    fn current_session() -> SessionIndex;
+
+    /// The validators of the current session.
+    #[ink(extension = 0x0102, returns_result = false)]
+    fn committee() -> Vec<AccountId>;
+
+    /// The current AlephBFT authorities.
+    #[ink(extension = 0x0103, returns_result = false)]
+    fn authorities() -> Vec<AuthorityId>;
+
+    /// Whether the ed25519 signature is valid.
+    #[ink(extension = 0x0201, returns_result = false)]
+    fn verify_ed25519(input: SignatureInput) -> bool;
+
+    /// Whether the sr25519 signature is valid.
+    #[ink(extension = 0x0202, returns_result = false)]
+    fn verify_sr25519(input: SignatureInput) -> bool;
+}
+
+/// The chain extension only returns the status code 0, failures make the contract trap.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum AlephExtensionError {
+    UnknownStatusCode(u32),
+}
+
+impl ink_env::chain_extension::FromStatusCode for AlephExtensionError {
+    fn from_status_code(status_code: u32) -> Result<(), Self> {
+        match status_code {
+            0 => Ok(()),
+            code => Err(Self::UnknownStatusCode(code)),
+        }
+    }
+}
+
+/// The environment of contracts running on the Aleph runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum AlephEnvironment {}
+
+impl Environment for AlephEnvironment {
+    const MAX_EVENT_TOPICS: usize = <DefaultEnvironment as Environment>::MAX_EVENT_TOPICS;
+
+    type AccountId = <DefaultEnvironment as Environment>::AccountId;
+    type Balance = <DefaultEnvironment as Environment>::Balance;
+    type Hash = <DefaultEnvironment as Environment>::Hash;
+    type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+    type Timestamp = <DefaultEnvironment as Environment>::Timestamp;
+
+    type ChainExtension = AlephExtension;
+}
@@ -0,0 +1,81 @@
+//! Benchmarks of the functions of the chain extension.
+//!
+//! The chain extension is not a pallet, so the benchmarks are defined for the `Pallet` below,
+//! which only exists so that the runtime can list them. They measure the work done by the
+//! functions, copying the input and output is charged by `pallet_contracts` itself.
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_support::{sp_runtime::RuntimeAppPublic, traits::OneSessionHandler};
+use primitives::KEY_TYPE;
+
+const SEED: u32 = 0;
+const MAX_VALIDATORS: u32 = 1000;
+
+pub trait Config:
+    pallet_session::Config<ValidatorId = <Self as frame_system::Config>::AccountId>
+    + pallet_aleph::Config
+{
+}
+
+pub struct Pallet<T: Config>(PhantomData<T>);
+
+fn message(len: u32) -> Vec<u8> {
+    (0..len).map(|i| i as u8).collect()
+}
+
+benchmarks! {
+    current_session {
+    }: {
+        current_session::<T>();
+    }
+
+    committee {
+        let n in 1 .. MAX_VALIDATORS;
+        let validators: Vec<T::AccountId> = (0..n).map(|i| account("validator", i, SEED)).collect();
+        pallet_session::Validators::<T>::put(validators);
+    }: {
+        committee::<T>();
+    }
+
+    authorities {
+        let n in 1 .. MAX_VALIDATORS;
+        let validators: Vec<(T::AccountId, T::AuthorityId)> = (0..n)
+            .map(|i| (account("validator", i, SEED), T::AuthorityId::generate_pair(None)))
+            .collect();
+        let new_session = || validators.iter().map(|(account, key)| (account, key.clone()));
+        pallet_aleph::Pallet::<T>::on_new_session(true, new_session(), new_session());
+    }: {
+        authorities::<T>();
+    }
+
+    verify_ed25519 {
+        let n in 0 .. MAX_MESSAGE_LEN;
+        let message = message(n);
+        let public = sp_io::crypto::ed25519_generate(KEY_TYPE, None);
+        let signature = sp_io::crypto::ed25519_sign(KEY_TYPE, &public, &message)
+            .expect("The key was just generated");
+        let input = SignatureInput {
+            public: public.0,
+            signature: signature.0,
+            message,
+        };
+    }: {
+        assert!(verify_ed25519(&input));
+    }
+
+    verify_sr25519 {
+        let n in 0 .. MAX_MESSAGE_LEN;
+        let message = message(n);
+        let public = sp_io::crypto::sr25519_generate(KEY_TYPE, None);
+        let signature = sp_io::crypto::sr25519_sign(KEY_TYPE, &public, &message)
+            .expect("The key was just generated");
+        let input = SignatureInput {
+            public: public.0,
+            signature: signature.0,
+            message,
+        };
+    }: {
+        assert!(verify_sr25519(&input));
+    }
+}
@@ -0,0 +1,153 @@
+//! Chain extension exposing Aleph specific functionality to ink! contracts.
+//!
+//! Every function takes its arguments SCALE encoded in the input buffer and writes its result
+//! SCALE encoded to the output buffer. The status code is always 0, errors make the contract trap.
+//!
+//! The finalized block known to `pallet_aleph` is not exposed: it is only reported by the block
+//! authors and not backed by a justification, so contracts must not rely on it.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use codec::{Decode, Encode};
+use frame_support::{log, weights::Weight};
+use pallet_contracts::chain_extension::{
+    ChainExtension, Environment, Ext, InitState, RetVal, SysConfig, UncheckedFrom,
+};
+use primitives::SessionIndex;
+use sp_core::{ed25519, sr25519};
+use sp_runtime::DispatchError;
+use sp_std::{marker::PhantomData, vec::Vec};
+pub use weights::WeightInfo;
+
+/// The current session index, returns `SessionIndex`.
+pub const CURRENT_SESSION: u32 = 0x0101;
+/// The validators of the current session, returns `Vec<AccountId>`.
+pub const COMMITTEE: u32 = 0x0102;
+/// The current AlephBFT authorities, returns `Vec<AuthorityId>`.
+pub const AUTHORITIES: u32 = 0x0103;
+/// Verifies an ed25519 signature given as `SignatureInput`, returns `bool`.
+pub const VERIFY_ED25519: u32 = 0x0201;
+/// Verifies an sr25519 signature given as `SignatureInput`, returns `bool`.
+pub const VERIFY_SR25519: u32 = 0x0202;
+
+/// The longest message whose signature can be verified.
+pub const MAX_MESSAGE_LEN: u32 = 16 * 1024;
+
+/// A signature to verify together with the signer's public key and the signed message.
+#[derive(Clone, Debug, Encode, Decode)]
+pub struct SignatureInput {
+    pub public: [u8; 32],
+    pub signature: [u8; 64],
+    pub message: Vec<u8>,
+}
+
+/// The chain extension of the Aleph runtime, `W` are its weights.
+pub struct AlephChainExtension<W>(PhantomData<W>);
+
+impl<W> Default for AlephChainExtension<W> {
+    fn default() -> Self {
+        AlephChainExtension(PhantomData)
+    }
+}
+
+pub(crate) fn current_session<T: pallet_session::Config>() -> SessionIndex {
+    pallet_session::Pallet::<T>::current_index()
+}
+
+pub(crate) fn committee<T: pallet_session::Config>() -> Vec<T::ValidatorId> {
+    pallet_session::Pallet::<T>::validators()
+}
+
+pub(crate) fn authorities<T: pallet_aleph::Config>() -> Vec<T::AuthorityId> {
+    pallet_aleph::Pallet::<T>::authorities()
+}
+
+pub(crate) fn verify_ed25519(input: &SignatureInput) -> bool {
+    sp_io::crypto::ed25519_verify(
+        &ed25519::Signature::from_raw(input.signature),
+        &input.message,
+        &ed25519::Public::from_raw(input.public),
+    )
+}
+
+pub(crate) fn verify_sr25519(input: &SignatureInput) -> bool {
+    sp_io::crypto::sr25519_verify(
+        &sr25519::Signature::from_raw(input.signature),
+        &input.message,
+        &sr25519::Public::from_raw(input.public),
+    )
+}
+
+// Checked before the input is read, so that the weight can be charged without copying a message
+// that is too long.
+fn check_signature_input_len(len: u32) -> Result<(), DispatchError> {
+    // The public key, the signature and the compact encoded length of the message.
+    const OVERHEAD: u32 = 32 + 64 + 4;
+    if len > MAX_MESSAGE_LEN + OVERHEAD {
+        return Err(DispatchError::Other(
+            "Message too long to verify its signature",
+        ));
+    }
+    Ok(())
+}
+
+impl<C, W> ChainExtension<C> for AlephChainExtension<W>
+where
+    C: pallet_contracts::Config + pallet_session::Config + pallet_aleph::Config,
+    W: WeightInfo,
+{
+    fn call<E>(func_id: u32, env: Environment<E, InitState>) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = C>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        let mut env = env.buf_in_buf_out();
+        let output = match func_id {
+            CURRENT_SESSION => {
+                env.charge_weight(W::current_session())?;
+                current_session::<C>().encode()
+            }
+            // The sets are bounded by the number of validators, so they are read before their
+            // size based weight is charged.
+            COMMITTEE => {
+                let committee = committee::<C>();
+                env.charge_weight(W::committee(committee.len() as u32))?;
+                committee.encode()
+            }
+            AUTHORITIES => {
+                let authorities = authorities::<C>();
+                env.charge_weight(W::authorities(authorities.len() as u32))?;
+                authorities.encode()
+            }
+            VERIFY_ED25519 => {
+                let len = env.in_len();
+                check_signature_input_len(len)?;
+                env.charge_weight(W::verify_ed25519(len))?;
+                let input: SignatureInput = env.read_as_unbounded(len)?;
+                verify_ed25519(&input).encode()
+            }
+            VERIFY_SR25519 => {
+                let len = env.in_len();
+                check_signature_input_len(len)?;
+                env.charge_weight(W::verify_sr25519(len))?;
+                let input: SignatureInput = env.read_as_unbounded(len)?;
+                verify_sr25519(&input).encode()
+            }
+            _ => {
+                log::warn!(target: "aleph-chain-extension", "Called an unknown function {}", func_id);
+                return Err(DispatchError::Other("Unknown chain extension function"));
+            }
+        };
+        env.write(&output, false, None)
+            .map_err(|_| DispatchError::Other("Output buffer of the chain extension too small"))?;
+        Ok(RetVal::Converging(0))
+    }
+}
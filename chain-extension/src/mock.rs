@@ -0,0 +1,203 @@
+#![cfg(test)]
+
+use super::*;
+
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{ConstU32, ConstU64, GenesisBuild, Nothing, Randomness},
+    weights::constants::WEIGHT_PER_SECOND,
+};
+use primitives::AuthorityId;
+use sp_core::H256;
+use sp_runtime::{
+    impl_opaque_keys,
+    testing::{Header, UintAuthorityId},
+    traits::{BlakeTwo256, ConvertInto, IdentityLookup, OpaqueKeys},
+    AccountId32,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+pub(crate) type AccountId = AccountId32;
+pub(crate) type Balance = u64;
+
+construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
+        Session: pallet_session::{Pallet, Call, Storage, Event, Config<T>},
+        Aleph: pallet_aleph::{Pallet, Storage},
+        Contracts: pallet_contracts::{Pallet, Call, Storage, Event<T>},
+    }
+);
+
+impl_opaque_keys! {
+    pub struct TestSessionKeys {
+        pub aleph: pallet_aleph::Pallet<Test>,
+    }
+}
+
+parameter_types! {
+    pub BlockWeights: frame_system::limits::BlockWeights =
+        frame_system::limits::BlockWeights::simple_max(2 * WEIGHT_PER_SECOND);
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = BlockWeights;
+    type BlockLength = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = ConstU64<250>;
+    type DbWeight = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+    type Balance = Balance;
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+    type DustRemoval = ();
+    type Event = Event;
+    type ExistentialDeposit = ConstU64<1>;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = ();
+}
+
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ConstU64<1>;
+    type WeightInfo = ();
+}
+
+impl pallet_session::Config for Test {
+    type Event = Event;
+    type ValidatorId = AccountId;
+    type ValidatorIdOf = ConvertInto;
+    type ShouldEndSession = pallet_session::PeriodicSessions<ConstU64<1>, ConstU64<0>>;
+    type NextSessionRotation = pallet_session::PeriodicSessions<ConstU64<1>, ConstU64<0>>;
+    type SessionManager = ();
+    type SessionHandler = <TestSessionKeys as OpaqueKeys>::KeyTypeIdProviders;
+    type Keys = TestSessionKeys;
+    type WeightInfo = ();
+}
+
+pub struct CurrentSession;
+impl frame_support::traits::Get<u32> for CurrentSession {
+    fn get() -> u32 {
+        Session::current_index()
+    }
+}
+
+impl pallet_aleph::Config for Test {
+    type AuthorityId = AuthorityId;
+    type CurrentSession = CurrentSession;
+    type AuthorityHistoryDepth = ConstU32<3>;
+    type WeightInfo = ();
+}
+
+pub struct TestRandomness;
+impl Randomness<H256, u64> for TestRandomness {
+    fn random(_subject: &[u8]) -> (H256, u64) {
+        (H256::zero(), 0)
+    }
+}
+
+impl sp_runtime::traits::Convert<Weight, Balance> for Test {
+    fn convert(weight: Weight) -> Balance {
+        weight
+    }
+}
+
+parameter_types! {
+    pub TestSchedule: pallet_contracts::Schedule<Test> = Default::default();
+}
+
+impl pallet_contracts::Config for Test {
+    type Time = Timestamp;
+    type Randomness = TestRandomness;
+    type Currency = Balances;
+    type Event = Event;
+    type Call = Call;
+    type CallFilter = Nothing;
+    type DepositPerItem = ConstU64<1>;
+    type DepositPerByte = ConstU64<1>;
+    type WeightPrice = Self;
+    type WeightInfo = ();
+    type ChainExtension = AlephChainExtension<()>;
+    type DeletionQueueDepth = ConstU32<128>;
+    type DeletionWeightLimit = ConstU64<{ WEIGHT_PER_SECOND }>;
+    type Schedule = TestSchedule;
+    type CallStack = [pallet_contracts::Frame<Self>; 31];
+    type AddressGenerator = pallet_contracts::DefaultAddressGenerator;
+}
+
+pub(crate) const ALICE: AccountId = AccountId32::new([1u8; 32]);
+
+pub(crate) fn validator(id: u64) -> AccountId {
+    let mut raw = [0u8; 32];
+    raw[..8].copy_from_slice(&id.to_le_bytes());
+    AccountId32::new(raw)
+}
+
+pub(crate) fn authority(id: u64) -> AuthorityId {
+    UintAuthorityId(id).to_public_key::<AuthorityId>()
+}
+
+pub fn new_test_ext(validators: &[u64]) -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+
+    let balances = validators
+        .iter()
+        .map(|id| (validator(*id), 1_000))
+        .chain(Some((ALICE, 1_000_000_000_000)))
+        .collect();
+    pallet_balances::GenesisConfig::<Test> { balances }
+        .assimilate_storage(&mut t)
+        .unwrap();
+
+    let keys = validators
+        .iter()
+        .map(|id| {
+            (
+                validator(*id),
+                validator(*id),
+                TestSessionKeys {
+                    aleph: authority(*id),
+                },
+            )
+        })
+        .collect();
+    pallet_session::GenesisConfig::<Test> { keys }
+        .assimilate_storage(&mut t)
+        .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
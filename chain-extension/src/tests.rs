@@ -0,0 +1,151 @@
+#![cfg(test)]
+
+use crate::{mock::*, *};
+use pallet_contracts_primitives::Code;
+use sp_core::Pair;
+
+const GAS_LIMIT: Weight = 100_000_000_000;
+
+fn deploy_contract() -> AccountId {
+    let wasm = wat::parse_file(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/fixtures/call_extension.wat"
+    ))
+    .expect("The fixture should compile");
+    Contracts::bare_instantiate(
+        ALICE,
+        1_000,
+        GAS_LIMIT,
+        None,
+        Code::Upload(wasm.into()),
+        Vec::new(),
+        Vec::new(),
+        false,
+    )
+    .result
+    .expect("The contract should be instantiated")
+    .account_id
+}
+
+fn call_extension(
+    contract: &AccountId,
+    func_id: u32,
+    input: Vec<u8>,
+) -> Result<Vec<u8>, DispatchError> {
+    let data = func_id.to_le_bytes().into_iter().chain(input).collect();
+    Contracts::bare_call(ALICE, contract.clone(), 0, GAS_LIMIT, None, data, false)
+        .result
+        .map(|result| result.data.0)
+}
+
+fn call_decoded<T: Decode>(contract: &AccountId, func_id: u32, input: Vec<u8>) -> T {
+    let output = call_extension(contract, func_id, input).expect("The call should succeed");
+    T::decode(&mut &output[..]).expect("The output should decode")
+}
+
+#[test]
+fn contract_reads_session_committee_and_authorities() {
+    new_test_ext(&[1, 2, 3]).execute_with(|| {
+        let contract = deploy_contract();
+
+        assert_eq!(
+            call_decoded::<SessionIndex>(&contract, CURRENT_SESSION, Vec::new()),
+            0
+        );
+        assert_eq!(
+            call_decoded::<Vec<AccountId>>(&contract, COMMITTEE, Vec::new()),
+            vec![validator(1), validator(2), validator(3)]
+        );
+        assert_eq!(
+            call_decoded::<Vec<primitives::AuthorityId>>(&contract, AUTHORITIES, Vec::new()),
+            vec![authority(1), authority(2), authority(3)]
+        );
+    });
+}
+
+#[test]
+fn contract_verifies_signatures() {
+    new_test_ext(&[1]).execute_with(|| {
+        let contract = deploy_contract();
+        let message = b"aleph".to_vec();
+
+        let pair = ed25519::Pair::from_seed(&[7u8; 32]);
+        let mut input = SignatureInput {
+            public: pair.public().0,
+            signature: pair.sign(&message).0,
+            message: message.clone(),
+        };
+        assert!(call_decoded::<bool>(
+            &contract,
+            VERIFY_ED25519,
+            input.encode()
+        ));
+        input.message = b"zero".to_vec();
+        assert!(!call_decoded::<bool>(
+            &contract,
+            VERIFY_ED25519,
+            input.encode()
+        ));
+
+        let pair = sr25519::Pair::from_seed(&[7u8; 32]);
+        let mut input = SignatureInput {
+            public: pair.public().0,
+            signature: pair.sign(&message).0,
+            message,
+        };
+        assert!(call_decoded::<bool>(
+            &contract,
+            VERIFY_SR25519,
+            input.encode()
+        ));
+        input.public = sr25519::Pair::from_seed(&[8u8; 32]).public().0;
+        assert!(!call_decoded::<bool>(
+            &contract,
+            VERIFY_SR25519,
+            input.encode()
+        ));
+    });
+}
+
+#[test]
+fn contract_traps_on_invalid_calls() {
+    new_test_ext(&[1]).execute_with(|| {
+        let contract = deploy_contract();
+
+        assert!(call_extension(&contract, 0x0999, Vec::new()).is_err());
+
+        let input = SignatureInput {
+            public: [0u8; 32],
+            signature: [0u8; 64],
+            message: vec![0u8; MAX_MESSAGE_LEN as usize + 1],
+        };
+        assert!(call_extension(&contract, VERIFY_ED25519, input.encode()).is_err());
+        assert!(call_extension(&contract, VERIFY_ED25519, vec![1, 2, 3]).is_err());
+    });
+}
+
+#[test]
+fn calls_are_charged_with_extension_weights() {
+    new_test_ext(&[1]).execute_with(|| {
+        let contract = deploy_contract();
+        let gas_consumed = |func_id: u32, input: Vec<u8>| {
+            let data = func_id.to_le_bytes().into_iter().chain(input).collect();
+            Contracts::bare_call(ALICE, contract.clone(), 0, GAS_LIMIT, None, data, false)
+                .gas_consumed
+        };
+        let message = vec![0u8; 1024];
+        let input = SignatureInput {
+            public: [0u8; 32],
+            signature: [0u8; 64],
+            message,
+        };
+        // Both calls go through the same code of the contract, they differ in what the chain
+        // extension charges.
+        let session = gas_consumed(CURRENT_SESSION, Vec::new());
+        let verification = gas_consumed(VERIFY_ED25519, input.encode());
+        assert!(
+            verification - session
+                >= <() as WeightInfo>::verify_ed25519(0) - <() as WeightInfo>::current_session()
+        );
+    });
+}
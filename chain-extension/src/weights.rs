@@ -0,0 +1,69 @@
+//! Weights for aleph_chain_extension.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for aleph_chain_extension.
+pub trait WeightInfo {
+    fn current_session() -> Weight;
+    fn committee(n: u32) -> Weight;
+    fn authorities(n: u32) -> Weight;
+    fn verify_ed25519(n: u32) -> Weight;
+    fn verify_sr25519(n: u32) -> Weight;
+}
+
+/// Weights for aleph_chain_extension based on the storage accesses and host functions it uses.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    // Storage: Session CurrentIndex (r:1 w:0)
+    fn current_session() -> Weight {
+        (2_150_000 as Weight).saturating_add(T::DbWeight::get().reads(1 as Weight))
+    }
+    // Storage: Session Validators (r:1 w:0)
+    fn committee(n: u32) -> Weight {
+        (2_610_000 as Weight)
+            .saturating_add((31_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+    }
+    // Storage: Aleph Authorities (r:1 w:0)
+    fn authorities(n: u32) -> Weight {
+        (2_610_000 as Weight)
+            .saturating_add((31_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+    }
+    fn verify_ed25519(n: u32) -> Weight {
+        (51_480_000 as Weight).saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+    }
+    fn verify_sr25519(n: u32) -> Weight {
+        (56_320_000 as Weight).saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn current_session() -> Weight {
+        (2_150_000 as Weight).saturating_add(RocksDbWeight::get().reads(1 as Weight))
+    }
+    fn committee(n: u32) -> Weight {
+        (2_610_000 as Weight)
+            .saturating_add((31_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+    }
+    fn authorities(n: u32) -> Weight {
+        (2_610_000 as Weight)
+            .saturating_add((31_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+    }
+    fn verify_ed25519(n: u32) -> Weight {
+        (51_480_000 as Weight).saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+    }
+    fn verify_sr25519(n: u32) -> Weight {
+        (56_320_000 as Weight).saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+    }
+}
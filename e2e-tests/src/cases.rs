@@ -3,9 +3,12 @@ use crate::{
     test::{
         batch_transactions as test_batch_transactions, change_validators as test_change_validators,
         channeling_fee as test_channeling_fee, fee_calculation as test_fee_calculation,
-        finalization as test_finalization, staking_era_payouts as test_staking_era_payouts,
+        finalization as test_finalization, pool_membership as test_pool_membership,
+        staking_era_payouts as test_staking_era_payouts,
         staking_new_validator as test_staking_new_validator, token_transfer as test_token_transfer,
-        treasury_access as test_treasury_access, validators_rotate as test_elections,
+        treasury_access as test_treasury_access,
+        treasury_council_access as test_treasury_council_access,
+        validators_rotate as test_elections,
     },
 };
 
@@ -22,6 +25,10 @@ pub fn possible_test_cases() -> PossibleTestCases {
         ("token_transfer", test_token_transfer as TestCase),
         ("channeling_fee", test_channeling_fee as TestCase),
         ("treasury_access", test_treasury_access as TestCase),
+        (
+            "treasury_council_access",
+            test_treasury_council_access as TestCase,
+        ),
         ("batch_transactions", test_batch_transactions as TestCase),
         ("staking_era_payouts", test_staking_era_payouts as TestCase),
         ("members_rotate", test_elections as TestCase),
@@ -29,6 +36,7 @@ pub fn possible_test_cases() -> PossibleTestCases {
             "staking_new_validator",
             test_staking_new_validator as TestCase,
         ),
+        ("pool_membership", test_pool_membership as TestCase),
         ("change_validators", test_change_validators as TestCase),
         ("fee_calculation", test_fee_calculation as TestCase),
     ]
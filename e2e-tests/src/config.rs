@@ -1,5 +1,11 @@
+use std::time::Duration;
+
+use aleph_client::ConnectionPool;
 use clap::Parser;
 
+/// How often the health of the nodes in `Config::connection_pool` is checked.
+const POOL_REFRESH: Duration = Duration::from_secs(30);
+
 #[derive(Debug, Parser, Clone)]
 #[clap(version = "1.0")]
 pub struct Config {
@@ -7,6 +13,10 @@ pub struct Config {
     #[clap(long, default_value = "127.0.0.1:9943")]
     pub node: String,
 
+    /// WS endpoint addresses of other nodes of the same chain, used for failing over reads
+    #[clap(long)]
+    pub backup_nodes: Option<Vec<String>>,
+
     /// Test cases to run.
     #[clap(long)]
     pub test_cases: Option<Vec<String>>,
@@ -19,3 +29,15 @@ pub struct Config {
     #[clap(long)]
     pub sudo: Option<String>,
 }
+
+impl Config {
+    /// A pool of `node` and all `backup_nodes`, checking their health every `POOL_REFRESH`.
+    pub fn connection_pool(&self) -> ConnectionPool {
+        let mut nodes = vec![self.node.clone()];
+        nodes.extend(self.backup_nodes.clone().unwrap_or_default());
+        let pool =
+            ConnectionPool::new(&nodes).expect("At least one of the nodes should be healthy");
+        pool.refresh_every(POOL_REFRESH);
+        pool
+    }
+}
@@ -6,22 +6,22 @@ use substrate_api_client::{
 };
 
 use aleph_client::{
-    balances_transfer, get_next_fee_multiplier, get_tx_fee_info, send_xt, AnyConnection, FeeInfo,
-    RootConnection, SignedConnection, TransferTransaction,
+    balances_transfer, get_next_fee_multiplier, get_tx_fee_info, try_send_xt, AnyConnection,
+    FeeInfo, RootConnection, SignedConnection, TransferTransaction,
 };
 
 use crate::{config::Config, transfer::setup_for_transfer};
 
 pub fn fee_calculation(config: &Config) -> anyhow::Result<()> {
     // An initial transfer is needed to establish the fee multiplier.
-    let (connection, to) = setup_for_transfer(config);
+    let (connection, to) = setup_for_transfer(config)?;
     let transfer_value = 1000u128;
-    balances_transfer(&connection, &to, transfer_value, XtStatus::Finalized);
+    balances_transfer(&connection, &to, transfer_value, XtStatus::Finalized)?;
 
     // An example transaction for which we will query fee details at different traffic level.
     let tx = prepare_transaction(&connection);
 
-    let (actual_multiplier, fee_info) = check_current_fees(&connection, &tx);
+    let (actual_multiplier, fee_info) = check_current_fees(&connection, &tx)?;
     assert_no_scaling(
         actual_multiplier,
         fee_info,
@@ -32,8 +32,8 @@ pub fn fee_calculation(config: &Config) -> anyhow::Result<()> {
     // The target saturation level is set to 25%, so unless we cross this limit,
     // the fees should not increase. Note that effectively it is 18.75% of the whole block.
     let root_connection = RootConnection::from(connection.clone());
-    fill_blocks(15, 5, &root_connection);
-    let (actual_multiplier, fee_info) = check_current_fees(&connection, &tx);
+    fill_blocks(15, 5, &root_connection)?;
+    let (actual_multiplier, fee_info) = check_current_fees(&connection, &tx)?;
     assert_no_scaling(
         actual_multiplier,
         fee_info,
@@ -44,8 +44,8 @@ pub fn fee_calculation(config: &Config) -> anyhow::Result<()> {
     // At 60% of occupancy the fees should increase by ~2.4% per block. However, the
     // intermediate blocks will be empty, so in order to have reliable reads we have to
     // simulate high traffic for a longer time.
-    fill_blocks(60, 4, &root_connection);
-    let (actual_multiplier, fee_info) = check_current_fees(&connection, &tx);
+    fill_blocks(60, 4, &root_connection)?;
+    let (actual_multiplier, fee_info) = check_current_fees(&connection, &tx)?;
     assert!(
         actual_multiplier.gt(&FixedU128::one()),
         "When the traffic is high the fee multiplier should increase",
@@ -56,8 +56,8 @@ pub fn fee_calculation(config: &Config) -> anyhow::Result<()> {
     );
 
     let (prev_multiplier, prev_fee_info) = (actual_multiplier, fee_info);
-    fill_blocks(60, 4, &root_connection);
-    let (actual_multiplier, fee_info) = check_current_fees(&connection, &tx);
+    fill_blocks(60, 4, &root_connection)?;
+    let (actual_multiplier, fee_info) = check_current_fees(&connection, &tx)?;
     assert!(
         actual_multiplier.gt(&prev_multiplier),
         "When the traffic is still high the fee multiplier should still increase",
@@ -68,8 +68,8 @@ pub fn fee_calculation(config: &Config) -> anyhow::Result<()> {
     );
 
     let (prev_multiplier, prev_fee_info) = (actual_multiplier, fee_info);
-    fill_blocks(0, 8, &root_connection);
-    let (actual_multiplier, fee_info) = check_current_fees(&connection, &tx);
+    fill_blocks(0, 8, &root_connection)?;
+    let (actual_multiplier, fee_info) = check_current_fees(&connection, &tx)?;
     // This is rather an ethical version of sleep.
     assert!(
         prev_multiplier.gt(&actual_multiplier),
@@ -86,12 +86,12 @@ pub fn fee_calculation(config: &Config) -> anyhow::Result<()> {
 fn check_current_fees<C: AnyConnection, Call: Encode>(
     connection: &C,
     tx: &UncheckedExtrinsicV4<Call>,
-) -> (FixedU128, FeeInfo) {
+) -> anyhow::Result<(FixedU128, FeeInfo)> {
     // The storage query will return an u128 value which is the 'inner' representation
     // i.e. scaled up by 10^18 (see `implement_fixed!` for `FixedU128).
-    let actual_multiplier = FixedU128::from_inner(get_next_fee_multiplier(connection));
-    let fee_info = get_tx_fee_info(connection, tx);
-    (actual_multiplier, fee_info)
+    let actual_multiplier = FixedU128::from_inner(get_next_fee_multiplier(connection)?);
+    let fee_info = get_tx_fee_info(connection, tx)?;
+    Ok((actual_multiplier, fee_info))
 }
 
 fn assert_no_scaling(
@@ -126,7 +126,7 @@ fn prepare_transaction(connection: &SignedConnection) -> TransferTransaction {
     )
 }
 
-fn fill_blocks(target_ratio: u32, blocks: u32, connection: &RootConnection) {
+fn fill_blocks(target_ratio: u32, blocks: u32, connection: &RootConnection) -> anyhow::Result<()> {
     for _ in 0..blocks {
         let xt = compose_extrinsic!(
             connection.as_connection(),
@@ -134,6 +134,7 @@ fn fill_blocks(target_ratio: u32, blocks: u32, connection: &RootConnection) {
             "fill_block",
             target_ratio * 10_000_000
         );
-        send_xt(connection, xt, Some("fill block"), XtStatus::InBlock);
+        try_send_xt(connection, xt, Some("fill block"), XtStatus::InBlock)?;
     }
+    Ok(())
 }
@@ -1,9 +1,9 @@
-use aleph_client::{create_connection, wait_for_finalized_block};
+use aleph_client::wait_for_finalized_block;
 
 use crate::config::Config;
 
 pub fn finalization(config: &Config) -> anyhow::Result<()> {
-    let connection = create_connection(&config.node);
+    let connection = config.connection_pool();
     wait_for_finalized_block(&connection, 1)?;
     Ok(())
 }
@@ -1,14 +1,16 @@
 pub use fee::fee_calculation;
 pub use finalization::finalization;
+pub use pools::pool_membership;
 pub use staking::{staking_era_payouts, staking_new_validator};
 pub use transfer::token_transfer;
-pub use treasury::{channeling_fee, treasury_access};
+pub use treasury::{channeling_fee, treasury_access, treasury_council_access};
 pub use utility::batch_transactions;
 pub use validators_change::change_validators;
 pub use validators_rotate::validators_rotate;
 
 mod fee;
 mod finalization;
+mod pools;
 mod staking;
 mod transfer;
 mod treasury;
@@ -0,0 +1,118 @@
+use log::info;
+use sp_core::Pair;
+use substrate_api_client::{AccountId, XtStatus};
+
+use aleph_client::{
+    balances_batch_transfer, get_free_balance, keypair_from_string, payout_stakers,
+    pools_claim_payout, pools_create, pools_join, pools_member, pools_nominate, pools_unbond,
+    pools_withdraw_unbonded, wait_for_era_completion, wait_for_full_era_completion,
+    SignedConnection,
+};
+use primitives::{staking::MIN_NOMINATOR_BOND, TOKEN};
+
+use crate::{accounts::accounts_from_seeds, config::Config};
+
+// 1. endow a depositor and a member of the pool
+// 2. create a pool and nominate one of the validators with it
+// 3. join the pool with the member account
+// 4. wait for the nomination to take effect and for the era to pass, then pay out the validator
+// 5. claim the share of the member, check that it got some rewards
+// 6. unbond the whole bond of the member
+// 7. wait for the bonding duration to pass, withdraw the unbonded funds and check that the member
+//    has left the pool
+pub fn pool_membership(config: &Config) -> anyhow::Result<()> {
+    let Config {
+        ref node, seeds, ..
+    } = config;
+
+    let validator = accounts_from_seeds(seeds)[0].clone();
+    let validator_account = AccountId::from(validator.public());
+    let connection = SignedConnection::new(node, validator.clone())?;
+
+    let depositor = keypair_from_string("//PoolDepositor");
+    let member = keypair_from_string("//PoolMember");
+    let member_account = AccountId::from(member.public());
+    balances_batch_transfer(
+        &connection,
+        vec![AccountId::from(depositor.public()), member_account.clone()],
+        MIN_NOMINATOR_BOND + TOKEN,
+    )?;
+
+    let depositor_connection = SignedConnection::new(node, depositor)?;
+    let pool_id = pools_create(&depositor_connection, MIN_NOMINATOR_BOND)?;
+    pools_nominate(
+        &depositor_connection,
+        pool_id,
+        &validator_account,
+        XtStatus::InBlock,
+    )?;
+    info!("Created pool {} nominating {}", pool_id, validator_account);
+
+    let member_connection = SignedConnection::new(node, member)?;
+    let bond = MIN_NOMINATOR_BOND / 2;
+    pools_join(&member_connection, bond, pool_id, XtStatus::InBlock)?;
+    let membership = pools_member(&connection, &member_account)?
+        .expect("The member should belong to the pool after joining");
+    assert_eq!(membership.pool_id, pool_id);
+    assert_eq!(membership.bonded, bond);
+
+    let current_era = wait_for_full_era_completion(&connection)?;
+    info!(
+        "Era {} started, claiming rewards for era {}",
+        current_era,
+        current_era - 1
+    );
+    payout_stakers(&connection, &validator_account, current_era - 1)?;
+
+    let balance_before = get_free_balance(&connection, &member_account)?;
+    pools_claim_payout(&member_connection, XtStatus::InBlock)?;
+    let balance_after = get_free_balance(&connection, &member_account)?;
+    assert!(
+        balance_after > balance_before,
+        "Expected the member to get a reward. Balance before: {}, balance after: {}",
+        balance_before,
+        balance_after
+    );
+
+    pools_unbond(&member_connection, bond, XtStatus::InBlock)?;
+    let membership = pools_member(&connection, &member_account)?
+        .expect("The member should stay in the pool until it withdraws");
+    assert_eq!(membership.bonded, 0);
+    assert_eq!(
+        membership
+            .unbonding
+            .iter()
+            .map(|chunk| chunk.value)
+            .sum::<u128>(),
+        bond
+    );
+
+    let withdrawal_era = membership
+        .unbonding
+        .iter()
+        .map(|chunk| chunk.era)
+        .max()
+        .expect("The member should have an unbonding chunk");
+    info!(
+        "Waiting for era {} to withdraw the unbonded funds",
+        withdrawal_era
+    );
+    wait_for_era_completion(&connection, withdrawal_era)?;
+
+    let balance_before = get_free_balance(&connection, &member_account)?;
+    pools_withdraw_unbonded(&member_connection, XtStatus::InBlock)?;
+    let balance_after = get_free_balance(&connection, &member_account)?;
+    assert!(
+        balance_after >= balance_before + bond - TOKEN,
+        "Expected the member to withdraw its bond of {}. Balance before: {}, balance after: {}",
+        bond,
+        balance_before,
+        balance_after
+    );
+    assert!(
+        pools_member(&connection, &member_account)?.is_none(),
+        "The member should leave the pool after withdrawing all its funds"
+    );
+
+    Ok(())
+}
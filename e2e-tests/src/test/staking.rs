@@ -49,27 +49,27 @@ pub fn staking_era_payouts(config: &Config) -> anyhow::Result<()> {
 
     let node = &config.node;
     let sender = validator_accounts[0].clone();
-    let connection = SignedConnection::new(node, sender);
+    let connection = SignedConnection::new(node, sender)?;
     let stashes_accounts = convert_authorities_to_account_id(&stashes_accounts_key_pairs);
 
-    balances_batch_transfer(&connection, stashes_accounts, MIN_VALIDATOR_BOND + TOKEN);
+    balances_batch_transfer(&connection, stashes_accounts, MIN_VALIDATOR_BOND + TOKEN)?;
 
-    staking_multi_bond(node, &validator_accounts, MIN_VALIDATOR_BOND);
+    staking_multi_bond(node, &validator_accounts, MIN_VALIDATOR_BOND)?;
 
-    validator_accounts.par_iter().for_each(|account| {
-        let connection = SignedConnection::new(node, account.clone());
-        staking_validate(&connection, 10, XtStatus::InBlock);
-    });
+    validator_accounts.par_iter().try_for_each(|account| {
+        let connection = SignedConnection::new(node, account.clone())?;
+        staking_validate(&connection, 10, XtStatus::InBlock).map(|_| ())
+    })?;
 
-    staking_multi_bond(node, &stashes_accounts_key_pairs, MIN_NOMINATOR_BOND);
+    staking_multi_bond(node, &stashes_accounts_key_pairs, MIN_NOMINATOR_BOND)?;
 
     stashes_accounts_key_pairs
         .par_iter()
         .zip(validator_accounts.par_iter())
-        .for_each(|(nominator, nominee)| {
-            let connection = SignedConnection::new(node, nominator.clone());
-            staking_nominate(&connection, nominee)
-        });
+        .try_for_each(|(nominator, nominee)| {
+            let connection = SignedConnection::new(node, nominator.clone())?;
+            staking_nominate(&connection, nominee).map(|_| ())
+        })?;
 
     // All the above calls influence the next era, so we need to wait that it passes.
     let current_era = wait_for_full_era_completion(&connection)?;
@@ -79,16 +79,18 @@ pub fn staking_era_payouts(config: &Config) -> anyhow::Result<()> {
         current_era - 1
     );
 
-    validator_accounts.into_par_iter().for_each(|key_pair| {
-        let stash_connection = SignedConnection::new(node, key_pair.clone());
-        let stash_account = AccountId::from(key_pair.public());
-        payout_stakers_and_assert_locked_balance(
-            &stash_connection,
-            &[stash_account.clone()],
-            &stash_account,
-            current_era,
-        )
-    });
+    validator_accounts
+        .into_par_iter()
+        .try_for_each(|key_pair| {
+            let stash_connection = SignedConnection::new(node, key_pair.clone())?;
+            let stash_account = AccountId::from(key_pair.public());
+            payout_stakers_and_assert_locked_balance(
+                &stash_connection,
+                &[stash_account.clone()],
+                &stash_account,
+                current_era,
+            )
+        })?;
 
     Ok(())
 }
@@ -114,15 +116,15 @@ pub fn staking_new_validator(config: &Config) -> anyhow::Result<()> {
     let sender = validator_accounts.remove(0);
     // signer of this connection is sudo, the same node which in this test is used as the new one
     // it's essential since keys from rotate_keys() needs to be run against that node
-    let connection: RootConnection = SignedConnection::new(node, sender).into();
+    let connection: RootConnection = SignedConnection::new(node, sender)?.into();
 
     change_members(
         &connection,
         convert_authorities_to_account_id(&validator_accounts),
         XtStatus::InBlock,
-    );
+    )?;
 
-    let current_session = get_current_session(&connection);
+    let current_session = get_current_session(&connection)?;
 
     let _ = wait_for_session(&connection, current_session + 2)?;
 
@@ -131,28 +133,29 @@ pub fn staking_new_validator(config: &Config) -> anyhow::Result<()> {
         &connection.as_signed(),
         vec![stash_account.clone()],
         MIN_VALIDATOR_BOND + TOKEN,
-    );
+    )?;
     // to cover txs fees
     balances_batch_transfer(
         &connection.as_signed(),
         vec![controller_account.clone()],
         TOKEN,
-    );
+    )?;
 
-    let stash_connection = SignedConnection::new(node, stash.clone());
+    let stash_connection = SignedConnection::new(node, stash.clone())?;
 
     staking_bond(
         &stash_connection,
         MIN_VALIDATOR_BOND,
         &controller_account,
         XtStatus::InBlock,
-    );
-    let bonded_controller_account = staking_bonded(&connection, &stash).unwrap_or_else(|| {
-        panic!(
-            "Expected that stash account {} is bonded to some controller!",
-            &stash_account
-        )
-    });
+    )?;
+    let bonded_controller_account =
+        staking_bonded(&connection, &stash_account)?.unwrap_or_else(|| {
+            panic!(
+                "Expected that stash account {} is bonded to some controller!",
+                &stash_account
+            )
+        });
     assert_eq!(
         bonded_controller_account, controller_account,
         "Expected that stash account {} is bonded to the controller account {}, got {} instead!",
@@ -160,13 +163,13 @@ pub fn staking_new_validator(config: &Config) -> anyhow::Result<()> {
     );
 
     let validator_keys = rotate_keys(&connection).expect("Failed to retrieve keys from chain");
-    let controller_connection = SignedConnection::new(node, controller.clone());
-    set_keys(&controller_connection, validator_keys, XtStatus::InBlock);
+    let controller_connection = SignedConnection::new(node, controller.clone())?;
+    set_keys(&controller_connection, validator_keys, XtStatus::InBlock)?;
 
     // to be elected in next era instead of expected validator_account_id
-    staking_validate(&controller_connection, 10, XtStatus::InBlock);
+    staking_validate(&controller_connection, 10, XtStatus::InBlock)?;
 
-    let ledger = staking_ledger(&connection, &controller);
+    let ledger = staking_ledger(&connection, &controller_account)?;
     assert!(
         ledger.is_some(),
         "Expected controller {} configuration to be non empty",
@@ -190,8 +193,8 @@ pub fn staking_new_validator(config: &Config) -> anyhow::Result<()> {
         &connection,
         convert_authorities_to_account_id(&validator_accounts),
         XtStatus::InBlock,
-    );
-    let current_session = get_current_session(&connection);
+    )?;
+    let current_session = get_current_session(&connection)?;
     let _ = wait_for_session(&connection, current_session + 2)?;
 
     let current_era = wait_for_full_era_completion(&connection)?;
@@ -206,7 +209,7 @@ pub fn staking_new_validator(config: &Config) -> anyhow::Result<()> {
         &[stash_account.clone()],
         &stash_account,
         current_era,
-    );
+    )?;
 
     Ok(())
 }
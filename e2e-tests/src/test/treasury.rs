@@ -1,17 +1,19 @@
-use codec::{Compact, Decode};
+use codec::{Compact, Decode, Encode};
 use frame_support::PalletId;
 use log::info;
-use sp_core::Pair;
+use sp_core::{blake2_256, Pair, H256};
 use sp_runtime::{traits::AccountIdConversion, AccountId32, MultiAddress};
 use std::{thread, thread::sleep, time::Duration};
 use substrate_api_client::{
-    compose_extrinsic, AccountId, Balance, GenericAddress, UncheckedExtrinsicV4, XtStatus,
+    compose_call, compose_extrinsic, AccountId, Balance, GenericAddress, UncheckedExtrinsicV4,
+    XtStatus,
 };
 
 use aleph_client::{
-    balances_transfer, get_free_balance, get_tx_fee_info, send_xt, wait_for_event, AnyConnection,
-    RootConnection, SignedConnection,
+    balances_transfer, get_free_balance, get_tx_fee_info, try_send_xt, wait_for_event,
+    AnyConnection, KeyPair, RootConnection, SignedConnection,
 };
+use primitives::staking::InflationModel;
 
 use crate::{
     accounts::{accounts_from_seeds, get_sudo},
@@ -33,7 +35,20 @@ fn calculate_staking_treasury_addition<C: AnyConnection>(connection: &C) -> u128
         .get_constant::<u64>("Timestamp", "MinimumPeriod")
         .unwrap();
     let millisecs_per_era = millisecs_per_block * session_period as u64 * sessions_per_era as u64;
-    let treasury_era_payout_from_staking = primitives::staking::era_payout(millisecs_per_era).1;
+    let inflation_model: InflationModel = connection
+        .as_connection()
+        .get_storage_value("Inflation", "Model", None)
+        .unwrap()
+        .unwrap_or_default();
+    // the inflation is the highest at the ideal staking rate, whatever the actual stake
+    let total_issuance = get_total_issuance(connection);
+    let treasury_era_payout_from_staking = primitives::staking::era_payout(
+        &inflation_model,
+        inflation_model.ideal_staking_rate * total_issuance,
+        total_issuance,
+        millisecs_per_era,
+    )
+    .1;
     info!(
         "[+] Possible treasury gain from staking is {}",
         treasury_era_payout_from_staking
@@ -42,19 +57,19 @@ fn calculate_staking_treasury_addition<C: AnyConnection>(connection: &C) -> u128
 }
 
 pub fn channeling_fee(config: &Config) -> anyhow::Result<()> {
-    let (connection, to) = setup_for_transfer(config);
+    let (connection, to) = setup_for_transfer(config)?;
     let treasury = get_treasury_account();
 
     let possibly_treasury_gain_from_staking = calculate_staking_treasury_addition(&connection);
-    let treasury_balance_before = get_free_balance(&connection, &treasury);
+    let treasury_balance_before = get_free_balance(&connection, &treasury)?;
     let issuance_before = get_total_issuance(&connection);
     info!(
         "[+] Treasury balance before tx: {}. Total issuance: {}.",
         treasury_balance_before, issuance_before
     );
 
-    let tx = balances_transfer(&connection, &to, 1000u128, XtStatus::Finalized);
-    let treasury_balance_after = get_free_balance(&connection, &treasury);
+    let tx = balances_transfer(&connection, &to, 1000u128, XtStatus::Finalized)?;
+    let treasury_balance_after = get_free_balance(&connection, &treasury)?;
     let issuance_after = get_total_issuance(&connection);
     check_treasury_issuance(
         possibly_treasury_gain_from_staking,
@@ -63,7 +78,7 @@ pub fn channeling_fee(config: &Config) -> anyhow::Result<()> {
         issuance_after,
     );
 
-    let fee_info = get_tx_fee_info(&connection, &tx);
+    let fee_info = get_tx_fee_info(&connection, &tx)?;
     let fee = fee_info.fee_without_weight + fee_info.adjusted_weight;
     check_treasury_balance(
         possibly_treasury_gain_from_staking,
@@ -130,15 +145,15 @@ pub fn treasury_access(config: &Config) -> anyhow::Result<()> {
 
     let proposer = accounts_from_seeds(seeds)[0].clone();
     let beneficiary = AccountId::from(proposer.public());
-    let connection = SignedConnection::new(node, proposer);
+    let connection = SignedConnection::new(node, proposer)?;
 
-    propose_treasury_spend(10u128, &beneficiary, &connection);
-    propose_treasury_spend(100u128, &beneficiary, &connection);
+    propose_treasury_spend(10u128, &beneficiary, &connection)?;
+    propose_treasury_spend(100u128, &beneficiary, &connection)?;
     let proposals_counter = get_proposals_counter(&connection);
     assert!(proposals_counter >= 2, "Proposal was not created");
 
     let sudo = get_sudo(config);
-    let connection = RootConnection::new(node, sudo);
+    let connection = RootConnection::new(node, sudo)?;
 
     treasury_approve(proposals_counter - 2, &connection)?;
     treasury_reject(proposals_counter - 1, &connection)?;
@@ -146,6 +161,120 @@ pub fn treasury_access(config: &Config) -> anyhow::Result<()> {
     Ok(())
 }
 
+pub fn treasury_council_access(config: &Config) -> anyhow::Result<()> {
+    let Config {
+        ref node, seeds, ..
+    } = config;
+
+    let members = accounts_from_seeds(seeds);
+    let proposer = members[0].clone();
+    let beneficiary = AccountId::from(proposer.public());
+    let connection = SignedConnection::new(node, proposer)?;
+
+    propose_treasury_spend(10u128, &beneficiary, &connection)?;
+    let proposals_counter = get_proposals_counter(&connection);
+    assert!(proposals_counter >= 1, "Proposal was not created");
+
+    let sudo = get_sudo(config);
+    let connection = RootConnection::new(node, sudo)?;
+    set_council_members(&members, &connection)?;
+
+    council_approve(proposals_counter - 1, node, &members)?;
+
+    Ok(())
+}
+
+fn set_council_members(members: &[KeyPair], connection: &RootConnection) -> anyhow::Result<()> {
+    let old_members: Vec<AccountId> = connection
+        .as_connection()
+        .get_storage_value("Council", "Members", None)
+        .unwrap()
+        .unwrap_or_default();
+    let new_members: Vec<AccountId> = members
+        .iter()
+        .map(|member| AccountId::from(member.public()))
+        .collect();
+    let call = compose_call!(
+        connection.as_connection().metadata,
+        "Council",
+        "set_members",
+        new_members,
+        None::<AccountId>,
+        old_members.len() as u32
+    );
+    let xt = compose_extrinsic!(connection.as_connection(), "Sudo", "sudo", call);
+    try_send_xt(connection, xt, Some("council members"), XtStatus::Finalized)?;
+    Ok(())
+}
+
+fn get_council_proposals_counter<C: AnyConnection>(connection: &C) -> u32 {
+    connection
+        .as_connection()
+        .get_storage_value("Council", "ProposalCount", None)
+        .unwrap()
+        .unwrap_or_default()
+}
+
+// Upper bound on the weight of the treasury approval, paid for by the member closing the motion.
+const APPROVAL_WEIGHT_BOUND: u64 = 1_000_000_000;
+
+/// Approves the treasury proposal with a motion of the council that every member votes for.
+fn council_approve(proposal_id: u32, node: &str, members: &[KeyPair]) -> anyhow::Result<()> {
+    let connections = members
+        .iter()
+        .map(|member| SignedConnection::new(node, member.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let proposer = &connections[0];
+
+    let call = compose_call!(
+        proposer.as_connection().metadata,
+        "Treasury",
+        "approve_proposal",
+        Compact(proposal_id)
+    );
+    let length_bound = call.encode().len() as u32;
+    let call_hash = H256::from(blake2_256(&call.encode()));
+    let motion_id = get_council_proposals_counter(proposer);
+
+    // The proposer votes for the motion by proposing it. With a threshold of one the call is
+    // executed right away, otherwise the motion is closed once everyone has voted.
+    let xt = compose_extrinsic!(
+        proposer.as_connection(),
+        "Council",
+        "propose",
+        Compact(members.len() as u32),
+        call,
+        Compact(length_bound)
+    );
+    try_send_xt(proposer, xt, Some("council motion"), XtStatus::Finalized)?;
+
+    if members.len() > 1 {
+        for connection in &connections[1..] {
+            let xt = compose_extrinsic!(
+                connection.as_connection(),
+                "Council",
+                "vote",
+                call_hash,
+                Compact(motion_id),
+                true
+            );
+            try_send_xt(connection, xt, Some("council vote"), XtStatus::Finalized)?;
+        }
+        let xt = compose_extrinsic!(
+            proposer.as_connection(),
+            "Council",
+            "close",
+            call_hash,
+            Compact(motion_id),
+            Compact(APPROVAL_WEIGHT_BOUND),
+            Compact(length_bound)
+        );
+        try_send_xt(proposer, xt, Some("council close"), XtStatus::Finalized)?;
+    }
+
+    wait_for_approval(proposer, proposal_id)
+}
+
 fn get_total_issuance<C: AnyConnection>(connection: &C) -> u128 {
     connection
         .as_connection()
@@ -164,7 +293,7 @@ fn propose_treasury_spend(
     value: u128,
     beneficiary: &AccountId32,
     connection: &SignedConnection,
-) -> ProposalTransaction {
+) -> anyhow::Result<ProposalTransaction> {
     let xt = compose_extrinsic!(
         connection.as_connection(),
         "Treasury",
@@ -172,13 +301,13 @@ fn propose_treasury_spend(
         Compact(value),
         GenericAddress::Id(beneficiary.clone())
     );
-    send_xt(
+    try_send_xt(
         connection,
         xt.clone(),
         Some("treasury spend"),
         XtStatus::Finalized,
-    );
-    xt
+    )?;
+    Ok(xt)
 }
 
 fn get_proposals_counter<C: AnyConnection>(connection: &C) -> u32 {
@@ -191,47 +320,53 @@ fn get_proposals_counter<C: AnyConnection>(connection: &C) -> u32 {
 
 type GovernanceTransaction = UncheckedExtrinsicV4<([u8; 2], Compact<u32>)>;
 
-fn send_treasury_approval(proposal_id: u32, connection: &RootConnection) -> GovernanceTransaction {
+fn send_treasury_approval(
+    proposal_id: u32,
+    connection: &RootConnection,
+) -> anyhow::Result<GovernanceTransaction> {
     let xt = compose_extrinsic!(
         connection.as_connection(),
         "Treasury",
         "approve_proposal",
         Compact(proposal_id)
     );
-    send_xt(
+    try_send_xt(
         connection,
         xt.clone(),
         Some("treasury approval"),
         XtStatus::Finalized,
-    );
-    xt
+    )?;
+    Ok(xt)
 }
 
 fn treasury_approve(proposal_id: u32, connection: &RootConnection) -> anyhow::Result<()> {
-    send_treasury_approval(proposal_id, connection);
+    send_treasury_approval(proposal_id, connection)?;
     wait_for_approval(connection, proposal_id)
 }
 
-fn send_treasury_rejection(proposal_id: u32, connection: &RootConnection) -> GovernanceTransaction {
+fn send_treasury_rejection(
+    proposal_id: u32,
+    connection: &RootConnection,
+) -> anyhow::Result<GovernanceTransaction> {
     let xt = compose_extrinsic!(
         connection.as_connection(),
         "Treasury",
         "reject_proposal",
         Compact(proposal_id)
     );
-    send_xt(
+    try_send_xt(
         connection,
         xt.clone(),
         Some("treasury rejection"),
         XtStatus::Finalized,
-    );
-    xt
+    )?;
+    Ok(xt)
 }
 
 fn treasury_reject(proposal_id: u32, connection: &RootConnection) -> anyhow::Result<()> {
     let (c, p) = (connection.clone(), proposal_id);
     let listener = thread::spawn(move || wait_for_rejection(&c, p));
-    send_treasury_rejection(proposal_id, connection);
+    send_treasury_rejection(proposal_id, connection)?;
     listener.join().unwrap()
 }
 
@@ -269,6 +404,6 @@ fn wait_for_rejection<C: AnyConnection>(connection: &C, proposal_id: u32) -> any
             info!("[+] Rejected proposal {:?}", e.proposal_id);
             proposal_id.eq(&e.proposal_id)
         },
-    )
-    .map(|_| ())
+    )?;
+    Ok(())
 }
@@ -12,7 +12,7 @@ use crate::config::Config;
 pub fn batch_transactions(config: &Config) -> anyhow::Result<()> {
     const NUMBER_OF_TRANSACTIONS: usize = 100;
 
-    let (connection, to) = setup_for_transfer(config);
+    let (connection, to) = setup_for_transfer(config)?;
 
     let call = compose_call!(
         connection.as_connection().metadata,
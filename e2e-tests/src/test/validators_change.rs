@@ -18,7 +18,7 @@ pub fn change_validators(config: &Config) -> anyhow::Result<()> {
     let mut accounts = accounts_from_seeds(seeds);
     let sudo = get_sudo(config);
 
-    let connection = RootConnection::new(node, sudo);
+    let connection = RootConnection::new(node, sudo)?;
 
     let members_before: Vec<AccountId> = connection
         .as_connection()
@@ -29,7 +29,7 @@ pub fn change_validators(config: &Config) -> anyhow::Result<()> {
 
     accounts.remove(0);
     let new_members: Vec<AccountId> = accounts.iter().map(|pair| pair.public().into()).collect();
-    change_members(&connection, new_members.clone(), XtStatus::InBlock);
+    change_members(&connection, new_members.clone(), XtStatus::InBlock)?;
 
     #[derive(Debug, Decode, Clone)]
     struct NewMembersEvent {
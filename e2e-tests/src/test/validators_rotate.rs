@@ -58,9 +58,9 @@ pub fn validators_rotate(cfg: &Config) -> anyhow::Result<()> {
     let node = &cfg.node;
     let accounts = accounts_from_seeds(&None);
     let sender = accounts.first().expect("Using default accounts").to_owned();
-    let connection = SignedConnection::new(node, sender);
+    let connection = SignedConnection::new(node, sender)?;
 
-    let mut current_session = get_current_session(&connection);
+    let mut current_session = get_current_session(&connection)?;
     if current_session < MINIMAL_TEST_SESSION_START {
         wait_for_session(&connection, MINIMAL_TEST_SESSION_START)?;
         current_session = MINIMAL_TEST_SESSION_START;
@@ -2,7 +2,7 @@ use crate::{
     data_io::{
         chain_info::{AuxFinalizationChainInfoProvider, CachedChainInfoProvider},
         status_provider::get_proposal_status,
-        AlephData, ChainInfoProvider,
+        AlephData, ChainInfoCacheConfig, ChainInfoProvider, ItemDelivery,
     },
     BlockHashNum, SessionBoundaries,
 };
@@ -11,19 +11,21 @@ use futures::channel::mpsc;
 use log::{debug, error, warn};
 use sc_client_api::HeaderBackend;
 use sp_runtime::traits::{Block as BlockT, NumberFor, One, Zero};
-use std::{default::Default, sync::Arc};
+use std::sync::Arc;
 
 type InterpretersChainInfoProvider<B, C> =
     CachedChainInfoProvider<B, AuxFinalizationChainInfoProvider<B, Arc<C>>>;
 
 /// Takes as input ordered `AlephData` from `AlephBFT` and pushes blocks that should be finalized
 /// to an output channel. The other end of the channel is held by the aggregator whose goal is to
-/// create multisignatures under the finalized blocks.
+/// create multisignatures under the finalized blocks. Out-of-band items contained in the data are
+/// delivered to their subscribers.
 pub struct OrderedDataInterpreter<B: BlockT, C: HeaderBackend<B>> {
     blocks_to_finalize_tx: mpsc::UnboundedSender<BlockHashNum<B>>,
     chain_info_provider: InterpretersChainInfoProvider<B, C>,
     last_finalized_by_aleph: BlockHashNum<B>,
     session_boundaries: SessionBoundaries<B>,
+    item_delivery: ItemDelivery,
 }
 
 fn get_last_block_prev_session<B: BlockT, C: HeaderBackend<B>>(
@@ -49,53 +51,52 @@ impl<B: BlockT, C: HeaderBackend<B>> OrderedDataInterpreter<B, C> {
         blocks_to_finalize_tx: mpsc::UnboundedSender<BlockHashNum<B>>,
         client: Arc<C>,
         session_boundaries: SessionBoundaries<B>,
+        chain_info_cache_config: ChainInfoCacheConfig,
+        item_delivery: ItemDelivery,
     ) -> Self {
         let last_finalized_by_aleph =
             get_last_block_prev_session(session_boundaries.clone(), client.clone());
         let chain_info_provider =
             AuxFinalizationChainInfoProvider::new(client, last_finalized_by_aleph.clone());
         let chain_info_provider =
-            CachedChainInfoProvider::new(chain_info_provider, Default::default());
+            CachedChainInfoProvider::new(chain_info_provider, chain_info_cache_config);
 
         OrderedDataInterpreter {
             blocks_to_finalize_tx,
             chain_info_provider,
             last_finalized_by_aleph,
             session_boundaries,
+            item_delivery,
         }
     }
 
-    fn block_to_finalize_from_data(&mut self, new_data: AlephData<B>) -> Option<BlockHashNum<B>> {
-        match new_data {
-            AlephData::Empty => None,
-            AlephData::HeadProposal(unvalidated_proposal) => {
-                let proposal = if let Some(proposal) =
-                    unvalidated_proposal.validate_bounds(&self.session_boundaries)
-                {
-                    proposal
-                } else {
-                    warn!(target: "aleph-finality", "Incorrect proposal {:?} passed through data availability, session bounds: {:?}", unvalidated_proposal, self.session_boundaries);
-                    return None;
-                };
+    fn block_to_finalize_from_data(&mut self, new_data: &AlephData<B>) -> Option<BlockHashNum<B>> {
+        let unvalidated_proposal = new_data.head_proposal()?;
+        let proposal = if let Some(proposal) =
+            unvalidated_proposal.validate_bounds(&self.session_boundaries)
+        {
+            proposal
+        } else {
+            warn!(target: "aleph-finality", "Incorrect proposal {:?} passed through data availability, session bounds: {:?}", unvalidated_proposal, self.session_boundaries);
+            return None;
+        };
 
-                // WARNING: If we ever enable pruning, this code (and the code in Data Store) must be carefully analyzed
-                // for possible safety violations.
+        // WARNING: If we ever enable pruning, this code (and the code in Data Store) must be carefully analyzed
+        // for possible safety violations.
 
-                use crate::data_io::proposal::ProposalStatus::*;
-                let status = get_proposal_status(&mut self.chain_info_provider, &proposal, None);
-                match status {
-                    Finalize(block) => Some(block),
-                    Ignore => {
-                        debug!(target: "aleph-finality", "Ignoring proposal {:?} in interpreter.", proposal);
-                        None
-                    }
-                    Pending(pending_status) => {
-                        panic!(
-                            "Pending proposal {:?} with status {:?} encountered in Data.",
-                            proposal, pending_status
-                        );
-                    }
-                }
+        use crate::data_io::proposal::ProposalStatus::*;
+        let status = get_proposal_status(&mut self.chain_info_provider, &proposal, None);
+        match status {
+            Finalize(block) => Some(block),
+            Ignore => {
+                debug!(target: "aleph-finality", "Ignoring proposal {:?} in interpreter.", proposal);
+                None
+            }
+            Pending(pending_status) => {
+                panic!(
+                    "Pending proposal {:?} with status {:?} encountered in Data.",
+                    proposal, pending_status
+                );
             }
         }
     }
@@ -106,7 +107,8 @@ impl<B: BlockT, C: HeaderBackend<B> + Send + 'static> aleph_bft::FinalizationHan
     for OrderedDataInterpreter<B, C>
 {
     async fn data_finalized(&mut self, data: AlephData<B>) {
-        if let Some(block) = self.block_to_finalize_from_data(data) {
+        self.item_delivery.deliver(data.items().to_vec());
+        if let Some(block) = self.block_to_finalize_from_data(&data) {
             self.last_finalized_by_aleph = block.clone();
             self.chain_info_provider
                 .inner()
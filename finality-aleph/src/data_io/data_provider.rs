@@ -61,6 +61,7 @@ pub fn get_proposal<B, C>(
     client: &C,
     best_block: BlockHashNum<B>,
     finalized_block: BlockHashNum<B>,
+    max_branch_len: usize,
 ) -> Result<AlephData<B>, ()>
 where
     B: BlockT,
@@ -69,9 +70,7 @@ where
     let mut curr_block = best_block;
     let mut branch: Vec<B::Hash> = Vec::new();
     while curr_block.num > finalized_block.num {
-        if curr_block.num - finalized_block.num
-            <= <NumberFor<B>>::saturated_from(MAX_DATA_BRANCH_LEN)
-        {
+        if curr_block.num - finalized_block.num <= <NumberFor<B>>::saturated_from(max_branch_len) {
             branch.push(curr_block.hash);
         }
         curr_block = get_parent(client, &curr_block).expect("block of num >= 1 must have a parent")
@@ -93,14 +92,18 @@ where
 
 const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_millis(100);
 
+#[derive(Clone, Debug)]
 pub struct ChainTrackerConfig {
     pub refresh_interval: Duration,
+    // Maximum length of the branches we propose, must not exceed `MAX_DATA_BRANCH_LEN`.
+    pub max_branch_len: usize,
 }
 
 impl Default for ChainTrackerConfig {
     fn default() -> ChainTrackerConfig {
         ChainTrackerConfig {
             refresh_interval: DEFAULT_REFRESH_INTERVAL,
+            max_branch_len: MAX_DATA_BRANCH_LEN,
         }
     }
 }
@@ -206,6 +209,7 @@ where
             &*self.client,
             best_block_in_session.clone(),
             finalized_block,
+            self.config.max_branch_len,
         ) {
             *self.data_to_propose.lock().await = proposal;
         }
@@ -302,7 +306,7 @@ struct DataProvider<B: BlockT> {
 // 2. If the node does not know of any block in session `k` or if `best_block` is equal to the last finalized block
 //    then the node proposes `Empty`, otherwise the node proposes a branch extending from one block above
 //    last finalized till `best_block` with the restriction that the branch must be truncated to length
-//    at most MAX_DATA_BRANCH_LEN.
+//    at most `config.max_branch_len`, which never exceeds MAX_DATA_BRANCH_LEN.
 #[async_trait]
 impl<B: BlockT> aleph_bft::DataProvider<AlephData<B>> for DataProvider<B> {
     async fn get_data(&mut self) -> AlephData<B> {
@@ -359,6 +363,7 @@ mod tests {
 
         let config = ChainTrackerConfig {
             refresh_interval: REFRESH_INTERVAL,
+            max_branch_len: MAX_DATA_BRANCH_LEN,
         };
 
         let (chain_tracker, data_provider) =
@@ -3,7 +3,7 @@ use crate::{
         chain_info::{CachedChainInfoProvider, ChainInfoProvider},
         proposal::{AlephProposal, ProposalStatus},
         status_provider::get_proposal_status,
-        AlephNetworkMessage,
+        AlephNetworkMessage, ChainInfoCacheConfig, ItemChecker,
     },
     network::{ComponentNetwork, DataNetwork, ReceiverComponent, RequestBlocks, SimpleNetwork},
     BlockHashNum, SessionBoundaries,
@@ -101,6 +101,7 @@ impl<B: BlockT, M: AlephNetworkMessage<B>> PendingMessageInfo<B, M> {
     }
 }
 
+#[derive(Clone, Debug)]
 pub struct DataStoreConfig {
     pub max_triggers_pending: usize,
     pub max_proposals_pending: usize,
@@ -108,6 +109,7 @@ pub struct DataStoreConfig {
     pub available_proposals_cache_capacity: usize,
     pub periodic_maintenance_interval: Duration,
     pub request_block_after: Duration,
+    pub chain_info_cache_config: ChainInfoCacheConfig,
 }
 
 impl Default for DataStoreConfig {
@@ -119,6 +121,7 @@ impl Default for DataStoreConfig {
             available_proposals_cache_capacity: 8000,
             periodic_maintenance_interval: Duration::from_secs(60),
             request_block_after: Duration::from_secs(100),
+            chain_info_cache_config: ChainInfoCacheConfig::default(),
         }
     }
 }
@@ -127,8 +130,8 @@ impl Default for DataStoreConfig {
 // a message `m` we must check whether the data `m.included_data()` is available to pass it to AlephBFT.
 // Data is represented by the `AlephData<B>` type -- we refer to the docs of this type to learn what
 // it represents and how honest nodes form `AlephData<B>` instances.
-// An `AlephData<B>` is considered available if it is either `Empty` or it is `HeadProposal(p)` where
-// `p` is a proposal satisfying one of the conditions below:
+// An `AlephData<B>` is considered available if all the out-of-band items it contains pass the `ItemChecker`
+// and it either contains no head proposal or its head proposal `p` satisfies one of the conditions below:
 // 1) the top block of `p`s branch is available AND the branch is correct (hashes correspond to existing blocks
 //    with correct number and the ancestry is correct) AND the parent of the bottom block in the branch is finalized.
 // 2) (Hopeless Fork) There exists a hash h_i on the branch, corresponding to height `num` in the chain, such that
@@ -187,6 +190,7 @@ where
     client: Arc<C>,
     block_requester: RB,
     config: DataStoreConfig,
+    item_checker: ItemChecker,
     messages_from_network: Arc<Mutex<R>>,
     messages_for_aleph: UnboundedSender<Message>,
 }
@@ -206,13 +210,15 @@ where
         client: Arc<C>,
         block_requester: RB,
         config: DataStoreConfig,
+        item_checker: ItemChecker,
         component_network: N,
     ) -> (Self, impl DataNetwork<Message>) {
         let (messages_for_aleph, messages_from_data_store) = mpsc::unbounded();
         let messages_to_network = component_network.sender().clone();
         let messages_from_network = component_network.receiver();
         let status = client.info();
-        let chain_info_provider = CachedChainInfoProvider::new(client.clone(), Default::default());
+        let chain_info_provider =
+            CachedChainInfoProvider::new(client.clone(), config.chain_info_cache_config.clone());
 
         let highest_finalized_num = status.finalized_number;
         (
@@ -229,6 +235,7 @@ where
                 client,
                 block_requester,
                 config,
+                item_checker,
                 messages_from_network,
                 messages_for_aleph,
             },
@@ -599,18 +606,20 @@ where
     fn on_message_received(&mut self, message: Message) {
         let mut proposals = Vec::new();
         for data in message.included_data() {
-            use crate::data_io::AlephData::*;
-            match data {
-                Empty => {}
-                HeadProposal(unvalidated_proposal) => {
-                    if let Some(proposal) =
-                        unvalidated_proposal.validate_bounds(&self.session_boundaries)
-                    {
-                        proposals.push(proposal);
-                    } else {
-                        warn!(target: "aleph-data-store", "Message {:?} dropped as it contains proposal {:?} not within bounds.", message, unvalidated_proposal);
-                        return;
-                    }
+            // Out-of-band items do not depend on the chain, so they are either valid right away
+            // or never.
+            if !self.item_checker.check_data(&data) {
+                warn!(target: "aleph-data-store", "Message {:?} dropped as it contains invalid items.", message);
+                return;
+            }
+            if let Some(unvalidated_proposal) = data.head_proposal() {
+                if let Some(proposal) =
+                    unvalidated_proposal.validate_bounds(&self.session_boundaries)
+                {
+                    proposals.push(proposal);
+                } else {
+                    warn!(target: "aleph-data-store", "Message {:?} dropped as it contains proposal {:?} not within bounds.", message, unvalidated_proposal);
+                    return;
                 }
             }
         }
@@ -0,0 +1,390 @@
+use crate::{
+    crypto::{AuthorityVerifier, KeyBox, Signature},
+    data_io::{AlephData, ExtendedData},
+    NodeIndex, SessionId,
+};
+use aleph_bft::{Index, KeyBox as _};
+use async_trait::async_trait;
+use codec::{Decode, Encode};
+use futures::channel::mpsc;
+use log::{debug, warn};
+use parking_lot::Mutex;
+use sp_runtime::traits::Block as BlockT;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+/// Maximum number of out-of-band items included in a single AlephBFT unit.
+pub const MAX_ITEMS_PER_DATA: usize = 8;
+/// Maximum size (in bytes) of the payload of a single out-of-band item.
+pub const MAX_ITEM_PAYLOAD_SIZE: usize = 1024;
+// Maximum number of items waiting to be proposed, the oldest ones are dropped first.
+const MAX_PENDING_ITEMS: usize = 256;
+
+/// Identifies the kind of an out-of-band item and hence the plugin responsible for validating and
+/// consuming it, e.g. `ItemKind(*b"rand")` for randomness beacon shares.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Encode, Decode)]
+pub struct ItemKind(pub [u8; 4]);
+
+/// An out-of-band item ordered by AlephBFT alongside block proposals. It is signed by its author,
+/// a member of the committee of the session it was created in.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct SignedItem {
+    pub kind: ItemKind,
+    pub payload: Vec<u8>,
+    pub author: NodeIndex,
+    pub signature: Signature,
+}
+
+// Need to be implemented manually, as deriving does not work (`Signature` is not `Hash`).
+impl Hash for SignedItem {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.kind.hash(state);
+        self.payload.hash(state);
+        self.author.hash(state);
+        self.signature.encode().hash(state);
+    }
+}
+
+fn signed_message(kind: &ItemKind, session_id: SessionId, payload: &[u8]) -> Vec<u8> {
+    (kind, session_id.0, payload).encode()
+}
+
+/// An item that was ordered by AlephBFT in the given session, delivered to the subscribers of
+/// its kind together with the size of the committee of that session.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrderedItem {
+    pub session_id: SessionId,
+    pub committee_size: usize,
+    pub item: SignedItem,
+}
+
+/// Decides whether an out-of-band item of a specific kind may be ordered. The signature of the
+/// author is already checked when this is called.
+pub trait ItemValidator: Send + Sync {
+    fn validate(&self, session_id: SessionId, item: &SignedItem) -> bool;
+}
+
+struct PendingItem {
+    session_id: SessionId,
+    kind: ItemKind,
+    payload: Vec<u8>,
+}
+
+/// Plugs out-of-band items into the finality gadget. Validators have to be registered before the
+/// registry is passed to the gadget, items of kinds without a validator are never accepted.
+#[derive(Clone, Default)]
+pub struct ItemRegistry {
+    validators: HashMap<ItemKind, Arc<dyn ItemValidator>>,
+    subscribers: Arc<Mutex<HashMap<ItemKind, Vec<mpsc::UnboundedSender<OrderedItem>>>>>,
+    pending: Arc<Mutex<VecDeque<PendingItem>>>,
+}
+
+impl fmt::Debug for ItemRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ItemRegistry")
+            .field("kinds", &self.validators.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl ItemRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the validator for items of the given kind, replacing the previous one if any.
+    pub fn register_validator(&mut self, kind: ItemKind, validator: Arc<dyn ItemValidator>) {
+        self.validators.insert(kind, validator);
+    }
+
+    /// Returns a stream of all the items of the given kind, in the order decided by AlephBFT.
+    pub fn subscribe(&self, kind: ItemKind) -> mpsc::UnboundedReceiver<OrderedItem> {
+        let (tx, rx) = mpsc::unbounded();
+        self.subscribers.lock().entry(kind).or_default().push(tx);
+        rx
+    }
+
+    /// Queues an item to be signed and proposed by this node in the given session. It is only
+    /// proposed if this node is a member of the committee of that session.
+    pub fn submit(&self, session_id: SessionId, kind: ItemKind, payload: Vec<u8>) {
+        if payload.len() > MAX_ITEM_PAYLOAD_SIZE {
+            warn!(target: "aleph-data-store", "Dropping submitted item of kind {:?} with payload of {} bytes.", kind, payload.len());
+            return;
+        }
+        let mut pending = self.pending.lock();
+        if pending.len() >= MAX_PENDING_ITEMS {
+            pending.pop_front();
+        }
+        pending.push_back(PendingItem {
+            session_id,
+            kind,
+            payload,
+        });
+    }
+
+    // Takes at most `limit` items queued for the given session, dropping items of earlier sessions.
+    fn take_pending(&self, session_id: SessionId, limit: usize) -> Vec<(ItemKind, Vec<u8>)> {
+        let mut pending = self.pending.lock();
+        pending.retain(|item| item.session_id >= session_id);
+        let mut taken = Vec::new();
+        let mut remaining = VecDeque::new();
+        while let Some(item) = pending.pop_front() {
+            if item.session_id == session_id && taken.len() < limit {
+                taken.push((item.kind, item.payload));
+            } else {
+                remaining.push_back(item);
+            }
+        }
+        *pending = remaining;
+        taken
+    }
+
+    fn deliver(&self, ordered: OrderedItem) {
+        if let Some(subscribers) = self.subscribers.lock().get_mut(&ordered.item.kind) {
+            subscribers.retain(|tx| tx.unbounded_send(ordered.clone()).is_ok());
+        }
+    }
+}
+
+/// Checks the out-of-band items included in AlephBFT data of a single session.
+#[derive(Clone)]
+pub struct ItemChecker {
+    session_id: SessionId,
+    verifier: AuthorityVerifier,
+    registry: ItemRegistry,
+}
+
+impl ItemChecker {
+    pub fn new(session_id: SessionId, verifier: AuthorityVerifier, registry: ItemRegistry) -> Self {
+        ItemChecker {
+            session_id,
+            verifier,
+            registry,
+        }
+    }
+
+    pub fn check(&self, item: &SignedItem) -> bool {
+        if item.payload.len() > MAX_ITEM_PAYLOAD_SIZE {
+            return false;
+        }
+        let validator = match self.registry.validators.get(&item.kind) {
+            Some(validator) => validator,
+            None => return false,
+        };
+        let message = signed_message(&item.kind, self.session_id, &item.payload);
+        self.verifier.verify(&message, &item.signature, item.author)
+            && validator.validate(self.session_id, item)
+    }
+
+    /// Checks all the items in the data, there are no items to check in the `Empty` and
+    /// `HeadProposal` variants.
+    pub fn check_data<B: BlockT>(&self, data: &AlephData<B>) -> bool {
+        match data {
+            AlephData::Extended(extended) => {
+                extended.items.len() <= MAX_ITEMS_PER_DATA
+                    && extended.items.iter().all(|item| self.check(item))
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Wraps a data provider, extending the data it outputs with the items submitted to the registry.
+pub struct ItemProvider<DP> {
+    inner: DP,
+    registry: ItemRegistry,
+    keybox: KeyBox,
+    session_id: SessionId,
+}
+
+impl<DP> ItemProvider<DP> {
+    pub fn new(inner: DP, registry: ItemRegistry, keybox: KeyBox, session_id: SessionId) -> Self {
+        ItemProvider {
+            inner,
+            registry,
+            keybox,
+            session_id,
+        }
+    }
+}
+
+#[async_trait]
+impl<B, DP> aleph_bft::DataProvider<AlephData<B>> for ItemProvider<DP>
+where
+    B: BlockT,
+    DP: aleph_bft::DataProvider<AlephData<B>> + Send,
+{
+    async fn get_data(&mut self) -> AlephData<B> {
+        let data = self.inner.get_data().await;
+        let pending = self
+            .registry
+            .take_pending(self.session_id, MAX_ITEMS_PER_DATA);
+        if pending.is_empty() {
+            return data;
+        }
+        let mut items = Vec::with_capacity(pending.len());
+        for (kind, payload) in pending {
+            let signature = self
+                .keybox
+                .sign(&signed_message(&kind, self.session_id, &payload))
+                .await;
+            items.push(SignedItem {
+                kind,
+                payload,
+                author: self.keybox.index(),
+                signature,
+            });
+        }
+        debug!(target: "aleph-data-store", "Extending data with {} items.", items.len());
+        AlephData::Extended(ExtendedData {
+            head_proposal: data.head_proposal().cloned(),
+            items,
+        })
+    }
+}
+
+/// Delivers the ordered items of a single session to the subscribers, skipping duplicates.
+pub struct ItemDelivery {
+    session_id: SessionId,
+    committee_size: usize,
+    registry: ItemRegistry,
+    delivered: HashSet<SignedItem>,
+}
+
+impl ItemDelivery {
+    pub fn new(session_id: SessionId, committee_size: usize, registry: ItemRegistry) -> Self {
+        ItemDelivery {
+            session_id,
+            committee_size,
+            registry,
+            delivered: HashSet::new(),
+        }
+    }
+
+    pub fn deliver(&mut self, items: Vec<SignedItem>) {
+        for item in items {
+            if self.delivered.insert(item.clone()) {
+                self.registry.deliver(OrderedItem {
+                    session_id: self.session_id,
+                    committee_size: self.committee_size,
+                    item,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{crypto::AuthorityPen, data_io::UnvalidatedAlephProposal};
+    use aleph_bft::DataProvider as _;
+    use aleph_primitives::{AuthorityId, KEY_TYPE};
+    use futures::StreamExt;
+    use sp_core::hash::H256;
+    use sp_keystore::{testing::KeyStore, CryptoStore};
+    use substrate_test_runtime_client::runtime::Block;
+
+    const KIND: ItemKind = ItemKind(*b"test");
+
+    struct AcceptAll;
+
+    impl ItemValidator for AcceptAll {
+        fn validate(&self, _: SessionId, _: &SignedItem) -> bool {
+            true
+        }
+    }
+
+    async fn keybox() -> (KeyBox, AuthorityVerifier) {
+        let key_store = Arc::new(KeyStore::new());
+        let authority_id = AuthorityId::from(
+            key_store
+                .ed25519_generate_new(KEY_TYPE, Some("//Alice"))
+                .await
+                .unwrap(),
+        );
+        let verifier = AuthorityVerifier::new(vec![authority_id.clone()]);
+        let pen = AuthorityPen::new(authority_id, key_store)
+            .await
+            .expect("The keys should sign successfully");
+        (KeyBox::new(NodeIndex(0), verifier.clone(), pen), verifier)
+    }
+
+    fn registry() -> ItemRegistry {
+        let mut registry = ItemRegistry::new();
+        registry.register_validator(KIND, Arc::new(AcceptAll));
+        registry
+    }
+
+    struct EmptyProvider;
+
+    #[async_trait]
+    impl aleph_bft::DataProvider<AlephData<Block>> for EmptyProvider {
+        async fn get_data(&mut self) -> AlephData<Block> {
+            AlephData::Empty
+        }
+    }
+
+    #[test]
+    fn head_proposal_encoding_is_unchanged() {
+        let proposal = UnvalidatedAlephProposal::<Block>::new(vec![H256::default()], 1);
+        let encoded = AlephData::HeadProposal(proposal.clone()).encode();
+        assert_eq!(encoded[0], 1);
+        assert_eq!(&encoded[1..], &proposal.encode()[..]);
+        assert_eq!(AlephData::<Block>::Empty.encode(), vec![0]);
+    }
+
+    #[tokio::test]
+    async fn provides_signed_items_that_pass_checks() {
+        let (keybox, verifier) = keybox().await;
+        let registry = registry();
+        let mut provider = ItemProvider::new(EmptyProvider, registry.clone(), keybox, SessionId(1));
+        registry.submit(SessionId(0), KIND, b"stale".to_vec());
+        registry.submit(SessionId(1), KIND, b"fresh".to_vec());
+
+        let data: AlephData<Block> = provider.get_data().await;
+        assert_eq!(data.items().len(), 1);
+        assert_eq!(data.items()[0].payload, b"fresh".to_vec());
+        assert!(
+            ItemChecker::new(SessionId(1), verifier.clone(), registry.clone()).check_data(&data)
+        );
+        assert!(!ItemChecker::new(SessionId(2), verifier, registry).check_data(&data));
+        assert_eq!(provider.get_data().await, AlephData::<Block>::Empty);
+    }
+
+    #[tokio::test]
+    async fn rejects_items_of_unknown_kinds() {
+        let (keybox, verifier) = keybox().await;
+        let registry = ItemRegistry::new();
+        let mut provider = ItemProvider::new(EmptyProvider, registry.clone(), keybox, SessionId(0));
+        registry.submit(SessionId(0), KIND, b"item".to_vec());
+
+        let data: AlephData<Block> = provider.get_data().await;
+        assert_eq!(data.items().len(), 1);
+        assert!(!ItemChecker::new(SessionId(0), verifier, registry).check_data(&data));
+    }
+
+    #[tokio::test]
+    async fn delivers_items_once() {
+        let (keybox, _) = keybox().await;
+        let registry = registry();
+        let mut subscription = registry.subscribe(KIND);
+        let mut provider = ItemProvider::new(EmptyProvider, registry.clone(), keybox, SessionId(0));
+        registry.submit(SessionId(0), KIND, b"item".to_vec());
+        let data: AlephData<Block> = provider.get_data().await;
+
+        let mut delivery = ItemDelivery::new(SessionId(0), 1, registry);
+        delivery.deliver(data.items().to_vec());
+        delivery.deliver(data.items().to_vec());
+
+        let ordered = subscription.next().await.expect("item was delivered");
+        assert_eq!(ordered.session_id, SessionId(0));
+        assert_eq!(ordered.committee_size, 1);
+        assert_eq!(ordered.item, data.items()[0]);
+        assert!(subscription.try_next().is_err());
+    }
+}
@@ -9,23 +9,79 @@ mod chain_info;
 mod data_interpreter;
 mod data_provider;
 mod data_store;
+mod extension;
 mod proposal;
 mod status_provider;
 
 pub use chain_info::ChainInfoProvider;
 pub use data_interpreter::OrderedDataInterpreter;
-pub use data_provider::ChainTracker;
+pub use data_provider::{ChainTracker, ChainTrackerConfig};
 pub use data_store::{DataStore, DataStoreConfig};
+pub use extension::{
+    ItemChecker, ItemDelivery, ItemKind, ItemProvider, ItemRegistry, ItemValidator, OrderedItem,
+    SignedItem, MAX_ITEMS_PER_DATA, MAX_ITEM_PAYLOAD_SIZE,
+};
 pub use proposal::UnvalidatedAlephProposal;
 
-// Maximum number of blocks above the last finalized allowed in an AlephBFT proposal.
+// Maximum number of blocks above the last finalized allowed in an AlephBFT proposal. The proposals we
+// create can be configured to be shorter (see `ChainTrackerConfig`), but we accept any up to this length.
 pub const MAX_DATA_BRANCH_LEN: usize = 7;
 
+/// A head proposal together with out-of-band items, see `AlephData::Extended`.
+#[derive(Clone, Debug, Encode, Decode)]
+pub struct ExtendedData<B: BlockT> {
+    pub head_proposal: Option<UnvalidatedAlephProposal<B>>,
+    pub items: Vec<SignedItem>,
+}
+
+// Need to be implemented manually, as deriving does not work (`BlockT` is not `Hash`).
+impl<B: BlockT> Hash for ExtendedData<B> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.head_proposal.hash(state);
+        self.items.hash(state);
+    }
+}
+
+// Clippy does not allow deriving PartialEq when implementing Hash manually
+impl<B: BlockT> PartialEq for ExtendedData<B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.head_proposal.eq(&other.head_proposal) && self.items.eq(&other.items)
+    }
+}
+
+impl<B: BlockT> Eq for ExtendedData<B> {}
+
 /// The data ordered by the Aleph consensus.
+/// The variant index doubles as the version of the encoding. `Empty` and `HeadProposal` form the
+/// original version and are encoded exactly as before, `Extended` is only used when there are
+/// out-of-band items to order, so nodes that cannot decode it are unaffected otherwise.
 #[derive(Clone, Debug, Encode, Decode)]
 pub enum AlephData<B: BlockT> {
+    #[codec(index = 0)]
     Empty,
+    #[codec(index = 1)]
     HeadProposal(UnvalidatedAlephProposal<B>),
+    #[codec(index = 2)]
+    Extended(ExtendedData<B>),
+}
+
+impl<B: BlockT> AlephData<B> {
+    /// The head proposal contained in the data, if any.
+    pub fn head_proposal(&self) -> Option<&UnvalidatedAlephProposal<B>> {
+        match self {
+            AlephData::Empty => None,
+            AlephData::HeadProposal(proposal) => Some(proposal),
+            AlephData::Extended(extended) => extended.head_proposal.as_ref(),
+        }
+    }
+
+    /// The out-of-band items contained in the data.
+    pub fn items(&self) -> &[SignedItem] {
+        match self {
+            AlephData::Extended(extended) => &extended.items,
+            _ => &[],
+        }
+    }
 }
 
 // Need to be implemented manually, as deriving does not work (`BlockT` is not `Hash`).
@@ -39,6 +95,10 @@ impl<B: BlockT> Hash for AlephData<B> {
                 (1u8).hash(state);
                 proposal.hash(state);
             }
+            AlephData::Extended(extended) => {
+                (2u8).hash(state);
+                extended.hash(state);
+            }
         }
     }
 }
@@ -49,6 +109,7 @@ impl<B: BlockT> PartialEq for AlephData<B> {
         match (self, other) {
             (AlephData::Empty, AlephData::Empty) => true,
             (AlephData::HeadProposal(p1), AlephData::HeadProposal(p2)) => p1.eq(p2),
+            (AlephData::Extended(e1), AlephData::Extended(e2)) => e1.eq(e2),
             _ => false,
         }
     }
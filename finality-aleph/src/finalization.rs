@@ -1,10 +1,13 @@
 use core::result::Result;
 use std::{marker::PhantomData, sync::Arc};
 
+use aleph_primitives::finality::INHERENT_IDENTIFIER;
+use async_trait::async_trait;
 use log::{debug, warn};
 use sc_client_api::{Backend, Finalizer, HeaderBackend, LockImportRun};
 use sp_api::{BlockId, NumberFor};
 use sp_blockchain::Error;
+use sp_inherents::{InherentData, InherentIdentifier};
 use sp_runtime::{traits::Block, Justification};
 
 pub trait BlockFinalizer<B: Block> {
@@ -70,3 +73,39 @@ where
         update_res
     }
 }
+
+/// Puts the newest finalized block known to the node into the inherent data, so that the runtime
+/// can learn about it from the blocks authored by the node.
+pub struct FinalizedInherentDataProvider<B: Block> {
+    number: NumberFor<B>,
+    hash: B::Hash,
+}
+
+impl<B: Block> FinalizedInherentDataProvider<B> {
+    pub fn new<C: HeaderBackend<B>>(client: &C) -> Self {
+        let info = client.info();
+        FinalizedInherentDataProvider {
+            number: info.finalized_number,
+            hash: info.finalized_hash,
+        }
+    }
+}
+
+#[async_trait]
+impl<B: Block> sp_inherents::InherentDataProvider for FinalizedInherentDataProvider<B> {
+    fn provide_inherent_data(
+        &self,
+        inherent_data: &mut InherentData,
+    ) -> Result<(), sp_inherents::Error> {
+        inherent_data.put_data(INHERENT_IDENTIFIER, &(self.number, self.hash))
+    }
+
+    async fn try_handle_error(
+        &self,
+        _: &InherentIdentifier,
+        _: &[u8],
+    ) -> Option<Result<(), sp_inherents::Error>> {
+        // The runtime does not check the inherent against the data of the importing node.
+        None
+    }
+}
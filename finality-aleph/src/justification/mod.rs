@@ -51,11 +51,11 @@ pub struct JustificationNotification<Block: BlockT> {
 #[derive(Clone)]
 pub struct JustificationHandlerConfig<B: BlockT> {
     /// How long should we wait when the session verifier is not yet available.
-    verifier_timeout: Duration,
+    pub verifier_timeout: Duration,
     /// How long should we wait for any notification.
-    notification_timeout: Duration,
+    pub notification_timeout: Duration,
     ///Distance (in amount of blocks) between the best and the block we want to request justification
-    min_allowed_delay: NumberFor<B>,
+    pub min_allowed_delay: NumberFor<B>,
 }
 
 impl<B: BlockT> Default for JustificationHandlerConfig<B> {
@@ -12,7 +12,9 @@ use futures::{
     channel::{mpsc, oneshot},
     Future, TryFutureExt,
 };
-use sc_client_api::{backend::Backend, BlockchainEvents, Finalizer, LockImportRun, TransactionFor};
+use sc_client_api::{
+    backend::Backend, AuxStore, BlockchainEvents, Finalizer, LockImportRun, TransactionFor,
+};
 use sc_consensus::BlockImport;
 use sc_network::{ExHashT, NetworkService};
 use sc_service::SpawnTaskHandle;
@@ -33,6 +35,7 @@ pub mod metrics;
 mod network;
 mod nodes;
 mod party;
+mod randomness;
 mod session;
 mod session_map;
 #[cfg(test)]
@@ -41,10 +44,20 @@ pub mod testing;
 pub use crate::metrics::Metrics;
 pub use aleph_bft::default_config as default_aleph_config;
 pub use aleph_primitives::{AuthorityId, AuthorityPair, AuthoritySignature};
+pub use data_io::{
+    ChainInfoCacheConfig, ChainTrackerConfig, DataStoreConfig, ItemKind, ItemRegistry,
+    ItemValidator, OrderedItem, SignedItem, MAX_DATA_BRANCH_LEN, MAX_ITEMS_PER_DATA,
+    MAX_ITEM_PAYLOAD_SIZE,
+};
+pub use finalization::FinalizedInherentDataProvider;
 pub use import::AlephBlockImport;
-pub use justification::JustificationNotification;
-pub use network::Protocol;
+pub use justification::{JustificationHandlerConfig, JustificationNotification};
+pub use network::{ConnectionManagerConfig, Protocol};
 pub use nodes::{run_nonvalidator_node, run_validator_node};
+pub use party::ConsensusDelayConfig;
+pub use randomness::{
+    InherentDataProvider as RandomnessInherentDataProvider, RandomnessBeacon, RANDOMNESS_ITEM_KIND,
+};
 pub use session::SessionPeriod;
 
 #[derive(Clone, Debug, Encode, Decode)]
@@ -94,6 +107,7 @@ pub trait ClientForAleph<B, BE>:
     + HeaderBackend<B>
     + HeaderMetadata<B, Error = sp_blockchain::Error>
     + BlockchainEvents<B>
+    + AuxStore
 where
     BE: Backend<B>,
     B: Block,
@@ -110,6 +124,7 @@ where
         + HeaderBackend<B>
         + HeaderMetadata<B, Error = sp_blockchain::Error>
         + BlockchainEvents<B>
+        + AuxStore
         + BlockImport<B, Transaction = TransactionFor<BE, B>, Error = sp_consensus::Error>,
 {
 }
@@ -176,4 +191,11 @@ pub struct AlephConfig<B: Block, H: ExHashT, C, SC> {
     pub session_period: SessionPeriod,
     pub millisecs_per_block: MillisecsPerBlock,
     pub unit_creation_delay: UnitCreationDelay,
+    pub consensus_delay_config: ConsensusDelayConfig,
+    pub justification_handler_config: JustificationHandlerConfig<B>,
+    pub data_store_config: DataStoreConfig,
+    pub chain_tracker_config: ChainTrackerConfig,
+    pub connection_manager_config: ConnectionManagerConfig,
+    pub item_registry: ItemRegistry,
+    pub randomness_beacon: RandomnessBeacon,
 }
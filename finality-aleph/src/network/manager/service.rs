@@ -72,13 +72,14 @@ impl PreSession {
 
 /// Configuration for the session manager service. Controls how often the maintenance and
 /// rebroadcasts are triggerred.
+#[derive(Clone, Debug)]
 pub struct Config {
-    discovery_cooldown: Duration,
-    maintenance_period: Duration,
+    pub discovery_cooldown: Duration,
+    pub maintenance_period: Duration,
 }
 
 impl Config {
-    fn new(discovery_cooldown: Duration, maintenance_period: Duration) -> Self {
+    pub fn new(discovery_cooldown: Duration, maintenance_period: Duration) -> Self {
         Config {
             discovery_cooldown,
             maintenance_period,
@@ -8,7 +8,8 @@ use crate::{
     crypto::AuthorityVerifier,
     finalization::AlephFinalizer,
     justification::{
-        JustificationHandler, JustificationRequestSchedulerImpl, SessionInfo, SessionInfoProvider,
+        JustificationHandler, JustificationHandlerConfig, JustificationRequestSchedulerImpl,
+        SessionInfo, SessionInfoProvider,
     },
     last_block_of_session, mpsc,
     mpsc::UnboundedSender,
@@ -32,6 +33,7 @@ struct JustificationParams<B: Block, H: ExHashT, C> {
     pub session_period: SessionPeriod,
     pub millisecs_per_block: MillisecsPerBlock,
     pub session_map: ReadOnlySessionMap,
+    pub config: JustificationHandlerConfig<B>,
 }
 
 struct SessionInfoProviderImpl {
@@ -88,6 +90,7 @@ where
         session_period,
         millisecs_per_block,
         session_map,
+        config,
     } = just_params;
 
     let handler = JustificationHandler::new(
@@ -97,7 +100,7 @@ where
         AlephFinalizer::new(client),
         JustificationRequestSchedulerImpl::new(&session_period, &millisecs_per_block, MAX_ATTEMPTS),
         metrics,
-        Default::default(),
+        config,
     );
 
     let (authority_justification_tx, authority_justification_rx) = mpsc::unbounded();
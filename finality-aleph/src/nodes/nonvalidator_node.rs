@@ -26,6 +26,7 @@ where
         millisecs_per_block,
         justification_rx,
         spawn_handle,
+        justification_handler_config,
         ..
     } = aleph_config;
     let map_updater = SessionMapUpdater::<_, _, B>::new(
@@ -45,6 +46,7 @@ where
         session_period,
         millisecs_per_block,
         session_map: session_authorities,
+        config: justification_handler_config,
     });
 
     debug!(target: "aleph-party", "JustificationHandler has started.");
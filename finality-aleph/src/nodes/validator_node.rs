@@ -1,8 +1,7 @@
 use crate::{
     mpsc,
     network::{
-        ConnectionIO, ConnectionManager, ConnectionManagerConfig, Service as NetworkService,
-        SessionManager, IO as NetworkIO,
+        ConnectionIO, ConnectionManager, Service as NetworkService, SessionManager, IO as NetworkIO,
     },
     nodes::{setup_justification_handler, JustificationParams},
     party::{ConsensusParty, ConsensusPartyParams},
@@ -35,7 +34,13 @@ where
         session_period,
         millisecs_per_block,
         justification_rx,
-        ..
+        consensus_delay_config,
+        justification_handler_config,
+        data_store_config,
+        chain_tracker_config,
+        connection_manager_config,
+        mut item_registry,
+        randomness_beacon,
     } = aleph_config;
 
     let block_requester = network.clone();
@@ -49,6 +54,13 @@ where
         map_updater.run(session_period).await
     });
 
+    let share_collector = randomness_beacon.register(&mut item_registry);
+    let beacon_client = client.clone();
+    spawn_handle.spawn("aleph/randomness_beacon", None, async move {
+        debug!(target: "aleph-randomness", "Randomness beacon has started.");
+        share_collector.run(beacon_client, session_period).await
+    });
+
     let (authority_justification_tx, handler_task) =
         setup_justification_handler(JustificationParams {
             justification_rx,
@@ -58,6 +70,7 @@ where
             session_period,
             millisecs_per_block,
             session_map: session_authorities.clone(),
+            config: justification_handler_config,
         });
 
     // Prepare and start the network
@@ -74,10 +87,7 @@ where
         commands_from_manager,
         messages_from_network,
     );
-    let connection_manager = ConnectionManager::new(
-        network.clone(),
-        ConnectionManagerConfig::with_session_period(&session_period, &millisecs_per_block),
-    );
+    let connection_manager = ConnectionManager::new(network.clone(), connection_manager_config);
     let session_manager = SessionManager::new(commands_for_service, messages_for_service);
     let network = NetworkService::new(
         network.clone(),
@@ -113,6 +123,10 @@ where
         metrics,
         authority_justification_tx,
         unit_creation_delay,
+        consensus_delay_config,
+        data_store_config,
+        chain_tracker_config,
+        item_registry,
     });
 
     debug!(target: "aleph-party", "Consensus party has started.");
@@ -0,0 +1,377 @@
+use crate::{
+    last_block_of_session, session_id_from_block_num, NumberFor, SessionId, SessionPeriod,
+};
+use codec::{Decode, Encode};
+use log::{debug, warn};
+use sc_client_api::AuxStore;
+use sp_runtime::traits::Block;
+use std::sync::Arc;
+
+const SESSION_MARKER_KEY: &[u8] = b"aleph_party_session_marker";
+
+/// The stages a session goes through from the point of view of a single node.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub enum SessionState {
+    /// The authorities of the session are known and its network was started, but the previous
+    /// session has not ended yet.
+    Prepared,
+    /// The session is in progress and the node takes part in it.
+    Running,
+    /// The last block of the session is finalized and its tasks are being stopped.
+    Draining,
+    /// All the tasks of the session are stopped.
+    Stopped,
+}
+
+impl SessionState {
+    fn can_become(&self, next: SessionState) -> bool {
+        use SessionState::*;
+        matches!(
+            (self, next),
+            (Prepared, Running)
+                | (Prepared, Stopped)
+                | (Running, Draining)
+                | (Running, Stopped)
+                | (Draining, Stopped)
+        )
+    }
+}
+
+/// The most advanced state reached by the node, persisted so that it survives restarts.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct SessionMarker {
+    pub session_id: SessionId,
+    pub state: SessionState,
+}
+
+impl SessionMarker {
+    /// The session a node with this marker should run after a restart, ignoring finality.
+    fn resume_session(&self) -> SessionId {
+        match self.state {
+            SessionState::Prepared => SessionId(self.session_id.0.saturating_sub(1)),
+            SessionState::Running => self.session_id,
+            SessionState::Draining | SessionState::Stopped => SessionId(self.session_id.0 + 1),
+        }
+    }
+
+    // The next session is prepared while the current one is still running, so markers of
+    // different sessions are ordered by the session they resume.
+    fn follows(&self, previous: &SessionMarker) -> bool {
+        if self.session_id == previous.session_id {
+            previous.state.can_become(self.state)
+        } else {
+            self.resume_session() >= previous.resume_session()
+        }
+    }
+}
+
+/// Storage of the session marker.
+pub trait MarkerStore: Send + Sync {
+    fn load(&self) -> Option<SessionMarker>;
+    fn store(&self, marker: &SessionMarker);
+}
+
+impl<C: AuxStore + Send + Sync> MarkerStore for Arc<C> {
+    fn load(&self) -> Option<SessionMarker> {
+        match self.get_aux(SESSION_MARKER_KEY) {
+            Ok(Some(encoded)) => SessionMarker::decode(&mut &encoded[..]).ok(),
+            Ok(None) => None,
+            Err(e) => {
+                warn!(target: "aleph-party", "Failed to read the session marker: {:?}", e);
+                None
+            }
+        }
+    }
+
+    fn store(&self, marker: &SessionMarker) {
+        if let Err(e) = self.insert_aux(&[(SESSION_MARKER_KEY, &marker.encode()[..])], &[]) {
+            warn!(target: "aleph-party", "Failed to persist the session marker {:?}: {:?}", marker, e);
+        }
+    }
+}
+
+/// Tracks and persists the state of the sessions the node goes through. The state only ever
+/// moves forward, so that a restarted node does not go back to a session it has already left.
+pub struct SessionLifecycle<S: MarkerStore> {
+    store: S,
+    marker: Option<SessionMarker>,
+}
+
+impl<S: MarkerStore> SessionLifecycle<S> {
+    pub fn new(store: S) -> Self {
+        let marker = store.load();
+        SessionLifecycle { store, marker }
+    }
+
+    pub fn marker(&self) -> Option<SessionMarker> {
+        self.marker
+    }
+
+    /// Moves the given session to the given state. Returns false, without changing anything, if
+    /// the transition would move the lifecycle backwards.
+    pub fn transition(&mut self, session_id: SessionId, state: SessionState) -> bool {
+        let marker = SessionMarker { session_id, state };
+        if let Some(current) = &self.marker {
+            if !marker.follows(current) {
+                debug!(target: "aleph-party", "Ignoring session transition to {:?}, current marker is {:?}", marker, current);
+                return false;
+            }
+        }
+        self.store.store(&marker);
+        self.marker = Some(marker);
+        true
+    }
+
+    /// The session a node should run after a restart, given the highest finalized block.
+    pub fn starting_session<B: Block>(
+        &self,
+        last_finalized: NumberFor<B>,
+        period: SessionPeriod,
+    ) -> SessionId {
+        let finalized_session = session_id_from_block_num::<B>(last_finalized, period);
+        // If the last block of a session is finalized the session is over, there is no point in
+        // waiting for it to be skipped.
+        let from_finality =
+            if last_finalized == last_block_of_session::<B>(finalized_session, period) {
+                SessionId(finalized_session.0 + 1)
+            } else {
+                finalized_session
+            };
+        let from_marker = match &self.marker {
+            Some(marker) => marker.resume_session(),
+            None => return from_finality,
+        };
+        if from_marker > from_finality {
+            warn!(target: "aleph-party", "Finality is behind the session marker {:?}, starting from session {:?}.", self.marker, from_marker);
+        }
+        std::cmp::max(from_finality, from_marker)
+    }
+}
+
+/// Whether the session ended, i.e. its last block is finalized.
+pub fn session_ended<B: Block>(
+    session_id: SessionId,
+    period: SessionPeriod,
+    last_finalized: NumberFor<B>,
+) -> bool {
+    last_finalized >= last_block_of_session::<B>(session_id, period)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::mocks::TBlock;
+    use parking_lot::Mutex;
+    use substrate_test_runtime_client::{DefaultTestClientBuilderExt, TestClientBuilderExt};
+
+    const PERIOD: SessionPeriod = SessionPeriod(10);
+
+    #[derive(Clone, Default)]
+    struct InMemoryStore(Arc<Mutex<Option<SessionMarker>>>);
+
+    impl MarkerStore for InMemoryStore {
+        fn load(&self) -> Option<SessionMarker> {
+            *self.0.lock()
+        }
+
+        fn store(&self, marker: &SessionMarker) {
+            *self.0.lock() = Some(*marker);
+        }
+    }
+
+    fn lifecycle(marker: Option<(u32, SessionState)>) -> SessionLifecycle<InMemoryStore> {
+        let store = InMemoryStore::default();
+        if let Some((session, state)) = marker {
+            store.store(&SessionMarker {
+                session_id: SessionId(session),
+                state,
+            });
+        }
+        SessionLifecycle::new(store)
+    }
+
+    fn starting_session(marker: Option<(u32, SessionState)>, last_finalized: u64) -> u32 {
+        lifecycle(marker)
+            .starting_session::<TBlock>(last_finalized, PERIOD)
+            .0
+    }
+
+    #[test]
+    fn restart_without_marker_follows_finality() {
+        // Session 2 consists of blocks 20..=29.
+        assert_eq!(starting_session(None, 19), 2);
+        assert_eq!(starting_session(None, 20), 2);
+        assert_eq!(starting_session(None, 25), 2);
+        assert_eq!(starting_session(None, 28), 2);
+        assert_eq!(starting_session(None, 29), 3);
+        assert_eq!(starting_session(None, 0), 0);
+    }
+
+    #[test]
+    fn restart_rejoins_running_session_at_every_offset() {
+        for offset in 0..9 {
+            let last_finalized = 20 + offset;
+            assert_eq!(
+                starting_session(Some((2, SessionState::Running)), last_finalized),
+                2,
+                "offset {}",
+                offset
+            );
+            assert_eq!(
+                starting_session(Some((3, SessionState::Prepared)), last_finalized),
+                2,
+                "offset {}",
+                offset
+            );
+        }
+        assert_eq!(starting_session(Some((2, SessionState::Running)), 29), 3);
+        assert_eq!(starting_session(Some((3, SessionState::Prepared)), 29), 3);
+    }
+
+    #[test]
+    fn restart_does_not_return_to_left_session() {
+        for offset in 0..10 {
+            let last_finalized = 20 + offset;
+            assert_eq!(
+                starting_session(Some((2, SessionState::Draining)), last_finalized),
+                3,
+                "offset {}",
+                offset
+            );
+            assert_eq!(
+                starting_session(Some((2, SessionState::Stopped)), last_finalized),
+                3,
+                "offset {}",
+                offset
+            );
+        }
+    }
+
+    #[test]
+    fn restart_follows_finality_ahead_of_marker() {
+        assert_eq!(starting_session(Some((0, SessionState::Running)), 25), 2);
+        assert_eq!(starting_session(Some((1, SessionState::Stopped)), 29), 3);
+        assert_eq!(starting_session(Some((1, SessionState::Prepared)), 15), 1);
+    }
+
+    #[test]
+    fn transitions_only_move_forward() {
+        let mut lifecycle = lifecycle(None);
+        assert!(lifecycle.transition(SessionId(1), SessionState::Prepared));
+        assert!(lifecycle.transition(SessionId(1), SessionState::Running));
+        assert!(!lifecycle.transition(SessionId(1), SessionState::Prepared));
+        assert!(lifecycle.transition(SessionId(2), SessionState::Prepared));
+        assert!(lifecycle.transition(SessionId(1), SessionState::Draining));
+        assert!(!lifecycle.transition(SessionId(2), SessionState::Prepared));
+        assert!(!lifecycle.transition(SessionId(1), SessionState::Running));
+        assert!(!lifecycle.transition(SessionId(0), SessionState::Running));
+        assert!(lifecycle.transition(SessionId(1), SessionState::Stopped));
+        assert!(lifecycle.transition(SessionId(2), SessionState::Running));
+        assert_eq!(
+            lifecycle.marker(),
+            Some(SessionMarker {
+                session_id: SessionId(2),
+                state: SessionState::Running,
+            })
+        );
+    }
+
+    #[test]
+    fn marker_survives_restart() {
+        let client = Arc::new(substrate_test_runtime_client::TestClientBuilder::new().build());
+        let mut lifecycle = SessionLifecycle::new(client.clone());
+        assert_eq!(lifecycle.marker(), None);
+        assert!(lifecycle.transition(SessionId(4), SessionState::Running));
+
+        let restarted = SessionLifecycle::new(client);
+        assert_eq!(
+            restarted.marker(),
+            Some(SessionMarker {
+                session_id: SessionId(4),
+                state: SessionState::Running,
+            })
+        );
+        assert_eq!(
+            restarted.starting_session::<TBlock>(0, PERIOD),
+            SessionId(4)
+        );
+    }
+
+    #[test]
+    fn every_accepted_transition_is_persisted() {
+        let store = InMemoryStore::default();
+        let mut lifecycle = SessionLifecycle::new(store.clone());
+        let marker = |session, state| SessionMarker {
+            session_id: SessionId(session),
+            state,
+        };
+
+        assert!(lifecycle.transition(SessionId(2), SessionState::Running));
+        assert_eq!(store.load(), Some(marker(2, SessionState::Running)));
+        assert!(lifecycle.transition(SessionId(3), SessionState::Prepared));
+        assert_eq!(store.load(), Some(marker(3, SessionState::Prepared)));
+        assert!(!lifecycle.transition(SessionId(1), SessionState::Running));
+        assert_eq!(store.load(), Some(marker(3, SessionState::Prepared)));
+        assert!(lifecycle.transition(SessionId(2), SessionState::Draining));
+        assert_eq!(store.load(), Some(marker(2, SessionState::Draining)));
+        assert_eq!(SessionLifecycle::new(store).marker(), lifecycle.marker());
+    }
+
+    #[test]
+    fn restart_in_the_middle_of_session_resumes_it() {
+        let store = InMemoryStore::default();
+        let mut lifecycle = SessionLifecycle::new(store.clone());
+        assert!(lifecycle.transition(SessionId(2), SessionState::Running));
+        assert!(lifecycle.transition(SessionId(3), SessionState::Prepared));
+
+        // The node goes down at block 25, in the middle of session 2.
+        let mut restarted = SessionLifecycle::new(store);
+        let session = restarted.starting_session::<TBlock>(25, PERIOD);
+        assert_eq!(session, SessionId(2));
+
+        // Running the session again has to be accepted, as well as its end and the next session.
+        assert!(restarted.transition(session, SessionState::Running));
+        assert!(restarted.transition(SessionId(3), SessionState::Prepared));
+        assert!(restarted.transition(session, SessionState::Draining));
+        assert!(restarted.transition(session, SessionState::Stopped));
+        assert!(restarted.transition(SessionId(3), SessionState::Running));
+    }
+
+    #[test]
+    fn ended_session_is_not_run_again() {
+        let store = InMemoryStore::default();
+        let mut lifecycle = SessionLifecycle::new(store.clone());
+        assert!(lifecycle.transition(SessionId(2), SessionState::Running));
+        assert!(session_ended::<TBlock>(SessionId(2), PERIOD, 29));
+        assert!(lifecycle.transition(SessionId(2), SessionState::Draining));
+
+        // The node goes down while stopping the tasks of the session and comes back with
+        // finality behind the end of that session.
+        let mut restarted = SessionLifecycle::new(store.clone());
+        assert_eq!(
+            restarted.starting_session::<TBlock>(27, PERIOD),
+            SessionId(3)
+        );
+        assert!(!restarted.transition(SessionId(2), SessionState::Running));
+        assert!(restarted.transition(SessionId(2), SessionState::Stopped));
+
+        let restarted = SessionLifecycle::new(store);
+        assert_eq!(
+            restarted.marker(),
+            Some(SessionMarker {
+                session_id: SessionId(2),
+                state: SessionState::Stopped,
+            })
+        );
+        assert_eq!(
+            restarted.starting_session::<TBlock>(27, PERIOD),
+            SessionId(3)
+        );
+    }
+
+    #[test]
+    fn session_end_is_detected() {
+        assert!(!session_ended::<TBlock>(SessionId(2), PERIOD, 28));
+        assert!(session_ended::<TBlock>(SessionId(2), PERIOD, 29));
+    }
+}
@@ -1,6 +1,9 @@
 use crate::{
     crypto::{AuthorityPen, AuthorityVerifier, KeyBox},
-    data_io::{ChainTracker, DataStore, OrderedDataInterpreter},
+    data_io::{
+        ChainTracker, ChainTrackerConfig, DataStore, DataStoreConfig, ItemChecker, ItemDelivery,
+        ItemProvider, ItemRegistry, OrderedDataInterpreter,
+    },
     default_aleph_config,
     justification::{AlephJustification, JustificationNotification, Verifier},
     last_block_of_session,
@@ -10,9 +13,9 @@ use crate::{
             SubtaskCommon as AuthoritySubtaskCommon, Subtasks as AuthoritySubtasks,
             Task as AuthorityTask,
         },
+        lifecycle::{session_ended, SessionLifecycle, SessionState},
         task::{Handle, Task},
     },
-    session_id_from_block_num,
     session_map::ReadOnlySessionMap,
     AuthorityId, Metrics, NodeIndex, SessionBoundaries, SessionId, SessionPeriod, SplitData,
     UnitCreationDelay,
@@ -27,12 +30,13 @@ use sc_client_api::Backend;
 use sp_consensus::SelectChain;
 use sp_keystore::CryptoStore;
 use sp_runtime::traits::{Block, Header};
-use std::{collections::HashSet, default::Default, marker::PhantomData, sync::Arc, time::Duration};
+use std::{collections::HashSet, marker::PhantomData, sync::Arc, time::Duration};
 
 mod aggregator;
 mod authority;
 mod chain_tracker;
 mod data_store;
+mod lifecycle;
 mod member;
 mod task;
 
@@ -71,6 +75,10 @@ pub(crate) struct ConsensusPartyParams<B: Block, SC, C, RB> {
     pub metrics: Option<Metrics<<B::Header as Header>::Hash>>,
     pub authority_justification_tx: mpsc::UnboundedSender<JustificationNotification<B>>,
     pub unit_creation_delay: UnitCreationDelay,
+    pub consensus_delay_config: ConsensusDelayConfig,
+    pub data_store_config: DataStoreConfig,
+    pub chain_tracker_config: ChainTrackerConfig,
+    pub item_registry: ItemRegistry,
 }
 
 pub(crate) struct ConsensusParty<B, C, BE, SC, RB>
@@ -94,6 +102,11 @@ where
     metrics: Option<Metrics<<B::Header as Header>::Hash>>,
     authority_justification_tx: mpsc::UnboundedSender<JustificationNotification<B>>,
     unit_creation_delay: UnitCreationDelay,
+    consensus_delay_config: ConsensusDelayConfig,
+    data_store_config: DataStoreConfig,
+    chain_tracker_config: ChainTrackerConfig,
+    item_registry: ItemRegistry,
+    lifecycle: SessionLifecycle<Arc<C>>,
 }
 
 const SESSION_STATUS_CHECK_PERIOD: Duration = Duration::from_millis(1000);
@@ -120,8 +133,14 @@ where
             metrics,
             authority_justification_tx,
             unit_creation_delay,
+            consensus_delay_config,
+            data_store_config,
+            chain_tracker_config,
+            item_registry,
         } = params;
+        let lifecycle = SessionLifecycle::new(client.clone());
         Self {
+            lifecycle,
             session_manager,
             client,
             keystore,
@@ -134,6 +153,10 @@ where
             spawn_handle,
             phantom: PhantomData,
             unit_creation_delay,
+            consensus_delay_config,
+            data_store_config,
+            chain_tracker_config,
+            item_registry,
         }
     }
 
@@ -155,20 +178,29 @@ where
             node_id,
             session_id,
             self.unit_creation_delay,
+            &self.consensus_delay_config,
         );
 
         let (chain_tracker, data_provider) = ChainTracker::new(
             self.select_chain.clone(),
             self.client.clone(),
             session_boundaries.clone(),
-            Default::default(),
+            self.chain_tracker_config.clone(),
             self.metrics.clone(),
         );
+        let data_provider = ItemProvider::new(
+            data_provider,
+            self.item_registry.clone(),
+            multikeychain.clone(),
+            session_id,
+        );
 
         let ordered_data_interpreter = OrderedDataInterpreter::<B, C>::new(
             blocks_for_aggregator,
             self.client.clone(),
             session_boundaries.clone(),
+            self.data_store_config.chain_info_cache_config.clone(),
+            ItemDelivery::new(session_id, authorities.len(), self.item_registry.clone()),
         );
 
         let subtask_common = AuthoritySubtaskCommon {
@@ -185,7 +217,12 @@ where
             session_boundaries.clone(),
             self.client.clone(),
             self.block_requester.clone(),
-            Default::default(),
+            self.data_store_config.clone(),
+            ItemChecker::new(
+                session_id,
+                AuthorityVerifier::new(authorities),
+                self.item_registry.clone(),
+            ),
             unfiltered_aleph_network,
         );
 
@@ -256,6 +293,38 @@ where
         )
     }
 
+    // Waits for the authorities of the session, unless the session ends before they show up.
+    async fn session_authorities(&self, session_id: SessionId) -> Option<Vec<AuthorityId>> {
+        let mut authorities_notification = self
+            .session_authorities
+            .subscribe_to_insertion(session_id)
+            .await;
+        loop {
+            tokio::select! {
+                authorities = &mut authorities_notification => match authorities {
+                    Ok(authorities) => return Some(authorities),
+                    Err(e) => panic!(
+                        "Error while receiving the notification about current session {:?}",
+                        e
+                    ),
+                },
+                _ = Delay::new(SESSION_STATUS_CHECK_PERIOD) => {
+                    if self.session_ended(session_id) {
+                        return None;
+                    }
+                },
+            }
+        }
+    }
+
+    fn session_ended(&self, session_id: SessionId) -> bool {
+        session_ended::<B>(
+            session_id,
+            self.session_period,
+            self.client.info().finalized_number,
+        )
+    }
+
     async fn run_session(&mut self, session_id: SessionId) {
         let last_block = last_block_of_session::<B>(session_id, self.session_period);
 
@@ -272,6 +341,7 @@ where
                 let last_finalized_number = self.client.info().finalized_number;
                 if last_finalized_number >= last_block {
                     debug!(target: "aleph-party", "Skipping session {:?} early because block {:?} is already finalized", session_id, last_finalized_number);
+                    self.lifecycle.transition(session_id, SessionState::Stopped);
                     return;
                 }
             }
@@ -279,18 +349,15 @@ where
 
         // We need to wait until session-authorities are available for current session.
         // This should only be needed for the first ever session as all other session are known
-        // at least one session earlier.
-        let authorities = match self
-            .session_authorities
-            .subscribe_to_insertion(session_id)
-            .await
-            .await
-        {
-            Err(e) => panic!(
-                "Error while receiving the notification about current session {:?}",
-                e
-            ),
-            Ok(authorities) => authorities,
+        // at least one session earlier. If they show up only after the session ended we move on,
+        // instead of getting stuck outside of both sessions.
+        let authorities = match self.session_authorities(session_id).await {
+            Some(authorities) => authorities,
+            None => {
+                warn!(target: "aleph-party", "Session {:?} ended before its authorities were known, skipping it.", session_id);
+                self.lifecycle.transition(session_id, SessionState::Stopped);
+                return;
+            }
         };
 
         trace!(target: "afa", "Authorities for session {:?}: {:?}", session_id, authorities);
@@ -312,6 +379,7 @@ where
             }
             None
         };
+        self.lifecycle.transition(session_id, SessionState::Running);
         let mut check_session_status = Delay::new(SESSION_STATUS_CHECK_PERIOD);
         let next_session_id = SessionId(session_id.0 + 1);
         let mut start_next_session_network = Some(
@@ -377,6 +445,7 @@ where
                             }
                         }
                     }
+                    self.lifecycle.transition(next_session_id, SessionState::Prepared);
                     start_next_session_network = None;
                 },
                 Some(_) = async {
@@ -389,6 +458,8 @@ where
                 },
             }
         }
+        self.lifecycle
+            .transition(session_id, SessionState::Draining);
         if let Some(task) = maybe_authority_task {
             debug!(target: "aleph-party", "Stopping the authority task.");
             task.stop().await;
@@ -396,12 +467,17 @@ where
         if let Err(e) = self.session_manager.stop_session(session_id) {
             warn!(target: "aleph-party", "Session Manager failed to stop in session {:?}: {:?}", session_id, e)
         }
+        self.lifecycle.transition(session_id, SessionState::Stopped);
     }
 
     pub async fn run(mut self) {
         let last_finalized_number = self.client.info().finalized_number;
-        let starting_session =
-            session_id_from_block_num::<B>(last_finalized_number, self.session_period);
+        let starting_session = self
+            .lifecycle
+            .starting_session::<B>(last_finalized_number, self.session_period);
+        if let Some(marker) = self.lifecycle.marker() {
+            info!(target: "aleph-party", "Restarting from session marker {:?}, finalized block #{:?}.", marker, last_finalized_number);
+        }
         for curr_id in starting_session.0.. {
             info!(target: "aleph-party", "Running session {:?}.", curr_id);
             self.run_session(SessionId(curr_id)).await;
@@ -409,25 +485,56 @@ where
     }
 }
 
+/// Parameters of the AlephBFT delays, apart from the `UnitCreationDelay` configured separately.
+#[derive(Clone, Debug)]
+pub struct ConsensusDelayConfig {
+    /// How often AlephBFT checks whether any of its delays has passed.
+    pub tick_interval: Duration,
+    /// How often AlephBFT repeats requests for missing units.
+    pub requests_interval: Duration,
+    /// Base delay between rebroadcasts of a unit, doubled with every subsequent rebroadcast.
+    pub unit_broadcast_delay: Duration,
+    /// Delay before creating the unit of the first round.
+    pub initial_unit_creation_delay: Duration,
+    /// The maximal round of units in a single session.
+    pub max_round: u16,
+}
+
+impl Default for ConsensusDelayConfig {
+    fn default() -> Self {
+        ConsensusDelayConfig {
+            tick_interval: Duration::from_millis(100),
+            requests_interval: Duration::from_millis(3000),
+            unit_broadcast_delay: Duration::from_millis(4000),
+            initial_unit_creation_delay: Duration::from_millis(2000),
+            max_round: 7000,
+        }
+    }
+}
+
 pub(crate) fn create_aleph_config(
     n_members: usize,
     node_id: NodeIndex,
     session_id: SessionId,
     unit_creation_delay: UnitCreationDelay,
+    delays: &ConsensusDelayConfig,
 ) -> aleph_bft::Config {
     let mut consensus_config = default_aleph_config(n_members.into(), node_id, session_id.0 as u64);
-    consensus_config.max_round = 7000;
+    consensus_config.max_round = delays.max_round;
+    let initial_unit_creation_delay = delays.initial_unit_creation_delay;
     let unit_creation_delay = Arc::new(move |t| {
         if t == 0 {
-            Duration::from_millis(2000)
+            initial_unit_creation_delay
         } else {
             exponential_slowdown(t, unit_creation_delay.0 as f64, 5000, 1.005)
         }
     });
-    let unit_broadcast_delay = Arc::new(|t| exponential_slowdown(t, 4000., 0, 2.));
+    let unit_broadcast_delay_millis = delays.unit_broadcast_delay.as_millis() as f64;
+    let unit_broadcast_delay =
+        Arc::new(move |t| exponential_slowdown(t, unit_broadcast_delay_millis, 0, 2.));
     let delay_config = DelayConfig {
-        tick_interval: Duration::from_millis(100),
-        requests_interval: Duration::from_millis(3000),
+        tick_interval: delays.tick_interval,
+        requests_interval: delays.requests_interval,
         unit_broadcast_delay,
         unit_creation_delay,
     };
@@ -0,0 +1,339 @@
+//! Randomness beacon run by the committee on top of the out-of-band items of AlephBFT.
+//!
+//! At the beginning of every session each committee member contributes a share: its signature
+//! under an empty item of kind `rand`. The seed of the session is the hash of the first
+//! `2/3 * n + 1` shares from distinct members in the order decided by AlephBFT, so all the honest
+//! members arrive at the same value. The seeds are passed to the runtime through an inherent
+//! together with the shares, which the runtime verifies against the keys of the committee.
+//!
+//! The shares are ed25519 signatures, not shares of a threshold signature, so the members whose
+//! shares come last can bias the seed by withholding them. Replacing them with BLS threshold
+//! signatures would make the seed unique, the rest of the pipeline would stay the same.
+
+use crate::{
+    crypto::Signature,
+    data_io::{ItemKind, ItemRegistry, ItemValidator, OrderedItem, SignedItem},
+    session::{session_id_from_block_num, SessionId, SessionPeriod},
+};
+use aleph_bft::NodeIndex;
+use aleph_primitives::randomness::{
+    quorum, seed, BeaconOutput, InherentError, InherentType, INHERENT_IDENTIFIER,
+    MAX_BEACON_OUTPUTS, SHARE_ITEM_KIND,
+};
+use async_trait::async_trait;
+use codec::Decode;
+use futures::{channel::mpsc, StreamExt};
+use log::{debug, info};
+use parking_lot::Mutex;
+use sc_client_api::BlockchainEvents;
+use sp_blockchain::HeaderBackend;
+use sp_inherents::{InherentData, InherentIdentifier};
+use sp_runtime::traits::{Block, Header};
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::Arc,
+};
+
+/// The kind of the out-of-band items carrying the randomness shares.
+pub const RANDOMNESS_ITEM_KIND: ItemKind = ItemKind(SHARE_ITEM_KIND);
+
+// The share is the signature itself, there is nothing else to put into the item.
+struct ShareValidator;
+
+impl ItemValidator for ShareValidator {
+    fn validate(&self, _: SessionId, item: &SignedItem) -> bool {
+        item.payload.is_empty()
+    }
+}
+
+struct SessionShares {
+    committee_size: usize,
+    shares: BTreeMap<NodeIndex, Signature>,
+}
+
+impl SessionShares {
+    fn new(committee_size: usize) -> Self {
+        SessionShares {
+            committee_size,
+            shares: BTreeMap::new(),
+        }
+    }
+
+    fn quorum(&self) -> usize {
+        quorum(self.committee_size)
+    }
+
+    // Returns the output as soon as the quorum of shares is collected, later shares are ignored.
+    fn add(
+        &mut self,
+        session_id: SessionId,
+        author: NodeIndex,
+        share: Signature,
+    ) -> Option<BeaconOutput> {
+        if self.shares.len() >= self.quorum() {
+            return None;
+        }
+        self.shares.entry(author).or_insert(share);
+        if self.shares.len() < self.quorum() {
+            return None;
+        }
+        let shares: Vec<_> = self
+            .shares
+            .iter()
+            .map(|(member, share)| (member.0 as u32, share.clone().into()))
+            .collect();
+        Some(BeaconOutput {
+            session: session_id.0,
+            seed: seed(session_id.0, &shares),
+            shares,
+        })
+    }
+}
+
+/// Keeps the most recent outputs of the beacon. It is shared between the finality gadget, which
+/// computes the outputs, and the block authoring and import, which need them as inherent data.
+#[derive(Clone, Default)]
+pub struct RandomnessBeacon {
+    outputs: Arc<Mutex<VecDeque<BeaconOutput>>>,
+}
+
+impl RandomnessBeacon {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn inherent_data_provider(&self) -> InherentDataProvider {
+        InherentDataProvider {
+            outputs: self.outputs.lock().iter().cloned().collect(),
+        }
+    }
+
+    /// Registers the shares with the item registry. The returned collector has to be run for
+    /// the node to contribute shares and compute the outputs.
+    pub(crate) fn register(&self, registry: &mut ItemRegistry) -> ShareCollector {
+        registry.register_validator(RANDOMNESS_ITEM_KIND, Arc::new(ShareValidator));
+        ShareCollector {
+            beacon: self.clone(),
+            registry: registry.clone(),
+            ordered_shares: registry.subscribe(RANDOMNESS_ITEM_KIND),
+            sessions: HashMap::new(),
+        }
+    }
+
+    fn add_output(&self, output: BeaconOutput) {
+        let mut outputs = self.outputs.lock();
+        outputs.push_back(output);
+        while outputs.len() > MAX_BEACON_OUTPUTS {
+            outputs.pop_front();
+        }
+    }
+}
+
+pub(crate) struct ShareCollector {
+    beacon: RandomnessBeacon,
+    registry: ItemRegistry,
+    ordered_shares: mpsc::UnboundedReceiver<OrderedItem>,
+    sessions: HashMap<SessionId, SessionShares>,
+}
+
+impl ShareCollector {
+    // Shares are submitted in advance for the next session, they are only proposed if we are
+    // a member of its committee.
+    fn contribute(&self, session_id: SessionId) {
+        self.registry
+            .submit(session_id, RANDOMNESS_ITEM_KIND, Vec::new());
+    }
+
+    fn on_share_ordered(&mut self, ordered: OrderedItem) {
+        let OrderedItem {
+            session_id,
+            committee_size,
+            item,
+        } = ordered;
+        let output = self
+            .sessions
+            .entry(session_id)
+            .or_insert_with(|| SessionShares::new(committee_size))
+            .add(session_id, item.author, item.signature);
+        if let Some(output) = output {
+            info!(target: "aleph-randomness", "Computed randomness seed for session {:?}.", session_id);
+            self.beacon.add_output(output);
+            let oldest = SessionId(session_id.0.saturating_sub(MAX_BEACON_OUTPUTS as u32));
+            self.sessions.retain(|id, _| *id > oldest);
+        }
+    }
+
+    pub async fn run<B, C>(mut self, client: Arc<C>, session_period: SessionPeriod)
+    where
+        B: Block,
+        C: BlockchainEvents<B> + HeaderBackend<B>,
+    {
+        let mut finality_stream = client.finality_notification_stream();
+        let mut current_session =
+            session_id_from_block_num::<B>(client.info().finalized_number, session_period);
+        self.contribute(current_session);
+        self.contribute(SessionId(current_session.0 + 1));
+        loop {
+            tokio::select! {
+                Some(ordered) = self.ordered_shares.next() => {
+                    self.on_share_ordered(ordered);
+                }
+                Some(block) = finality_stream.next() => {
+                    let session_id = session_id_from_block_num::<B>(*block.header.number(), session_period);
+                    if session_id > current_session {
+                        current_session = session_id;
+                        self.contribute(SessionId(current_session.0 + 1));
+                    }
+                }
+                else => {
+                    debug!(target: "aleph-randomness", "Randomness beacon streams ended. Terminating.");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Puts the known beacon outputs into the inherent data, so that they can be included in and
+/// checked against the blocks.
+pub struct InherentDataProvider {
+    outputs: InherentType,
+}
+
+#[async_trait]
+impl sp_inherents::InherentDataProvider for InherentDataProvider {
+    fn provide_inherent_data(
+        &self,
+        inherent_data: &mut InherentData,
+    ) -> Result<(), sp_inherents::Error> {
+        inherent_data.put_data(INHERENT_IDENTIFIER, &self.outputs)
+    }
+
+    async fn try_handle_error(
+        &self,
+        identifier: &InherentIdentifier,
+        error: &[u8],
+    ) -> Option<Result<(), sp_inherents::Error>> {
+        if *identifier != INHERENT_IDENTIFIER {
+            return None;
+        }
+        let error = InherentError::decode(&mut &*error).ok()?;
+        Some(Err(sp_inherents::Error::Application(Box::from(format!(
+            "{:?}",
+            error
+        )))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        crypto::{AuthorityPen, AuthorityVerifier, KeyBox},
+        data_io::{AlephData, ItemChecker, ItemDelivery, ItemProvider},
+    };
+    use aleph_bft::DataProvider;
+    use aleph_primitives::{AuthorityId, KEY_TYPE};
+    use sp_keystore::{testing::KeyStore, CryptoStore};
+    use substrate_test_runtime_client::runtime::Block as TBlock;
+
+    struct EmptyProvider;
+
+    #[async_trait]
+    impl DataProvider<AlephData<TBlock>> for EmptyProvider {
+        async fn get_data(&mut self) -> AlephData<TBlock> {
+            AlephData::Empty
+        }
+    }
+
+    async fn keyboxes(n: usize) -> (Vec<KeyBox>, Vec<AuthorityId>) {
+        let key_store = Arc::new(KeyStore::new());
+        let mut authorities = Vec::new();
+        for i in 0..n {
+            authorities.push(AuthorityId::from(
+                key_store
+                    .ed25519_generate_new(KEY_TYPE, Some(&format!("//{}", i)))
+                    .await
+                    .unwrap(),
+            ));
+        }
+        let verifier = AuthorityVerifier::new(authorities.clone());
+        let mut keyboxes = Vec::new();
+        for (i, authority) in authorities.iter().cloned().enumerate() {
+            let pen = AuthorityPen::new(authority, key_store.clone())
+                .await
+                .expect("The keys should sign successfully");
+            keyboxes.push(KeyBox::new(NodeIndex(i), verifier.clone(), pen));
+        }
+        (keyboxes, authorities)
+    }
+
+    // Every member contributes a share in the session and returns the ordered shares.
+    async fn shares(keyboxes: Vec<KeyBox>, session_id: SessionId) -> Vec<SignedItem> {
+        let mut items = Vec::new();
+        for keybox in keyboxes {
+            let mut registry = ItemRegistry::new();
+            let _collector = RandomnessBeacon::new().register(&mut registry);
+            registry.submit(session_id, RANDOMNESS_ITEM_KIND, Vec::new());
+            let mut provider = ItemProvider::new(EmptyProvider, registry, keybox, session_id);
+            let data: AlephData<TBlock> = provider.get_data().await;
+            items.extend(data.items().iter().cloned());
+        }
+        items
+    }
+
+    fn outputs(beacon: &RandomnessBeacon) -> Vec<BeaconOutput> {
+        beacon.outputs.lock().iter().cloned().collect()
+    }
+
+    #[tokio::test]
+    async fn only_empty_shares_pass_checks() {
+        let (keyboxes, authorities) = keyboxes(2).await;
+        let mut items = shares(keyboxes, SessionId(1)).await;
+        let mut registry = ItemRegistry::new();
+        let _collector = RandomnessBeacon::new().register(&mut registry);
+        let checker = ItemChecker::new(SessionId(1), AuthorityVerifier::new(authorities), registry);
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(|item| checker.check(item)));
+
+        items[0].payload = b"bias".to_vec();
+        assert!(!checker.check(&items[0]));
+    }
+
+    #[tokio::test]
+    async fn computes_the_same_seed_after_quorum_regardless_of_later_shares() {
+        let (keyboxes, authorities) = keyboxes(4).await;
+        let items = shares(keyboxes, SessionId(3)).await;
+
+        let mut registry = ItemRegistry::new();
+        let beacon = RandomnessBeacon::new();
+        let mut collector = beacon.register(&mut registry);
+        let mut delivery = ItemDelivery::new(SessionId(3), 4, registry.clone());
+        delivery.deliver(items[..2].to_vec());
+        delivery.deliver(items[..2].to_vec());
+        while let Ok(Some(ordered)) = collector.ordered_shares.try_next() {
+            collector.on_share_ordered(ordered);
+        }
+        assert!(outputs(&beacon).is_empty());
+
+        delivery.deliver(items[2..].to_vec());
+        while let Ok(Some(ordered)) = collector.ordered_shares.try_next() {
+            collector.on_share_ordered(ordered);
+        }
+        let computed = outputs(&beacon);
+        assert_eq!(computed.len(), 1);
+        assert_eq!(computed[0].session, 3);
+        assert_eq!(computed[0].verify(&authorities), Ok(()));
+
+        // Another node receiving the same shares in the same order, starting with the last one.
+        let mut registry = ItemRegistry::new();
+        let other_beacon = RandomnessBeacon::new();
+        let mut other_collector = other_beacon.register(&mut registry);
+        let mut delivery = ItemDelivery::new(SessionId(3), 4, registry.clone());
+        delivery.deliver(vec![items[2].clone(), items[0].clone(), items[1].clone()]);
+        while let Ok(Some(ordered)) = other_collector.ordered_shares.try_next() {
+            other_collector.on_share_ordered(ordered);
+        }
+        assert_eq!(outputs(&other_beacon), computed);
+    }
+}
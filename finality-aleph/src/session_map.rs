@@ -6,6 +6,7 @@ use futures::StreamExt;
 use log::{debug, error, trace};
 use sc_client_api::{Backend, FinalityNotification};
 use sc_utils::mpsc::TracingUnboundedReceiver;
+use sp_api::ApiExt;
 use sp_runtime::{
     generic::BlockId,
     traits::{Block, Header, NumberFor},
@@ -26,6 +27,9 @@ pub trait AuthorityProvider<B> {
     fn authorities(&self, block: B) -> Option<Vec<AuthorityId>>;
     /// returns next session authorities where current session is for block
     fn next_authorities(&self, block: B) -> Option<Vec<AuthorityId>>;
+    /// returns authorities of a past or the current session from the history kept on chain,
+    /// which does not require the state at the blocks of that session
+    fn session_authorities(&self, session_id: SessionId) -> Option<Vec<AuthorityId>>;
 }
 
 /// Default implementation of authority provider trait.
@@ -77,6 +81,18 @@ where
             .ok()
             .flatten()
     }
+
+    fn session_authorities(&self, session_id: SessionId) -> Option<Vec<AuthorityId>> {
+        let at = BlockId::Hash(self.client.info().finalized_hash);
+        let runtime_api = self.client.runtime_api();
+        match runtime_api.api_version::<dyn AlephSessionApi<B>>(&at) {
+            Ok(Some(version)) if version >= 2 => runtime_api
+                .session_authorities(&at, session_id.0)
+                .ok()
+                .flatten(),
+            _ => None,
+        }
+    }
 }
 
 pub trait FinalityNotificator<B, N> {
@@ -211,6 +227,9 @@ where
     B: Block,
     AP: AuthorityProvider<NumberFor<B>>,
 {
+    if let Some(authorities) = authority_provider.session_authorities(session_id) {
+        return authorities;
+    }
     if session_id == SessionId(0) {
         authority_provider
             .authorities(<NumberFor<B>>::saturated_from(0u32))
@@ -351,6 +370,7 @@ mod tests {
     struct MockProvider {
         pub session_map: HashMap<NumberFor<TBlock>, Vec<AuthorityId>>,
         pub next_session_map: HashMap<NumberFor<TBlock>, Vec<AuthorityId>>,
+        pub history: HashMap<SessionId, Vec<AuthorityId>>,
         pub asked_for: Arc<Mutex<Vec<NumberFor<TBlock>>>>,
     }
 
@@ -364,6 +384,7 @@ mod tests {
             Self {
                 session_map: HashMap::new(),
                 next_session_map: HashMap::new(),
+                history: HashMap::new(),
                 asked_for: Arc::new(Mutex::new(Vec::new())),
             }
         }
@@ -390,6 +411,10 @@ mod tests {
             asked.push(b);
             self.next_session_map.get(&b).cloned()
         }
+
+        fn session_authorities(&self, session_id: SessionId) -> Option<Vec<AuthorityId>> {
+            self.history.get(&session_id).cloned()
+        }
     }
 
     impl FinalityNotificator<FinalityNotification<TBlock>, NumberFor<TBlock>> for MockNotificator {
@@ -516,6 +541,50 @@ mod tests {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn catches_up_from_history_without_state() {
+        let (_sender, receiver) = tracing_unbounded("test");
+        let mut mock_provider = MockProvider::new();
+        let mut mock_notificator = MockNotificator::new(receiver);
+
+        // The state at the first blocks of the past sessions is pruned.
+        mock_provider
+            .history
+            .insert(SessionId(0), authorities(0, 4));
+        mock_provider
+            .history
+            .insert(SessionId(1), authorities(4, 8));
+        mock_provider
+            .history
+            .insert(SessionId(2), authorities(8, 12));
+        mock_provider
+            .next_session_map
+            .insert(2, authorities(12, 16));
+
+        mock_notificator.last_finalized = 2;
+
+        let asked = mock_provider.asked_for.clone();
+        let updater = SessionMapUpdater::new(mock_provider, mock_notificator);
+        let session_map = updater.readonly_session_map();
+
+        let _handle = tokio::spawn(updater.run(SessionPeriod(1)));
+
+        // wait a bit
+        Delay::new(Duration::from_millis(50)).await;
+
+        assert_eq!(vec![2], *asked.lock().unwrap());
+        assert_eq!(session_map.get(SessionId(0)).await, Some(authorities(0, 4)));
+        assert_eq!(session_map.get(SessionId(1)).await, Some(authorities(4, 8)));
+        assert_eq!(
+            session_map.get(SessionId(2)).await,
+            Some(authorities(8, 12))
+        );
+        assert_eq!(
+            session_map.get(SessionId(3)).await,
+            Some(authorities(12, 16))
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn prunes_old_sessions() {
         let (_sender, receiver) = tracing_unbounded("test");
@@ -1,5 +1,9 @@
 use crate::{
-    data_io::{AlephData, AlephNetworkMessage, DataStore, DataStoreConfig, MAX_DATA_BRANCH_LEN},
+    crypto::AuthorityVerifier,
+    data_io::{
+        AlephData, AlephNetworkMessage, ChainInfoCacheConfig, DataStore, DataStoreConfig,
+        ItemChecker, ItemRegistry, MAX_DATA_BRANCH_LEN,
+    },
     network::{DataNetwork, RequestBlocks, SimpleNetwork},
     session::{SessionBoundaries, SessionId, SessionPeriod},
     testing::{
@@ -162,6 +166,7 @@ fn prepare_data_store(
         available_proposals_cache_capacity: 8000,
         periodic_maintenance_interval: Duration::from_millis(20),
         request_block_after: Duration::from_millis(30),
+        chain_info_cache_config: ChainInfoCacheConfig::default(),
     };
 
     let session_boundaries = if let Some(session_boundaries) = session_boundaries {
@@ -174,6 +179,11 @@ fn prepare_data_store(
         client.clone(),
         block_requester,
         data_store_config,
+        ItemChecker::new(
+            SessionId(0),
+            AuthorityVerifier::new(Vec::new()),
+            ItemRegistry::new(),
+        ),
         test_network,
     );
 
@@ -1,7 +1,7 @@
 mod config;
 mod ws_rpc_client;
 
-use aleph_client::create_custom_connection;
+use aleph_client::ConnectionPool;
 use clap::Parser;
 use codec::{Compact, Decode, Encode};
 use config::Config;
@@ -81,7 +81,9 @@ fn main() -> Result<(), anyhow::Error> {
         "connection-pool created: {}ms",
         time_stats.elapsed().as_millis()
     );
-    let connection = pool.get(0).unwrap().get(0).unwrap().clone();
+    let connection = pool
+        .submit_with(|connection| connection.clone())
+        .expect("At least one of the nodes should be healthy");
 
     info!(
         "preparing transactions: {}ms",
@@ -109,17 +111,11 @@ fn main() -> Result<(), anyhow::Error> {
         },
     );
 
-    let pool_getter = |thread_id: ThreadId, tx_ix: usize| {
-        pool.get(thread_id % pool.len())
-            .and_then(|threads_pool| threads_pool.get(tx_ix % threads_pool.len()))
-            .unwrap()
-    };
-
     info!("flooding: {}ms", time_stats.elapsed().as_millis());
     let tick = Instant::now();
 
     flood(
-        pool_getter,
+        &pool,
         txs,
         tx_status,
         &histogram,
@@ -150,10 +146,8 @@ fn main() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-type ThreadId = usize;
-
-fn flood<'a>(
-    pool: impl Fn(ThreadId, usize) -> &'a Api<sr25519::Pair, WsRpcClient> + Sync,
+fn flood(
+    pool: &ConnectionPool<WsRpcClient>,
     txs: Vec<TransferTransaction>,
     status: XtStatus,
     histogram: &Arc<Mutex<HdrHistogram<u64>>>,
@@ -161,7 +155,6 @@ fn flood<'a>(
     interval_duration: Duration,
     thread_pool: &rayon::ThreadPool,
 ) {
-    let mut current_start_ix = 0;
     thread_pool.install(|| {
         txs
             .chunks(transactions_in_interval)
@@ -170,22 +163,15 @@ fn flood<'a>(
                 let start = Instant::now();
                 info!("Starting {} interval", interval_idx);
 
-                let interval_len = interval.len();
-
                 interval.
                     into_par_iter()
-                    .enumerate()
-                    .map(|( tx_ix, tx )| (tx_ix + current_start_ix, tx))
-                    .for_each(|(tx_ix, tx)| {
-
-                    const DEFAULT_THREAD_ID: usize = 0;
-                    let thread_id = thread_pool.current_thread_index().unwrap_or(DEFAULT_THREAD_ID);
-                    send_tx(
-                        pool(thread_id, tx_ix),
+                    .for_each(|tx| {
+                    pool.with_connection(|connection| send_tx(
+                        connection,
                         tx,
                         status,
                         Arc::clone(histogram),
-                    );
+                    )).expect("At least one of the nodes should be healthy");
                 });
 
                 let exec_time = start.elapsed();
@@ -201,7 +187,6 @@ fn flood<'a>(
                         exec_time.as_millis()
                     );
                 }
-                current_start_ix += interval_len;
             });
     });
 }
@@ -494,21 +479,15 @@ fn send_tx<Call>(
     *hist += elapsed_time as u64;
 }
 
-fn create_connection_pool(
-    nodes: &[String],
-    threads: usize,
-) -> Vec<Vec<Api<sr25519::Pair, WsRpcClient>>> {
-    repeat(nodes)
-        .cycle()
+/// Every thread should have its own connection to each of the nodes, since a single connection
+/// processes requests one by one.
+fn create_connection_pool(nodes: &[String], threads: usize) -> ConnectionPool<WsRpcClient> {
+    let addresses = repeat(nodes)
         .take(threads)
-        .map(|urls| {
-            urls.iter()
-                .map(|url| {
-                    create_custom_connection(url).expect("it should return initialized connection")
-                })
-                .collect()
-        })
-        .collect()
+        .flatten()
+        .cloned()
+        .collect::<Vec<_>>();
+    ConnectionPool::new(&addresses).expect("At least one of the nodes should be healthy")
 }
 
 fn get_nonce(connection: &Api<sr25519::Pair, WsRpcClient>, account: &AccountId) -> u32 {
@@ -539,6 +518,8 @@ fn zip_and_store_txs(txs: &[TransferTransaction], path: impl AsRef<Path>) {
 
 #[cfg(test)]
 mod tests {
+    use aleph_client::create_custom_connection;
+
     use super::*;
 
     #[ignore] // requires access to a chain
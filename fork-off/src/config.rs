@@ -32,6 +32,11 @@ pub struct Config {
     #[clap(long, default_value = "./chainspec_from_snapshot.json")]
     pub combined_spec_path: String,
 
+    /// Where to additionally write the snapshot in the format of the `try-runtime` subcommand of
+    /// aleph-node, so that runtime upgrades can be checked against it offline.
+    #[clap(long)]
+    pub try_runtime_snapshot_path: Option<String>,
+
     /// Whether to read the state from the ready snapshot file.
     #[clap(long)]
     pub use_snapshot_file: bool,
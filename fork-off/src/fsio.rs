@@ -3,6 +3,7 @@ use std::{
     io::{ErrorKind, Write},
 };
 
+use codec::Encode;
 use log::info;
 use serde_json::Value;
 
@@ -49,3 +50,23 @@ pub fn read_snapshot_from_file(path: String) -> Storage {
     info!("Read snapshot of {} key-val pairs", snapshot.len());
     snapshot
 }
+
+fn decode_hex(data: &str) -> Vec<u8> {
+    hex::decode(data.trim_start_matches("0x")).expect("Snapshot should contain hex encoded data")
+}
+
+/// Saves the snapshot as SCALE encoded key-value pairs, the format `try-runtime snap` reads.
+pub fn save_try_runtime_snapshot_to_file(snapshot: &Storage, path: String) {
+    let mut pairs: Vec<(Vec<u8>, Vec<u8>)> = snapshot
+        .iter()
+        .map(|(key, value)| (decode_hex(key), decode_hex(value)))
+        .collect();
+    pairs.sort();
+    let data = pairs.encode();
+    info!(
+        "Writing try-runtime snapshot of {} key-val pairs and {} total bytes",
+        pairs.len(),
+        data.len()
+    );
+    write_to_file(path, &data);
+}
@@ -9,7 +9,10 @@ use crate::{
     chainspec_combining::combine_states,
     config::{Config, StoragePath},
     fetching::StateFetcher,
-    fsio::{read_json_from_file, read_snapshot_from_file, save_snapshot_to_file, write_to_file},
+    fsio::{
+        read_json_from_file, read_snapshot_from_file, save_snapshot_to_file,
+        save_try_runtime_snapshot_to_file, write_to_file,
+    },
 };
 
 mod chainspec_combining;
@@ -28,6 +31,7 @@ async fn main() -> anyhow::Result<()> {
         http_rpc_endpoint,
         initial_spec_path,
         snapshot_path,
+        try_runtime_snapshot_path,
         combined_spec_path,
         use_snapshot_file,
         storage_keep_state,
@@ -60,6 +64,9 @@ async fn main() -> anyhow::Result<()> {
         save_snapshot_to_file(state, snapshot_path.clone());
     }
     let state = read_snapshot_from_file(snapshot_path);
+    if let Some(path) = try_runtime_snapshot_path {
+        save_try_runtime_snapshot_to_file(&state, path);
+    }
 
     let initial_state: Storage =
         serde_json::from_value(initial_spec["genesis"]["raw"]["top"].take())
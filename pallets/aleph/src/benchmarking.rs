@@ -0,0 +1,29 @@
+//! Benchmarks of the session hook of pallet_aleph.
+
+use super::*;
+use crate::Pallet as Aleph;
+use frame_benchmarking::{account, benchmarks};
+use frame_support::{
+    sp_runtime::RuntimeAppPublic,
+    traits::{Get, OneSessionHandler},
+};
+
+const SEED: u32 = 0;
+const MAX_VALIDATORS: u32 = 1000;
+
+benchmarks! {
+    on_new_session {
+        let v in 1 .. MAX_VALIDATORS;
+        let validators: Vec<(T::AccountId, T::AuthorityId)> = (0..v)
+            .map(|i| (account("validator", i, SEED), T::AuthorityId::generate_pair(None)))
+            .collect();
+        let session = T::CurrentSession::get();
+    }: {
+        let new_session = || validators.iter().map(|(account, key)| (account, key.clone()));
+        <Aleph<T> as OneSessionHandler<T::AccountId>>::on_new_session(true, new_session(), new_session());
+    }
+    verify {
+        assert_eq!(Aleph::<T>::authorities().len(), v as usize);
+        assert!(Aleph::<T>::session_authorities(session).is_some());
+    }
+}
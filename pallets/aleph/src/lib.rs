@@ -1,39 +1,58 @@
 //! This pallet is a runtime companion of Aleph finality gadget.
 //!
-//! Currently, it only provides support for changing sessions but in the future
+//! Currently, it only provides support for changing sessions, keeps a bounded history of
+//! authority sets and the newest block the block authors claim to be finalized, but in the future
 //! it will allow reporting equivocation in AlephBFT.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
 mod tests;
 
 mod migrations;
+pub mod weights;
 
 use sp_std::prelude::*;
 
 use frame_support::{
     log,
-    sp_runtime::BoundToRuntimeAppPublic,
+    sp_runtime::{traits::Hash, BoundToRuntimeAppPublic},
     traits::{OneSessionHandler, StorageVersion},
+    weights::DispatchClass,
     Parameter,
 };
 pub use pallet::*;
+use primitives::{finality::INHERENT_IDENTIFIER, SessionIndex};
+pub use weights::WeightInfo;
 
 /// The current storage version.
-const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
 
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
-    use frame_support::{pallet_prelude::*, sp_runtime::RuntimeAppPublic};
-    use frame_system::pallet_prelude::BlockNumberFor;
+    use frame_support::{
+        inherent::MakeFatalError, pallet_prelude::*, sp_runtime::RuntimeAppPublic,
+    };
+    use frame_system::{
+        ensure_none,
+        pallet_prelude::{BlockNumberFor, OriginFor},
+    };
 
     #[pallet::config]
     pub trait Config: frame_system::Config {
         type AuthorityId: Member + Parameter + RuntimeAppPublic + MaybeSerializeDeserialize;
+        /// The index of the current session, already updated when a new session starts.
+        type CurrentSession: Get<SessionIndex>;
+        /// For how many sessions the authority sets are kept in `SessionAuthorities`.
+        #[pallet::constant]
+        type AuthorityHistoryDepth: Get<SessionIndex>;
+        /// Weights of the session hook of this pallet.
+        type WeightInfo: WeightInfo;
     }
 
     #[pallet::pallet]
@@ -48,30 +67,135 @@ pub mod pallet {
             T::DbWeight::get().reads(1)
                 + match on_chain {
                     _ if on_chain == STORAGE_VERSION => 0,
+                    _ if on_chain == StorageVersion::new(2) => {
+                        migrations::v2_to_v3::migrate::<T, Self>()
+                    }
                     _ if on_chain == StorageVersion::new(1) => {
                         migrations::v1_to_v2::migrate::<T, Self>()
+                            + migrations::v2_to_v3::migrate::<T, Self>()
                     }
                     _ if on_chain == StorageVersion::new(0) => {
                         migrations::v0_to_v1::migrate::<T, Self>()
                             + migrations::v1_to_v2::migrate::<T, Self>()
+                            + migrations::v2_to_v3::migrate::<T, Self>()
                     }
                     _ => {
                         log::warn!(
                             target: "pallet_aleph",
-                            "On chain storage version of pallet aleph is {:?} but it should not be bigger than 2",
+                            "On chain storage version of pallet aleph is {:?} but it should not be bigger than 3",
                             on_chain
                         );
                         0
                     }
                 }
         }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<(), &'static str> {
+            let on_chain = <Pallet<T> as GetStorageVersion>::on_chain_storage_version();
+            if on_chain == StorageVersion::new(0) {
+                migrations::v0_to_v1::pre_upgrade::<T, Self>()?;
+            }
+            if on_chain < StorageVersion::new(2) {
+                migrations::v1_to_v2::pre_upgrade::<Self>()?;
+            }
+            if on_chain < StorageVersion::new(3) {
+                migrations::v2_to_v3::pre_upgrade::<Self>()?;
+            }
+            Ok(())
+        }
+
+        // The version before the upgrade is gone at this point, so the checks of all the
+        // migrations are run, each of them has to hold after the last one.
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade() -> Result<(), &'static str> {
+            migrations::v0_to_v1::post_upgrade::<Self>()?;
+            migrations::v1_to_v2::post_upgrade::<Self>()?;
+            migrations::v2_to_v3::post_upgrade::<T, Self>()
+        }
     }
 
     #[pallet::storage]
     #[pallet::getter(fn authorities)]
     pub(super) type Authorities<T: Config> = StorageValue<_, Vec<T::AuthorityId>, ValueQuery>;
 
+    /// The authority sets of the last `AuthorityHistoryDepth` sessions together with their hashes.
+    #[pallet::storage]
+    #[pallet::getter(fn session_authorities)]
+    pub(super) type SessionAuthorities<T: Config> =
+        StorageMap<_, Twox64Concat, SessionIndex, (Vec<T::AuthorityId>, T::Hash), OptionQuery>;
+
+    /// The newest block the block authors claim to be finalized, as its number and hash.
+    ///
+    /// Finality is decided outside of the runtime and no justification is checked here: the
+    /// runtime only makes sure that the reported block is an ancestor of the current one. A
+    /// malicious author may report a block that is not finalized at all, so this must not be
+    /// trusted by anything that depends on finality, e.g. contracts.
+    #[pallet::storage]
+    #[pallet::getter(fn author_reported_finalized)]
+    pub type AuthorReportedFinalized<T: Config> =
+        StorageValue<_, (T::BlockNumber, T::Hash), OptionQuery>;
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The reported block is not newer than the last finalized one.
+        OutdatedFinalized,
+        /// The reported block is not a known ancestor of the current block.
+        UnknownFinalized,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Notes the block the author of the current block claims to be finalized, see
+        /// `AuthorReportedFinalized` for what is and is not checked.
+        #[pallet::weight((T::DbWeight::get().reads_writes(3, 1), DispatchClass::Mandatory))]
+        pub fn note_finalized(
+            origin: OriginFor<T>,
+            number: T::BlockNumber,
+            hash: T::Hash,
+        ) -> DispatchResult {
+            ensure_none(origin)?;
+            Self::ensure_finalized_acceptable(number, hash)?;
+            <AuthorReportedFinalized<T>>::put((number, hash));
+            Ok(())
+        }
+    }
+
+    #[pallet::inherent]
+    impl<T: Config> ProvideInherent for Pallet<T> {
+        type Call = Call<T>;
+        type Error = MakeFatalError<()>;
+        const INHERENT_IDENTIFIER: InherentIdentifier = INHERENT_IDENTIFIER;
+
+        fn create_inherent(data: &InherentData) -> Option<Self::Call> {
+            let (number, hash) = data
+                .get_data::<(T::BlockNumber, T::Hash)>(&INHERENT_IDENTIFIER)
+                .ok()
+                .flatten()?;
+            Self::ensure_finalized_acceptable(number, hash)
+                .ok()
+                .map(|_| Call::note_finalized { number, hash })
+        }
+
+        fn is_inherent(call: &Self::Call) -> bool {
+            matches!(call, Call::note_finalized { .. })
+        }
+    }
+
     impl<T: Config> Pallet<T> {
+        fn ensure_finalized_acceptable(number: T::BlockNumber, hash: T::Hash) -> DispatchResult {
+            if let Some((last, _)) = <AuthorReportedFinalized<T>>::get() {
+                ensure!(number > last, Error::<T>::OutdatedFinalized);
+            }
+            // Hashes of blocks older than `BlockHashCount` are not kept, so they are unknown.
+            ensure!(
+                number < <frame_system::Pallet<T>>::block_number()
+                    && <frame_system::Pallet<T>>::block_hash(number) == hash,
+                Error::<T>::UnknownFinalized
+            );
+            Ok(())
+        }
+
         pub(crate) fn initialize_authorities(authorities: &[T::AuthorityId]) {
             if !authorities.is_empty() {
                 assert!(
@@ -79,12 +203,26 @@ pub mod pallet {
                     "Authorities are already initialized!"
                 );
                 <Authorities<T>>::put(authorities);
+                Self::record_authorities(T::CurrentSession::get(), authorities);
             }
         }
 
         pub(crate) fn update_authorities(authorities: &[T::AuthorityId]) {
             <Authorities<T>>::put(authorities);
         }
+
+        pub(crate) fn record_authorities(session: SessionIndex, authorities: &[T::AuthorityId]) {
+            let hash = T::Hashing::hash_of(&authorities);
+            <SessionAuthorities<T>>::insert(session, (authorities.to_vec(), hash));
+            if let Some(expired) = session.checked_sub(T::AuthorityHistoryDepth::get()) {
+                <SessionAuthorities<T>>::remove(expired);
+            }
+        }
+
+        /// The hash of the authority set of the given session, if it is still kept.
+        pub fn authority_set_hash(session: SessionIndex) -> Option<T::Hash> {
+            <SessionAuthorities<T>>::get(session).map(|(_, hash)| hash)
+        }
     }
 
     impl<T: Config> BoundToRuntimeAppPublic for Pallet<T> {
@@ -108,10 +246,15 @@ pub mod pallet {
             I: Iterator<Item = (&'a T::AccountId, T::AuthorityId)>,
             T::AccountId: 'a,
         {
+            let (_, authorities): (Vec<_>, Vec<_>) = validators.unzip();
             if changed {
-                let (_, authorities): (Vec<_>, Vec<_>) = validators.unzip();
                 Self::update_authorities(authorities.as_slice());
             }
+            Self::record_authorities(T::CurrentSession::get(), authorities.as_slice());
+            frame_system::Pallet::<T>::register_extra_weight_unchecked(
+                T::WeightInfo::on_new_session(authorities.len() as u32),
+                DispatchClass::Mandatory,
+            );
         }
 
         fn on_disabled(_validator_index: u32) {}
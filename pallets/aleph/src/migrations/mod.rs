@@ -1,2 +1,3 @@
 pub mod v0_to_v1;
 pub mod v1_to_v2;
+pub mod v2_to_v3;
@@ -1,9 +1,13 @@
 use crate::Config;
+#[cfg(feature = "try-runtime")]
+use codec::Decode;
 use frame_support::{
     generate_storage_alias, log,
     traits::{Get, PalletInfoAccess, StorageVersion},
     weights::Weight,
 };
+#[cfg(feature = "try-runtime")]
+use frame_support::{storage::unhashed, traits::GetStorageVersion};
 use sp_std::vec::Vec;
 
 generate_storage_alias!(
@@ -59,3 +63,29 @@ pub fn migrate<T: Config, P: PalletInfoAccess>() -> Weight {
 
     T::DbWeight::get().reads(2) + T::DbWeight::get().writes(writes)
 }
+
+// In version 0 both items are stored as `Option`s, the migration cannot translate values that
+// do not decode as such.
+#[cfg(feature = "try-runtime")]
+pub fn pre_upgrade<T: Config, P: GetStorageVersion>() -> Result<(), &'static str> {
+    if P::on_chain_storage_version() != StorageVersion::new(0) {
+        return Err("Migration from STORAGE_VERSION 0 to 1 requires version 0");
+    }
+    if let Some(raw) = unhashed::get_raw(&SessionForValidatorsChange::hashed_key()) {
+        Option::<u32>::decode(&mut &raw[..])
+            .map_err(|_| "SessionForValidatorsChange is not an Option<u32>")?;
+    }
+    if let Some(raw) = unhashed::get_raw(&Validators::<T>::hashed_key()) {
+        Option::<Vec<T::AccountId>>::decode(&mut &raw[..])
+            .map_err(|_| "Validators is not an Option<Vec<AccountId>>")?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "try-runtime")]
+pub fn post_upgrade<P: GetStorageVersion>() -> Result<(), &'static str> {
+    if P::on_chain_storage_version() < StorageVersion::new(1) {
+        return Err("Migration from STORAGE_VERSION 0 to 1 did not bump the version");
+    }
+    Ok(())
+}
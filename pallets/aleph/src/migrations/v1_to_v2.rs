@@ -1,4 +1,6 @@
 use crate::Config;
+#[cfg(feature = "try-runtime")]
+use frame_support::traits::GetStorageVersion;
 use frame_support::{
     generate_storage_alias, log,
     traits::{Get, PalletInfoAccess, StorageVersion},
@@ -53,3 +55,27 @@ pub fn migrate<T: Config, P: PalletInfoAccess>() -> Weight {
 
     T::DbWeight::get().reads(reads) + T::DbWeight::get().writes(writes)
 }
+
+// Runs before the migration from version 0 if both are applied in one upgrade.
+#[cfg(feature = "try-runtime")]
+pub fn pre_upgrade<P: GetStorageVersion>() -> Result<(), &'static str> {
+    if P::on_chain_storage_version() > StorageVersion::new(1) {
+        return Err("Migration from STORAGE_VERSION 1 to 2 requires version at most 1");
+    }
+    Ok(())
+}
+
+#[cfg(feature = "try-runtime")]
+pub fn post_upgrade<P: GetStorageVersion>() -> Result<(), &'static str> {
+    if P::on_chain_storage_version() < StorageVersion::new(2) {
+        return Err("Migration from STORAGE_VERSION 1 to 2 did not bump the version");
+    }
+    if SessionForValidatorsChange::exists()
+        || MillisecsPerBlock::exists()
+        || SessionPeriod::exists()
+        || Validators::exists()
+    {
+        return Err("Migration from STORAGE_VERSION 1 to 2 left removed items in storage");
+    }
+    Ok(())
+}
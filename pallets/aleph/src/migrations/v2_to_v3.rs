@@ -0,0 +1,50 @@
+use crate::{Config, Pallet};
+#[cfg(feature = "try-runtime")]
+use frame_support::traits::GetStorageVersion;
+use frame_support::{
+    log,
+    traits::{Get, PalletInfoAccess, StorageVersion},
+    weights::Weight,
+};
+
+/// Records the authorities of the current session in `SessionAuthorities`, which until now was
+/// only filled when a session started, so that the set of the session the upgrade happens in is
+/// known before the next session begins.
+pub fn migrate<T: Config, P: PalletInfoAccess>() -> Weight {
+    log::info!(target: "pallet_aleph", "Running migration from STORAGE_VERSION 2 to 3");
+
+    let session = T::CurrentSession::get();
+    let mut writes = 0;
+    if Pallet::<T>::session_authorities(session).is_none() {
+        let authorities = Pallet::<T>::authorities();
+        log::info!(target: "pallet_aleph", "Recording {} authorities of session {}", authorities.len(), session);
+        // Sessions older than the current one were never recorded, so there is nothing to prune.
+        Pallet::<T>::record_authorities(session, &authorities);
+        writes += 1;
+    }
+
+    // store new version
+    StorageVersion::new(3).put::<P>();
+    writes += 1;
+
+    T::DbWeight::get().reads(3) + T::DbWeight::get().writes(writes)
+}
+
+#[cfg(feature = "try-runtime")]
+pub fn pre_upgrade<P: GetStorageVersion>() -> Result<(), &'static str> {
+    if P::on_chain_storage_version() > StorageVersion::new(2) {
+        return Err("Migration from STORAGE_VERSION 2 to 3 requires version at most 2");
+    }
+    Ok(())
+}
+
+#[cfg(feature = "try-runtime")]
+pub fn post_upgrade<T: Config, P: GetStorageVersion>() -> Result<(), &'static str> {
+    if P::on_chain_storage_version() < StorageVersion::new(3) {
+        return Err("Migration from STORAGE_VERSION 2 to 3 did not bump the version");
+    }
+    if Pallet::<T>::session_authorities(T::CurrentSession::get()).is_none() {
+        return Err("Migration from STORAGE_VERSION 2 to 3 did not record the current authorities");
+    }
+    Ok(())
+}
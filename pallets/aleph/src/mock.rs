@@ -130,8 +130,22 @@ impl pallet_timestamp::Config for Test {
     type WeightInfo = ();
 }
 
+parameter_types! {
+    pub const AuthorityHistoryDepth: u32 = 3;
+}
+
+pub struct CurrentSession;
+impl frame_support::traits::Get<u32> for CurrentSession {
+    fn get() -> u32 {
+        Session::current_index()
+    }
+}
+
 impl Config for Test {
     type AuthorityId = AuthorityId;
+    type CurrentSession = CurrentSession;
+    type AuthorityHistoryDepth = AuthorityHistoryDepth;
+    type WeightInfo = ();
 }
 
 pub fn to_authorities(authorities: &[u64]) -> Vec<AuthorityId> {
@@ -2,12 +2,15 @@
 
 use std::collections::HashMap;
 
-use crate::{migrations, mock::*, pallet, Config};
+use crate::{migrations, mock::*, pallet, Call, Config, Error};
 use frame_support::{
-    generate_storage_alias,
+    assert_noop, assert_ok, generate_storage_alias,
+    inherent::{InherentData, ProvideInherent},
     storage::migration::{get_storage_value, put_storage_value},
     traits::{GetStorageVersion, OneSessionHandler, StorageVersion},
 };
+use primitives::finality::INHERENT_IDENTIFIER;
+use sp_core::H256;
 
 generate_storage_alias!(
     Aleph, SessionForValidatorsChange => Value<u32>
@@ -105,6 +108,30 @@ fn migration_from_v1_to_v2_works() {
     })
 }
 
+#[test]
+fn migration_from_v2_to_v3_records_current_authorities() {
+    new_test_ext(&[(1u64, 1u64), (2u64, 2u64)]).execute_with(|| {
+        initialize_session();
+        run_session(1);
+        // Runtimes before version 3 did not record the authorities in the upgrade session.
+        pallet::SessionAuthorities::<Test>::remove(1);
+        StorageVersion::new(2).put::<Aleph>();
+
+        let _weight = migrations::v2_to_v3::migrate::<Test, Aleph>();
+
+        assert_eq!(
+            <pallet::Pallet<Test> as GetStorageVersion>::on_chain_storage_version(),
+            StorageVersion::new(3),
+            "Storage version after applying migration should be incremented"
+        );
+        assert_eq!(
+            Aleph::session_authorities(1).map(|(authorities, _)| authorities),
+            Some(Aleph::authorities()),
+        );
+        assert!(Aleph::authority_set_hash(1).is_some());
+    })
+}
+
 #[test]
 fn test_update_authorities() {
     new_test_ext(&[(1u64, 1u64), (2u64, 2u64)]).execute_with(|| {
@@ -163,3 +190,109 @@ fn test_session_rotation() {
         assert_eq!(Aleph::authorities(), to_authorities(&[3, 4]));
     })
 }
+
+#[test]
+fn keeps_bounded_authority_history() {
+    new_test_ext(&[(1u64, 1u64), (2u64, 2u64)]).execute_with(|| {
+        assert_eq!(
+            Aleph::session_authorities(0).map(|(authorities, _)| authorities),
+            Some(to_authorities(&[1, 2]))
+        );
+
+        initialize_session();
+        run_session(1);
+        Aleph::on_new_session(
+            true,
+            new_session_validators(&[3u64, 4u64]),
+            new_session_validators(&[]),
+        );
+        assert_eq!(
+            Aleph::session_authorities(1).map(|(authorities, _)| authorities),
+            Some(to_authorities(&[3, 4]))
+        );
+        assert_ne!(Aleph::authority_set_hash(0), Aleph::authority_set_hash(1));
+
+        run_session(3);
+        Aleph::on_new_session(
+            false,
+            new_session_validators(&[3u64, 4u64]),
+            new_session_validators(&[]),
+        );
+        assert_eq!(
+            Aleph::session_authorities(3).map(|(authorities, _)| authorities),
+            Some(to_authorities(&[3, 4]))
+        );
+        assert_eq!(Aleph::authority_set_hash(3), Aleph::authority_set_hash(1));
+        assert_eq!(Aleph::session_authorities(0), None);
+    })
+}
+
+fn finalized_data(number: u64, hash: H256) -> InherentData {
+    let mut data = InherentData::new();
+    data.put_data(INHERENT_IDENTIFIER, &(number, hash)).unwrap();
+    data
+}
+
+#[test]
+fn notes_finalized_ancestors_only() {
+    new_test_ext(&[(1u64, 1u64), (2u64, 2u64)]).execute_with(|| {
+        System::set_block_number(5);
+        frame_system::BlockHash::<Test>::insert(2, H256::repeat_byte(2));
+        frame_system::BlockHash::<Test>::insert(3, H256::repeat_byte(3));
+        frame_system::BlockHash::<Test>::insert(5, H256::repeat_byte(5));
+
+        assert_noop!(
+            Aleph::note_finalized(Origin::none(), 3, H256::repeat_byte(7)),
+            Error::<Test>::UnknownFinalized
+        );
+        assert_noop!(
+            Aleph::note_finalized(Origin::none(), 5, H256::repeat_byte(5)),
+            Error::<Test>::UnknownFinalized
+        );
+        assert_ok!(Aleph::note_finalized(
+            Origin::none(),
+            3,
+            H256::repeat_byte(3)
+        ));
+        assert_eq!(
+            Aleph::author_reported_finalized(),
+            Some((3, H256::repeat_byte(3)))
+        );
+        assert_noop!(
+            Aleph::note_finalized(Origin::none(), 2, H256::repeat_byte(2)),
+            Error::<Test>::OutdatedFinalized
+        );
+        assert!(Aleph::note_finalized(Origin::root(), 3, H256::repeat_byte(3)).is_err());
+    })
+}
+
+#[test]
+fn creates_finalized_inherent_only_when_acceptable() {
+    new_test_ext(&[(1u64, 1u64), (2u64, 2u64)]).execute_with(|| {
+        System::set_block_number(5);
+        frame_system::BlockHash::<Test>::insert(3, H256::repeat_byte(3));
+
+        assert_eq!(
+            Aleph::create_inherent(&finalized_data(3, H256::repeat_byte(3))),
+            Some(Call::note_finalized {
+                number: 3,
+                hash: H256::repeat_byte(3)
+            })
+        );
+        assert_eq!(
+            Aleph::create_inherent(&finalized_data(3, H256::repeat_byte(4))),
+            None
+        );
+        assert_eq!(Aleph::create_inherent(&InherentData::new()), None);
+
+        assert_ok!(Aleph::note_finalized(
+            Origin::none(),
+            3,
+            H256::repeat_byte(3)
+        ));
+        assert_eq!(
+            Aleph::create_inherent(&finalized_data(3, H256::repeat_byte(3))),
+            None
+        );
+    })
+}
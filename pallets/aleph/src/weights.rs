@@ -0,0 +1,39 @@
+//! Weights for pallet_aleph.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_aleph.
+pub trait WeightInfo {
+    fn on_new_session(v: u32) -> Weight;
+}
+
+/// Weights for pallet_aleph, based on the storage accesses they perform.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    // Storage: Aleph Authorities (r:0 w:1)
+    // Storage: Session CurrentIndex (r:1 w:0)
+    // Storage: Aleph SessionAuthorities (r:0 w:2)
+    fn on_new_session(v: u32) -> Weight {
+        (9_870_000 as Weight)
+            .saturating_add((412_000 as Weight).saturating_mul(v as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn on_new_session(v: u32) -> Weight {
+        (9_870_000 as Weight)
+            .saturating_add((412_000 as Weight).saturating_mul(v as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+}
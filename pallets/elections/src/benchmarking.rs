@@ -0,0 +1,95 @@
+//! Benchmarks of the calls and session hooks of pallet_elections.
+
+use super::*;
+use crate::Pallet as Elections;
+use frame_benchmarking::{account, benchmarks};
+use frame_support::{
+    traits::{Currency, Get},
+    BoundedVec,
+};
+use frame_system::RawOrigin;
+use pallet_staking::{ActiveEra, ActiveEraInfo, ErasStakers, Exposure};
+use primitives::MAX_VALIDATORS;
+use sp_std::vec::Vec;
+
+const SEED: u32 = 0;
+
+fn accounts<T: Config>(count: u32) -> Vec<T::AccountId> {
+    (0..count).map(|i| account("validator", i, SEED)).collect()
+}
+
+// Starts the first era with `count` validators staking in it.
+fn setup_era<T: Config + pallet_staking::Config>(count: u32) -> Vec<T::AccountId> {
+    let validators = accounts::<T>(count);
+    ActiveEra::<T>::put(ActiveEraInfo {
+        index: 1,
+        start: None,
+    });
+    for validator in &validators {
+        let exposure = Exposure {
+            total: 1_000u32.into(),
+            own: 1_000u32.into(),
+            others: Vec::new(),
+        };
+        ErasStakers::<T>::insert(1, validator, exposure);
+    }
+    validators
+}
+
+benchmarks! {
+    where_clause {
+        where
+            T: pallet_session::Config + pallet_staking::Config,
+            <<T as pallet_staking::Config>::Currency as Currency<T::AccountId>>::Balance: Into<u128>,
+            <T as pallet_session::Config>::ValidatorId: From<T::AccountId>,
+            <T as pallet_session::Config>::ValidatorId: Into<T::AccountId>,
+    }
+
+    change_members {
+        let m in 1 .. MAX_VALIDATORS;
+        let members = accounts::<T>(m);
+    }: _(RawOrigin::Root, members.clone())
+    verify {
+        assert_eq!(Members::<T>::get(), members);
+    }
+
+    set_members_per_session {
+    }: _(RawOrigin::Root, 10)
+    verify {
+        assert_eq!(MembersPerSession::<T>::get(), 10);
+    }
+
+    new_session {
+        let v in 1 .. MAX_VALIDATORS;
+        setup_era::<T>(v);
+        MembersPerSession::<T>::put(v.min(T::SessionPeriod::get()));
+    }: {
+        let _ = Elections::<T>::plan_committee(1);
+    }
+
+    end_session {
+        let v in 1 .. MAX_VALIDATORS;
+        let validators = setup_era::<T>(v);
+        let committee_size = v.min(T::SessionPeriod::get());
+        MembersPerSession::<T>::put(committee_size);
+        let committee = validators[..committee_size as usize].to_vec();
+        for member in &committee {
+            SessionValidatorBlockCount::<T>::insert(member, 1);
+        }
+        let session_validators: Vec<<T as pallet_session::Config>::ValidatorId> =
+            committee.into_iter().map(|member| member.into()).collect();
+        pallet_session::Validators::<T>::put(session_validators);
+        let depth = T::PerformanceHistoryDepth::get();
+        SessionPerformance::<T>::insert(0, BoundedVec::default());
+        RecordedSessions::<T>::put((0, depth - 1));
+    }: {
+        Elections::<T>::settle_session(depth);
+    }
+    verify {
+        assert_eq!(SessionValidatorBlockCount::<T>::iter().count(), 0);
+        let recorded = SessionPerformance::<T>::get(depth).map(|records| records.len());
+        assert_eq!(recorded, Some(v as usize));
+        assert!(SessionPerformance::<T>::get(0).is_none());
+        assert_eq!(RecordedSessions::<T>::get(), Some((1, depth)));
+    }
+}
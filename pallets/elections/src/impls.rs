@@ -1,6 +1,13 @@
-use crate::{Config, ErasReserved, MembersPerSession, Pallet, SessionValidatorBlockCount};
+use crate::{
+    Config, ErasReserved, MembersPerSession, Pallet, SessionPerformance,
+    SessionValidatorBlockCount, WeightInfo,
+};
 use frame_election_provider_support::sp_arithmetic::Perquintill;
-use frame_support::{pallet_prelude::Get, traits::Currency};
+use frame_support::{
+    pallet_prelude::{DispatchClass, Get},
+    traits::Currency,
+};
+use primitives::{Balance, ValidatorPerformance, ValidatorSessionRecord};
 use sp_staking::{EraIndex, SessionIndex};
 use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
 
@@ -44,6 +51,31 @@ fn compute_validator_scaled_totals<V>(validator_totals: Vec<(V, u128)>) -> Vec<(
         .collect()
 }
 
+/// Splits `validators_payout` between the validators proportionally to their projected era points,
+/// i.e. the points `collected` so far increased by their adjusted points for each of the
+/// `sessions_left`.
+fn project_era_rewards<V>(
+    performances: &mut [ValidatorPerformance<V>],
+    collected: impl Fn(&V) -> u32,
+    sessions_left: u64,
+    validators_payout: Balance,
+) {
+    let projected_points = |performance: &ValidatorPerformance<V>| {
+        collected(&performance.validator) as u64
+            + performance.adjusted_points as u64 * sessions_left
+    };
+    let projected_total: u64 = performances.iter().map(projected_points).sum();
+    if projected_total == 0 {
+        return;
+    }
+
+    for performance in performances.iter_mut() {
+        performance.projected_era_reward =
+            Perquintill::from_rational(projected_points(performance), projected_total)
+                * validators_payout;
+    }
+}
+
 fn rotate<T: Clone + PartialEq>(
     current_era: EraIndex,
     current_session: SessionIndex,
@@ -151,24 +183,28 @@ impl<T> Pallet<T>
 
     // Choose a subset of all the validators for current era that contains all the
     // reserved nodes. Non reserved ones are chosen in consecutive batches for every session
-    fn rotate_committee() -> Option<Vec<T::AccountId>> {
+    // Returns the committee together with the number of validators it was chosen from.
+    fn rotate_committee() -> (Option<Vec<T::AccountId>>, u32) {
         let current_era = match pallet_staking::ActiveEra::<T>::get() {
             Some(ae) if ae.index > 0 => ae.index,
-            _ => return None,
+            _ => return (None, 0),
         };
         let all_validators: Vec<T::AccountId> =
             pallet_staking::ErasStakers::<T>::iter_key_prefix(current_era).collect();
+        let validator_count = all_validators.len() as u32;
         let reserved = ErasReserved::<T>::get();
         let n_validators = MembersPerSession::<T>::get() as usize;
         let current_session = pallet_session::Pallet::<T>::current_index();
 
-        rotate(
+        let committee = rotate(
             current_era,
             current_session,
             n_validators,
             all_validators,
             reserved,
-        )
+        );
+
+        (committee, validator_count)
     }
 
     fn populate_reserved_on_next_era_start(start_index: SessionIndex) {
@@ -186,13 +222,15 @@ impl<T> Pallet<T>
         }
     }
 
-    fn adjust_rewards_for_session() {
+    // Returns the number of validators that were rewarded.
+    fn adjust_rewards_for_session(session: SessionIndex) -> u32 {
         let active_era = match pallet_staking::ActiveEra::<T>::get() {
             Some(ae) if ae.index > 0 => ae.index,
-            _ => return,
+            _ => return 0,
         };
 
         let (committee, non_committee) = Self::get_committee_and_non_committee(active_era);
+        let validator_count = (committee.len() + non_committee.len()) as u32;
         let nr_of_sessions = T::SessionsPerEra::get();
         let blocks_per_session = Self::blocks_to_produce_per_session();
         let validator_totals = Self::compute_validator_scaled_totals(active_era);
@@ -200,9 +238,151 @@ impl<T> Pallet<T>
         let rewards =
             Self::reward_for_session_non_committee(non_committee, nr_of_sessions, blocks_per_session, &validator_totals)
                 .into_iter()
-                .chain(Self::reward_for_session_committee(committee, nr_of_sessions, blocks_per_session, &validator_totals).into_iter());
+                .map(|(validator, points)| (validator, points, false))
+                .chain(Self::reward_for_session_committee(committee, nr_of_sessions, blocks_per_session, &validator_totals)
+                    .into_iter()
+                    .map(|(validator, points)| (validator, points, true)));
+        let records: Vec<_> = rewards
+            .map(|(validator, points, in_committee)| ValidatorSessionRecord {
+                blocks_expected: match in_committee {
+                    true => blocks_per_session,
+                    false => 0,
+                },
+                blocks_produced: SessionValidatorBlockCount::<T>::get(&validator),
+                validator,
+                in_committee,
+                points,
+            })
+            .collect();
+
+        pallet_staking::Pallet::<T>::reward_by_ids(
+            records.iter().map(|record| (record.validator.clone(), record.points)),
+        );
+        Self::record_performance(session, records);
+
+        validator_count
+    }
+
+    /// Performance of the validators in `session`, the current one or a past one still kept in
+    /// `SessionPerformance`, with `validators_payout` being the reward for the whole era.
+    ///
+    /// In the current session the era reward is projected from the points collected so far, with
+    /// the remaining sessions of the era assumed to go like the current one. A past session is
+    /// projected as if the whole era went like it.
+    pub fn validators_performance(
+        session: SessionIndex,
+        validators_payout: Balance,
+    ) -> Option<Vec<ValidatorPerformance<T::AccountId>>> {
+        let current_session = pallet_session::Pallet::<T>::current_index();
+        if session == current_session {
+            return Some(Self::current_performance(current_session, validators_payout));
+        }
+        if session > current_session {
+            return None;
+        }
+
+        let mut performances: Vec<_> = SessionPerformance::<T>::get(session)?
+            .into_iter()
+            .map(|record| ValidatorPerformance {
+                validator: record.validator,
+                session,
+                in_committee: record.in_committee,
+                blocks_expected: record.blocks_expected,
+                blocks_produced: record.blocks_produced,
+                adjusted_points: record.points,
+                projected_era_reward: 0,
+            })
+            .collect();
+        project_era_rewards(&mut performances, |_| 0, 1, validators_payout);
+
+        Some(performances)
+    }
+
+    fn current_performance(
+        session: SessionIndex,
+        validators_payout: Balance,
+    ) -> Vec<ValidatorPerformance<T::AccountId>> {
+        let active_era = match pallet_staking::ActiveEra::<T>::get() {
+            Some(ae) if ae.index > 0 => ae.index,
+            _ => return Vec::new(),
+        };
+
+        let (committee, non_committee) = Self::get_committee_and_non_committee(active_era);
+        let nr_of_sessions = T::SessionsPerEra::get();
+        let blocks_per_session = Self::blocks_to_produce_per_session();
+        let validator_totals = Self::compute_validator_scaled_totals(active_era);
+        let era_start =
+            pallet_staking::ErasStartSessionIndex::<T>::get(active_era).unwrap_or(session);
+        // including the current one, whose points are not yet in the era points
+        let sessions_left = nr_of_sessions.saturating_sub(session.saturating_sub(era_start)) as u64;
+
+        let mut performances: Vec<_> = committee
+            .into_iter()
+            .map(|validator| (validator, true))
+            .chain(
+                non_committee
+                    .into_iter()
+                    .map(|validator| (validator, false)),
+            )
+            .map(|(validator, in_committee)| {
+                let total = BTreeMap::<_, _>::get(&validator_totals, &validator).unwrap_or(&0);
+                // validators outside of the committee get rewarded as if they produced all the blocks
+                let (blocks_expected, blocks_produced, blocks_rewarded) = match in_committee {
+                    true => {
+                        let blocks_created = SessionValidatorBlockCount::<T>::get(&validator);
+                        (blocks_per_session, blocks_created, blocks_created)
+                    }
+                    false => (0, 0, blocks_per_session),
+                };
+                let adjusted_points = calculate_adjusted_session_points(
+                    nr_of_sessions,
+                    blocks_per_session,
+                    blocks_rewarded,
+                    *total,
+                );
+                ValidatorPerformance {
+                    validator,
+                    session,
+                    in_committee,
+                    blocks_expected,
+                    blocks_produced,
+                    adjusted_points,
+                    projected_era_reward: 0,
+                }
+            })
+            .collect();
+
+        let era_points = pallet_staking::ErasRewardPoints::<T>::get(active_era);
+        project_era_rewards(
+            &mut performances,
+            |validator| era_points.individual.get(validator).copied().unwrap_or(0),
+            sessions_left,
+            validators_payout,
+        );
 
-        pallet_staking::Pallet::<T>::reward_by_ids(rewards);
+        performances
+    }
+
+    /// Chooses the committee for the session `new_index`, without calling the wrapped session
+    /// manager. Returns the committee and the number of validators of the active era.
+    pub(crate) fn plan_committee(new_index: SessionIndex) -> (Option<Vec<T::AccountId>>, u32) {
+        // new session is always called before the end_session of the previous session
+        // so we need to populate reserved set here not on start_session nor end_session
+        let (committee, validator_count) = Self::rotate_committee();
+        Self::populate_reserved_on_next_era_start(new_index);
+
+        (committee, validator_count)
+    }
+
+    /// Rewards the validators for the ending session and records their performance, without
+    /// calling the wrapped session manager. Returns the number of validators of the active era.
+    pub(crate) fn settle_session(end_index: SessionIndex) -> u32 {
+        let validator_count = Self::adjust_rewards_for_session(end_index);
+
+        // clear block count
+        SessionValidatorBlockCount::<T>::remove_all(None);
+
+        validator_count
     }
 }
 
@@ -229,10 +409,11 @@ impl<T> pallet_session::SessionManager<T::AccountId> for Pallet<T>
         <T as pallet_session::Config>::ValidatorId: Into<T::AccountId>, {
     fn new_session(new_index: SessionIndex) -> Option<Vec<T::AccountId>> {
         <T as Config>::SessionManager::new_session(new_index);
-        // new session is always called before the end_session of the previous session
-        // so we need to populate reserved set here not on start_session nor end_session
-        let committee = Self::rotate_committee();
-        Self::populate_reserved_on_next_era_start(new_index);
+        let (committee, validator_count) = Self::plan_committee(new_index);
+        frame_system::Pallet::<T>::register_extra_weight_unchecked(
+            <T as Config>::WeightInfo::new_session(validator_count),
+            DispatchClass::Mandatory,
+        );
 
         committee
     }
@@ -243,10 +424,11 @@ impl<T> pallet_session::SessionManager<T::AccountId> for Pallet<T>
 
     fn end_session(end_index: SessionIndex) {
         <T as Config>::SessionManager::end_session(end_index);
-        Self::adjust_rewards_for_session();
-
-        // clear block count
-        SessionValidatorBlockCount::<T>::remove_all(None);
+        let validator_count = Self::settle_session(end_index);
+        frame_system::Pallet::<T>::register_extra_weight_unchecked(
+            <T as Config>::WeightInfo::end_session(validator_count),
+            DispatchClass::Mandatory,
+        );
     }
 
     fn start_session(start_index: SessionIndex) {
@@ -257,10 +439,31 @@ impl<T> pallet_session::SessionManager<T::AccountId> for Pallet<T>
 #[cfg(test)]
 mod tests {
     use crate::impls::{
-        calculate_adjusted_session_points, compute_validator_scaled_totals, rotate, MAX_REWARD,
+        calculate_adjusted_session_points, compute_validator_scaled_totals, project_era_rewards,
+        rotate, MAX_REWARD,
     };
+    use primitives::ValidatorPerformance;
     use std::collections::VecDeque;
 
+    fn performance(validator: u64, adjusted_points: u32) -> ValidatorPerformance<u64> {
+        ValidatorPerformance {
+            validator,
+            session: 7,
+            in_committee: true,
+            blocks_expected: 30,
+            blocks_produced: 30,
+            adjusted_points,
+            projected_era_reward: 0,
+        }
+    }
+
+    fn projected_rewards(performances: &[ValidatorPerformance<u64>]) -> Vec<u128> {
+        performances
+            .iter()
+            .map(|performance| performance.projected_era_reward)
+            .collect()
+    }
+
     #[test]
     fn given_era_zero_when_rotating_committee_then_committee_is_empty() {
         assert_eq!(None, rotate(0, 0, 4, (0..10).collect(), vec![1, 2, 3, 4]));
@@ -386,4 +589,36 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn era_reward_is_split_proportionally_to_session_points_when_nothing_is_collected() {
+        let mut performances = vec![performance(1, 100), performance(2, 300)];
+
+        project_era_rewards(&mut performances, |_| 0, 1, 1_000);
+
+        assert_eq!(projected_rewards(&performances), vec![250, 750]);
+    }
+
+    #[test]
+    fn era_reward_accounts_for_points_collected_in_the_previous_sessions() {
+        let mut performances = vec![performance(1, 100), performance(2, 100)];
+        let collected = |validator: &u64| match validator {
+            1 => 400,
+            _ => 0,
+        };
+
+        project_era_rewards(&mut performances, collected, 2, 1_000);
+
+        // 400 + 2 * 100 against 2 * 100
+        assert_eq!(projected_rewards(&performances), vec![750, 250]);
+    }
+
+    #[test]
+    fn era_reward_is_not_projected_without_any_points() {
+        let mut performances = vec![performance(1, 0), performance(2, 0)];
+
+        project_era_rewards(&mut performances, |_| 0, 3, 1_000);
+
+        assert_eq!(projected_rewards(&performances), vec![0, 0]);
+    }
 }
@@ -4,14 +4,18 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 mod impls;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
 mod tests;
+pub mod weights;
 
 use frame_support::traits::StorageVersion;
 pub use pallet::*;
+pub use weights::WeightInfo;
 
 const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
 
@@ -23,10 +27,14 @@ pub mod pallet {
     use frame_election_provider_support::{
         ElectionDataProvider, ElectionProvider, Support, Supports,
     };
-    use frame_support::{pallet_prelude::*, traits::Get};
-    use frame_system::{ensure_root, pallet_prelude::OriginFor};
+    use frame_support::{log, pallet_prelude::*, traits::Get};
+    use frame_system::{
+        ensure_root,
+        pallet_prelude::{BlockNumberFor, OriginFor},
+    };
     use pallet_session::SessionManager;
-    use primitives::DEFAULT_MEMBERS_PER_SESSION;
+    use primitives::{ValidatorSessionRecord, DEFAULT_MEMBERS_PER_SESSION};
+    use sp_staking::SessionIndex;
     use sp_std::{collections::btree_map::BTreeMap, prelude::Vec};
 
     #[pallet::config]
@@ -39,6 +47,14 @@ pub mod pallet {
         #[pallet::constant]
         type SessionPeriod: Get<u32>;
         type SessionManager: SessionManager<<Self as frame_system::Config>::AccountId>;
+        /// For how many sessions the performance records are kept in `SessionPerformance`.
+        #[pallet::constant]
+        type PerformanceHistoryDepth: Get<SessionIndex>;
+        /// The most validators whose performance is recorded in a single session.
+        #[pallet::constant]
+        type MaxValidators: Get<u32>;
+        /// Weights of the calls and session hooks of this pallet.
+        type WeightInfo: WeightInfo;
     }
 
     #[pallet::event]
@@ -66,9 +82,45 @@ pub mod pallet {
     pub type SessionValidatorBlockCount<T: Config> =
         StorageMap<_, Twox64Concat, T::AccountId, BlockCount, ValueQuery>;
 
+    /// How the validators performed in each of the last `PerformanceHistoryDepth` sessions. A
+    /// session is recorded when it ends, starting with the first era.
+    #[pallet::storage]
+    #[pallet::getter(fn session_performance)]
+    pub type SessionPerformance<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        SessionIndex,
+        BoundedVec<ValidatorSessionRecord<T::AccountId>, T::MaxValidators>,
+        OptionQuery,
+    >;
+
+    /// The oldest and the newest session kept in `SessionPerformance`, if any.
+    #[pallet::storage]
+    pub type RecordedSessions<T> = StorageValue<_, (SessionIndex, SessionIndex), OptionQuery>;
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        /// Prunes the performance history at once if an upgrade has decreased its depth, instead
+        /// of leaving the excess records for the session hooks, which remove one record at a time.
+        fn on_runtime_upgrade() -> Weight {
+            let pruned = match RecordedSessions::<T>::get() {
+                Some((_, newest)) => Self::prune_performance(newest),
+                None => 0,
+            };
+            T::DbWeight::get().reads_writes(1, pruned as Weight)
+        }
+
+        fn integrity_test() {
+            assert!(
+                T::PerformanceHistoryDepth::get() > 0,
+                "PerformanceHistoryDepth must keep at least the last session"
+            );
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        #[pallet::weight((T::BlockWeights::get().max_block, DispatchClass::Operational))]
+        #[pallet::weight((T::WeightInfo::change_members(members.len() as u32), DispatchClass::Operational))]
         pub fn change_members(origin: OriginFor<T>, members: Vec<T::AccountId>) -> DispatchResult {
             ensure_root(origin)?;
             Members::<T>::put(members.clone());
@@ -77,7 +129,7 @@ pub mod pallet {
             Ok(())
         }
 
-        #[pallet::weight((T::BlockWeights::get().max_block, DispatchClass::Operational))]
+        #[pallet::weight((T::WeightInfo::set_members_per_session(), DispatchClass::Operational))]
         pub fn set_members_per_session(
             origin: OriginFor<T>,
             members_per_session: u32,
@@ -113,7 +165,54 @@ pub mod pallet {
         }
     }
 
-    impl<T: Config> Pallet<T> {}
+    impl<T: Config> Pallet<T> {
+        /// Records the performance of the validators in `session` and prunes the records that are
+        /// no longer within `PerformanceHistoryDepth`. Only the first `MaxValidators` records are
+        /// kept.
+        pub(crate) fn record_performance(
+            session: SessionIndex,
+            mut records: Vec<ValidatorSessionRecord<T::AccountId>>,
+        ) {
+            let max_validators = T::MaxValidators::get() as usize;
+            if records.len() > max_validators {
+                log::warn!(
+                    target: "pallet_elections",
+                    "Recording the performance of only {} out of {} validators in session {}",
+                    max_validators,
+                    records.len(),
+                    session
+                );
+                records.truncate(max_validators);
+            }
+            let records: BoundedVec<_, T::MaxValidators> = records.try_into().unwrap_or_default();
+            SessionPerformance::<T>::insert(session, records);
+            let oldest = RecordedSessions::<T>::get().map_or(session, |(oldest, _)| oldest);
+            RecordedSessions::<T>::put((oldest.min(session), session));
+            Self::prune_performance(session);
+        }
+
+        /// Removes all the records older than the last `PerformanceHistoryDepth` sessions ending
+        /// with `newest`. Returns the number of removed records.
+        fn prune_performance(newest: SessionIndex) -> u32 {
+            let (oldest, _) = match RecordedSessions::<T>::get() {
+                Some(recorded) => recorded,
+                None => return 0,
+            };
+            let cutoff = newest
+                .saturating_add(1)
+                .saturating_sub(T::PerformanceHistoryDepth::get());
+            if cutoff <= oldest {
+                return 0;
+            }
+
+            for expired in oldest..cutoff {
+                SessionPerformance::<T>::remove(expired);
+            }
+            RecordedSessions::<T>::put((cutoff, newest));
+
+            cutoff - oldest
+        }
+    }
 
     #[derive(Debug)]
     pub enum Error {
@@ -94,6 +94,8 @@ where
 
 parameter_types! {
     pub const SessionPeriod: u32 = 5;
+    pub static PerformanceHistoryDepth: u32 = 10;
+    pub const MaxValidators: u32 = 3;
 }
 
 impl Config for Test {
@@ -101,6 +103,9 @@ impl Config for Test {
     type DataProvider = StakingMock;
     type SessionPeriod = SessionPeriod;
     type SessionManager = ();
+    type PerformanceHistoryDepth = PerformanceHistoryDepth;
+    type MaxValidators = MaxValidators;
+    type WeightInfo = ();
 }
 
 type AccountIdBoundedVec = BoundedVec<AccountId, ()>;
@@ -1,8 +1,30 @@
 #![cfg(test)]
 
 use frame_election_provider_support::{ElectionProvider, Support};
+use frame_support::traits::Hooks;
+use primitives::ValidatorSessionRecord;
+use sp_staking::SessionIndex;
 
-use crate::mock::*;
+use crate::{mock::*, RecordedSessions, SessionPerformance};
+
+fn record(session: SessionIndex) {
+    Elections::record_performance(
+        session,
+        vec![ValidatorSessionRecord {
+            validator: 1,
+            in_committee: true,
+            blocks_expected: 10,
+            blocks_produced: session,
+            points: 100,
+        }],
+    );
+}
+
+fn recorded_sessions() -> Vec<SessionIndex> {
+    let mut sessions: Vec<_> = SessionPerformance::<Test>::iter_keys().collect();
+    sessions.sort_unstable();
+    sessions
+}
 
 #[test]
 fn test_elect() {
@@ -18,3 +40,78 @@ fn test_elect() {
         assert_eq!(elected.unwrap(), &[(1, supp.clone()), (2, supp)]);
     });
 }
+
+#[test]
+fn performance_of_the_ended_session_is_recorded() {
+    new_test_ext(vec![1, 2]).execute_with(|| {
+        record(5);
+
+        let records = Elections::session_performance(5).expect("Session should be recorded");
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].blocks_expected, 10);
+        assert_eq!(records[0].blocks_produced, 5);
+        assert_eq!(RecordedSessions::<Test>::get(), Some((5, 5)));
+    });
+}
+
+#[test]
+fn performance_is_recorded_for_at_most_max_validators() {
+    new_test_ext(vec![1, 2]).execute_with(|| {
+        let records = (0..MaxValidators::get() as u64 + 2)
+            .map(|validator| ValidatorSessionRecord {
+                validator,
+                in_committee: false,
+                blocks_expected: 0,
+                blocks_produced: 0,
+                points: 100,
+            })
+            .collect();
+        Elections::record_performance(5, records);
+
+        let recorded: Vec<_> = Elections::session_performance(5)
+            .expect("Session should be recorded")
+            .into_iter()
+            .map(|record| record.validator)
+            .collect();
+        assert_eq!(recorded, vec![0, 1, 2]);
+    });
+}
+
+#[test]
+fn performance_history_keeps_exactly_the_last_depth_sessions() {
+    new_test_ext(vec![1, 2]).execute_with(|| {
+        let depth = PerformanceHistoryDepth::get();
+        (0..depth).for_each(record);
+        assert_eq!(recorded_sessions(), (0..depth).collect::<Vec<_>>());
+
+        record(depth);
+
+        assert_eq!(recorded_sessions(), (1..=depth).collect::<Vec<_>>());
+        assert_eq!(RecordedSessions::<Test>::get(), Some((1, depth)));
+    });
+}
+
+#[test]
+fn performance_history_prunes_all_the_sessions_below_the_cutoff() {
+    new_test_ext(vec![1, 2]).execute_with(|| {
+        (0..3).for_each(record);
+
+        record(20);
+
+        assert_eq!(recorded_sessions(), vec![20]);
+        assert_eq!(RecordedSessions::<Test>::get(), Some((11, 20)));
+    });
+}
+
+#[test]
+fn upgrade_decreasing_history_depth_prunes_the_excess_records() {
+    new_test_ext(vec![1, 2]).execute_with(|| {
+        (0..10).for_each(record);
+
+        PerformanceHistoryDepth::set(3);
+        Elections::on_runtime_upgrade();
+
+        assert_eq!(recorded_sessions(), vec![7, 8, 9]);
+        assert_eq!(RecordedSessions::<Test>::get(), Some((7, 9)));
+    });
+}
@@ -0,0 +1,90 @@
+//! Weights for pallet_elections.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_elections.
+pub trait WeightInfo {
+    fn change_members(m: u32) -> Weight;
+    fn set_members_per_session() -> Weight;
+    fn new_session(v: u32) -> Weight;
+    fn end_session(v: u32) -> Weight;
+}
+
+/// Weights for pallet_elections, based on the storage accesses they perform.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    // Storage: Elections Members (r:0 w:1)
+    fn change_members(m: u32) -> Weight {
+        (7_120_000 as Weight)
+            .saturating_add((22_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Storage: Elections MembersPerSession (r:0 w:1)
+    fn set_members_per_session() -> Weight {
+        (4_350_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Storage: Staking ActiveEra (r:1 w:0)
+    // Storage: Staking ErasStakers (r:1 w:0)
+    // Storage: Elections ErasReserved (r:1 w:1)
+    // Storage: Elections MembersPerSession (r:1 w:0)
+    // Storage: Session CurrentIndex (r:1 w:0)
+    // Storage: Staking ErasStartSessionIndex (r:1 w:0)
+    // Storage: Staking Invulnerables (r:1 w:0)
+    fn new_session(v: u32) -> Weight {
+        (21_480_000 as Weight)
+            .saturating_add((1_310_000 as Weight).saturating_mul(v as Weight))
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(v as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Storage: Staking ActiveEra (r:1 w:0)
+    // Storage: Session Validators (r:1 w:0)
+    // Storage: Staking ErasStakers (r:1 w:0)
+    // Storage: Elections MembersPerSession (r:1 w:0)
+    // Storage: Elections SessionValidatorBlockCount (r:1 w:1)
+    // Storage: Staking ErasRewardPoints (r:1 w:1)
+    // Storage: Elections SessionPerformance (r:0 w:2)
+    // Storage: Elections RecordedSessions (r:1 w:1)
+    fn end_session(v: u32) -> Weight {
+        (34_210_000 as Weight)
+            .saturating_add((5_140_000 as Weight).saturating_mul(v as Weight))
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(v as Weight)))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(v as Weight)))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn change_members(m: u32) -> Weight {
+        (7_120_000 as Weight)
+            .saturating_add((22_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_members_per_session() -> Weight {
+        (4_350_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn new_session(v: u32) -> Weight {
+        (21_480_000 as Weight)
+            .saturating_add((1_310_000 as Weight).saturating_mul(v as Weight))
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(v as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn end_session(v: u32) -> Weight {
+        (34_210_000 as Weight)
+            .saturating_add((5_140_000 as Weight).saturating_mul(v as Weight))
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(v as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(v as Weight)))
+    }
+}
@@ -0,0 +1,21 @@
+//! Benchmarks of the calls of pallet_inflation.
+
+use super::*;
+use frame_benchmarking::benchmarks;
+use frame_support::traits::EnsureOrigin;
+use sp_runtime::Perbill;
+
+benchmarks! {
+    set_inflation_model {
+        let model = InflationModel {
+            min_inflation: Perbill::from_percent(2),
+            max_inflation: Perbill::from_percent(10),
+            ideal_staking_rate: Perbill::from_percent(60),
+            treasury_share: Perbill::from_percent(20),
+        };
+        let origin = T::UpdateOrigin::successful_origin();
+    }: _<T::Origin>(origin, model)
+    verify {
+        assert_eq!(Model::<T>::get(), model);
+    }
+}
@@ -0,0 +1,142 @@
+//! This pallet keeps the inflation model the era payouts of staking are computed with.
+//!
+//! The model is set through governance, see `primitives::staking::InflationModel` for the shape of
+//! the inflation curve. Chains that paid out the fixed yearly inflation before this pallet existed
+//! migrate to a flat model that pays out the same amount at the total issuance of the upgrade.
+//!
+//! Note that this migration changes the monetary policy of such chains: before, the yearly payout
+//! was the fixed `LEGACY_YEARLY_INFLATION`, afterwards it is the percentage of the total issuance
+//! that amount was at the upgrade, so it grows together with the issuance. Governance has to set
+//! a new model right after the upgrade if that is not intended.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+mod migrations;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use frame_support::traits::{Currency, StorageVersion};
+pub use pallet::*;
+use primitives::{staking::InflationModel, Balance};
+pub use weights::WeightInfo;
+
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::{BlockNumberFor, OriginFor};
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+        /// The currency whose total issuance the legacy inflation is migrated against.
+        type Currency: Currency<Self::AccountId, Balance = Balance>;
+        /// The origin allowed to change the inflation model.
+        type UpdateOrigin: EnsureOrigin<Self::Origin>;
+        /// Weights of the calls of this pallet.
+        type WeightInfo: WeightInfo;
+    }
+
+    #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
+    pub struct Pallet<T>(_);
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain = <Pallet<T> as GetStorageVersion>::on_chain_storage_version();
+            T::DbWeight::get().reads(1)
+                + match on_chain {
+                    _ if on_chain == StorageVersion::new(0) => {
+                        migrations::v0_to_v1::migrate::<T, Self>()
+                    }
+                    _ => 0,
+                }
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<(), &'static str> {
+            let on_chain = <Pallet<T> as GetStorageVersion>::on_chain_storage_version();
+            if on_chain == StorageVersion::new(0) {
+                migrations::v0_to_v1::pre_upgrade::<T>()?;
+            }
+            Ok(())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade() -> Result<(), &'static str> {
+            migrations::v0_to_v1::post_upgrade::<T, Self>()
+        }
+    }
+
+    /// The legacy inflation at the current total issuance, used only until the model is stored.
+    #[pallet::type_value]
+    pub fn LegacyModel<T: Config>() -> InflationModel {
+        InflationModel::legacy(T::Currency::total_issuance())
+    }
+
+    /// The inflation model of the current and the upcoming eras.
+    #[pallet::storage]
+    #[pallet::getter(fn inflation_model)]
+    pub type Model<T: Config> = StorageValue<_, InflationModel, ValueQuery, LegacyModel<T>>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        InflationModelChanged(InflationModel),
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The inflation bounds are not ordered or the ideal staking rate is zero.
+        InvalidInflationModel,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Sets the model the payouts of the following eras are computed with.
+        #[pallet::weight(T::WeightInfo::set_inflation_model())]
+        pub fn set_inflation_model(origin: OriginFor<T>, model: InflationModel) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            ensure!(model.is_valid(), Error::<T>::InvalidInflationModel);
+            Model::<T>::put(model);
+            Self::deposit_event(Event::InflationModelChanged(model));
+
+            Ok(())
+        }
+    }
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig {
+        /// The model of the first eras. If not given, the chain starts with the legacy inflation
+        /// at its genesis issuance.
+        pub inflation_model: Option<InflationModel>,
+    }
+
+    #[cfg(feature = "std")]
+    impl Default for GenesisConfig {
+        fn default() -> Self {
+            Self {
+                inflation_model: None,
+            }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> GenesisBuild<T> for GenesisConfig {
+        fn build(&self) {
+            let model = self
+                .inflation_model
+                .unwrap_or_else(|| InflationModel::legacy(T::Currency::total_issuance()));
+            assert!(model.is_valid(), "The genesis inflation model is invalid!");
+            Model::<T>::put(model);
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod v0_to_v1;
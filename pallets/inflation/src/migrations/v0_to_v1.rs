@@ -0,0 +1,50 @@
+use crate::{Config, Model};
+#[cfg(feature = "try-runtime")]
+use frame_support::traits::GetStorageVersion;
+use frame_support::{
+    log,
+    traits::{Currency, Get, PalletInfoAccess, StorageVersion},
+    weights::Weight,
+};
+use primitives::staking::InflationModel;
+
+// The payouts were computed from `LEGACY_YEARLY_INFLATION` before the model was kept in storage.
+// The model is relative to the total issuance, so from now on the payouts follow the issuance
+// instead of staying fixed, see the pallet documentation.
+pub fn migrate<T: Config, P: PalletInfoAccess>() -> Weight {
+    log::info!(target: "pallet_inflation", "Running migration from STORAGE_VERSION 0 to 1");
+
+    let model = InflationModel::legacy(T::Currency::total_issuance());
+    log::warn!(
+        target: "pallet_inflation",
+        "Replacing the fixed legacy inflation with {:?} of the total issuance a year, the payouts \
+         will change together with the issuance",
+        model.max_inflation
+    );
+    log::info!(target: "pallet_inflation", "Keeping the legacy inflation as {:?}", model);
+    Model::<T>::put(model);
+
+    // store new version
+    StorageVersion::new(1).put::<P>();
+
+    T::DbWeight::get().reads(1) + T::DbWeight::get().writes(2)
+}
+
+#[cfg(feature = "try-runtime")]
+pub fn pre_upgrade<T: Config>() -> Result<(), &'static str> {
+    if Model::<T>::exists() {
+        return Err("Migration from STORAGE_VERSION 0 to 1 requires the model to be absent");
+    }
+    Ok(())
+}
+
+#[cfg(feature = "try-runtime")]
+pub fn post_upgrade<T: Config, P: GetStorageVersion>() -> Result<(), &'static str> {
+    if P::on_chain_storage_version() != StorageVersion::new(1) {
+        return Err("Migration from STORAGE_VERSION 0 to 1 did not bump the version");
+    }
+    if !Model::<T>::get().is_valid() {
+        return Err("Migration from STORAGE_VERSION 0 to 1 left an invalid model");
+    }
+    Ok(())
+}
@@ -0,0 +1,101 @@
+#![cfg(test)]
+
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{GetStorageVersion, Hooks, StorageVersion},
+};
+use primitives::staking::{InflationModel, LEGACY_YEARLY_INFLATION};
+use sp_runtime::{DispatchError, Perbill};
+
+use crate::{mock::*, Error, Event as InflationEvent, Model};
+
+fn model() -> InflationModel {
+    InflationModel {
+        min_inflation: Perbill::from_percent(2),
+        max_inflation: Perbill::from_percent(10),
+        ideal_staking_rate: Perbill::from_percent(60),
+        treasury_share: Perbill::from_percent(20),
+    }
+}
+
+#[test]
+fn genesis_keeps_the_legacy_inflation_at_the_genesis_issuance() {
+    let total_issuance = 3 * LEGACY_YEARLY_INFLATION;
+    new_test_ext(vec![(1, total_issuance)]).execute_with(|| {
+        assert_eq!(
+            Inflation::inflation_model(),
+            InflationModel::legacy(total_issuance)
+        );
+        assert_eq!(
+            Inflation::inflation_model().max_inflation,
+            Perbill::from_rational(1u32, 3u32)
+        );
+        assert_eq!(
+            Inflation::on_chain_storage_version(),
+            StorageVersion::new(1)
+        );
+    });
+}
+
+#[test]
+fn genesis_sets_the_configured_model() {
+    new_test_ext_with_model(vec![(1, LEGACY_YEARLY_INFLATION)], Some(model())).execute_with(|| {
+        assert_eq!(Inflation::inflation_model(), model());
+    });
+}
+
+#[test]
+fn root_sets_the_model() {
+    new_test_ext(vec![]).execute_with(|| {
+        assert_ok!(Inflation::set_inflation_model(Origin::root(), model()));
+        assert_eq!(Inflation::inflation_model(), model());
+        System::assert_last_event(InflationEvent::<Test>::InflationModelChanged(model()).into());
+
+        assert_noop!(
+            Inflation::set_inflation_model(Origin::signed(1), model()),
+            DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn invalid_models_are_rejected() {
+    new_test_ext(vec![]).execute_with(|| {
+        let inverted = InflationModel {
+            min_inflation: Perbill::from_percent(12),
+            ..model()
+        };
+        assert_noop!(
+            Inflation::set_inflation_model(Origin::root(), inverted),
+            Error::<Test>::InvalidInflationModel
+        );
+        let no_ideal_rate = InflationModel {
+            ideal_staking_rate: Perbill::zero(),
+            ..model()
+        };
+        assert_noop!(
+            Inflation::set_inflation_model(Origin::root(), no_ideal_rate),
+            Error::<Test>::InvalidInflationModel
+        );
+    });
+}
+
+#[test]
+fn migration_keeps_paying_out_the_legacy_inflation() {
+    let total_issuance = 4 * LEGACY_YEARLY_INFLATION;
+    new_test_ext(vec![(1, total_issuance)]).execute_with(|| {
+        StorageVersion::new(0).put::<Inflation>();
+        Model::<Test>::kill();
+
+        Inflation::on_runtime_upgrade();
+
+        assert_eq!(
+            Inflation::on_chain_storage_version(),
+            StorageVersion::new(1)
+        );
+        let model = Inflation::inflation_model();
+        assert_eq!(model, InflationModel::legacy(total_issuance));
+        assert_eq!(model.max_inflation, Perbill::from_percent(25));
+        assert_eq!(model.min_inflation, Perbill::from_percent(25));
+    });
+}
@@ -0,0 +1,31 @@
+//! Weights for pallet_inflation.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_inflation.
+pub trait WeightInfo {
+    fn set_inflation_model() -> Weight;
+}
+
+/// Weights for pallet_inflation based on the storage accesses they perform.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    // Storage: Inflation Model (r:0 w:1)
+    fn set_inflation_model() -> Weight {
+        (6_210_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn set_inflation_model() -> Weight {
+        (6_210_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+}
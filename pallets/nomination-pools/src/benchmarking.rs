@@ -0,0 +1,111 @@
+//! Benchmarks of the calls of pallet_nomination_pools.
+
+use super::*;
+use crate::Pallet as NominationPools;
+use frame_benchmarking::{account, benchmarks};
+use frame_support::traits::Get;
+use frame_system::RawOrigin;
+
+const SEED: u32 = 0;
+
+fn funded_account<T: Config>(name: &'static str) -> T::AccountId {
+    let account = account(name, 0, SEED);
+    let balance = NominationPools::<T>::min_create_bond().saturating_mul(10u32.into());
+    T::Currency::make_free_balance_be(&account, balance);
+    account
+}
+
+// Creates a nominating pool with rewards to pay out to its members.
+fn create_pool<T: Config>() -> (T::AccountId, PoolId) {
+    let depositor = funded_account::<T>("depositor");
+    let amount = NominationPools::<T>::min_create_bond().saturating_mul(2u32.into());
+    NominationPools::<T>::create(RawOrigin::Signed(depositor.clone()).into(), amount)
+        .expect("The pool should be created");
+    let pool_id = LastPoolId::<T>::get();
+    NominationPools::<T>::nominate(
+        RawOrigin::Signed(depositor.clone()).into(),
+        pool_id,
+        account("validator", 0, SEED),
+    )
+    .expect("The pool should nominate");
+    pay_rewards::<T>(pool_id);
+    (depositor, pool_id)
+}
+
+fn pay_rewards<T: Config>(pool_id: PoolId) {
+    let reward_account = NominationPools::<T>::reward_account(pool_id);
+    let balance = T::Currency::free_balance(&reward_account)
+        .saturating_add(NominationPools::<T>::min_create_bond());
+    T::Currency::make_free_balance_be(&reward_account, balance);
+}
+
+fn join<T: Config>(pool_id: PoolId) -> T::AccountId {
+    let member = funded_account::<T>("member");
+    NominationPools::<T>::join(
+        RawOrigin::Signed(member.clone()).into(),
+        T::MinJoinBond::get(),
+        pool_id,
+    )
+    .expect("The member should join");
+    pay_rewards::<T>(pool_id);
+    member
+}
+
+benchmarks! {
+    create {
+        let depositor = funded_account::<T>("depositor");
+        let amount = NominationPools::<T>::min_create_bond();
+    }: _(RawOrigin::Signed(depositor.clone()), amount)
+    verify {
+        assert!(PoolMembers::<T>::contains_key(&depositor));
+    }
+
+    nominate {
+        let (depositor, pool_id) = create_pool::<T>();
+        let validator: T::AccountId = account("validator", 1, SEED);
+    }: _(RawOrigin::Signed(depositor), pool_id, validator)
+
+    join {
+        let (_, pool_id) = create_pool::<T>();
+        let member = funded_account::<T>("member");
+        let amount = T::MinJoinBond::get();
+    }: _(RawOrigin::Signed(member.clone()), amount, pool_id)
+    verify {
+        assert!(PoolMembers::<T>::contains_key(&member));
+    }
+
+    bond_extra {
+        let (_, pool_id) = create_pool::<T>();
+        let member = join::<T>(pool_id);
+        let extra = T::MinJoinBond::get();
+    }: _(RawOrigin::Signed(member), extra)
+
+    claim_payout {
+        let (_, pool_id) = create_pool::<T>();
+        let member = join::<T>(pool_id);
+    }: _(RawOrigin::Signed(member.clone()))
+    verify {
+        assert!(NominationPools::<T>::pending_rewards(&member).unwrap().is_zero());
+    }
+
+    // The depositor leaving chills the pool.
+    unbond {
+        let (depositor, _) = create_pool::<T>();
+        let amount = PoolMembers::<T>::get(&depositor).unwrap().bonded;
+    }: _(RawOrigin::Signed(depositor.clone()), amount)
+    verify {
+        assert!(PoolMembers::<T>::get(&depositor).unwrap().bonded.is_zero());
+    }
+
+    // The last member withdrawing destroys the pool.
+    withdraw_unbonded {
+        let (depositor, pool_id) = create_pool::<T>();
+        let amount = PoolMembers::<T>::get(&depositor).unwrap().bonded;
+        NominationPools::<T>::unbond(RawOrigin::Signed(depositor.clone()).into(), amount)
+            .expect("The depositor should unbond");
+        T::Staking::set_current_era(T::Staking::unbonding_era());
+    }: _(RawOrigin::Signed(depositor))
+    verify {
+        assert!(!BondedPools::<T>::contains_key(pool_id));
+    }
+}
@@ -0,0 +1,714 @@
+//! Nomination pools let accounts holding less than the minimal nominator bond take part in staking.
+//!
+//! A pool has a bonded account, which bonds the funds of all its members and nominates a single
+//! validator, so from the staking perspective a pool is just one nominator with one target. Era
+//! rewards of the pool are paid out to a separate reward account and split between the members
+//! proportionally to their bonds.
+//!
+//! The account creating a pool, the depositor, bonds at least the minimal nominator bond and
+//! chooses the nominated validator. It can leave only as the last member, which destroys the pool.
+//!
+//! Aleph Zero does not slash stakers, so the bond of a member is never decreased by the pool and
+//! its unbonding funds are withdrawn in full.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use codec::{Decode, Encode};
+use frame_support::{
+    dispatch::DispatchResult,
+    traits::{Currency, ExistenceRequirement, StorageVersion},
+    PalletId,
+};
+use scale_info::TypeInfo;
+use sp_runtime::{
+    traits::{AccountIdConversion, Saturating, UniqueSaturatedFrom, UniqueSaturatedInto, Zero},
+    FixedPointNumber, FixedU128, RuntimeDebug,
+};
+use sp_staking::EraIndex;
+use sp_std::vec::Vec;
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
+pub type PoolId = u32;
+
+pub type BalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// The staking operations a pool performs with its bonded account.
+///
+/// The bonded account is both the stash and the controller, rewards are paid to `payee`.
+pub trait StakingInterface<AccountId, Balance> {
+    /// The minimal bond of a nominator.
+    fn minimum_bond() -> Balance;
+    /// The current staking era.
+    fn current_era() -> EraIndex;
+    /// The era in which the funds unbonded now can be withdrawn.
+    fn unbonding_era() -> EraIndex;
+    fn bond(stash: AccountId, value: Balance, payee: AccountId) -> DispatchResult;
+    fn bond_extra(stash: AccountId, extra: Balance) -> DispatchResult;
+    fn unbond(stash: AccountId, value: Balance) -> DispatchResult;
+    /// Unlocks the funds of all the unbonding chunks whose era has passed.
+    fn withdraw_unbonded(stash: AccountId) -> DispatchResult;
+    fn nominate(stash: AccountId, target: AccountId) -> DispatchResult;
+    fn chill(stash: AccountId) -> DispatchResult;
+    /// Moves the staking to the given era, so that the unbonded funds can be withdrawn.
+    #[cfg(feature = "runtime-benchmarks")]
+    fn set_current_era(era: EraIndex);
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum PoolState {
+    /// Anyone can join the pool.
+    Open,
+    /// The depositor has left, the pool is removed once its unbonding funds are withdrawn.
+    Destroying,
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct BondedPool<AccountId, Balance> {
+    pub depositor: AccountId,
+    pub state: PoolState,
+    /// The number of members, including the ones that are only withdrawing their funds.
+    pub members: u32,
+    /// The sum of bonds of all the members.
+    pub bonded: Balance,
+}
+
+/// The bookkeeping of the rewards paid out to the reward account of a pool.
+///
+/// The reward counter is the total reward a unit of bond has earned since the pool was created. A
+/// member is owed its bond times the increase of the counter since the member last claimed.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct RewardPool<Balance> {
+    pub last_recorded_reward_counter: FixedU128,
+    /// All the rewards paid out to the pool until the counter was last recorded.
+    pub last_recorded_total_payouts: Balance,
+    pub total_rewards_claimed: Balance,
+}
+
+impl<Balance: Zero> Default for RewardPool<Balance> {
+    fn default() -> Self {
+        RewardPool {
+            last_recorded_reward_counter: FixedU128::zero(),
+            last_recorded_total_payouts: Zero::zero(),
+            total_rewards_claimed: Zero::zero(),
+        }
+    }
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct UnbondingChunk<Balance> {
+    /// The era in which the funds can be withdrawn.
+    pub era: EraIndex,
+    pub value: Balance,
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct PoolMember<Balance> {
+    pub pool_id: PoolId,
+    pub bonded: Balance,
+    pub last_recorded_reward_counter: FixedU128,
+    pub unbonding: Vec<UnbondingChunk<Balance>>,
+}
+
+#[derive(Encode)]
+enum AccountType {
+    Bonded,
+    Reward,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+    use frame_support::{pallet_prelude::*, traits::Get, transactional};
+    use frame_system::{ensure_signed, pallet_prelude::OriginFor};
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+        /// The currency used for staking.
+        type Currency: Currency<Self::AccountId>;
+        type Staking: StakingInterface<Self::AccountId, BalanceOf<Self>>;
+        /// Used to derive the bonded and reward accounts of the pools.
+        #[pallet::constant]
+        type PalletId: Get<PalletId>;
+        /// The minimal bond of a pool member.
+        #[pallet::constant]
+        type MinJoinBond: Get<BalanceOf<Self>>;
+        #[pallet::constant]
+        type MaxMembersPerPool: Get<u32>;
+        /// The maximal number of eras a member can have funds unbonding in at once.
+        #[pallet::constant]
+        type MaxUnbonding: Get<u32>;
+        type WeightInfo: WeightInfo;
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A pool has been created by the depositor.
+        Created {
+            depositor: T::AccountId,
+            pool_id: PoolId,
+        },
+        /// A member has bonded funds in the pool.
+        Bonded {
+            member: T::AccountId,
+            pool_id: PoolId,
+            bonded: BalanceOf<T>,
+        },
+        /// The pool nominates the validator.
+        Nominated {
+            pool_id: PoolId,
+            validator: T::AccountId,
+        },
+        /// A member has been paid out its share of the pool rewards.
+        PaidOut {
+            member: T::AccountId,
+            pool_id: PoolId,
+            payout: BalanceOf<T>,
+        },
+        /// A member has started unbonding funds, withdrawable in the era.
+        Unbonded {
+            member: T::AccountId,
+            pool_id: PoolId,
+            value: BalanceOf<T>,
+            era: EraIndex,
+        },
+        /// A member has withdrawn its unbonded funds.
+        Withdrawn {
+            member: T::AccountId,
+            pool_id: PoolId,
+            value: BalanceOf<T>,
+        },
+        /// A member with no bonded or unbonding funds has left the pool.
+        MemberRemoved {
+            member: T::AccountId,
+            pool_id: PoolId,
+        },
+        /// The last member has left the pool.
+        Destroyed { pool_id: PoolId },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The pool does not exist.
+        PoolNotFound,
+        /// The account is already a member of a pool.
+        AccountBelongsToPool,
+        /// The account is not a member of any pool.
+        NotAMember,
+        /// The pool does not accept new bonds.
+        PoolNotOpen,
+        /// The pool has the maximal number of members.
+        MaxMembers,
+        /// The bond of a member would be non-zero and below the minimal bond.
+        MinimumBondNotMet,
+        /// Only the depositor of the pool can do this.
+        NotDepositor,
+        /// The depositor can leave only as the last member of the pool.
+        DepositorCannotLeave,
+        /// The member has less funds bonded than it wants to unbond.
+        NotEnoughBonded,
+        /// The member has funds unbonding in too many eras.
+        MaxUnbondingLimit,
+        /// None of the unbonding funds of the member can be withdrawn yet.
+        CannotWithdrawAny,
+    }
+
+    #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
+    #[pallet::without_storage_info]
+    pub struct Pallet<T>(_);
+
+    #[pallet::storage]
+    pub type LastPoolId<T> = StorageValue<_, PoolId, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn bonded_pools)]
+    pub type BondedPools<T: Config> =
+        StorageMap<_, Twox64Concat, PoolId, BondedPool<T::AccountId, BalanceOf<T>>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn reward_pools)]
+    pub type RewardPools<T: Config> = StorageMap<_, Twox64Concat, PoolId, RewardPool<BalanceOf<T>>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn pool_members)]
+    pub type PoolMembers<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, PoolMember<BalanceOf<T>>>;
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Creates a new pool with the caller as its depositor and first member, bonding `amount`.
+        ///
+        /// The caller additionally transfers the existential deposit to the reward account of the
+        /// pool.
+        #[pallet::weight(T::WeightInfo::create())]
+        #[transactional]
+        pub fn create(
+            origin: OriginFor<T>,
+            #[pallet::compact] amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                !PoolMembers::<T>::contains_key(&who),
+                Error::<T>::AccountBelongsToPool
+            );
+            ensure!(
+                amount >= Self::min_create_bond(),
+                Error::<T>::MinimumBondNotMet
+            );
+
+            let pool_id = LastPoolId::<T>::get().saturating_add(1);
+            let bonded_account = Self::bonded_account(pool_id);
+            let reward_account = Self::reward_account(pool_id);
+            T::Currency::transfer(
+                &who,
+                &reward_account,
+                T::Currency::minimum_balance(),
+                ExistenceRequirement::KeepAlive,
+            )?;
+            T::Currency::transfer(
+                &who,
+                &bonded_account,
+                amount,
+                ExistenceRequirement::KeepAlive,
+            )?;
+            T::Staking::bond(bonded_account, amount, reward_account)?;
+
+            LastPoolId::<T>::put(pool_id);
+            BondedPools::<T>::insert(
+                pool_id,
+                BondedPool {
+                    depositor: who.clone(),
+                    state: PoolState::Open,
+                    members: 1,
+                    bonded: amount,
+                },
+            );
+            RewardPools::<T>::insert(pool_id, RewardPool::default());
+            PoolMembers::<T>::insert(
+                &who,
+                PoolMember {
+                    pool_id,
+                    bonded: amount,
+                    last_recorded_reward_counter: FixedU128::zero(),
+                    unbonding: Vec::new(),
+                },
+            );
+
+            Self::deposit_event(Event::Created {
+                depositor: who.clone(),
+                pool_id,
+            });
+            Self::deposit_event(Event::Bonded {
+                member: who,
+                pool_id,
+                bonded: amount,
+            });
+            Ok(())
+        }
+
+        /// Makes the pool nominate `validator`. Can be called only by the depositor of the pool.
+        #[pallet::weight(T::WeightInfo::nominate())]
+        pub fn nominate(
+            origin: OriginFor<T>,
+            pool_id: PoolId,
+            validator: T::AccountId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let pool = BondedPools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+            ensure!(pool.depositor == who, Error::<T>::NotDepositor);
+            ensure!(pool.state == PoolState::Open, Error::<T>::PoolNotOpen);
+
+            T::Staking::nominate(Self::bonded_account(pool_id), validator.clone())?;
+
+            Self::deposit_event(Event::Nominated { pool_id, validator });
+            Ok(())
+        }
+
+        /// Joins the pool bonding `amount`.
+        #[pallet::weight(T::WeightInfo::join())]
+        #[transactional]
+        pub fn join(
+            origin: OriginFor<T>,
+            #[pallet::compact] amount: BalanceOf<T>,
+            pool_id: PoolId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                !PoolMembers::<T>::contains_key(&who),
+                Error::<T>::AccountBelongsToPool
+            );
+            ensure!(
+                amount >= T::MinJoinBond::get(),
+                Error::<T>::MinimumBondNotMet
+            );
+            let mut pool = BondedPools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+            ensure!(pool.state == PoolState::Open, Error::<T>::PoolNotOpen);
+            ensure!(
+                pool.members < T::MaxMembersPerPool::get(),
+                Error::<T>::MaxMembers
+            );
+
+            // The rewards paid out so far belong to the current members.
+            let reward_counter = Self::record_rewards(pool_id, &pool);
+            Self::bond(&who, pool_id, amount)?;
+
+            pool.members += 1;
+            pool.bonded = pool.bonded.saturating_add(amount);
+            BondedPools::<T>::insert(pool_id, pool);
+            PoolMembers::<T>::insert(
+                &who,
+                PoolMember {
+                    pool_id,
+                    bonded: amount,
+                    last_recorded_reward_counter: reward_counter,
+                    unbonding: Vec::new(),
+                },
+            );
+
+            Self::deposit_event(Event::Bonded {
+                member: who,
+                pool_id,
+                bonded: amount,
+            });
+            Ok(())
+        }
+
+        /// Bonds `extra` more in the pool of the caller. Pays out the pending rewards first.
+        #[pallet::weight(T::WeightInfo::bond_extra())]
+        #[transactional]
+        pub fn bond_extra(
+            origin: OriginFor<T>,
+            #[pallet::compact] extra: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let mut member = PoolMembers::<T>::get(&who).ok_or(Error::<T>::NotAMember)?;
+            let pool_id = member.pool_id;
+            let mut pool = BondedPools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+            ensure!(pool.state == PoolState::Open, Error::<T>::PoolNotOpen);
+
+            Self::pay_out(&who, &mut member, &pool)?;
+            Self::bond(&who, pool_id, extra)?;
+
+            member.bonded = member.bonded.saturating_add(extra);
+            ensure!(
+                member.bonded >= T::MinJoinBond::get(),
+                Error::<T>::MinimumBondNotMet
+            );
+            pool.bonded = pool.bonded.saturating_add(extra);
+            BondedPools::<T>::insert(pool_id, pool);
+            PoolMembers::<T>::insert(&who, member);
+
+            Self::deposit_event(Event::Bonded {
+                member: who,
+                pool_id,
+                bonded: extra,
+            });
+            Ok(())
+        }
+
+        /// Pays out the share of the pool rewards the caller has earned.
+        #[pallet::weight(T::WeightInfo::claim_payout())]
+        #[transactional]
+        pub fn claim_payout(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let mut member = PoolMembers::<T>::get(&who).ok_or(Error::<T>::NotAMember)?;
+            let pool = BondedPools::<T>::get(member.pool_id).ok_or(Error::<T>::PoolNotFound)?;
+
+            Self::pay_out(&who, &mut member, &pool)?;
+            PoolMembers::<T>::insert(&who, member);
+            Ok(())
+        }
+
+        /// Starts unbonding `amount` of the bond of the caller. Pays out the pending rewards first.
+        ///
+        /// The remaining bond has to be either zero or at least the minimal bond. The depositor
+        /// keeps at least the minimal nominator bond unless it is the last member, in which case
+        /// unbonding everything starts destroying the pool.
+        #[pallet::weight(T::WeightInfo::unbond())]
+        #[transactional]
+        pub fn unbond(
+            origin: OriginFor<T>,
+            #[pallet::compact] amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let mut member = PoolMembers::<T>::get(&who).ok_or(Error::<T>::NotAMember)?;
+            let pool_id = member.pool_id;
+            let mut pool = BondedPools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+            ensure!(amount <= member.bonded, Error::<T>::NotEnoughBonded);
+
+            let remaining = member.bonded.saturating_sub(amount);
+            let leaves = remaining.is_zero();
+            if pool.depositor == who {
+                ensure!(
+                    !leaves || pool.members == 1,
+                    Error::<T>::DepositorCannotLeave
+                );
+                ensure!(
+                    leaves || remaining >= Self::min_create_bond(),
+                    Error::<T>::MinimumBondNotMet
+                );
+            } else {
+                ensure!(
+                    leaves || remaining >= T::MinJoinBond::get(),
+                    Error::<T>::MinimumBondNotMet
+                );
+            }
+
+            let era = T::Staking::unbonding_era();
+            match member.unbonding.iter_mut().find(|chunk| chunk.era == era) {
+                Some(chunk) => chunk.value = chunk.value.saturating_add(amount),
+                None => {
+                    ensure!(
+                        (member.unbonding.len() as u32) < T::MaxUnbonding::get(),
+                        Error::<T>::MaxUnbondingLimit
+                    );
+                    member.unbonding.push(UnbondingChunk { era, value: amount });
+                }
+            }
+
+            Self::pay_out(&who, &mut member, &pool)?;
+
+            let bonded_account = Self::bonded_account(pool_id);
+            if pool.depositor == who && leaves {
+                // Staking does not let a nominator go below the minimal bond.
+                T::Staking::chill(bonded_account.clone())?;
+                pool.state = PoolState::Destroying;
+            }
+            // Frees the staking unbonding chunks that have already passed.
+            T::Staking::withdraw_unbonded(bonded_account.clone())?;
+            T::Staking::unbond(bonded_account, amount)?;
+
+            member.bonded = remaining;
+            pool.bonded = pool.bonded.saturating_sub(amount);
+            BondedPools::<T>::insert(pool_id, pool);
+            PoolMembers::<T>::insert(&who, member);
+
+            Self::deposit_event(Event::Unbonded {
+                member: who,
+                pool_id,
+                value: amount,
+                era,
+            });
+            Ok(())
+        }
+
+        /// Withdraws the unbonded funds of the caller whose era has come.
+        ///
+        /// A member with nothing bonded or unbonding left is removed from the pool. Once the last
+        /// member is removed, the pool is destroyed and the rest of its funds go to the depositor.
+        #[pallet::weight(T::WeightInfo::withdraw_unbonded())]
+        #[transactional]
+        pub fn withdraw_unbonded(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let mut member = PoolMembers::<T>::get(&who).ok_or(Error::<T>::NotAMember)?;
+            let pool_id = member.pool_id;
+            let mut pool = BondedPools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+
+            let current_era = T::Staking::current_era();
+            let (withdrawable, unbonding): (Vec<_>, Vec<_>) = member
+                .unbonding
+                .into_iter()
+                .partition(|chunk| chunk.era <= current_era);
+            let value = withdrawable
+                .iter()
+                .fold(BalanceOf::<T>::zero(), |sum, chunk| {
+                    sum.saturating_add(chunk.value)
+                });
+            ensure!(!value.is_zero(), Error::<T>::CannotWithdrawAny);
+            member.unbonding = unbonding;
+
+            let bonded_account = Self::bonded_account(pool_id);
+            T::Staking::withdraw_unbonded(bonded_account.clone())?;
+            T::Currency::transfer(
+                &bonded_account,
+                &who,
+                value,
+                ExistenceRequirement::AllowDeath,
+            )?;
+            Self::deposit_event(Event::Withdrawn {
+                member: who.clone(),
+                pool_id,
+                value,
+            });
+
+            if !member.bonded.is_zero() || !member.unbonding.is_empty() {
+                PoolMembers::<T>::insert(&who, member);
+                return Ok(());
+            }
+
+            PoolMembers::<T>::remove(&who);
+            pool.members = pool.members.saturating_sub(1);
+            Self::deposit_event(Event::MemberRemoved {
+                member: who,
+                pool_id,
+            });
+            if pool.members > 0 {
+                BondedPools::<T>::insert(pool_id, pool);
+                return Ok(());
+            }
+
+            Self::destroy(pool_id, &pool.depositor)
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// The account bonding the funds of the pool and nominating.
+        pub fn bonded_account(pool_id: PoolId) -> T::AccountId {
+            T::PalletId::get().into_sub_account((AccountType::Bonded, pool_id))
+        }
+
+        /// The account the staking rewards of the pool are paid out to.
+        pub fn reward_account(pool_id: PoolId) -> T::AccountId {
+            T::PalletId::get().into_sub_account((AccountType::Reward, pool_id))
+        }
+
+        /// The minimal bond of the depositor, enough for the pool to nominate on its own.
+        pub fn min_create_bond() -> BalanceOf<T> {
+            T::Staking::minimum_bond().max(T::MinJoinBond::get())
+        }
+
+        /// The rewards of `who` that would be paid out if it claimed them now.
+        pub fn pending_rewards(who: &T::AccountId) -> Option<BalanceOf<T>> {
+            let member = PoolMembers::<T>::get(who)?;
+            let pool = BondedPools::<T>::get(member.pool_id)?;
+            let reward_pool = RewardPools::<T>::get(member.pool_id)?;
+            let (reward_counter, _) =
+                Self::current_reward_counter(member.pool_id, &pool, &reward_pool);
+            Some(Self::earned(&member, reward_counter))
+        }
+
+        fn bond(who: &T::AccountId, pool_id: PoolId, amount: BalanceOf<T>) -> DispatchResult {
+            let bonded_account = Self::bonded_account(pool_id);
+            T::Currency::transfer(
+                who,
+                &bonded_account,
+                amount,
+                ExistenceRequirement::KeepAlive,
+            )?;
+            T::Staking::bond_extra(bonded_account, amount)
+        }
+
+        // The reward counter of the pool including the payouts since it was last recorded, and
+        // the total payouts it accounts for.
+        fn current_reward_counter(
+            pool_id: PoolId,
+            pool: &BondedPool<T::AccountId, BalanceOf<T>>,
+            reward_pool: &RewardPool<BalanceOf<T>>,
+        ) -> (FixedU128, BalanceOf<T>) {
+            let balance = T::Currency::free_balance(&Self::reward_account(pool_id))
+                .saturating_sub(T::Currency::minimum_balance());
+            let total_payouts = balance.saturating_add(reward_pool.total_rewards_claimed);
+            if pool.bonded.is_zero() {
+                // Nobody earns the payouts, they stay to be shared once someone bonds again.
+                return (
+                    reward_pool.last_recorded_reward_counter,
+                    reward_pool.last_recorded_total_payouts,
+                );
+            }
+            let new_payouts: u128 = total_payouts
+                .saturating_sub(reward_pool.last_recorded_total_payouts)
+                .unique_saturated_into();
+            let bonded: u128 = pool.bonded.unique_saturated_into();
+            let increase =
+                FixedU128::checked_from_rational(new_payouts, bonded).unwrap_or_default();
+            (
+                reward_pool
+                    .last_recorded_reward_counter
+                    .saturating_add(increase),
+                total_payouts,
+            )
+        }
+
+        // Records the current reward counter of the pool, it has to be done before the total bond
+        // of the pool changes.
+        fn record_rewards(
+            pool_id: PoolId,
+            pool: &BondedPool<T::AccountId, BalanceOf<T>>,
+        ) -> FixedU128 {
+            let mut reward_pool = RewardPools::<T>::get(pool_id).unwrap_or_default();
+            let (reward_counter, total_payouts) =
+                Self::current_reward_counter(pool_id, pool, &reward_pool);
+            reward_pool.last_recorded_reward_counter = reward_counter;
+            reward_pool.last_recorded_total_payouts = total_payouts;
+            RewardPools::<T>::insert(pool_id, reward_pool);
+            reward_counter
+        }
+
+        fn earned(member: &PoolMember<BalanceOf<T>>, reward_counter: FixedU128) -> BalanceOf<T> {
+            let bonded: u128 = member.bonded.unique_saturated_into();
+            let earned = reward_counter
+                .saturating_sub(member.last_recorded_reward_counter)
+                .saturating_mul_int(bonded);
+            BalanceOf::<T>::unique_saturated_from(earned)
+        }
+
+        // Pays out the rewards `member` has earned, the caller stores the updated `member`.
+        fn pay_out(
+            who: &T::AccountId,
+            member: &mut PoolMember<BalanceOf<T>>,
+            pool: &BondedPool<T::AccountId, BalanceOf<T>>,
+        ) -> DispatchResult {
+            let pool_id = member.pool_id;
+            let reward_counter = Self::record_rewards(pool_id, pool);
+            let payout = Self::earned(member, reward_counter);
+            member.last_recorded_reward_counter = reward_counter;
+            if payout.is_zero() {
+                return Ok(());
+            }
+
+            T::Currency::transfer(
+                &Self::reward_account(pool_id),
+                who,
+                payout,
+                ExistenceRequirement::KeepAlive,
+            )?;
+            RewardPools::<T>::mutate(pool_id, |reward_pool| {
+                if let Some(reward_pool) = reward_pool {
+                    reward_pool.total_rewards_claimed =
+                        reward_pool.total_rewards_claimed.saturating_add(payout);
+                }
+            });
+
+            Self::deposit_event(Event::PaidOut {
+                member: who.clone(),
+                pool_id,
+                payout,
+            });
+            Ok(())
+        }
+
+        fn destroy(pool_id: PoolId, depositor: &T::AccountId) -> DispatchResult {
+            for account in [Self::bonded_account(pool_id), Self::reward_account(pool_id)] {
+                let balance = T::Currency::free_balance(&account);
+                if !balance.is_zero() {
+                    T::Currency::transfer(
+                        &account,
+                        depositor,
+                        balance,
+                        ExistenceRequirement::AllowDeath,
+                    )?;
+                }
+            }
+            BondedPools::<T>::remove(pool_id);
+            RewardPools::<T>::remove(pool_id);
+
+            Self::deposit_event(Event::Destroyed { pool_id });
+            Ok(())
+        }
+    }
+}
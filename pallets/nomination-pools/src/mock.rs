@@ -0,0 +1,245 @@
+#![cfg(test)]
+
+use super::*;
+use crate as pallet_nomination_pools;
+
+use frame_support::{construct_runtime, parameter_types, sp_io, traits::GenesisBuild};
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+    DispatchError,
+};
+use std::{cell::RefCell, collections::BTreeMap};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        NominationPools: pallet_nomination_pools::{Pallet, Call, Storage, Event<T>},
+    }
+);
+
+pub(crate) type AccountId = u64;
+pub(crate) type Balance = u128;
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = frame_support::traits::ConstU64<250>;
+    type DbWeight = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: Balance = 1;
+}
+
+impl pallet_balances::Config for Test {
+    type Balance = Balance;
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+    type DustRemoval = ();
+    type Event = Event;
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = ();
+}
+
+pub(crate) const MIN_NOMINATOR_BOND: Balance = 100;
+pub(crate) const BONDING_DURATION: EraIndex = 3;
+
+/// The bond of a stash in the mock staking.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Ledger {
+    pub active: Balance,
+    pub unlocking: Vec<(EraIndex, Balance)>,
+    pub payee: AccountId,
+    pub target: Option<AccountId>,
+}
+
+thread_local! {
+    static CURRENT_ERA: RefCell<EraIndex> = RefCell::new(0);
+    static LEDGERS: RefCell<BTreeMap<AccountId, Ledger>> = RefCell::new(BTreeMap::new());
+}
+
+pub(crate) fn set_current_era(era: EraIndex) {
+    CURRENT_ERA.with(|current| *current.borrow_mut() = era);
+}
+
+pub(crate) fn ledger(stash: AccountId) -> Option<Ledger> {
+    LEDGERS.with(|ledgers| ledgers.borrow().get(&stash).cloned())
+}
+
+fn mutate_ledger(
+    stash: AccountId,
+    f: impl FnOnce(&mut Ledger) -> DispatchResult,
+) -> DispatchResult {
+    LEDGERS.with(|ledgers| match ledgers.borrow_mut().get_mut(&stash) {
+        Some(ledger) => f(ledger),
+        None => Err(DispatchError::Other("NotStash")),
+    })
+}
+
+/// Keeps the bonds in memory and does not lock the bonded funds.
+pub struct StakingMock;
+
+impl StakingInterface<AccountId, Balance> for StakingMock {
+    fn minimum_bond() -> Balance {
+        MIN_NOMINATOR_BOND
+    }
+
+    fn current_era() -> EraIndex {
+        CURRENT_ERA.with(|current| *current.borrow())
+    }
+
+    fn unbonding_era() -> EraIndex {
+        Self::current_era() + BONDING_DURATION
+    }
+
+    fn bond(stash: AccountId, value: Balance, payee: AccountId) -> DispatchResult {
+        LEDGERS.with(|ledgers| {
+            let mut ledgers = ledgers.borrow_mut();
+            if ledgers.contains_key(&stash) {
+                return Err(DispatchError::Other("AlreadyBonded"));
+            }
+            ledgers.insert(
+                stash,
+                Ledger {
+                    active: value,
+                    payee,
+                    ..Default::default()
+                },
+            );
+            Ok(())
+        })
+    }
+
+    fn bond_extra(stash: AccountId, extra: Balance) -> DispatchResult {
+        mutate_ledger(stash, |ledger| {
+            ledger.active += extra;
+            Ok(())
+        })
+    }
+
+    fn unbond(stash: AccountId, value: Balance) -> DispatchResult {
+        let era = Self::unbonding_era();
+        mutate_ledger(stash, |ledger| {
+            if value > ledger.active {
+                return Err(DispatchError::Other("InsufficientBond"));
+            }
+            if ledger.target.is_some() && ledger.active - value < MIN_NOMINATOR_BOND {
+                return Err(DispatchError::Other("InsufficientBond"));
+            }
+            ledger.active -= value;
+            ledger.unlocking.push((era, value));
+            Ok(())
+        })
+    }
+
+    fn withdraw_unbonded(stash: AccountId) -> DispatchResult {
+        let current_era = Self::current_era();
+        mutate_ledger(stash, |ledger| {
+            ledger.unlocking.retain(|(era, _)| *era > current_era);
+            Ok(())
+        })?;
+        LEDGERS.with(|ledgers| {
+            let mut ledgers = ledgers.borrow_mut();
+            if ledgers.get(&stash).map_or(false, |ledger| {
+                ledger.active == 0 && ledger.unlocking.is_empty()
+            }) {
+                ledgers.remove(&stash);
+            }
+        });
+        Ok(())
+    }
+
+    fn nominate(stash: AccountId, target: AccountId) -> DispatchResult {
+        mutate_ledger(stash, |ledger| {
+            if ledger.active < MIN_NOMINATOR_BOND {
+                return Err(DispatchError::Other("InsufficientBond"));
+            }
+            ledger.target = Some(target);
+            Ok(())
+        })
+    }
+
+    fn chill(stash: AccountId) -> DispatchResult {
+        mutate_ledger(stash, |ledger| {
+            ledger.target = None;
+            Ok(())
+        })
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn set_current_era(era: EraIndex) {
+        set_current_era(era)
+    }
+}
+
+parameter_types! {
+    pub const PoolsPalletId: PalletId = PalletId(*b"py/nopls");
+    pub const MinJoinBond: Balance = 10;
+    pub const MaxMembersPerPool: u32 = 3;
+    pub const MaxUnbonding: u32 = 2;
+}
+
+impl Config for Test {
+    type Event = Event;
+    type Currency = Balances;
+    type Staking = StakingMock;
+    type PalletId = PoolsPalletId;
+    type MinJoinBond = MinJoinBond;
+    type MaxMembersPerPool = MaxMembersPerPool;
+    type MaxUnbonding = MaxUnbonding;
+    type WeightInfo = ();
+}
+
+pub(crate) const DEPOSITOR: AccountId = 10;
+pub(crate) const VALIDATOR: AccountId = 100;
+
+pub fn new_test_ext(accounts: &[AccountId]) -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .expect("Storage should be build.");
+
+    let balances = accounts.iter().map(|account| (*account, 1_000)).collect();
+    pallet_balances::GenesisConfig::<Test> { balances }
+        .assimilate_storage(&mut t)
+        .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        set_current_era(0);
+        LEDGERS.with(|ledgers| ledgers.borrow_mut().clear());
+    });
+    ext
+}
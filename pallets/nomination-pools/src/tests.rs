@@ -0,0 +1,262 @@
+#![cfg(test)]
+
+use frame_support::{assert_noop, assert_ok};
+
+use crate::{mock::*, *};
+
+const MEMBER: AccountId = 1;
+const OTHER_MEMBER: AccountId = 2;
+
+fn create_pool(amount: Balance) -> PoolId {
+    assert_ok!(NominationPools::create(Origin::signed(DEPOSITOR), amount));
+    let pool_id = LastPoolId::<Test>::get();
+    assert_ok!(NominationPools::nominate(
+        Origin::signed(DEPOSITOR),
+        pool_id,
+        VALIDATOR
+    ));
+    pool_id
+}
+
+fn pay_rewards(pool_id: PoolId, amount: Balance) {
+    let reward_account = NominationPools::reward_account(pool_id);
+    let balance = Balances::free_balance(&reward_account);
+    Balances::make_free_balance_be(&reward_account, balance + amount);
+}
+
+fn claimed(who: AccountId, f: impl FnOnce()) -> Balance {
+    let before = Balances::free_balance(&who);
+    f();
+    Balances::free_balance(&who) - before
+}
+
+#[test]
+fn pool_bonds_and_nominates() {
+    new_test_ext(&[DEPOSITOR, MEMBER]).execute_with(|| {
+        let pool_id = create_pool(200);
+        let bonded_account = NominationPools::bonded_account(pool_id);
+
+        let ledger = ledger(bonded_account).unwrap();
+        assert_eq!(ledger.active, 200);
+        assert_eq!(ledger.payee, NominationPools::reward_account(pool_id));
+        assert_eq!(ledger.target, Some(VALIDATOR));
+        assert_eq!(Balances::free_balance(&DEPOSITOR), 1_000 - 200 - 1);
+
+        assert_ok!(NominationPools::join(Origin::signed(MEMBER), 50, pool_id));
+        assert_eq!(ledger(bonded_account).unwrap().active, 250);
+        assert_eq!(NominationPools::bonded_pools(pool_id).unwrap().bonded, 250);
+        assert_eq!(NominationPools::pool_members(MEMBER).unwrap().bonded, 50);
+
+        assert_noop!(
+            NominationPools::nominate(Origin::signed(MEMBER), pool_id, MEMBER),
+            Error::<Test>::NotDepositor
+        );
+    });
+}
+
+#[test]
+fn bonds_have_to_meet_the_minimum() {
+    new_test_ext(&[DEPOSITOR, MEMBER, OTHER_MEMBER, 3]).execute_with(|| {
+        assert_noop!(
+            NominationPools::create(Origin::signed(DEPOSITOR), MIN_NOMINATOR_BOND - 1),
+            Error::<Test>::MinimumBondNotMet
+        );
+        let pool_id = create_pool(MIN_NOMINATOR_BOND);
+        assert_noop!(
+            NominationPools::create(Origin::signed(DEPOSITOR), MIN_NOMINATOR_BOND),
+            Error::<Test>::AccountBelongsToPool
+        );
+
+        assert_noop!(
+            NominationPools::join(Origin::signed(MEMBER), 9, pool_id),
+            Error::<Test>::MinimumBondNotMet
+        );
+        assert_noop!(
+            NominationPools::join(Origin::signed(MEMBER), 10, pool_id + 1),
+            Error::<Test>::PoolNotFound
+        );
+        assert_ok!(NominationPools::join(Origin::signed(MEMBER), 10, pool_id));
+        assert_ok!(NominationPools::join(
+            Origin::signed(OTHER_MEMBER),
+            10,
+            pool_id
+        ));
+        assert_noop!(
+            NominationPools::join(Origin::signed(3), 10, pool_id),
+            Error::<Test>::MaxMembers
+        );
+    });
+}
+
+#[test]
+fn rewards_are_shared_proportionally_to_bonds() {
+    new_test_ext(&[DEPOSITOR, MEMBER, OTHER_MEMBER]).execute_with(|| {
+        let pool_id = create_pool(200);
+        assert_ok!(NominationPools::join(Origin::signed(MEMBER), 100, pool_id));
+        pay_rewards(pool_id, 300);
+
+        // Rewards paid out before joining belong to the previous members.
+        assert_ok!(NominationPools::join(
+            Origin::signed(OTHER_MEMBER),
+            300,
+            pool_id
+        ));
+        assert_eq!(NominationPools::pending_rewards(&OTHER_MEMBER), Some(0));
+        pay_rewards(pool_id, 600);
+
+        assert_eq!(NominationPools::pending_rewards(&DEPOSITOR), Some(400));
+        let payout = claimed(DEPOSITOR, || {
+            assert_ok!(NominationPools::claim_payout(Origin::signed(DEPOSITOR)))
+        });
+        assert_eq!(payout, 400);
+        let payout = claimed(MEMBER, || {
+            assert_ok!(NominationPools::claim_payout(Origin::signed(MEMBER)))
+        });
+        assert_eq!(payout, 200);
+        let payout = claimed(OTHER_MEMBER, || {
+            assert_ok!(NominationPools::claim_payout(Origin::signed(OTHER_MEMBER)))
+        });
+        assert_eq!(payout, 300);
+
+        // Claiming twice pays nothing.
+        let payout = claimed(MEMBER, || {
+            assert_ok!(NominationPools::claim_payout(Origin::signed(MEMBER)))
+        });
+        assert_eq!(payout, 0);
+        assert_eq!(
+            Balances::free_balance(&NominationPools::reward_account(pool_id)),
+            1
+        );
+    });
+}
+
+#[test]
+fn changing_the_bond_pays_out_pending_rewards() {
+    new_test_ext(&[DEPOSITOR, MEMBER]).execute_with(|| {
+        let pool_id = create_pool(100);
+        assert_ok!(NominationPools::join(Origin::signed(MEMBER), 100, pool_id));
+        pay_rewards(pool_id, 100);
+
+        assert_ok!(NominationPools::bond_extra(Origin::signed(MEMBER), 100));
+        assert_eq!(Balances::free_balance(&MEMBER), 1_000 - 200 + 50);
+        pay_rewards(pool_id, 300);
+
+        let payout = claimed(MEMBER, || {
+            assert_ok!(NominationPools::unbond(Origin::signed(MEMBER), 200))
+        });
+        assert_eq!(payout, 200);
+        let payout = claimed(DEPOSITOR, || {
+            assert_ok!(NominationPools::claim_payout(Origin::signed(DEPOSITOR)))
+        });
+        assert_eq!(payout, 50 + 100);
+    });
+}
+
+#[test]
+fn members_withdraw_after_the_bonding_duration() {
+    new_test_ext(&[DEPOSITOR, MEMBER]).execute_with(|| {
+        let pool_id = create_pool(200);
+        assert_ok!(NominationPools::join(Origin::signed(MEMBER), 100, pool_id));
+
+        assert_noop!(
+            NominationPools::unbond(Origin::signed(MEMBER), 101),
+            Error::<Test>::NotEnoughBonded
+        );
+        assert_noop!(
+            NominationPools::unbond(Origin::signed(MEMBER), 95),
+            Error::<Test>::MinimumBondNotMet
+        );
+        assert_ok!(NominationPools::unbond(Origin::signed(MEMBER), 60));
+        set_current_era(1);
+        assert_ok!(NominationPools::unbond(Origin::signed(MEMBER), 40));
+        assert_eq!(
+            NominationPools::pool_members(MEMBER).unwrap().unbonding,
+            vec![
+                UnbondingChunk { era: 3, value: 60 },
+                UnbondingChunk { era: 4, value: 40 },
+            ]
+        );
+
+        set_current_era(2);
+        assert_noop!(
+            NominationPools::withdraw_unbonded(Origin::signed(MEMBER)),
+            Error::<Test>::CannotWithdrawAny
+        );
+        set_current_era(3);
+        let withdrawn = claimed(MEMBER, || {
+            assert_ok!(NominationPools::withdraw_unbonded(Origin::signed(MEMBER)))
+        });
+        assert_eq!(withdrawn, 60);
+        assert!(NominationPools::pool_members(MEMBER).is_some());
+
+        set_current_era(4);
+        let withdrawn = claimed(MEMBER, || {
+            assert_ok!(NominationPools::withdraw_unbonded(Origin::signed(MEMBER)))
+        });
+        assert_eq!(withdrawn, 40);
+        assert!(NominationPools::pool_members(MEMBER).is_none());
+        assert_eq!(NominationPools::bonded_pools(pool_id).unwrap().members, 1);
+        assert_eq!(Balances::free_balance(&MEMBER), 1_000);
+    });
+}
+
+#[test]
+fn unbonding_eras_are_limited() {
+    new_test_ext(&[DEPOSITOR, MEMBER]).execute_with(|| {
+        let pool_id = create_pool(200);
+        assert_ok!(NominationPools::join(Origin::signed(MEMBER), 100, pool_id));
+
+        assert_ok!(NominationPools::unbond(Origin::signed(MEMBER), 10));
+        assert_ok!(NominationPools::unbond(Origin::signed(MEMBER), 10));
+        set_current_era(1);
+        assert_ok!(NominationPools::unbond(Origin::signed(MEMBER), 10));
+        set_current_era(2);
+        assert_noop!(
+            NominationPools::unbond(Origin::signed(MEMBER), 10),
+            Error::<Test>::MaxUnbondingLimit
+        );
+    });
+}
+
+#[test]
+fn depositor_leaving_destroys_the_pool() {
+    new_test_ext(&[DEPOSITOR, MEMBER]).execute_with(|| {
+        let pool_id = create_pool(200);
+        assert_ok!(NominationPools::join(Origin::signed(MEMBER), 100, pool_id));
+
+        assert_noop!(
+            NominationPools::unbond(Origin::signed(DEPOSITOR), 200),
+            Error::<Test>::DepositorCannotLeave
+        );
+        assert_noop!(
+            NominationPools::unbond(Origin::signed(DEPOSITOR), 101),
+            Error::<Test>::MinimumBondNotMet
+        );
+
+        assert_ok!(NominationPools::unbond(Origin::signed(MEMBER), 100));
+        set_current_era(3);
+        assert_ok!(NominationPools::withdraw_unbonded(Origin::signed(MEMBER)));
+        pay_rewards(pool_id, 50);
+
+        assert_ok!(NominationPools::unbond(Origin::signed(DEPOSITOR), 200));
+        let pool = NominationPools::bonded_pools(pool_id).unwrap();
+        assert_eq!(pool.state, PoolState::Destroying);
+        let bonded_account = NominationPools::bonded_account(pool_id);
+        assert_eq!(ledger(bonded_account).unwrap().target, None);
+        assert_noop!(
+            NominationPools::join(Origin::signed(MEMBER), 100, pool_id),
+            Error::<Test>::PoolNotOpen
+        );
+
+        set_current_era(6);
+        assert_ok!(NominationPools::withdraw_unbonded(Origin::signed(
+            DEPOSITOR
+        )));
+        assert!(NominationPools::bonded_pools(pool_id).is_none());
+        assert!(NominationPools::reward_pools(pool_id).is_none());
+        assert!(NominationPools::pool_members(DEPOSITOR).is_none());
+        assert!(ledger(bonded_account).is_none());
+        assert_eq!(Balances::free_balance(&DEPOSITOR), 1_000 + 50);
+        assert_eq!(Balances::free_balance(&MEMBER), 1_000);
+    });
+}
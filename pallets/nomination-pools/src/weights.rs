@@ -0,0 +1,156 @@
+//! Weights for pallet_nomination_pools.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_nomination_pools.
+pub trait WeightInfo {
+    fn create() -> Weight;
+    fn nominate() -> Weight;
+    fn join() -> Weight;
+    fn bond_extra() -> Weight;
+    fn claim_payout() -> Weight;
+    fn unbond() -> Weight;
+    fn withdraw_unbonded() -> Weight;
+}
+
+/// Weights for pallet_nomination_pools based on the storage accesses they perform.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    // Storage: NominationPools PoolMembers (r:1 w:1)
+    // Storage: NominationPools LastPoolId (r:1 w:1)
+    // Storage: System Account (r:3 w:3)
+    // Storage: Staking Bonded (r:1 w:1)
+    // Storage: Staking Ledger (r:1 w:1)
+    // Storage: Staking MinNominatorBond (r:1 w:0)
+    // Storage: Staking CurrentEra (r:1 w:0)
+    // Storage: Staking HistoryDepth (r:1 w:0)
+    // Storage: Balances Locks (r:1 w:1)
+    // Storage: Staking Payee (r:0 w:1)
+    // Storage: NominationPools BondedPools (r:0 w:1)
+    // Storage: NominationPools RewardPools (r:0 w:1)
+    fn create() -> Weight {
+        (112_400_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(11 as Weight))
+            .saturating_add(T::DbWeight::get().writes(12 as Weight))
+    }
+    // Storage: NominationPools BondedPools (r:1 w:0)
+    // Storage: Staking Ledger (r:1 w:0)
+    // Storage: Staking MinNominatorBond (r:1 w:0)
+    // Storage: Staking Nominators (r:1 w:1)
+    // Storage: Staking MaxNominatorsCount (r:1 w:0)
+    // Storage: Staking Validators (r:2 w:0)
+    // Storage: Staking CurrentEra (r:1 w:0)
+    // Storage: Staking CounterForNominators (r:1 w:1)
+    fn nominate() -> Weight {
+        (48_730_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    // Storage: NominationPools PoolMembers (r:1 w:1)
+    // Storage: NominationPools BondedPools (r:1 w:1)
+    // Storage: NominationPools RewardPools (r:1 w:1)
+    // Storage: System Account (r:3 w:2)
+    // Storage: Staking Ledger (r:1 w:1)
+    // Storage: Balances Locks (r:1 w:1)
+    fn join() -> Weight {
+        (81_250_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+    }
+    // Storage: NominationPools PoolMembers (r:1 w:1)
+    // Storage: NominationPools BondedPools (r:1 w:1)
+    // Storage: NominationPools RewardPools (r:1 w:1)
+    // Storage: System Account (r:3 w:3)
+    // Storage: Staking Ledger (r:1 w:1)
+    // Storage: Balances Locks (r:1 w:1)
+    fn bond_extra() -> Weight {
+        (96_180_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(8 as Weight))
+    }
+    // Storage: NominationPools PoolMembers (r:1 w:1)
+    // Storage: NominationPools BondedPools (r:1 w:0)
+    // Storage: NominationPools RewardPools (r:1 w:1)
+    // Storage: System Account (r:2 w:2)
+    fn claim_payout() -> Weight {
+        (52_640_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    // Storage: NominationPools PoolMembers (r:1 w:1)
+    // Storage: NominationPools BondedPools (r:1 w:1)
+    // Storage: NominationPools RewardPools (r:1 w:1)
+    // Storage: System Account (r:3 w:3)
+    // Storage: Staking Ledger (r:1 w:1)
+    // Storage: Staking CurrentEra (r:1 w:0)
+    // Storage: Staking Nominators (r:1 w:1)
+    // Storage: Staking Validators (r:1 w:0)
+    // Storage: Staking MinNominatorBond (r:1 w:0)
+    // Storage: Staking CounterForNominators (r:1 w:1)
+    // Storage: Balances Locks (r:1 w:1)
+    fn unbond() -> Weight {
+        (128_910_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(13 as Weight))
+            .saturating_add(T::DbWeight::get().writes(10 as Weight))
+    }
+    // Storage: NominationPools PoolMembers (r:1 w:1)
+    // Storage: NominationPools BondedPools (r:1 w:1)
+    // Storage: NominationPools RewardPools (r:0 w:1)
+    // Storage: Staking CurrentEra (r:1 w:0)
+    // Storage: Staking Ledger (r:1 w:1)
+    // Storage: Staking Bonded (r:1 w:1)
+    // Storage: Staking Payee (r:0 w:1)
+    // Storage: Balances Locks (r:1 w:1)
+    // Storage: System Account (r:3 w:3)
+    fn withdraw_unbonded() -> Weight {
+        (119_370_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(11 as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn create() -> Weight {
+        (112_400_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(12 as Weight))
+    }
+    fn nominate() -> Weight {
+        (48_730_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn join() -> Weight {
+        (81_250_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+    }
+    fn bond_extra() -> Weight {
+        (96_180_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(8 as Weight))
+    }
+    fn claim_payout() -> Weight {
+        (52_640_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn unbond() -> Weight {
+        (128_910_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(13 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(10 as Weight))
+    }
+    fn withdraw_unbonded() -> Weight {
+        (119_370_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(11 as Weight))
+    }
+}
@@ -0,0 +1,174 @@
+//! This pallet brings the output of the randomness beacon run by the AlephBFT committee on chain.
+//!
+//! Every session the committee members contribute signed shares through the consensus data
+//! payload, and the first shares ordered by AlephBFT determine the seed of that session. Block
+//! authors include the newest seed they know about through an inherent and the pallet exposes it
+//! through the `Randomness` trait. Until the first seed arrives the fallback source is used.
+//!
+//! The seed is accepted only together with the quorum of shares it was computed from, each of
+//! them verified against the keys of the committee of its session.
+//!
+//! Note that the shares are ed25519 signatures rather than shares of a threshold signature, so the
+//! seed is not unique: the last contributors can still bias it by withholding their shares, and
+//! whoever includes the output may pick among the quorums they know of. Until the shares become
+//! threshold signature shares the beacon must not be used where such bias matters, in particular
+//! the runtime does not give it to contracts.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use frame_support::traits::{Randomness, StorageVersion};
+pub use pallet::*;
+use primitives::{
+    randomness::{BeaconOutput, InherentError, InherentType, OutputError, INHERENT_IDENTIFIER},
+    AuthorityId, SessionIndex,
+};
+use sp_runtime::traits::Hash;
+use sp_std::vec::Vec;
+
+/// Provides the keys of the committee members of past sessions, ordered by the member index.
+pub trait CommitteeKeys {
+    fn committee(session: SessionIndex) -> Option<Vec<AuthorityId>>;
+}
+
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+    use frame_support::pallet_prelude::*;
+    use frame_system::{ensure_none, pallet_prelude::OriginFor};
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        /// The index of the current session. Only outputs of already finished sessions are
+        /// accepted, so that the committee had the time to compute them before they are checked.
+        type CurrentSession: Get<SessionIndex>;
+        /// The keys the shares of the outputs are verified against.
+        type CommitteeKeys: CommitteeKeys;
+        /// Used until the first output of the beacon gets included.
+        type FallbackRandomness: Randomness<Self::Hash, Self::BlockNumber>;
+    }
+
+    #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
+    #[pallet::without_storage_info]
+    pub struct Pallet<T>(_);
+
+    /// The newest beacon output together with the number of the block that included it.
+    #[pallet::storage]
+    #[pallet::getter(fn latest_output)]
+    pub type LatestOutput<T: Config> = StorageValue<_, (BeaconOutput, T::BlockNumber), OptionQuery>;
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The output is not newer than the one already stored.
+        OutdatedSession,
+        /// The output belongs to a session that has not finished yet.
+        UnfinishedSession,
+        /// The committee of the session of the output is not known.
+        UnknownCommittee,
+        /// The number of shares is different than the quorum of the committee.
+        WrongShareCount,
+        /// The shares are not ordered by the member index or some member has more than one.
+        UnorderedShares,
+        /// Some share is not a signature of a committee member.
+        InvalidShare,
+        /// The seed is not the one determined by the shares.
+        SeedMismatch,
+    }
+
+    impl<T> From<OutputError> for Error<T> {
+        fn from(error: OutputError) -> Self {
+            match error {
+                OutputError::WrongShareCount => Error::WrongShareCount,
+                OutputError::UnorderedShares => Error::UnorderedShares,
+                OutputError::InvalidShare => Error::InvalidShare,
+                OutputError::SeedMismatch => Error::SeedMismatch,
+            }
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        #[pallet::weight((T::DbWeight::get().reads_writes(3, 1), DispatchClass::Mandatory))]
+        pub fn set_beacon_output(origin: OriginFor<T>, output: BeaconOutput) -> DispatchResult {
+            ensure_none(origin)?;
+            Self::ensure_acceptable(&output)?;
+            <LatestOutput<T>>::put((output, <frame_system::Pallet<T>>::block_number()));
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        fn ensure_acceptable(output: &BeaconOutput) -> DispatchResult {
+            if let Some((latest, _)) = <LatestOutput<T>>::get() {
+                ensure!(output.session > latest.session, Error::<T>::OutdatedSession);
+            }
+            ensure!(
+                output.session < T::CurrentSession::get(),
+                Error::<T>::UnfinishedSession
+            );
+            let committee =
+                T::CommitteeKeys::committee(output.session).ok_or(Error::<T>::UnknownCommittee)?;
+            output.verify(&committee).map_err(Error::<T>::from)?;
+            Ok(())
+        }
+
+        fn known_outputs(data: &InherentData) -> InherentType {
+            data.get_data::<InherentType>(&INHERENT_IDENTIFIER)
+                .ok()
+                .flatten()
+                .unwrap_or_default()
+        }
+    }
+
+    #[pallet::inherent]
+    impl<T: Config> ProvideInherent for Pallet<T> {
+        type Call = Call<T>;
+        type Error = InherentError;
+        const INHERENT_IDENTIFIER: InherentIdentifier = INHERENT_IDENTIFIER;
+
+        fn create_inherent(data: &InherentData) -> Option<Self::Call> {
+            Self::known_outputs(data)
+                .into_iter()
+                .filter(|output| Self::ensure_acceptable(output).is_ok())
+                .max_by_key(|output| output.session)
+                .map(|output| Call::set_beacon_output { output })
+        }
+
+        /// Checks the output only against the committee keys stored on chain. The outputs known
+        /// locally may be computed from a different quorum of shares, which is just as valid, so
+        /// comparing against them would make honest nodes reject each other's blocks.
+        fn check_inherent(call: &Self::Call, _: &InherentData) -> Result<(), Self::Error> {
+            let output = match call {
+                Call::set_beacon_output { output } => output,
+                _ => return Ok(()),
+            };
+            let committee = T::CommitteeKeys::committee(output.session)
+                .ok_or(InherentError::UnknownCommittee(output.session))?;
+            output
+                .verify(&committee)
+                .map_err(|_| InherentError::InvalidOutput(output.session))
+        }
+
+        fn is_inherent(call: &Self::Call) -> bool {
+            matches!(call, Call::set_beacon_output { .. })
+        }
+    }
+
+    impl<T: Config> Randomness<T::Hash, T::BlockNumber> for Pallet<T> {
+        fn random(subject: &[u8]) -> (T::Hash, T::BlockNumber) {
+            match <LatestOutput<T>>::get() {
+                Some((output, block_number)) => {
+                    (T::Hashing::hash_of(&(subject, output.seed)), block_number)
+                }
+                None => T::FallbackRandomness::random(subject),
+            }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+#![cfg(test)]
+
+use super::*;
+use crate as pallet_randomness_beacon;
+
+use frame_support::{
+    construct_runtime, parameter_types, sp_io, traits::Randomness, weights::RuntimeDbWeight,
+};
+use primitives::{AuthorityId, AuthorityPair, SessionIndex};
+use sp_core::{Pair, H256};
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        RandomnessBeacon: pallet_randomness_beacon::{Pallet, Call, Storage, Inherent},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const TestDbWeight: RuntimeDbWeight = RuntimeDbWeight {
+        read: 25,
+        write: 100
+    };
+    pub static CurrentSession: SessionIndex = 0;
+    pub static Committee: Option<Vec<AuthorityId>> = Some(committee_keys());
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type DbWeight = TestDbWeight;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+pub struct FallbackRandomness;
+
+impl Randomness<H256, u64> for FallbackRandomness {
+    fn random(_subject: &[u8]) -> (H256, u64) {
+        (H256::zero(), 0)
+    }
+}
+
+/// The committee of every session, until `Committee` is changed.
+pub const COMMITTEE_SIZE: u8 = 4;
+
+pub fn committee_pair(member: u8) -> AuthorityPair {
+    AuthorityPair::from_seed(&[member; 32])
+}
+
+fn committee_keys() -> Vec<AuthorityId> {
+    (0..COMMITTEE_SIZE)
+        .map(|member| committee_pair(member).public())
+        .collect()
+}
+
+pub struct SameCommitteeEverySession;
+
+impl CommitteeKeys for SameCommitteeEverySession {
+    fn committee(_session: SessionIndex) -> Option<Vec<AuthorityId>> {
+        Committee::get()
+    }
+}
+
+impl Config for Test {
+    type CurrentSession = CurrentSession;
+    type CommitteeKeys = SameCommitteeEverySession;
+    type FallbackRandomness = FallbackRandomness;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let t = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
@@ -0,0 +1,219 @@
+#![cfg(test)]
+
+use crate::{mock::*, pallet, Call, Error};
+use frame_support::{
+    assert_noop, assert_ok,
+    inherent::{InherentData, ProvideInherent},
+    traits::Randomness,
+};
+use frame_system::RawOrigin;
+use primitives::randomness::{
+    quorum, seed, share_message, BeaconOutput, InherentError, INHERENT_IDENTIFIER,
+};
+use sp_core::{Pair, H256};
+
+/// The output of `session` computed from the shares of `members`.
+fn shared_output(session: u32, members: &[u8]) -> BeaconOutput {
+    let shares: Vec<_> = members
+        .iter()
+        .map(|member| {
+            let signature = committee_pair(*member).sign(&share_message(session));
+            (*member as u32, signature)
+        })
+        .collect();
+    BeaconOutput {
+        session,
+        seed: seed(session, &shares),
+        shares,
+    }
+}
+
+fn output(session: u32) -> BeaconOutput {
+    let members: Vec<_> = (0..quorum(COMMITTEE_SIZE as usize) as u8).collect();
+    shared_output(session, &members)
+}
+
+fn inherent_data(outputs: Vec<BeaconOutput>) -> InherentData {
+    let mut data = InherentData::new();
+    data.put_data(INHERENT_IDENTIFIER, &outputs).unwrap();
+    data
+}
+
+#[test]
+fn uses_fallback_until_first_output() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(RandomnessBeacon::random(b"subject"), (H256::zero(), 0));
+
+        CurrentSession::set(1);
+        assert_ok!(RandomnessBeacon::set_beacon_output(
+            RawOrigin::None.into(),
+            output(0)
+        ));
+
+        let (random, block_number) = RandomnessBeacon::random(b"subject");
+        assert_ne!(random, H256::zero());
+        assert_eq!(block_number, 1);
+        assert_ne!(RandomnessBeacon::random(b"other subject").0, random);
+    });
+}
+
+#[test]
+fn accepts_only_newer_outputs_of_finished_sessions() {
+    new_test_ext().execute_with(|| {
+        CurrentSession::set(3);
+        assert_noop!(
+            RandomnessBeacon::set_beacon_output(RawOrigin::None.into(), output(3)),
+            Error::<Test>::UnfinishedSession
+        );
+        assert_ok!(RandomnessBeacon::set_beacon_output(
+            RawOrigin::None.into(),
+            output(2)
+        ));
+        assert_noop!(
+            RandomnessBeacon::set_beacon_output(RawOrigin::None.into(), output(1)),
+            Error::<Test>::OutdatedSession
+        );
+        assert!(RandomnessBeacon::set_beacon_output(RawOrigin::Root.into(), output(2)).is_err());
+    });
+}
+
+#[test]
+fn creates_inherent_with_newest_acceptable_output() {
+    new_test_ext().execute_with(|| {
+        CurrentSession::set(3);
+        let mut forged = output(2);
+        forged.seed = [2; 32];
+        let data = inherent_data(vec![output(1), output(3), forged, output(2)]);
+        assert_eq!(
+            RandomnessBeacon::create_inherent(&data),
+            Some(Call::set_beacon_output { output: output(2) })
+        );
+        assert_eq!(
+            RandomnessBeacon::create_inherent(&InherentData::new()),
+            None
+        );
+    });
+}
+
+#[test]
+fn accepts_inherent_with_seed_different_than_computed_locally() {
+    new_test_ext().execute_with(|| {
+        let call = pallet::Call::<Test>::set_beacon_output { output: output(2) };
+        let locally_computed = shared_output(2, &[1, 2, 3]);
+        assert_ne!(locally_computed.seed, output(2).seed);
+        assert_eq!(
+            RandomnessBeacon::check_inherent(&call, &inherent_data(vec![locally_computed])),
+            Ok(())
+        );
+    });
+}
+
+#[test]
+fn rejects_forged_seed() {
+    new_test_ext().execute_with(|| {
+        CurrentSession::set(3);
+        let mut forged = output(2);
+        forged.seed = [7; 32];
+        let call = pallet::Call::<Test>::set_beacon_output {
+            output: forged.clone(),
+        };
+
+        assert_eq!(
+            RandomnessBeacon::check_inherent(&call, &inherent_data(vec![forged.clone()])),
+            Err(InherentError::InvalidOutput(2))
+        );
+        assert_noop!(
+            RandomnessBeacon::set_beacon_output(RawOrigin::None.into(), forged),
+            Error::<Test>::SeedMismatch
+        );
+    });
+}
+
+#[test]
+fn rejects_shares_not_signed_by_committee() {
+    new_test_ext().execute_with(|| {
+        CurrentSession::set(3);
+        let mut outsider = output(2);
+        outsider.shares[0].1 = committee_pair(COMMITTEE_SIZE).sign(&share_message(2));
+        outsider.seed = seed(2, &outsider.shares);
+        let mut wrong_session = output(2);
+        wrong_session.shares[0].1 = committee_pair(0).sign(&share_message(1));
+        wrong_session.seed = seed(2, &wrong_session.shares);
+
+        for output in [outsider, wrong_session] {
+            assert_noop!(
+                RandomnessBeacon::set_beacon_output(RawOrigin::None.into(), output),
+                Error::<Test>::InvalidShare
+            );
+        }
+    });
+}
+
+#[test]
+fn rejects_outputs_without_quorum_of_distinct_members() {
+    new_test_ext().execute_with(|| {
+        CurrentSession::set(3);
+        assert_noop!(
+            RandomnessBeacon::set_beacon_output(RawOrigin::None.into(), shared_output(2, &[0, 1])),
+            Error::<Test>::WrongShareCount
+        );
+        assert_noop!(
+            RandomnessBeacon::set_beacon_output(
+                RawOrigin::None.into(),
+                shared_output(2, &[0, 1, 2, 3])
+            ),
+            Error::<Test>::WrongShareCount
+        );
+        assert_noop!(
+            RandomnessBeacon::set_beacon_output(
+                RawOrigin::None.into(),
+                shared_output(2, &[0, 0, 1])
+            ),
+            Error::<Test>::UnorderedShares
+        );
+        assert_noop!(
+            RandomnessBeacon::set_beacon_output(
+                RawOrigin::None.into(),
+                shared_output(2, &[2, 1, 0])
+            ),
+            Error::<Test>::UnorderedShares
+        );
+    });
+}
+
+#[test]
+fn verifies_inherent_against_committee() {
+    new_test_ext().execute_with(|| {
+        let mut forged = output(2);
+        forged.seed = [7; 32];
+        let check = |output: BeaconOutput| {
+            let call = pallet::Call::<Test>::set_beacon_output { output };
+            RandomnessBeacon::check_inherent(&call, &InherentData::new())
+        };
+
+        assert_eq!(check(output(2)), Ok(()));
+        assert_eq!(check(forged), Err(InherentError::InvalidOutput(2)));
+        assert_eq!(
+            check(shared_output(2, &[0])),
+            Err(InherentError::InvalidOutput(2))
+        );
+    });
+}
+
+#[test]
+fn rejects_seed_of_session_with_unknown_committee() {
+    new_test_ext().execute_with(|| {
+        CurrentSession::set(3);
+        Committee::set(None);
+        let call = pallet::Call::<Test>::set_beacon_output { output: output(2) };
+
+        assert_eq!(
+            RandomnessBeacon::check_inherent(&call, &InherentData::new()),
+            Err(InherentError::UnknownCommittee(2))
+        );
+        assert_noop!(
+            RandomnessBeacon::set_beacon_output(RawOrigin::None.into(), output(2)),
+            Error::<Test>::UnknownCommittee
+        );
+    });
+}
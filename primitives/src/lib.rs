@@ -1,8 +1,8 @@
 #![allow(clippy::too_many_arguments, clippy::unnecessary_mut_passed)]
 #![cfg_attr(not(feature = "std"), no_std)]
-use codec::{Decode, Encode};
+use codec::{Codec, Decode, Encode};
 use sp_core::crypto::KeyTypeId;
-use sp_runtime::ConsensusEngineId;
+use sp_runtime::{traits::Block as BlockT, ConsensusEngineId};
 pub use sp_staking::SessionIndex;
 use sp_std::vec::Vec;
 
@@ -53,33 +53,172 @@ pub enum ApiError {
     DecodeKey,
 }
 
+/// For how many sessions the authority sets are kept on chain.
+pub const DEFAULT_AUTHORITY_HISTORY_DEPTH: SessionIndex = 1000;
+
+/// How a validator performs in the current session, or performed in a past one.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, sp_std::fmt::Debug)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ValidatorPerformance<AccountId> {
+    pub validator: AccountId,
+    pub session: SessionIndex,
+    pub in_committee: bool,
+    /// Blocks the validator should produce in the session, zero outside of the committee.
+    pub blocks_expected: u32,
+    pub blocks_produced: u32,
+    /// Reward points the validator gets for the session, as things stand.
+    pub adjusted_points: u32,
+    /// The era reward of the validator and its nominators, assuming that the rest of the era goes
+    /// like the session.
+    pub projected_era_reward: Balance,
+}
+
+/// How a validator of the active era performed in a past session, as recorded on chain.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, sp_std::fmt::Debug, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ValidatorSessionRecord<AccountId> {
+    pub validator: AccountId,
+    pub in_committee: bool,
+    /// Blocks the validator should have produced in the session, zero outside of the committee.
+    pub blocks_expected: u32,
+    pub blocks_produced: u32,
+    /// Reward points the validator got for the session.
+    pub points: u32,
+}
+
+/// For how many sessions the performance records of validators are kept on chain: the sessions of
+/// the active era and of the previous one. A record takes 45 bytes, so with `MAX_VALIDATORS` the
+/// history is bounded by about 9MB, but with a hundred validators it stays under 1MB.
+pub const DEFAULT_PERFORMANCE_HISTORY_DEPTH: SessionIndex = 2 * DEFAULT_SESSIONS_PER_ERA;
+
+/// The most validators whose performance is recorded in a single session.
+pub const MAX_VALIDATORS: u32 = 1000;
+
 sp_api::decl_runtime_apis! {
+    #[api_version(2)]
     pub trait AlephSessionApi
     {
         fn next_session_authorities() -> Result<Vec<AuthorityId>, ApiError>;
         fn authorities() -> Vec<AuthorityId>;
         fn session_period() -> u32;
         fn millisecs_per_block() -> u64;
+        /// The authorities of a past or the current session, if still kept on chain.
+        fn session_authorities(session: SessionIndex) -> Option<Vec<AuthorityId>>;
+        /// The hash of the authority set of a past or the current session, if still kept on chain.
+        fn authority_set_hash(session: SessionIndex) -> Option<<Block as BlockT>::Hash>;
+    }
+
+    pub trait ElectionsApi<AccountId: Codec>
+    {
+        /// Performance of the validators in the current session or in a past one, if still kept
+        /// on chain.
+        fn validators_performance(
+            session: SessionIndex,
+        ) -> Option<Vec<ValidatorPerformance<AccountId>>>;
+        /// Performance of the validators in a past session, if still kept on chain.
+        fn session_performance(
+            session: SessionIndex,
+        ) -> Option<Vec<ValidatorSessionRecord<AccountId>>>;
+    }
+
+    pub trait InflationApi
+    {
+        /// The inflation model the payouts of the eras are computed with.
+        fn inflation_model() -> staking::InflationModel;
+        /// The payouts of the active era to the validators and to the treasury, provided that the
+        /// stake and the total issuance stay as they are.
+        fn projected_era_payout() -> (Balance, Balance);
     }
 }
 
 pub mod staking {
     use super::Balance;
     use crate::TOKEN;
-    use sp_runtime::Perbill;
+    use codec::{Decode, Encode, MaxEncodedLen};
+    use scale_info::TypeInfo;
+    use sp_runtime::{Perbill, RuntimeDebug};
 
     pub const MIN_VALIDATOR_BOND: u128 = 25_000 * TOKEN;
     pub const MIN_NOMINATOR_BOND: u128 = 100 * TOKEN;
     pub const MAX_NOMINATORS_REWARDED_PER_VALIDATOR: u32 = 1024;
 
-    pub fn era_payout(miliseconds_per_era: u64) -> (Balance, Balance) {
-        const YEARLY_INFLATION: Balance = 30_000_000 * TOKEN;
-        // Milliseconds per year for the Julian year (365.25 days).
-        const MILLISECONDS_PER_YEAR: u64 = 1000 * 3600 * 24 * 36525 / 100;
+    /// The yearly inflation before it became configurable, paid out regardless of the issuance.
+    pub const LEGACY_YEARLY_INFLATION: Balance = 30_000_000 * TOKEN;
+    // Milliseconds per year for the Julian year (365.25 days).
+    const MILLISECONDS_PER_YEAR: u64 = 1000 * 3600 * 24 * 36525 / 100;
+
+    /// How much is minted every year, depending on how much of the issuance is staked.
+    ///
+    /// The inflation grows linearly from `min_inflation` with nothing staked to `max_inflation` at
+    /// the ideal staking rate, and falls back linearly to `min_inflation` with everything staked.
+    #[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+    pub struct InflationModel {
+        /// Yearly inflation, as a fraction of the total issuance, at the extreme staking rates.
+        pub min_inflation: Perbill,
+        /// Yearly inflation, as a fraction of the total issuance, at the ideal staking rate.
+        pub max_inflation: Perbill,
+        /// The fraction of the total issuance that should be staked.
+        pub ideal_staking_rate: Perbill,
+        /// The part of every payout that goes to the treasury instead of the validators.
+        pub treasury_share: Perbill,
+    }
+
+    impl InflationModel {
+        /// The model paying out `LEGACY_YEARLY_INFLATION` a year at the given total issuance,
+        /// whatever the staking rate, split 90/10 between the validators and the treasury. At any
+        /// other total issuance it pays out the same percentage, not the same amount.
+        pub fn legacy(total_issuance: Balance) -> Self {
+            let inflation = match total_issuance {
+                0 => Perbill::zero(),
+                _ => Perbill::from_rational(LEGACY_YEARLY_INFLATION, total_issuance),
+            };
+            InflationModel {
+                min_inflation: inflation,
+                max_inflation: inflation,
+                ideal_staking_rate: Perbill::from_percent(50),
+                treasury_share: Perbill::from_percent(10),
+            }
+        }
+
+        /// Whether the inflation bounds are ordered and the ideal staking rate is positive.
+        pub fn is_valid(&self) -> bool {
+            self.min_inflation <= self.max_inflation && !self.ideal_staking_rate.is_zero()
+        }
+
+        /// Yearly inflation, as a fraction of the total issuance, at the given staking rate.
+        pub fn yearly_inflation(&self, staking_rate: Perbill) -> Perbill {
+            let ideal = self.ideal_staking_rate;
+            let progress = if staking_rate <= ideal {
+                Perbill::from_rational(staking_rate.deconstruct(), ideal.deconstruct())
+            } else {
+                let above_ideal = staking_rate.saturating_sub(ideal).deconstruct();
+                let max_above_ideal = Perbill::one().saturating_sub(ideal).deconstruct();
+                Perbill::one().saturating_sub(Perbill::from_rational(above_ideal, max_above_ideal))
+            };
+            let spread = self.max_inflation.saturating_sub(self.min_inflation);
+            self.min_inflation.saturating_add(progress * spread)
+        }
+    }
+
+    /// The payouts for an era of the given length, to the validators and to the treasury.
+    pub fn era_payout(
+        model: &InflationModel,
+        total_staked: Balance,
+        total_issuance: Balance,
+        miliseconds_per_era: u64,
+    ) -> (Balance, Balance) {
+        if total_issuance == 0 {
+            return (0, 0);
+        }
+        let staking_rate = Perbill::from_rational(total_staked, total_issuance);
+        let yearly_inflation = model.yearly_inflation(staking_rate) * total_issuance;
 
         let portion = Perbill::from_rational(miliseconds_per_era, MILLISECONDS_PER_YEAR);
-        let total_payout = portion * YEARLY_INFLATION;
-        let validators_payout = Perbill::from_percent(90) * total_payout;
+        let total_payout = portion * yearly_inflation;
+        let validators_payout = Perbill::one().saturating_sub(model.treasury_share) * total_payout;
         let rest = total_payout - validators_payout;
 
         (validators_payout, rest)
@@ -113,4 +252,246 @@ pub mod staking {
             )*
         };
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const ISSUANCE: Balance = 300_000_000 * TOKEN;
+        const MILLISECONDS_PER_ERA: u64 = 1000 * 900 * 96;
+
+        fn model() -> InflationModel {
+            InflationModel {
+                min_inflation: Perbill::from_percent(2),
+                max_inflation: Perbill::from_percent(10),
+                ideal_staking_rate: Perbill::from_percent(60),
+                treasury_share: Perbill::from_percent(20),
+            }
+        }
+
+        #[test]
+        fn legacy_model_pays_out_as_before() {
+            let portion = Perbill::from_rational(MILLISECONDS_PER_ERA, MILLISECONDS_PER_YEAR);
+            let total_payout = portion * LEGACY_YEARLY_INFLATION;
+            let validators_payout = Perbill::from_percent(90) * total_payout;
+
+            for staked in [0, ISSUANCE / 3, ISSUANCE] {
+                let (validators, treasury) = era_payout(
+                    &InflationModel::legacy(ISSUANCE),
+                    staked,
+                    ISSUANCE,
+                    MILLISECONDS_PER_ERA,
+                );
+                assert_eq!(validators, validators_payout);
+                assert_eq!(treasury, total_payout - validators_payout);
+            }
+        }
+
+        #[test]
+        fn inflation_grows_up_to_the_ideal_staking_rate() {
+            let model = model();
+            assert_eq!(
+                model.yearly_inflation(Perbill::zero()),
+                Perbill::from_percent(2)
+            );
+            assert_eq!(
+                model.yearly_inflation(Perbill::from_percent(30)),
+                Perbill::from_percent(6)
+            );
+            assert_eq!(
+                model.yearly_inflation(Perbill::from_percent(60)),
+                Perbill::from_percent(10)
+            );
+        }
+
+        #[test]
+        fn inflation_falls_above_the_ideal_staking_rate() {
+            let model = model();
+            assert_eq!(
+                model.yearly_inflation(Perbill::from_percent(80)),
+                Perbill::from_percent(6)
+            );
+            assert_eq!(
+                model.yearly_inflation(Perbill::one()),
+                Perbill::from_percent(2)
+            );
+        }
+
+        #[test]
+        fn payout_is_split_with_the_treasury() {
+            let year = MILLISECONDS_PER_YEAR;
+            let (validators, treasury) = era_payout(&model(), ISSUANCE * 3 / 5, ISSUANCE, year);
+            assert_eq!(validators + treasury, ISSUANCE / 10);
+            assert_eq!(treasury, ISSUANCE / 50);
+
+            assert_eq!(era_payout(&model(), 0, 0, year), (0, 0));
+        }
+
+        #[test]
+        fn models_need_ordered_bounds_and_positive_ideal_rate() {
+            assert!(model().is_valid());
+            assert!(InflationModel::legacy(ISSUANCE).is_valid());
+            assert!(!InflationModel {
+                min_inflation: Perbill::from_percent(11),
+                ..model()
+            }
+            .is_valid());
+            assert!(!InflationModel {
+                ideal_staking_rate: Perbill::zero(),
+                ..model()
+            }
+            .is_valid());
+        }
+    }
+}
+
+pub mod finality {
+    use sp_inherents::InherentIdentifier;
+
+    /// Identifies the newest finalized block known to the block author, passed to the runtime as
+    /// the pair of its number and hash.
+    pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"alephfin";
+}
+
+pub mod randomness {
+    use super::{AuthorityId, AuthoritySignature, SessionIndex};
+    use codec::{Decode, Encode};
+    use scale_info::TypeInfo;
+    use sp_core::hashing::blake2_256;
+    use sp_inherents::{InherentIdentifier, IsFatalError};
+    use sp_runtime::RuntimeAppPublic;
+    use sp_std::vec::Vec;
+
+    pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"alephrnd";
+    /// How many of the most recent beacon outputs a node keeps and puts into the inherent data.
+    pub const MAX_BEACON_OUTPUTS: usize = 16;
+    /// The kind of the out-of-band AlephBFT items carrying the shares.
+    pub const SHARE_ITEM_KIND: [u8; 4] = *b"rand";
+
+    pub type Seed = [u8; 32];
+    /// A signature of the committee member with the given index under `share_message`.
+    pub type Share = (u32, AuthoritySignature);
+
+    /// The randomness produced by the committee of the given session, together with the shares
+    /// it was computed from.
+    #[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+    pub struct BeaconOutput {
+        pub session: SessionIndex,
+        pub seed: Seed,
+        /// Shares of exactly `quorum` distinct members, ordered by the member index.
+        pub shares: Vec<Share>,
+    }
+
+    /// Why a beacon output is not backed by the committee of its session.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum OutputError {
+        /// The number of shares is different than the quorum of the committee.
+        WrongShareCount,
+        /// The shares are not ordered by the member index, or some member has more than one.
+        UnorderedShares,
+        /// Some share is not a signature of a committee member.
+        InvalidShare,
+        /// The seed is not the one determined by the shares.
+        SeedMismatch,
+    }
+
+    /// The number of shares determining the seed of a committee with `committee_size` members.
+    pub fn quorum(committee_size: usize) -> usize {
+        committee_size * 2 / 3 + 1
+    }
+
+    /// The message signed by the committee members of `session`, the same as the one signed
+    /// for an empty out-of-band item of kind `SHARE_ITEM_KIND`.
+    pub fn share_message(session: SessionIndex) -> Vec<u8> {
+        (SHARE_ITEM_KIND, session, Vec::<u8>::new()).encode()
+    }
+
+    /// The seed determined by `shares` of the committee of `session`.
+    pub fn seed(session: SessionIndex, shares: &[Share]) -> Seed {
+        blake2_256(&(session, shares).encode())
+    }
+
+    impl BeaconOutput {
+        /// Checks that the output is backed by the quorum of `committee`, the keys of the
+        /// committee members of its session.
+        pub fn verify(&self, committee: &[AuthorityId]) -> Result<(), OutputError> {
+            if self.shares.len() != quorum(committee.len()) {
+                return Err(OutputError::WrongShareCount);
+            }
+            if self.shares.windows(2).any(|pair| pair[0].0 >= pair[1].0) {
+                return Err(OutputError::UnorderedShares);
+            }
+            let message = share_message(self.session);
+            for (member, signature) in &self.shares {
+                match committee.get(*member as usize) {
+                    Some(key) if key.verify(&message, signature) => {}
+                    _ => return Err(OutputError::InvalidShare),
+                }
+            }
+            if self.seed != seed(self.session, &self.shares) {
+                return Err(OutputError::SeedMismatch);
+            }
+            Ok(())
+        }
+    }
+
+    /// The beacon outputs known to the node, the newest acceptable one gets included in a block.
+    pub type InherentType = Vec<BeaconOutput>;
+
+    #[derive(Encode, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(Decode))]
+    pub enum InherentError {
+        /// The included output is not backed by the committee of its session.
+        InvalidOutput(SessionIndex),
+        /// The committee of the session of the included output is not known on chain.
+        UnknownCommittee(SessionIndex),
+    }
+
+    impl IsFatalError for InherentError {
+        fn is_fatal_error(&self) -> bool {
+            true
+        }
+    }
+}
+
+pub mod proxy {
+    use codec::{Decode, Encode, MaxEncodedLen};
+    use scale_info::TypeInfo;
+    use sp_runtime::RuntimeDebug;
+
+    /// The kinds of calls a proxy account may make on behalf of the account that added it.
+    ///
+    /// The runtime decides which calls match which type. The encoding is part of the runtime
+    /// interface, so new variants may only be appended.
+    #[derive(
+        Copy,
+        Clone,
+        Eq,
+        PartialEq,
+        Ord,
+        PartialOrd,
+        Encode,
+        Decode,
+        RuntimeDebug,
+        MaxEncodedLen,
+        TypeInfo,
+    )]
+    pub enum ProxyType {
+        /// All calls.
+        Any,
+        /// All calls that cannot move funds out of the account.
+        NonTransfer,
+        /// Staking and session calls, e.g. `bond`, `validate`, `set_keys` and `payout_stakers`.
+        Staking,
+        /// Setting and purging the session keys.
+        SessionKeys,
+        /// Treasury and council calls.
+        Governance,
+    }
+
+    impl Default for ProxyType {
+        fn default() -> Self {
+            ProxyType::Any
+        }
+    }
 }
@@ -0,0 +1,34 @@
+#!/bin/bash
+
+# Regenerates the weight files of our pallets and of the chain extension. Run it on the reference
+# hardware, the weights depend on the machine they are measured on.
+
+set -e
+
+STEPS=${STEPS:-50}
+REPEAT=${REPEAT:-20}
+
+cargo build --release -p aleph-node --features runtime-benchmarks
+
+function benchmark() {
+  local pallet=$1
+  local output=$2
+  shift 2
+  ./target/release/aleph-node benchmark \
+    --chain=testnet \
+    --execution=wasm \
+    --wasm-execution=compiled \
+    --pallet="${pallet}" \
+    --extrinsic='*' \
+    --steps="${STEPS}" \
+    --repeat="${REPEAT}" \
+    --output="${output}" \
+    "$@"
+}
+
+benchmark pallet_aleph ./pallets/aleph/src/weights.rs --template=./scripts/pallet-weight-template.hbs
+benchmark pallet_elections ./pallets/elections/src/weights.rs --template=./scripts/pallet-weight-template.hbs
+benchmark pallet_inflation ./pallets/inflation/src/weights.rs --template=./scripts/pallet-weight-template.hbs
+benchmark pallet_nomination_pools ./pallets/nomination-pools/src/weights.rs --template=./scripts/pallet-weight-template.hbs
+benchmark aleph_chain_extension ./chain-extension/src/weights.rs --template=./scripts/pallet-weight-template.hbs
+
@@ -0,0 +1,20 @@
+#!/bin/bash
+
+# Refreshes the runtime metadata snapshot from which `aleph-client` generates its typed bindings.
+# Run it against a node running the current runtime (e.g. one started with `scripts/run_nodes.sh`)
+# whenever `spec_version` or `transaction_version` is bumped and commit the result. CI runs it against
+# the freshly built node and fails if the committed snapshot differs.
+
+set -e
+
+NODE_URL=${NODE_URL:-"http://127.0.0.1:9933"}
+OUTPUT=${OUTPUT:-"aleph-client/metadata/aleph_runtime.scale"}
+
+curl -sS -H "Content-Type: application/json" \
+  -d '{"id": 1, "jsonrpc": "2.0", "method": "state_getMetadata", "params": []}' \
+  "${NODE_URL}" \
+  | jq -r .result \
+  | sed 's/^0x//' \
+  | xxd -r -p > "${OUTPUT}"
+
+echo "Metadata written to ${OUTPUT}"